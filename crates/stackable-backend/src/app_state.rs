@@ -0,0 +1,89 @@
+//! Initializes the app state when the server starts.
+
+use std::any::type_name;
+use std::error::Error;
+use std::future::Future;
+
+use futures::future::{try_join_all, BoxFuture};
+use futures::FutureExt;
+use stackable_core::app_state::AppState;
+
+use crate::error::{AppStateError, AppStateResult};
+
+type Insert = Box<dyn Send + FnOnce(&mut AppState)>;
+type Initializer = BoxFuture<'static, AppStateResult<Insert>>;
+
+/// Registers the values of an [`AppState`], values that need to be initialized asynchronously
+/// are initialized concurrently by [`build`](Self::build).
+#[derive(Default)]
+pub struct AppStateBuilder {
+    state: AppState,
+    initializers: Vec<Initializer>,
+}
+
+impl std::fmt::Debug for AppStateBuilder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AppStateBuilder")
+            .field("state", &self.state)
+            .field("initializers", &self.initializers.len())
+            .finish()
+    }
+}
+
+impl AppStateBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Inserts a value.
+    pub fn with_value<T>(mut self, value: T) -> Self
+    where
+        T: 'static + Send + Sync,
+    {
+        self.state.insert(value);
+
+        self
+    }
+
+    /// Inserts the value that an initializer resolves to, e.g.: a database pool.
+    ///
+    /// The initializer is run once by [`build`](Self::build).
+    pub fn with_initializer<T, F, Fut, E>(mut self, f: F) -> Self
+    where
+        T: 'static + Send + Sync,
+        F: 'static + Send + FnOnce() -> Fut,
+        Fut: 'static + Send + Future<Output = Result<T, E>>,
+        E: Into<Box<dyn Error + Send + Sync>>,
+    {
+        self.initializers.push(
+            async move {
+                let value = f().await.map_err(|e| AppStateError::Initialize {
+                    name: type_name::<T>(),
+                    source: e.into(),
+                })?;
+
+                let insert: Insert = Box::new(move |state: &mut AppState| state.insert(value));
+                Ok(insert)
+            }
+            .boxed(),
+        );
+
+        self
+    }
+
+    /// Runs the initializers and returns the app state.
+    ///
+    /// Fails with the error of the first initializer that fails.
+    pub async fn build(self) -> AppStateResult<AppState> {
+        let Self {
+            mut state,
+            initializers,
+        } = self;
+
+        for insert in try_join_all(initializers).await? {
+            insert(&mut state);
+        }
+
+        Ok(state)
+    }
+}
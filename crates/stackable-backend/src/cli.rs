@@ -1,17 +1,38 @@
-use std::env;
-use std::net::ToSocketAddrs;
+#[cfg(feature = "tls")]
+use std::convert::Infallible;
+use std::future::Future;
+use std::net::{SocketAddr, TcpListener, ToSocketAddrs};
 use std::path::PathBuf;
+use std::time::Duration;
+use std::{env, fs};
 
 use anyhow::{anyhow, Context};
 use clap::Parser;
+use futures::future::{self, Either};
+#[cfg(feature = "http3")]
+use http::header::ALT_SVC;
+#[cfg(feature = "tls")]
+use hyper::{Body, Request, Response};
+use stackable_bridge::contract::BridgeContract;
+use stackable_core::build_info::BuildInfo;
 use stackable_core::dev::StackctlMetadata;
+use stackable_core::mock::Mocks;
+use stackable_core::server::{ServerConfig, TlsConfig};
+#[cfg(feature = "tls")]
+use tower::Service;
 use typed_builder::TypedBuilder;
 use yew::BaseComponent;
 
+use crate::app_state::AppStateBuilder;
+use crate::dashboard::Dashboard;
 use crate::endpoint::Endpoint;
+use crate::metrics::Metrics;
 use crate::props::ServerAppProps;
-use crate::server::Server;
-use crate::Frontend;
+use crate::recorder::FileRecorder;
+use crate::render_cache::{self, RenderCache};
+use crate::server::{Server, Shutdown};
+use crate::utils::random_str_with_len;
+use crate::{session, Frontend, Tasks};
 
 #[derive(Parser)]
 struct Arguments {
@@ -21,6 +42,12 @@ struct Arguments {
     /// The ditectory that contains the frontend artifact.
     #[arg(long, env = "STACKABLE_FRONTEND_DIR")]
     frontend_dir: Option<PathBuf>,
+    /// The path to the server configuration generated by `stackctl build`.
+    #[arg(long, env = ServerConfig::ENV_NAME)]
+    server_config: Option<PathBuf>,
+    /// The secret to derive encryption keys from.
+    #[arg(long, env = "STACKABLE_SECRET_KEY", hide_env_values = true)]
+    secret_key: Option<String>,
 }
 
 #[derive(Debug, TypedBuilder)]
@@ -29,6 +56,12 @@ where
     COMP: BaseComponent,
 {
     endpoint: Endpoint<COMP, CTX>,
+    #[builder(default, setter(skip))]
+    shutdown: Shutdown,
+    #[builder(default, setter(skip))]
+    tasks: Option<Tasks>,
+    #[builder(default, setter(skip))]
+    app_state: Option<AppStateBuilder>,
 }
 
 impl<COMP, CTX> Cli<COMP, CTX>
@@ -36,8 +69,50 @@ where
     COMP: BaseComponent<Properties = ServerAppProps<CTX>>,
     CTX: 'static,
 {
+    /// Runs a hook after the production server has drained requests in flight.
+    ///
+    /// Hooks are run in the order they are added.
+    pub fn with_shutdown_hook<F, Fut>(mut self, hook: F) -> Self
+    where
+        F: 'static + Send + FnOnce() -> Fut,
+        Fut: 'static + Send + Future<Output = ()>,
+    {
+        self.shutdown = self.shutdown.with_hook(hook);
+
+        self
+    }
+
+    /// Starts jobs of the tasks with the server, and stops them before shutdown hooks are run.
+    pub fn with_tasks(mut self, tasks: Tasks) -> Self {
+        self.tasks = Some(tasks);
+
+        self
+    }
+
+    /// Initializes the app state before the server starts listening.
+    ///
+    /// The server exits if an initializer fails, which fails the readiness check of stackctl.
+    pub fn with_app_state(mut self, app_state: AppStateBuilder) -> Self {
+        self.app_state = Some(app_state);
+
+        self
+    }
+
     pub async fn run(self) -> anyhow::Result<()> {
-        let Self { mut endpoint } = self;
+        let Self {
+            mut endpoint,
+            mut shutdown,
+            tasks,
+            app_state,
+        } = self;
+
+        // Written for `stackctl test --contracts`.
+        if let Ok(m) = env::var(BridgeContract::ENV_NAME) {
+            fs::write(m, endpoint.bridge_contract().to_json()?)
+                .context("failed to write bridge contract")?;
+
+            return Ok(());
+        }
 
         let args = Arguments::parse();
 
@@ -56,10 +131,161 @@ where
             endpoint = endpoint.with_frontend(Frontend::new_path(p));
         }
 
+        let server_config = match (meta.as_ref(), args.server_config.as_ref()) {
+            (Some(m), _) => m.server_config.clone(),
+            (None, Some(p)) => fs::read_to_string(p)
+                .context("failed to read server configuration")
+                .and_then(|m| {
+                    ServerConfig::from_json(&m).context("failed to parse server configuration")
+                })?,
+            (None, None) => ServerConfig::default(),
+        };
+        server_config
+            .validate()
+            .context("invalid server configuration")?;
+
+        if let Some(ref m) = server_config.bridge_path {
+            endpoint = endpoint.with_bridge_path(m);
+        }
+
+        if let Some(ref m) = server_config.client_ip_header {
+            endpoint = endpoint.with_client_ip_header(m);
+        }
+
+        if let Some(ref m) = server_config.allowed_origins {
+            endpoint = endpoint.with_allowed_origins(m.iter().cloned());
+        }
+
+        endpoint = endpoint.with_virtual_hosts(&server_config.vhosts);
+
+        let secret = match (args.secret_key, meta.as_ref()) {
+            (Some(m), _) => Some(m),
+            // The development server restarts frequently, a random secret is good enough.
+            (None, Some(_)) => Some(random_str_with_len(32)),
+            (None, None) => None,
+        };
+
+        if let Some(ref m) = server_config.session {
+            let store = session::create_store(m).context("failed to create session store")?;
+            tokio::spawn(session::purge_periodically(
+                store.clone(),
+                Duration::from_secs(600),
+            ));
+
+            let mut config = m.clone();
+            // The development server is served over HTTP.
+            if meta.is_some() {
+                config.secure = false;
+            }
+
+            let secret = secret.as_ref().ok_or_else(|| {
+                anyhow!("STACKABLE_SECRET_KEY must be set to encrypt session cookies")
+            })?;
+
+            endpoint = endpoint
+                .with_session_store(store)
+                .with_session_config(&config)
+                .with_session_secret(secret);
+        }
+
+        endpoint = endpoint
+            .with_hydration_config(&server_config.hydration)
+            .with_fallback_config(&server_config.fallback)
+            .with_csrf_protection(server_config.csrf.enabled);
+
+        if let Some(ref m) = server_config.state_compression {
+            endpoint = endpoint.with_state_compression(m.clone());
+        }
+
+        if let Some(ref m) = server_config.render_cache {
+            let store =
+                render_cache::create_store(m).context("failed to create render cache store")?;
+
+            endpoint = endpoint.with_render_cache(RenderCache::new(store, m));
+        }
+
+        if let Some(ref m) = server_config.render {
+            endpoint = endpoint.with_render_config(m);
+
+            if m.streaming {
+                endpoint = endpoint.with_streaming();
+            }
+        }
+
+        endpoint = endpoint.with_request_limits(&server_config.limits);
+
+        if let Some(ref m) = server_config.cors {
+            endpoint = endpoint.with_cors(m);
+        }
+
+        if let Some(ref m) = server_config.rate_limit {
+            endpoint = endpoint.with_rate_limit(m);
+        }
+
+        if let Some(ref m) = server_config.admin {
+            endpoint = endpoint.with_admin(m);
+        }
+
+        if server_config.metrics || meta.is_some() {
+            endpoint = endpoint.with_metrics(Metrics::new());
+        }
+
+        if server_config.encrypt_state {
+            let secret = secret.as_ref().ok_or_else(|| {
+                anyhow!("STACKABLE_SECRET_KEY must be set to encrypt state payloads")
+            })?;
+
+            endpoint = endpoint.with_state_encryption(secret);
+        }
+
         if let Some(ref meta) = meta {
             endpoint = endpoint
                 .with_frontend(Frontend::new_path(&meta.frontend_dev_build_dir))
-                .with_auto_refresh();
+                .with_auto_refresh()
+                .with_diagnostics()
+                .with_dashboard(Dashboard::new(&server_config, meta.status_path.clone()));
+
+            if let Some(ref m) = meta.refresh_path {
+                endpoint = endpoint.with_refresh_path(m);
+            }
+
+            if meta.strict_mode {
+                endpoint = endpoint.with_strict_mode();
+            }
+
+            for (alias, host) in meta.host_aliases.iter() {
+                endpoint = endpoint.with_host_alias(alias, host);
+            }
+
+            if let Some(ref m) = meta.routes_path {
+                fs::write(m, serde_json::to_string(&endpoint.api_routes())?)
+                    .context("failed to write routes")?;
+            }
+
+            if let (Some(path), Some(tasks)) = (meta.jobs_path.as_ref(), tasks.as_ref()) {
+                fs::write(path, serde_json::to_string(&tasks.jobs())?)
+                    .context("failed to write jobs")?;
+            }
+
+            if let Some(ref m) = meta.mocks_path {
+                let mocks = fs::read_to_string(m).context("failed to read mocks")?;
+                let mocks = Mocks::from_json(&mocks).context("failed to parse mocks")?;
+
+                tracing::info!("Mocking bridge requests with {} mocks", mocks.len());
+                endpoint = endpoint.with_mocks(mocks);
+            }
+
+            if let Some(ref m) = meta.record_path {
+                let recorder = FileRecorder::open(m.clone()).context("failed to open recording")?;
+
+                tracing::info!("Recording bridge requests to {}", m.display());
+                endpoint = endpoint.with_recorder(recorder);
+            }
+        }
+
+        if let Some(m) = app_state {
+            let app_state = m.build().await.context("failed to initialize app state")?;
+            endpoint = endpoint.with_app_state(app_state);
         }
 
         let listen_addr = addr
@@ -71,12 +297,188 @@ where
                     .ok_or_else(|| anyhow!("failed to parse address"))
             })?;
 
-        tracing::info!("Listening at: http://{}/", addr);
+        if let Some(m) = BuildInfo::current_id() {
+            tracing::info!("Build ID: {}", m);
+        }
+
+        let shutdown_timeout = Some(server_config.shutdown.timeout_secs)
+            .filter(|m| *m > 0)
+            .map(Duration::from_secs);
+
+        if let Some(tasks) = tasks {
+            tasks.start();
+            shutdown = shutdown
+                .with_first_hook(move || async move { tasks.shutdown(shutdown_timeout).await });
+        }
 
-        Server::<()>::bind(listen_addr)
-            .serve_service(endpoint.into_tower_service())
-            .await?;
+        // The development server is restarted by stackctl, so it is not drained.
+        if meta.is_none() {
+            shutdown = shutdown.with_signal(shutdown_signal(), shutdown_timeout);
+        }
+
+        // Bound before the public listener, so the server does not start if it is taken.
+        let admin = match (server_config.admin.as_ref(), endpoint.admin_tower_service()) {
+            (Some(m), Some(svc)) => {
+                let admin_addr = meta
+                    .as_ref()
+                    .and_then(|m| m.admin_listen_addr.as_deref())
+                    .unwrap_or(&m.listen);
+                let listener = TcpListener::bind(admin_addr)
+                    .with_context(|| format!("failed to listen at {admin_addr}"))?;
+
+                tracing::info!("Admin listening at: http://{}/", admin_addr);
+
+                Some((Server::<()>::from_tcp(listener)?, svc))
+            }
+            _ => None,
+        };
+
+        // The development server is served over HTTP.
+        let tls = server_config.tls.as_ref().filter(|_| meta.is_none());
+        let svc = endpoint.into_tower_service();
+
+        let serve = async {
+            match tls {
+                Some(m) => Self::serve_tls(listen_addr, m, svc, shutdown).await,
+                None => {
+                    tracing::info!("Listening at: http://{}/", addr);
+
+                    Server::<()>::bind(listen_addr)
+                        .with_shutdown(shutdown)
+                        .serve_service(svc)
+                        .await
+                        .map_err(anyhow::Error::from)
+                }
+            }
+        };
+
+        // The admin listener is closed once the public listener has been drained.
+        let serve_admin = async {
+            match admin {
+                Some((server, svc)) => server
+                    .serve_service(svc)
+                    .await
+                    .context("failed to serve admin listener"),
+                None => future::pending().await,
+            }
+        };
+
+        let result = match future::select(Box::pin(serve), Box::pin(serve_admin)).await {
+            Either::Left((m, _)) | Either::Right((m, _)) => m,
+        };
+
+        crate::trace::shutdown();
+        result?;
+
+        Ok(())
+    }
+
+    #[cfg(feature = "tls")]
+    async fn serve_tls<S, F>(
+        listen_addr: SocketAddr,
+        config: &TlsConfig,
+        svc: S,
+        shutdown: Shutdown,
+    ) -> anyhow::Result<()>
+    where
+        S: Service<Request<Body>, Response = Response<Body>, Error = Infallible, Future = F>
+            + Clone
+            + Send
+            + 'static,
+        F: Future<Output = Result<Response<Body>, Infallible>> + Send + 'static,
+    {
+        #[cfg(not(feature = "http3"))]
+        if config.http3 {
+            return Err(anyhow!(
+                "HTTP/3 requires the http3 feature of stackable-backend"
+            ));
+        }
+
+        tracing::info!("Listening at: https://{}/", listen_addr);
+
+        let server = Server::<()>::bind_tls(listen_addr, config)
+            .await
+            .context("failed to serve over TLS")?
+            .with_shutdown(shutdown);
+
+        if !config.http3 {
+            server.serve_service(svc).await?;
+            return Ok(());
+        }
+
+        #[cfg(feature = "http3")]
+        {
+            use futures::future::select;
+            use tower::ServiceExt;
+
+            tracing::info!("Listening at: https://{}/ (HTTP/3)", listen_addr);
+
+            // Browsers discover HTTP/3 from responses over HTTPS.
+            let alt_svc = crate::http3::alt_svc(listen_addr.port());
+            let https_svc = svc.clone().map_response(move |mut resp: Response<Body>| {
+                resp.headers_mut().insert(ALT_SVC, alt_svc.clone());
+                resp
+            });
+
+            let https = async {
+                server
+                    .serve_service(https_svc)
+                    .await
+                    .map_err(anyhow::Error::from)
+            };
+            let http3 = async {
+                crate::http3::serve(listen_addr, config, svc)
+                    .await
+                    .context("failed to serve HTTP/3")
+            };
+
+            // HTTP/3 connections are closed once the HTTPS server has been drained.
+            match select(Box::pin(https), Box::pin(http3)).await {
+                Either::Left((m, _)) | Either::Right((m, _)) => m?,
+            }
+        }
 
         Ok(())
     }
+
+    #[cfg(not(feature = "tls"))]
+    async fn serve_tls<S>(
+        _listen_addr: SocketAddr,
+        _config: &TlsConfig,
+        _svc: S,
+        _shutdown: Shutdown,
+    ) -> anyhow::Result<()> {
+        Err(anyhow!(
+            "server.tls requires the tls feature of stackable-backend"
+        ))
+    }
+}
+
+/// Resolves when the process receives `SIGTERM` or `Ctrl-C`.
+async fn shutdown_signal() {
+    #[cfg(unix)]
+    {
+        use futures::future::select;
+        use tokio::signal::unix::{signal, SignalKind};
+
+        let mut terminate = match signal(SignalKind::terminate()) {
+            Ok(m) => m,
+            Err(e) => {
+                tracing::error!("failed to listen to SIGTERM: {}", e);
+                let _ = tokio::signal::ctrl_c().await;
+                return;
+            }
+        };
+
+        select(
+            Box::pin(tokio::signal::ctrl_c()),
+            Box::pin(terminate.recv()),
+        )
+        .await;
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = tokio::signal::ctrl_c().await;
+    }
 }
@@ -0,0 +1,223 @@
+//! The dashboard of the development server at `/__stackable/`.
+//!
+//! It shows what the server has registered, so it is easier to find out why a route is not
+//! served as expected.
+
+use std::collections::VecDeque;
+use std::fmt::Write;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+use stackable_bridge::contract::BridgeContract;
+use stackable_core::build_info::BuildInfo;
+use stackable_core::dev::DevStatus;
+use stackable_core::server::ServerConfig;
+
+use crate::error_page::ErrorReport;
+use crate::utils::escape_html;
+
+/// The path of the dashboard.
+pub(crate) const DASHBOARD_PATH: &str = "/__stackable";
+
+/// The number of render errors that are kept.
+const MAX_ERRORS: usize = 10;
+
+#[derive(Debug, Clone)]
+struct RecentError {
+    at: SystemTime,
+    path: String,
+    message: String,
+}
+
+/// What the dashboard shows, besides the routes and the bridge of the endpoint.
+#[derive(Debug, Clone)]
+pub(crate) struct Dashboard {
+    started_at: SystemTime,
+    server_config: Arc<str>,
+    status_path: Option<PathBuf>,
+    errors: Arc<Mutex<VecDeque<RecentError>>>,
+}
+
+/// Formats the time since an event, e.g.: `5m ago`.
+fn format_age(at: SystemTime) -> String {
+    let age = SystemTime::now()
+        .duration_since(at)
+        .unwrap_or(Duration::ZERO)
+        .as_secs();
+
+    match age {
+        0..=59 => format!("{age}s ago"),
+        60..=3599 => format!("{}m ago", age / 60),
+        _ => format!("{}h ago", age / 3600),
+    }
+}
+
+fn write_list<I, S>(s: &mut String, title: &str, items: I)
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let _ = write!(s, "\n    <h2>{}</h2>\n    <ul>", escape_html(title));
+
+    let mut empty = true;
+    for m in items {
+        let _ = write!(s, "<li><code>{}</code></li>", escape_html(m.as_ref()));
+        empty = false;
+    }
+    if empty {
+        s.push_str("<li>None</li>");
+    }
+
+    s.push_str("</ul>");
+}
+
+impl Dashboard {
+    pub fn new(server_config: &ServerConfig, status_path: Option<PathBuf>) -> Self {
+        Self {
+            started_at: SystemTime::now(),
+            server_config: serde_json::to_string_pretty(server_config)
+                .unwrap_or_default()
+                .into(),
+            status_path,
+            errors: Arc::default(),
+        }
+    }
+
+    /// Records a failed render, the latest errors are shown.
+    pub fn record_error(&self, report: &ErrorReport) {
+        let mut errors = self.errors.lock().expect("failed to lock errors");
+
+        if errors.len() >= MAX_ERRORS {
+            errors.pop_back();
+        }
+        errors.push_front(RecentError {
+            at: SystemTime::now(),
+            path: report.path().to_string(),
+            message: report.message().to_string(),
+        });
+    }
+
+    /// Reads the status written by stackctl, if any.
+    pub async fn status(&self) -> Option<DevStatus> {
+        let content = tokio::fs::read_to_string(self.status_path.as_ref()?)
+            .await
+            .ok()?;
+
+        DevStatus::from_json(&content)
+            .map_err(|e| tracing::warn!("failed to parse status of stackctl: {}", e))
+            .ok()
+    }
+
+    pub fn render(
+        &self,
+        status: Option<&DevStatus>,
+        api_routes: &[String],
+        mount_paths: &[String],
+        contract: &BridgeContract,
+    ) -> String {
+        let mut s = String::new();
+
+        let _ = write!(
+            &mut s,
+            r#"<!doctype html>
+<html>
+<head>
+    <meta charset="utf-8">
+    <title>Stackable Development Server</title>
+    <style>
+        body {{ font-family: sans-serif; margin: 2rem; color: #1f2328; }}
+        h2 {{ margin-top: 2rem; }}
+        pre {{ background: #f6f8fa; padding: 1rem; overflow: auto; }}
+        .error {{ color: #cf222e; }}
+    </style>
+</head>
+<body>
+    <h1>Stackable Development Server</h1>
+    <p>Build: <code>{}</code>, started {}.</p>"#,
+            escape_html(&BuildInfo::current_id().unwrap_or_else(|| "unknown".to_string())),
+            format_age(self.started_at),
+        );
+
+        write_list(&mut s, "API Routes", api_routes);
+        write_list(&mut s, "Frontends", mount_paths);
+        write_list(
+            &mut s,
+            "Bridge Queries and Mutations",
+            contract.queries.iter().map(|m| &m.name),
+        );
+        write_list(
+            &mut s,
+            "Bridge Streams",
+            contract.streams.iter().map(|m| &m.name),
+        );
+        write_list(
+            &mut s,
+            "Bridge Uploads",
+            contract.uploads.iter().map(|m| &m.name),
+        );
+
+        if let Some(status) = status {
+            let _ = write!(
+                &mut s,
+                "\n    <h2>Env</h2>\n    <p>Profile: <code>{}</code></p>",
+                escape_html(&status.env_profile)
+            );
+            write_list(&mut s, "Env Variables", &status.env_names);
+
+            s.push_str("\n    <h2>Recent Rebuilds</h2>\n    <ul>");
+            for m in status.rebuilds.iter() {
+                let _ = write!(
+                    &mut s,
+                    "<li>{}, {:.2}s: ",
+                    format_age(m.started_at),
+                    m.duration.as_secs_f64()
+                );
+                match m.error {
+                    Some(ref e) => {
+                        let _ = write!(
+                            &mut s,
+                            r#"<span class="error">failed</span><pre>{}</pre>"#,
+                            escape_html(e)
+                        );
+                    }
+                    None => s.push_str("succeeded"),
+                }
+                s.push_str("</li>");
+            }
+            if status.rebuilds.is_empty() {
+                s.push_str("<li>None</li>");
+            }
+            s.push_str("</ul>");
+        }
+
+        s.push_str("\n    <h2>Recent Render Errors</h2>\n    <ul>");
+        let errors = self.errors.lock().expect("failed to lock errors");
+        for m in errors.iter() {
+            let _ = write!(
+                &mut s,
+                r#"<li>{}, <code>{}</code>: <pre class="error">{}</pre></li>"#,
+                format_age(m.at),
+                escape_html(&m.path),
+                escape_html(&m.message)
+            );
+        }
+        if errors.is_empty() {
+            s.push_str("<li>None</li>");
+        }
+        s.push_str("</ul>");
+
+        let _ = write!(
+            &mut s,
+            r#"
+    <h2>Server Configuration</h2>
+    <pre>{}</pre>
+    <p>This page is only shown by the development server.</p>
+</body>
+</html>"#,
+            escape_html(&self.server_config)
+        );
+
+        s
+    }
+}
@@ -3,12 +3,22 @@ use std::marker::PhantomData;
 
 use futures::future::LocalBoxFuture;
 use futures::{Future, FutureExt};
+use stackable_bridge::contract::BridgeContract;
 use stackable_bridge::{Bridge, BridgeMetadata};
 use yew::prelude::*;
 
 use crate::props::ServerAppProps;
 use crate::utils::ThreadLocalLazy;
 
+#[cfg(feature = "tower-service")]
+type BoxService = tower::util::BoxCloneService<
+    hyper::Request<hyper::Body>,
+    hyper::Response<hyper::Body>,
+    std::convert::Infallible,
+>;
+#[cfg(feature = "tower-service")]
+type BoxLayer = Box<dyn Send + FnOnce(BoxService) -> BoxService>;
+
 type BoxedSendFn<IN, OUT> = Box<dyn Send + Fn(IN) -> LocalBoxFuture<'static, OUT>>;
 type SendFn<IN, OUT> = ThreadLocalLazy<BoxedSendFn<IN, OUT>>;
 
@@ -27,6 +37,105 @@ where
     #[cfg(feature = "warp-filter")]
     auto_refresh: bool,
 
+    #[cfg(feature = "warp-filter")]
+    error_page: crate::ErrorPage,
+
+    #[cfg(feature = "warp-filter")]
+    diagnostics: bool,
+
+    #[cfg(feature = "warp-filter")]
+    strict_mode: bool,
+
+    #[cfg(feature = "warp-filter")]
+    streaming: bool,
+
+    #[cfg(feature = "warp-filter")]
+    render_pool: Option<crate::render_pool::RenderPool>,
+
+    #[cfg(feature = "warp-filter")]
+    render_cache: Option<crate::render_cache::RenderCache>,
+
+    #[cfg(feature = "warp-filter")]
+    client_ip_header: Option<String>,
+
+    #[cfg(feature = "warp-filter")]
+    cors: Option<stackable_core::server::CorsConfig>,
+
+    #[cfg(feature = "warp-filter")]
+    hydration: stackable_core::hydration::HydrationConfig,
+
+    #[cfg(feature = "warp-filter")]
+    fallback: stackable_core::server::FallbackConfig,
+
+    #[cfg(feature = "warp-filter")]
+    csrf: bool,
+
+    #[cfg(feature = "warp-filter")]
+    render_limit: Option<crate::limits::ConcurrencyLimiter>,
+
+    #[cfg(feature = "warp-filter")]
+    bridge_limit: Option<crate::limits::ConcurrencyLimiter>,
+
+    #[cfg(feature = "warp-filter")]
+    rate_limiter: Option<crate::rate_limit::RateLimiter>,
+
+    #[cfg(feature = "warp-filter")]
+    bridge_path: Option<String>,
+
+    #[cfg(feature = "warp-filter")]
+    refresh_path: String,
+
+    #[cfg(feature = "warp-filter")]
+    allowed_origins: Option<std::sync::Arc<[String]>>,
+
+    #[cfg(feature = "warp-filter")]
+    session_store: Option<std::sync::Arc<dyn crate::session::SessionStore>>,
+
+    #[cfg(feature = "warp-filter")]
+    session_config: stackable_core::server::SessionConfig,
+
+    #[cfg(feature = "warp-filter")]
+    session_secret: Option<std::sync::Arc<[u8]>>,
+
+    #[cfg(feature = "warp-filter")]
+    state_secret: Option<std::sync::Arc<[u8]>>,
+
+    #[cfg(feature = "warp-filter")]
+    state_compression: Option<stackable_core::compression::StateCompressionConfig>,
+
+    #[cfg(feature = "warp-filter")]
+    metrics: Option<crate::metrics::Metrics>,
+
+    #[cfg(feature = "warp-filter")]
+    metrics_path: String,
+
+    #[cfg(feature = "warp-filter")]
+    admin: Option<stackable_core::server::AdminConfig>,
+
+    #[cfg(feature = "warp-filter")]
+    app_state: stackable_core::app_state::AppState,
+
+    #[cfg(feature = "warp-filter")]
+    mocks: Option<stackable_core::mock::Mocks>,
+
+    #[cfg(feature = "warp-filter")]
+    recorder: Option<std::sync::Arc<dyn stackable_bridge::recorder::BridgeRecorder>>,
+
+    #[cfg(feature = "warp-filter")]
+    dashboard: Option<crate::dashboard::Dashboard>,
+
+    #[cfg(feature = "warp-filter")]
+    guards: Vec<crate::guard::Guard>,
+
+    #[cfg(feature = "tower-service")]
+    layers: Vec<BoxLayer>,
+
+    #[cfg(feature = "tower-service")]
+    vhosts: std::collections::BTreeMap<String, stackable_core::server::VirtualHost>,
+
+    #[cfg(feature = "tower-service")]
+    host_aliases: Vec<(String, String)>,
+
     _marker: PhantomData<COMP>,
 }
 
@@ -73,6 +182,72 @@ where
             frontend: None,
             #[cfg(feature = "warp-filter")]
             auto_refresh: false,
+            #[cfg(feature = "warp-filter")]
+            error_page: crate::ErrorPage::default(),
+            #[cfg(feature = "warp-filter")]
+            diagnostics: false,
+            #[cfg(feature = "warp-filter")]
+            strict_mode: false,
+            #[cfg(feature = "warp-filter")]
+            streaming: false,
+            #[cfg(feature = "warp-filter")]
+            render_pool: None,
+            #[cfg(feature = "warp-filter")]
+            render_cache: None,
+            #[cfg(feature = "warp-filter")]
+            client_ip_header: None,
+            #[cfg(feature = "warp-filter")]
+            cors: None,
+            #[cfg(feature = "warp-filter")]
+            hydration: Default::default(),
+            #[cfg(feature = "warp-filter")]
+            fallback: Default::default(),
+            #[cfg(feature = "warp-filter")]
+            csrf: true,
+            #[cfg(feature = "warp-filter")]
+            render_limit: None,
+            #[cfg(feature = "warp-filter")]
+            bridge_limit: None,
+            #[cfg(feature = "warp-filter")]
+            rate_limiter: None,
+            #[cfg(feature = "warp-filter")]
+            bridge_path: None,
+            #[cfg(feature = "warp-filter")]
+            refresh_path: "/_refresh".to_string(),
+            #[cfg(feature = "warp-filter")]
+            allowed_origins: None,
+            #[cfg(feature = "warp-filter")]
+            session_store: None,
+            #[cfg(feature = "warp-filter")]
+            session_config: Default::default(),
+            #[cfg(feature = "warp-filter")]
+            session_secret: None,
+            #[cfg(feature = "warp-filter")]
+            state_secret: None,
+            #[cfg(feature = "warp-filter")]
+            state_compression: None,
+            #[cfg(feature = "warp-filter")]
+            metrics: None,
+            #[cfg(feature = "warp-filter")]
+            metrics_path: "/__stackable/metrics".to_string(),
+            #[cfg(feature = "warp-filter")]
+            admin: None,
+            #[cfg(feature = "warp-filter")]
+            app_state: Default::default(),
+            #[cfg(feature = "warp-filter")]
+            mocks: None,
+            #[cfg(feature = "warp-filter")]
+            recorder: None,
+            #[cfg(feature = "warp-filter")]
+            dashboard: None,
+            #[cfg(feature = "warp-filter")]
+            guards: Vec::new(),
+            #[cfg(feature = "tower-service")]
+            layers: Vec::new(),
+            #[cfg(feature = "tower-service")]
+            vhosts: Default::default(),
+            #[cfg(feature = "tower-service")]
+            host_aliases: Vec::new(),
             _marker: PhantomData,
         }
     }
@@ -94,6 +269,72 @@ where
             frontend: self.frontend,
             #[cfg(feature = "warp-filter")]
             auto_refresh: self.auto_refresh,
+            #[cfg(feature = "warp-filter")]
+            error_page: self.error_page,
+            #[cfg(feature = "warp-filter")]
+            diagnostics: self.diagnostics,
+            #[cfg(feature = "warp-filter")]
+            strict_mode: self.strict_mode,
+            #[cfg(feature = "warp-filter")]
+            streaming: self.streaming,
+            #[cfg(feature = "warp-filter")]
+            render_pool: self.render_pool,
+            #[cfg(feature = "warp-filter")]
+            render_cache: self.render_cache,
+            #[cfg(feature = "warp-filter")]
+            client_ip_header: self.client_ip_header,
+            #[cfg(feature = "warp-filter")]
+            cors: self.cors,
+            #[cfg(feature = "warp-filter")]
+            hydration: self.hydration,
+            #[cfg(feature = "warp-filter")]
+            fallback: self.fallback,
+            #[cfg(feature = "warp-filter")]
+            csrf: self.csrf,
+            #[cfg(feature = "warp-filter")]
+            render_limit: self.render_limit,
+            #[cfg(feature = "warp-filter")]
+            bridge_limit: self.bridge_limit,
+            #[cfg(feature = "warp-filter")]
+            rate_limiter: self.rate_limiter,
+            #[cfg(feature = "warp-filter")]
+            bridge_path: self.bridge_path,
+            #[cfg(feature = "warp-filter")]
+            refresh_path: self.refresh_path,
+            #[cfg(feature = "warp-filter")]
+            allowed_origins: self.allowed_origins,
+            #[cfg(feature = "warp-filter")]
+            session_store: self.session_store,
+            #[cfg(feature = "warp-filter")]
+            session_config: self.session_config,
+            #[cfg(feature = "warp-filter")]
+            session_secret: self.session_secret,
+            #[cfg(feature = "warp-filter")]
+            state_secret: self.state_secret,
+            #[cfg(feature = "warp-filter")]
+            state_compression: self.state_compression,
+            #[cfg(feature = "warp-filter")]
+            metrics: self.metrics,
+            #[cfg(feature = "warp-filter")]
+            metrics_path: self.metrics_path,
+            #[cfg(feature = "warp-filter")]
+            admin: self.admin,
+            #[cfg(feature = "warp-filter")]
+            app_state: self.app_state,
+            #[cfg(feature = "warp-filter")]
+            mocks: self.mocks,
+            #[cfg(feature = "warp-filter")]
+            recorder: self.recorder,
+            #[cfg(feature = "warp-filter")]
+            dashboard: self.dashboard,
+            #[cfg(feature = "warp-filter")]
+            guards: self.guards,
+            #[cfg(feature = "tower-service")]
+            layers: self.layers,
+            #[cfg(feature = "tower-service")]
+            vhosts: self.vhosts,
+            #[cfg(feature = "tower-service")]
+            host_aliases: self.host_aliases,
             _marker: PhantomData,
         }
     }
@@ -118,6 +359,72 @@ where
             frontend: self.frontend,
             #[cfg(feature = "warp-filter")]
             auto_refresh: self.auto_refresh,
+            #[cfg(feature = "warp-filter")]
+            error_page: self.error_page,
+            #[cfg(feature = "warp-filter")]
+            diagnostics: self.diagnostics,
+            #[cfg(feature = "warp-filter")]
+            strict_mode: self.strict_mode,
+            #[cfg(feature = "warp-filter")]
+            streaming: self.streaming,
+            #[cfg(feature = "warp-filter")]
+            render_pool: self.render_pool,
+            #[cfg(feature = "warp-filter")]
+            render_cache: self.render_cache,
+            #[cfg(feature = "warp-filter")]
+            client_ip_header: self.client_ip_header,
+            #[cfg(feature = "warp-filter")]
+            cors: self.cors,
+            #[cfg(feature = "warp-filter")]
+            hydration: self.hydration,
+            #[cfg(feature = "warp-filter")]
+            fallback: self.fallback,
+            #[cfg(feature = "warp-filter")]
+            csrf: self.csrf,
+            #[cfg(feature = "warp-filter")]
+            render_limit: self.render_limit,
+            #[cfg(feature = "warp-filter")]
+            bridge_limit: self.bridge_limit,
+            #[cfg(feature = "warp-filter")]
+            rate_limiter: self.rate_limiter,
+            #[cfg(feature = "warp-filter")]
+            bridge_path: self.bridge_path,
+            #[cfg(feature = "warp-filter")]
+            refresh_path: self.refresh_path,
+            #[cfg(feature = "warp-filter")]
+            allowed_origins: self.allowed_origins,
+            #[cfg(feature = "warp-filter")]
+            session_store: self.session_store,
+            #[cfg(feature = "warp-filter")]
+            session_config: self.session_config,
+            #[cfg(feature = "warp-filter")]
+            session_secret: self.session_secret,
+            #[cfg(feature = "warp-filter")]
+            state_secret: self.state_secret,
+            #[cfg(feature = "warp-filter")]
+            state_compression: self.state_compression,
+            #[cfg(feature = "warp-filter")]
+            metrics: self.metrics,
+            #[cfg(feature = "warp-filter")]
+            metrics_path: self.metrics_path,
+            #[cfg(feature = "warp-filter")]
+            admin: self.admin,
+            #[cfg(feature = "warp-filter")]
+            app_state: self.app_state,
+            #[cfg(feature = "warp-filter")]
+            mocks: self.mocks,
+            #[cfg(feature = "warp-filter")]
+            recorder: self.recorder,
+            #[cfg(feature = "warp-filter")]
+            dashboard: self.dashboard,
+            #[cfg(feature = "warp-filter")]
+            guards: self.guards,
+            #[cfg(feature = "tower-service")]
+            layers: self.layers,
+            #[cfg(feature = "tower-service")]
+            vhosts: self.vhosts,
+            #[cfg(feature = "tower-service")]
+            host_aliases: self.host_aliases,
             _marker: PhantomData,
         }
     }
@@ -126,49 +433,169 @@ where
         self.bridge = Some(bridge);
         self
     }
+
+    /// Returns the types registered with the bridge of this endpoint.
+    pub fn bridge_contract(&self) -> BridgeContract {
+        self.bridge
+            .as_ref()
+            .map(|m| m.contract().clone())
+            .unwrap_or_default()
+    }
 }
 
 #[cfg(feature = "warp-filter")]
 mod feat_warp_filter {
+    use std::convert::Infallible;
     use std::fmt::Write;
     use std::future::Future;
+    use std::net::SocketAddr;
+    use std::panic::AssertUnwindSafe;
     use std::rc::Rc;
+    use std::sync::Arc;
+    use std::time::{Duration, Instant};
 
-    use bounce::helmet::render_static;
+    use bounce::helmet::{render_static, HelmetTag};
     use bytes::Bytes;
     use futures::{SinkExt, StreamExt, TryFutureExt};
+    use http::header::{
+        HeaderMap, HeaderValue, CACHE_CONTROL, CONTENT_TYPE, LINK, LOCATION, SET_COOKIE,
+    };
     use http::status::StatusCode;
     use once_cell::sync::Lazy;
+    use serde::Deserialize;
+    use stackable_bridge::hydration::HydrationData;
+    use stackable_bridge::profiler::RenderProfile;
+    use stackable_bridge::recorder::BridgeRecorder;
+    use stackable_bridge::request::RequestContext;
+    use stackable_bridge::response::ResponseControl;
     use stackable_bridge::{BridgeError, BridgeMetadata};
-    use tokio::sync::oneshot as sync_oneshot;
+    use stackable_core::app_state::AppState;
+    use stackable_core::build_info::BuildInfo;
+    use stackable_core::compression::StateCompressionConfig;
+    use stackable_core::dev::{strict_mode_difference, STRICT_MODE_META_NAME};
+    use stackable_core::frontend::{DEGRADED_ATTR_NAME, DEGRADED_EVENT_NAME, READY_ATTR_NAME};
+    use stackable_core::hydration::{HydrationConfig, HYDRATION_ELEMENT_ID};
+    use stackable_core::images::{ImageManifest, IMAGES_ELEMENT_ID};
+    use stackable_core::locales::{
+        LocaleManifest, LOCALES_ELEMENT_ID, LOCALE_COOKIE_NAME, LOCALE_META_NAME,
+    };
+    use stackable_core::mock::Mocks;
+    use stackable_core::observability::TRACEPARENT_HEADER;
+    use stackable_core::pwa::{NOOP_SERVICE_WORKER, SERVICE_WORKER_FILE_NAME};
+    use stackable_core::render_cache::RenderCacheControl;
+    use stackable_core::server::{
+        AdminConfig, ConcurrencyLimit, CorsConfig, FallbackConfig, FallbackMode, RateLimitConfig,
+        RenderConfig, RequestLimits, SessionConfig,
+    };
+    use stackable_core::session::Session;
+    use tokio::sync::{mpsc, oneshot as sync_oneshot};
+    use tracing::Instrument;
     use warp::body::bytes;
+    use warp::hyper::Body;
     use warp::path::FullPath;
     use warp::reject::not_found;
     use warp::reply::Response;
     use warp::ws::{Message, Ws};
     use warp::{header, log, reply, Filter, Rejection, Reply};
-    use yew::platform::{LocalHandle, Runtime};
+    use yew::platform::{spawn_local, LocalHandle, Runtime};
 
     use super::*;
+    use crate::dashboard::{Dashboard, DASHBOARD_PATH};
+    use crate::error::IndexHtmlError;
+    use crate::error_page::{install_panic_hook, ErrorReport};
+    use crate::guard::{guard, Guard};
+    use crate::limits::{limit, ConcurrencyLimiter};
+    use crate::metrics::{EndpointMetrics, Metrics};
+    use crate::prepared_state::{complete_len, process_prepared_states, StateProcessor};
+    use crate::rate_limit::{rate_limit, RateLimiter};
+    use crate::render_cache::RenderCache;
+    use crate::render_pool::RenderPool;
     use crate::root::{StackableRoot, StackableRootProps};
-    use crate::utils::random_str;
-    use crate::Frontend;
+    use crate::session::{SessionManager, SessionStore};
+    use crate::utils::{client_ip, escape_html, random_str, random_str_with_len};
+    use crate::{ErrorPage, Frontend};
 
     // A server id that is different every time it starts.
     static SERVER_ID: Lazy<String> = Lazy::new(random_str);
 
-    static AUTO_REFRESH_SCRIPT: Lazy<String> = Lazy::new(|| {
+    /// A message that the auto refresh script sends besides the server id.
+    #[derive(Debug, Deserialize)]
+    #[serde(tag = "type", rename_all = "kebab-case")]
+    enum RefreshMessage {
+        /// A message of the browser console, or an uncaught error.
+        Console { level: String, message: String },
+    }
+
+    impl RefreshMessage {
+        fn log(&self) {
+            match self {
+                Self::Console { level, message } => match level.as_str() {
+                    "error" => tracing::error!(target: "browser", "{}", message),
+                    "warn" => tracing::warn!(target: "browser", "{}", message),
+                    "debug" => tracing::debug!(target: "browser", "{}", message),
+                    _ => tracing::info!(target: "browser", "{}", message),
+                },
+            }
+        }
+    }
+
+    fn auto_refresh_script(refresh_path: &str) -> String {
         format!(
             r#"
 <script>
     (() => {{
         const protocol = window.location.protocol === 'https' ? 'wss' : 'ws';
-        const wsUrl = `${{protocol}}://${{window.location.host}}/_refresh`;
+        const wsUrl = `${{protocol}}://${{window.location.host}}{}`;
         const serverId = '{}';
 
+        // Messages of the browser console are forwarded to the terminal of stackctl.
+        const pending = [];
+        let openWs = null;
+        const forward = (level, args) => {{
+            const message = args.map((m) => {{
+                if (m instanceof Error) {{
+                    return m.stack || String(m);
+                }}
+                if (typeof m === 'string') {{
+                    return m;
+                }}
+                try {{
+                    return JSON.stringify(m);
+                }} catch(e) {{
+                    return String(m);
+                }}
+            }}).join(' ');
+            const data = JSON.stringify({{ type: 'console', level, message }});
+
+            if (openWs) {{
+                openWs.send(data);
+            }} else if (pending.length < 100) {{
+                pending.push(data);
+            }}
+        }};
+        for (const level of ['debug', 'log', 'info', 'warn', 'error']) {{
+            const original = console[level];
+            console[level] = (...args) => {{
+                try {{
+                    forward(level, args);
+                }} catch(e) {{
+                    // do nothing if errored.
+                }}
+                original.apply(console, args);
+            }};
+        }}
+        window.addEventListener('error', (e) => {{
+            forward('error', [e.error || e.message]);
+        }});
+        window.addEventListener('unhandledrejection', (e) => {{
+            forward('error', ['Unhandled rejection:', e.reason]);
+        }});
+
         const connectWs = () => {{
             const ws = new WebSocket(wsUrl);
             ws.addEventListener('open', () => {{
+                openWs = ws;
+                pending.splice(0).forEach((m) => ws.send(m));
                 const invId = setInterval(() => {{
                     try {{
                         ws.send(serverId);
@@ -181,6 +608,7 @@ mod feat_warp_filter {
                 }});
             }});
             ws.addEventListener('close', () => {{
+                openWs = null;
                 setTimeout(connectWs, 1000);
             }});
             ws.addEventListener('message', (e) => {{
@@ -193,175 +621,1460 @@ mod feat_warp_filter {
         connectWs();
     }})();
 </script>"#,
+            refresh_path,
             SERVER_ID.as_str()
         )
-    });
+    }
 
-    impl<COMP, CTX, BCTX> Endpoint<COMP, CTX, BCTX>
-    where
-        COMP: BaseComponent<Properties = ServerAppProps<CTX>>,
-        CTX: 'static,
-        BCTX: 'static,
-    {
-        pub fn with_auto_refresh(mut self) -> Self {
-            self.auto_refresh = true;
+    /// A script that marks the page as degraded if the frontend fails to load.
+    ///
+    /// Server-rendered links and `GET` forms keep working without the frontend. Pages are only
+    /// served to `GET` requests, so forms that post do not work unless the application serves
+    /// their action. The script lets applications show a notice, it is written without modern
+    /// syntax, as it targets browsers that cannot run the frontend.
+    fn degraded_fallback_script() -> String {
+        format!(
+            r#"
+<script>
+    (function () {{
+        var root = document.documentElement;
+        var degrade = function (reason) {{
+            if (window.stackableDegraded || root.hasAttribute('{READY_ATTR_NAME}')) {{
+                return;
+            }}
+            window.stackableDegraded = {{ reason: reason }};
+            root.setAttribute('{DEGRADED_ATTR_NAME}', '');
+
+            var event;
+            try {{
+                event = new CustomEvent('{DEGRADED_EVENT_NAME}', {{ detail: {{ reason: reason }} }});
+            }} catch (e) {{
+                event = document.createEvent('CustomEvent');
+                event.initCustomEvent('{DEGRADED_EVENT_NAME}', false, false, {{ reason: reason }});
+            }}
+            window.dispatchEvent(event);
+        }};
 
-            self
-        }
+        if (typeof WebAssembly !== 'object' || !('noModule' in document.createElement('script'))) {{
+            // Dispatched after the page is parsed, so listeners in the body receive it.
+            document.addEventListener('DOMContentLoaded', function () {{
+                degrade('unsupported');
+            }});
+            return;
+        }}
+
+        // Failed scripts and preloads do not bubble, they are only seen while capturing.
+        window.addEventListener('error', function (e) {{
+            var target = e.target;
+            if (!target || (target.tagName !== 'SCRIPT' && target.tagName !== 'LINK')) {{
+                return;
+            }}
+            var src = target.src || target.href || '';
+            if (target.type === 'module' || /\.(js|wasm)(\?|#|$)/.test(src)) {{
+                degrade('load');
+            }}
+        }}, true);
+
+        // The frontend is the only promise before it has started, e.g.: a failed instantiation.
+        window.addEventListener('unhandledrejection', function () {{
+            degrade('instantiate');
+        }});
+    }})();
+</script>"#
+        )
+    }
 
-        fn create_index_filter(
-            &self,
-        ) -> Option<
-            impl Clone
-                + Send
-                + Filter<
-                    Extract = (Response,),
-                    Error = Rejection,
-                    Future = impl Future<Output = Result<(Response,), Rejection>>,
-                >,
-        > {
-            let index_html = self.frontend.as_ref()?.index_html();
-            let affix_context = self.affix_context.clone();
-            let bridge = self.bridge.clone().unwrap_or_default();
-            let auto_refresh = self.auto_refresh;
-            let affix_bridge_context = self.affix_bridge_context.clone();
+    /// The locale manifest, read by the frontend before it loads translation files.
+    fn locales_script(locales: &LocaleManifest) -> String {
+        let json = locales
+            .to_json()
+            .expect("failed to serialise locales")
+            .replace("</", "<\\/");
 
-            let create_render_inner = move |props, tx: sync_oneshot::Sender<String>| async move {
-                let props = (affix_context.get())(props).await;
-                let bridge_metadata =
-                    Rc::new((affix_bridge_context.get())(BridgeMetadata::new()).await);
+        format!(r#"<script type="application/json" id="{LOCALES_ELEMENT_ID}">{json}</script>"#)
+    }
 
-                let mut head_s = String::new();
-                let mut body_s = String::new();
-                let mut helmet_tags = Vec::new();
+    fn images_script(images: &ImageManifest) -> String {
+        let json = images
+            .to_json()
+            .expect("failed to serialise images")
+            .replace("</", "<\\/");
 
-                if !props.is_client_only() {
-                    let (reader, writer) = render_static();
+        format!(r#"<script type="application/json" id="{IMAGES_ELEMENT_ID}">{json}</script>"#)
+    }
 
-                    body_s =
-                        yew::LocalServerRenderer::<StackableRoot<COMP, CTX, BCTX>>::with_props(
-                            StackableRootProps {
-                                server_app_props: props,
-                                helmet_writer: writer,
-                                bridge,
-                                bridge_metadata,
-                            },
-                        )
-                        .render()
-                        .await;
+    /// Replaced by the body of streamed pages.
+    const STREAM_MARKER: &str = "<!--%STACKABLE_STREAM%-->";
+
+    /// Returns the helmet tags of a streamed page, which a script moves into the head.
+    ///
+    /// The head is sent before components are rendered, so their tags are sent after the body.
+    /// Attributes of `<html>` and `<body>` are applied by the helmet after hydration.
+    fn streamed_head_tags(tags: Vec<HelmetTag>) -> String {
+        let mut s = r#"<template id="stackable-streamed-head">"#.to_string();
+        for tag in tags {
+            if !matches!(tag, HelmetTag::Html { .. } | HelmetTag::Body { .. }) {
+                let _ = tag.write_static(&mut s);
+            }
+        }
+        s.push_str(
+            r#"</template>
+<script>
+    (() => {
+        const m = document.getElementById('stackable-streamed-head');
+        if (m.content.querySelector('title')) {
+            document.querySelectorAll('head > title').forEach((e) => e.remove());
+        }
+        document.head.append(m.content);
+        m.remove();
+    })();
+</script>"#,
+        );
 
-                    helmet_tags = reader.render().await;
-                    let _ = write!(
-                        &mut head_s,
-                        r#"<meta name="stackable-mode" content="hydrate">"#
-                    );
-                }
+        s
+    }
 
-                // With development server, we read index.html every time.
-                if auto_refresh {
-                    body_s.push_str(AUTO_REFRESH_SCRIPT.as_str());
-                }
+    /// Returns the element that carries the hydration data of a page, if there is any.
+    fn hydration_script(data: &HydrationData, warn_size: usize, path: &str) -> Option<String> {
+        if data.is_empty() {
+            return None;
+        }
 
-                let s = index_html.render(helmet_tags, head_s, body_s).await;
-                let _ = tx.send(s);
-            };
+        let json = data
+            .to_json()
+            .expect("failed to serialise hydration data")
+            .replace("</", "<\\/");
+
+        if json.len() > warn_size {
+            let largest = data
+                .sizes()
+                .into_iter()
+                .take(3)
+                .map(|(k, v)| format!("{k} ({v} bytes)"))
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            tracing::warn!(
+                "hydration data of {} is {} bytes, over the limit of {} bytes, largest values: {}",
+                path,
+                json.len(),
+                warn_size,
+                largest
+            );
+        }
 
-            let render_html = move |props| async move {
-                let (tx, rx) = sync_oneshot::channel::<String>();
+        Some(format!(
+            r#"<script type="application/json" id="{HYDRATION_ELEMENT_ID}">{json}</script>"#
+        ))
+    }
 
-                // We spawn into a local runtime early for higher efficiency.
-                match LocalHandle::try_current() {
-                    Some(handle) => handle.spawn_local(create_render_inner(props, tx)),
-                    // TODO: Allow Overriding Runtime with Endpoint.
-                    None => Runtime::default().spawn_pinned(move || create_render_inner(props, tx)),
-                }
+    /// A script that shows the size of prepared states in the development server.
+    fn state_size_overlay(sizes: &[(usize, usize)]) -> String {
+        let original_len: usize = sizes.iter().map(|m| m.0).sum();
+        let compressed_len: usize = sizes.iter().map(|m| m.1).sum();
 
-                warp::reply::html(rx.await.expect("renderer panicked?"))
-            };
+        format!(
+            r#"
+<script>
+    (() => {{
+        const message = 'State: {} payload(s), {:.1} KiB -> {:.1} KiB';
+        console.info(message);
+
+        const overlay = document.createElement('div');
+        overlay.textContent = message;
+        overlay.style.cssText = 'position: fixed; bottom: 8px; right: 8px; z-index: 2147483647;'
+            + 'padding: 4px 8px; background: rgba(0, 0, 0, 0.7); color: #fff;'
+            + 'font: 12px monospace; border-radius: 4px; pointer-events: none;';
+        document.documentElement.appendChild(overlay);
+    }})();
+</script>"#,
+            sizes.len(),
+            original_len as f64 / 1024.0,
+            compressed_len as f64 / 1024.0,
+        )
+    }
 
-            let f = warp::get()
-                .and(warp::path::full())
-                .and(
-                    warp::query::raw()
-                        .or_else(|_| async move { Ok::<_, Rejection>((String::new(),)) }),
+    /// A script that shows the latency and error rates of bridge endpoints in the development
+    /// server.
+    fn bridge_metrics_overlay(snapshot: &[(&str, EndpointMetrics)]) -> String {
+        let rows = snapshot
+            .iter()
+            .map(|(name, m)| {
+                let p95 = match m.quantile_upper_bound(0.95) {
+                    Some(m) => format!("<= {:.0}ms", m * 1000.0),
+                    None => "> 10s".to_string(),
+                };
+
+                format!(
+                    "<tr><td>{}</td><td>{}</td><td>{}</td><td>{:.1}ms</td><td>{}</td></tr>",
+                    // Type names only contain path characters, angle brackets are escaped for
+                    // generic types.
+                    name.replace('<', "&lt;").replace('>', "&gt;"),
+                    m.requests,
+                    m.errors,
+                    m.mean_duration().as_secs_f64() * 1000.0,
+                    p95
                 )
-                .then(move |path: FullPath, raw_queries| {
-                    let props = ServerAppProps::from_warp_request(path, raw_queries);
-                    let render_html = render_html.clone();
+            })
+            .collect::<String>();
 
-                    async move { render_html(props).await.into_response() }
-                });
+        format!(
+            r#"
+<script>
+    (() => {{
+        const overlay = document.createElement('div');
+        overlay.innerHTML = '<table><tr><th>Bridge</th><th>Requests</th><th>Errors</th>'
+            + '<th>Mean</th><th>P95</th></tr>{}</table>';
+        overlay.style.cssText = 'position: fixed; bottom: 8px; left: 8px; z-index: 2147483647;'
+            + 'padding: 4px 8px; background: rgba(0, 0, 0, 0.7); color: #fff; text-align: left;'
+            + 'font: 12px monospace; border-radius: 4px; pointer-events: none;';
+        document.documentElement.appendChild(overlay);
+    }})();
+</script>"#,
+            rows.replace('\'', "\\'")
+        )
+    }
 
-            Some(f)
+    /// A script that shows where two renders of a page differ in strict mode.
+    fn strict_mode_overlay(first: &str, second: &str) -> String {
+        let message = format!(
+            "Strict mode: the page rendered differently on the second render.\n\
+             First:  {first}\nSecond: {second}"
+        );
+        // Escapes `</` so the message cannot close the script element.
+        let message = serde_json::to_string(&message)
+            .unwrap_or_default()
+            .replace("</", "<\\/");
+
+        format!(
+            r#"
+<script>
+    (() => {{
+        const message = {message};
+        console.error(message);
+
+        const overlay = document.createElement('div');
+        overlay.textContent = message;
+        overlay.style.cssText = 'position: fixed; top: 8px; right: 8px; z-index: 2147483647;'
+            + 'padding: 4px 8px; background: rgba(160, 0, 0, 0.85); color: #fff;'
+            + 'font: 12px monospace; border-radius: 4px; white-space: pre-wrap;'
+            + 'max-width: 640px; pointer-events: none;';
+        document.documentElement.appendChild(overlay);
+    }})();
+</script>"#
+        )
+    }
+
+    /// A script that shows the slowest profiled components of server-side rendering and offers
+    /// the profile as folded stacks for flamegraph tools.
+    fn render_profile_overlay(profile: &RenderProfile) -> String {
+        let rows = profile
+            .slowest(5)
+            .into_iter()
+            .map(|(stack, duration)| {
+                format!(
+                    "{:.1}ms {}",
+                    duration.as_secs_f64() * 1000.0,
+                    stack.rsplit(';').next().unwrap_or_default()
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        // Escapes `</` so the values cannot close the script element.
+        let to_js = |s: &str| {
+            serde_json::to_string(s)
+                .unwrap_or_default()
+                .replace("</", "<\\/")
+        };
+
+        format!(
+            r#"
+<script>
+    (() => {{
+        const rows = {};
+        const folded = {};
+
+        const overlay = document.createElement('div');
+        overlay.textContent = 'SSR render\n' + rows + '\n';
+        overlay.style.cssText = 'position: fixed; bottom: 40px; right: 8px; z-index: 2147483647;'
+            + 'padding: 4px 8px; background: rgba(0, 0, 0, 0.7); color: #fff;'
+            + 'font: 12px monospace; border-radius: 4px; white-space: pre;';
+
+        const link = document.createElement('a');
+        link.textContent = 'Download flamegraph (folded stacks)';
+        link.href = URL.createObjectURL(new Blob([folded], {{ type: 'text/plain' }}));
+        link.download = 'ssr-profile-' + Date.now() + '.folded';
+        link.style.color = '#9cf';
+        overlay.appendChild(link);
+
+        document.documentElement.appendChild(overlay);
+    }})();
+</script>"#,
+            to_js(&rows),
+            to_js(&profile.to_folded()),
+        )
+    }
+
+    /// Normalises a path to start with `/` and not end with `/`.
+    fn normalise_path(path: &str) -> String {
+        format!("/{}", path.trim_matches('/'))
+    }
+
+    /// A filter that matches the request path exactly.
+    fn exact_path(path: &str) -> impl Clone + Send + Filter<Extract = (), Error = Rejection> {
+        let path = normalise_path(path);
+
+        warp::path::full()
+            .and_then(move |m: FullPath| {
+                let matched = normalise_path(m.as_str()) == path;
+                async move {
+                    match matched {
+                        true => Ok(()),
+                        false => Err(not_found()),
+                    }
+                }
+            })
+            .untuple_one()
+    }
+
+    /// A filter that rejects paths with any of the prefixes.
+    fn reject_prefixes(
+        prefixes: Arc<[String]>,
+    ) -> impl Clone + Send + Filter<Extract = (), Error = Rejection> {
+        warp::path::full()
+            .and_then(move |m: FullPath| {
+                let matched = prefixes
+                    .iter()
+                    .any(|prefix| m.as_str().starts_with(prefix.as_str()));
+                async move {
+                    match matched {
+                        true => Err(not_found()),
+                        false => Ok(()),
+                    }
+                }
+            })
+            .untuple_one()
+    }
+
+    /// Creates the metadata of a bridge request from the authorization header.
+    ///
+    /// Returns `None` if the header is not a bearer token.
+    fn bridge_metadata(token: Option<String>, app_state: AppState) -> Option<BridgeMetadata<()>> {
+        let mut meta = BridgeMetadata::<()>::new().with_app_state(app_state);
+
+        if let Some(m) = token {
+            if !m.starts_with("Bearer ") {
+                return None;
+            }
+
+            meta = meta.with_token(m.split_at(7).1);
+        }
+
+        Some(meta)
+    }
+
+    /// A filter that loads the session of a request, if sessions are enabled.
+    fn load_session(
+        manager: Option<SessionManager>,
+    ) -> impl Clone + Send + Filter<Extract = (Option<Session>,), Error = Infallible> {
+        header::optional::<String>("cookie").then(move |cookies: Option<String>| {
+            let manager = manager.clone();
+
+            async move {
+                match manager {
+                    Some(m) => Some(m.load(cookies.as_deref()).await),
+                    None => None,
+                }
+            }
+        })
+    }
+
+    /// Answers with the status that components have set, or with their redirect.
+    fn controlled_response(control: &ResponseControl, mut resp: Response) -> Response {
+        let status = match control.status().and_then(|m| StatusCode::from_u16(m).ok()) {
+            Some(m) => m,
+            None => return resp,
+        };
+
+        if let Some(location) = control.location() {
+            let mut redirect = Response::new(Body::empty());
+            *redirect.status_mut() = status;
+            match HeaderValue::from_str(&location) {
+                Ok(m) => {
+                    redirect.headers_mut().insert(LOCATION, m);
+                }
+                Err(_) => tracing::warn!("invalid redirect location: {}", location),
+            }
+
+            return redirect;
+        }
+
+        *resp.status_mut() = status;
+
+        resp
+    }
+
+    /// Collects the request for components.
+    ///
+    /// The client address is read from the header that a reverse proxy sets, if one is configured.
+    fn request_context(
+        headers: &HeaderMap,
+        remote_addr: Option<SocketAddr>,
+        client_ip_header: Option<&str>,
+        locale: Option<&str>,
+    ) -> RequestContext {
+        let mut request = RequestContext::new();
+
+        for (name, value) in headers.iter() {
+            if let Ok(m) = value.to_str() {
+                request = request.with_header(name.as_str(), m);
+            }
+        }
+
+        if let Some(m) = client_ip(headers, remote_addr, client_ip_header) {
+            request = request.with_client_ip(m);
+        }
+
+        if let Some(m) = locale {
+            request = request.with_locale(m);
+        }
+
+        request
+    }
+
+    /// Saves the session of a request after its response.
+    async fn save_session(
+        manager: Option<SessionManager>,
+        session: Option<Session>,
+        mut resp: Response,
+    ) -> Response {
+        if let (Some(manager), Some(session)) = (manager, session) {
+            manager.save(&session, &mut resp).await;
+        }
+
+        resp
+    }
+
+    fn bridge_error_reply(e: BridgeError) -> Response {
+        match e {
+            BridgeError::Encoding(_)
+            | BridgeError::Json(_)
+            | BridgeError::InvalidIndex(_)
+            | BridgeError::InvalidType(_) => {
+                reply::with_status("", StatusCode::BAD_REQUEST).into_response()
+            }
+            BridgeError::Aborted | BridgeError::OffsetMismatch(_) | BridgeError::ReloadRequired => {
+                reply::with_status("", StatusCode::CONFLICT).into_response()
+            }
+            BridgeError::TooLarge(_) => {
+                reply::with_status("", StatusCode::PAYLOAD_TOO_LARGE).into_response()
+            }
+            BridgeError::CsrfRejected => {
+                reply::with_status("", StatusCode::FORBIDDEN).into_response()
+            }
+            BridgeError::Network(_) => {
+                reply::with_status("", StatusCode::INTERNAL_SERVER_ERROR).into_response()
+            }
+        }
+    }
+
+    /// Creates the CORS filter of the bridge.
+    ///
+    /// Headers that the frontend sends to the bridge are always allowed.
+    fn bridge_cors(config: &CorsConfig) -> warp::cors::Builder {
+        let mut cors = warp::cors()
+            .allow_methods(config.methods.iter().map(|m| m.as_str()))
+            .allow_headers(
+                [
+                    "content-type",
+                    "accept",
+                    "authorization",
+                    Bridge::BUILD_ID_HEADER,
+                    Bridge::CSRF_HEADER,
+                    TRACEPARENT_HEADER,
+                ]
+                .into_iter()
+                .chain(config.headers.iter().map(|m| m.as_str())),
+            )
+            .expose_header(Bridge::RELOAD_REQUIRED_HEADER)
+            .allow_credentials(config.credentials);
+
+        cors = if config.origins.iter().any(|m| m == "*") {
+            cors.allow_any_origin()
+        } else {
+            cors.allow_origins(config.origins.iter().map(|m| m.as_str()))
+        };
+
+        if let Some(m) = config.max_age {
+            cors = cors.max_age(Duration::from_secs(m));
+        }
+
+        cors
+    }
+
+    /// Issues a CSRF token with a page, which the frontend copies into bridge mutations.
+    fn issue_csrf_token(resp: &mut Response) {
+        let cookie = format!(
+            "{}={}; Path=/; SameSite=Strict",
+            Bridge::CSRF_COOKIE,
+            random_str_with_len(32)
+        );
+
+        if let Ok(m) = HeaderValue::from_str(&cookie) {
+            resp.headers_mut().append(SET_COOKIE, m);
+        }
+    }
+
+    /// Returns `true` if the CSRF token in the header of a request matches its cookie.
+    fn is_csrf_verified(cookie: Option<&str>, header: Option<&str>) -> bool {
+        matches!((cookie, header), (Some(m), Some(n)) if !m.is_empty() && m == n)
+    }
+
+    /// Checks the origin of a request against the allow list.
+    ///
+    /// If no allow list is configured, all origins are allowed.
+    fn is_origin_allowed(allowed_origins: Option<&[String]>, origin: Option<&str>) -> bool {
+        let allowed_origins = match allowed_origins {
+            Some(m) => m,
+            None => return true,
+        };
+
+        match origin {
+            Some(origin) => allowed_origins
+                .iter()
+                .any(|m| m == "*" || m.trim_end_matches('/') == origin),
+            None => false,
+        }
+    }
+
+    impl<COMP, CTX, BCTX> Endpoint<COMP, CTX, BCTX>
+    where
+        COMP: BaseComponent<Properties = ServerAppProps<CTX>>,
+        CTX: 'static,
+        BCTX: 'static,
+    {
+        pub fn with_auto_refresh(mut self) -> Self {
+            self.auto_refresh = true;
+
+            self
+        }
+
+        /// Returns the paths that are served by the endpoint instead of pages, e.g.: the path
+        /// of the bridge.
+        pub fn api_routes(&self) -> Vec<String> {
+            let mut routes = Vec::new();
+
+            if let Some(ref m) = self.bridge {
+                routes.push(normalise_path(
+                    self.bridge_path.as_deref().unwrap_or(m.path()),
+                ));
+            }
+            if self.metrics.is_some() {
+                routes.push(normalise_path(&self.metrics_path));
+            }
+            if self.auto_refresh {
+                routes.push(normalise_path(&self.refresh_path));
+            }
+            if self.dashboard.is_some() {
+                routes.push(DASHBOARD_PATH.to_string());
+            }
+
+            routes
+        }
+
+        /// Sets the path of the websocket that notifies the browser to refresh.
+        ///
+        /// Defaults to `/_refresh`.
+        pub fn with_refresh_path<S>(mut self, path: S) -> Self
+        where
+            S: Into<String>,
+        {
+            self.refresh_path = path.into();
+
+            self
+        }
+
+        /// Sets the path that the bridge is mounted at.
+        ///
+        /// This overrides the path of the bridge, which is useful when the server is behind a
+        /// proxy that rewrites paths.
+        pub fn with_bridge_path<S>(mut self, path: S) -> Self
+        where
+            S: Into<String>,
+        {
+            self.bridge_path = Some(path.into());
+
+            self
+        }
+
+        /// Sets the store that sessions are persisted in.
+        pub fn with_session_store(mut self, store: Arc<dyn SessionStore>) -> Self {
+            self.session_store = Some(store);
+
+            self
+        }
+
+        /// Returns the store that sessions are persisted in, if any.
+        pub fn session_store(&self) -> Option<&Arc<dyn SessionStore>> {
+            self.session_store.as_ref()
+        }
+
+        /// Sets the cookie and the expiry of sessions.
+        pub fn with_session_config(mut self, config: &SessionConfig) -> Self {
+            self.session_config = config.clone();
+
+            self
+        }
+
+        /// Sets the secret that session cookies are encrypted with.
+        ///
+        /// Sessions are only enabled with a session store and a secret.
+        pub fn with_session_secret<S>(mut self, secret: S) -> Self
+        where
+            S: AsRef<[u8]>,
+        {
+            self.session_secret = Some(secret.as_ref().into());
+
+            self
+        }
+
+        fn session_manager(&self) -> Option<SessionManager> {
+            let store = self.session_store.clone()?;
+
+            match self.session_secret {
+                Some(ref m) => Some(SessionManager::new(store, &self.session_config, m)),
+                None => {
+                    tracing::warn!("sessions are disabled, as no session secret is set");
+                    None
+                }
+            }
+        }
+
+        /// Encrypts the state that components prepare during server-side rendering.
+        ///
+        /// A key is derived from the secret for each response and delivered to the browser in a
+        /// short-lived cookie, so the state embedded in the page cannot be read or modified.
+        pub fn with_state_encryption<S>(mut self, secret: S) -> Self
+        where
+            S: AsRef<[u8]>,
+        {
+            self.state_secret = Some(secret.as_ref().into());
+
+            self
+        }
+
+        /// Compresses the state that components prepare during server-side rendering.
+        ///
+        /// States smaller than the threshold of the configuration are left as is.
+        pub fn with_state_compression(mut self, config: StateCompressionConfig) -> Self {
+            self.state_compression = Some(config);
+
+            self
+        }
+
+        /// Collects request counts and latencies, render times and the latency and error rates
+        /// of bridge requests.
+        ///
+        /// Measurements are served in the Prometheus text format at the metrics path and shown in
+        /// an overlay when diagnostics are enabled.
+        pub fn with_metrics(mut self, metrics: Metrics) -> Self {
+            self.metrics = Some(metrics);
+
+            self
+        }
+
+        /// Sets the path that metrics are served at.
+        ///
+        /// Defaults to `/__stackable/metrics`.
+        pub fn with_metrics_path<S>(mut self, path: S) -> Self
+        where
+            S: Into<String>,
+        {
+            self.metrics_path = path.into();
+
+            self
+        }
+
+        /// Sets the values that are shared by all requests, read with `ServerAppProps::app_state`
+        /// and `BridgeMetadata::app_state`.
+        ///
+        /// [`AppStateBuilder`](crate::AppStateBuilder) initializes values asynchronously.
+        pub fn with_app_state(mut self, app_state: AppState) -> Self {
+            self.app_state = app_state;
+
+            self
+        }
+
+        /// Resolves queries and mutations of the bridge that match a mock with the mock, in
+        /// bridge requests and server-side rendering.
+        ///
+        /// This is set by [`Cli`](crate::Cli) for `stackctl serve --mock`.
+        pub fn with_mocks(mut self, mocks: Mocks) -> Self {
+            self.mocks = Some(mocks);
+
+            self
+        }
+
+        /// Sets the recorder that receives the results of queries and mutations of the bridge,
+        /// in bridge requests and server-side rendering.
+        ///
+        /// This is set by [`Cli`](crate::Cli) for `stackctl serve --record`.
+        pub fn with_recorder<R>(mut self, recorder: R) -> Self
+        where
+            R: 'static + BridgeRecorder,
+        {
+            self.recorder = Some(Arc::new(recorder));
+
+            self
+        }
+
+        /// Serves the dashboard of the development server at `/__stackable/`.
+        ///
+        /// This is set by [`Cli`](crate::Cli) for `stackctl serve`.
+        pub(crate) fn with_dashboard(mut self, dashboard: Dashboard) -> Self {
+            self.dashboard = Some(dashboard);
+
+            self
+        }
+
+        /// Runs a guard before requests under a path prefix are handled, e.g.: to check
+        /// authentication.
+        ///
+        /// The guard is passed the method, uri and headers of the request, and the address of
+        /// the client as a `SocketAddr` extension. Returning a response denies the request with it.
+        /// Guards are run in the order they are added.
+        pub fn with_guard<S, F, Fut>(mut self, prefix: S, guard: F) -> Self
+        where
+            S: Into<String>,
+            F: 'static + Send + Sync + Fn(http::Request<()>) -> Fut,
+            Fut: 'static + Send + Future<Output = Result<(), Response>>,
+        {
+            self.guards.push(Guard::new(prefix.into(), guard));
+
+            self
+        }
+
+        /// Sets the origins that are allowed to connect to websocket endpoints and the bridge.
+        ///
+        /// `"*"` allows any origin.
+        pub fn with_allowed_origins<I, S>(mut self, origins: I) -> Self
+        where
+            I: IntoIterator<Item = S>,
+            S: Into<String>,
+        {
+            self.allowed_origins = Some(origins.into_iter().map(|m| m.into()).collect());
+
+            self
+        }
+
+        fn create_index_filter(
+            &self,
+        ) -> Option<
+            impl Clone
+                + Send
+                + Filter<
+                    Extract = (Response,),
+                    Error = Rejection,
+                    Future = impl Future<Output = Result<(Response,), Rejection>>,
+                >,
+        > {
+            let index_htmls = self.frontend.as_ref()?.index_htmls();
+            // Panics of renders are reported with their backtraces.
+            install_panic_hook();
+            let affix_context = self.affix_context.clone();
+            let mut bridge = self.bridge.clone().unwrap_or_default();
+            if let Some(m) = self.mocks.clone() {
+                bridge = bridge.with_mocks(m);
+            }
+            if let Some(m) = self.recorder.clone() {
+                bridge = bridge.with_recorder(m);
+            }
+            let auto_refresh_script: Option<Arc<str>> = self
+                .auto_refresh
+                .then(|| auto_refresh_script(&normalise_path(&self.refresh_path)).into());
+            let affix_bridge_context = self.affix_bridge_context.clone();
+            let fallback_script: Arc<str> = degraded_fallback_script().into();
+            // The frontend reads the path of the bridge from the page, as it is not known when
+            // the frontend is built.
+            let bridge_path_meta: Option<Arc<str>> = self.bridge_path.as_deref().map(|m| {
+                format!(
+                    r#"<meta name="{}" content="{}">"#,
+                    Bridge::PATH_META_NAME,
+                    escape_html(&normalise_path(m))
+                )
+                .into()
+            });
+
+            let error_page = self.error_page.clone();
+            let dashboard = self.dashboard.clone();
+            let diagnostics = self.diagnostics;
+            let strict_mode = self.strict_mode;
+            let streaming = self.streaming;
+            let render_pool = self.render_pool.clone();
+            let state_secret = self.state_secret.clone();
+            let state_compression = self.state_compression.clone();
+            let metrics = self.metrics.clone().filter(|_| self.diagnostics);
+            let render_metrics = self.metrics.clone();
+            let session_manager = self.session_manager();
+            let render_cache = self.render_cache.clone();
+            let hydration = self.hydration.clone();
+            let client_ip_header = self.client_ip_header.clone();
+            let csrf = self.csrf;
+            let props_index_htmls = index_htmls.clone();
+            let props_app_state = self.app_state.clone();
+            let fallback_mode = self.fallback.mode;
+            let serve_stale = self
+                .render_limit
+                .as_ref()
+                .map(|m| m.serves_stale())
+                .unwrap_or(false);
+            let not_found_prefixes: Arc<[String]> = self.fallback.not_found_prefixes.clone().into();
+
+            let render_inner = move |props: ServerAppProps<()>| async move {
+                let path = props.path().to_string();
+                let session = props.session().cloned();
+                let response_control = ResponseControl::new();
+                let hydration_data = HydrationData::collect(hydration.format);
+                let render_cache_control = props.render_cache().cloned();
+                let app_state = props.app_state().clone();
+                let locales_script = props.locales().map(locales_script);
+                let images_script = props.images().map(images_script);
+                let html_attrs = props
+                    .locales()
+                    .zip(props.locale())
+                    .map(|(m, locale)| (locale.to_string(), m.html_attrs(locale)));
+                let props = (affix_context.get())(props).await;
+                // Spans of resolvers are children of the render, which belongs to the request.
+                let mut bridge_metadata = BridgeMetadata::new()
+                    .with_span(tracing::Span::current())
+                    .with_app_state(app_state);
+                if let Some(m) = session {
+                    bridge_metadata = bridge_metadata.with_session(m);
+                }
+                if let Some(m) = render_cache_control {
+                    bridge_metadata = bridge_metadata.with_render_cache(m);
+                }
+                let bridge_metadata = Rc::new((affix_bridge_context.get())(bridge_metadata).await);
+
+                // Added first, so it sees the frontend fail to load.
+                let mut head_s = fallback_script.to_string();
+                let mut state_cookie = None;
+
+                if let Some(ref m) = bridge_path_meta {
+                    head_s.push_str(m);
+                }
+
+                if let Some(ref m) = locales_script {
+                    head_s.push_str(m);
+                }
+                if let Some(ref m) = images_script {
+                    head_s.push_str(m);
+                }
+                if let Some((ref locale, _)) = html_attrs {
+                    let _ = write!(
+                        &mut head_s,
+                        r#"<meta name="{LOCALE_META_NAME}" content="{locale}">"#
+                    );
+                }
+
+                // Assets of the route are not referenced by index.html.
+                let preload = index_htmls.preload_manifest(&path);
+                if let Some(m) = preload {
+                    for asset in m.route_assets(&path) {
+                        let integrity = index_htmls.integrity(&path, &asset.href);
+                        head_s.push_str(&asset.to_link_tag_with_integrity(integrity));
+                    }
+                }
+
+                let index_html = index_htmls.select(&path);
+
+                // Strict mode compares complete renders, so pages are not streamed with it.
+                let mut resp = if streaming && !strict_mode && !props.is_client_only() {
+                    let (reader, writer) = render_static();
+                    let render_profile = diagnostics.then(RenderProfile::new);
+                    let mut states =
+                        StateProcessor::new(state_compression.as_ref(), state_secret.as_deref());
+
+                    let _ = write!(
+                        &mut head_s,
+                        r#"<meta name="stackable-mode" content="hydrate">"#
+                    );
+                    if let Some(ref m) = states {
+                        if let Some(ref meta) = m.meta {
+                            head_s.push_str(meta);
+                        }
+                        state_cookie = m.cookie.clone();
+                    }
+
+                    // The shell is rendered around a marker, which is replaced by the body as it
+                    // is rendered.
+                    let shell = index_html
+                        .render(Vec::new(), html_attrs.map(|m| m.1), head_s, STREAM_MARKER)
+                        .await?;
+                    let (shell_start, shell_end) = shell
+                        .split_once(STREAM_MARKER)
+                        .map(|(start, end)| (start.to_string(), end.to_string()))
+                        .ok_or(IndexHtmlError::MissingBody)?;
+
+                    let root =
+                        yew::LocalServerRenderer::<StackableRoot<COMP, CTX, BCTX>>::with_props(
+                            StackableRootProps {
+                                server_app_props: props,
+                                helmet_writer: writer,
+                                bridge,
+                                bridge_metadata,
+                                render_profile: render_profile.clone(),
+                                response_control: Some(response_control.clone()),
+                                hydration_data: hydration_data.clone(),
+                            },
+                        );
+                    let render_start = Instant::now();
+                    let mut chunks = Box::pin(root.render_stream());
+                    // Components before the first suspense have rendered, so the status is known.
+                    let first_chunk = chunks.next().await;
+                    let (mut sender, body) = Body::channel();
+                    let stream_path = path.clone();
+
+                    // Redirects are answered without the page, as are pages that are not found if
+                    // they are replaced.
+                    let replaced = fallback_mode != FallbackMode::Render
+                        && response_control.status() == Some(404);
+                    if response_control.location().is_none() && !replaced {
+                        spawn_local(
+                            async move {
+                                let stream_body = async {
+                                    let mut chunks =
+                                        futures::stream::iter(first_chunk).chain(chunks);
+
+                                    sender.send_data(shell_start.into()).await?;
+
+                                    let mut pending = String::new();
+                                    while let Some(chunk) = chunks.next().await {
+                                        pending.push_str(&chunk);
+                                        let rest = pending.split_off(complete_len(&pending));
+                                        let chunk = std::mem::replace(&mut pending, rest);
+                                        if chunk.is_empty() {
+                                            continue;
+                                        }
+
+                                        let chunk = match states {
+                                            Some(ref mut m) => m.process(&chunk),
+                                            None => chunk,
+                                        };
+                                        sender.send_data(chunk.into()).await?;
+                                    }
+
+                                    if let Some(ref m) = render_metrics {
+                                        m.record_render(render_start.elapsed());
+                                    }
+
+                                    // Data that is only known after rendering is appended at the end.
+                                    let mut tail = pending;
+                                    tail.push_str(&streamed_head_tags(reader.render().await));
+                                    if let Some(m) = hydration_script(
+                                        &hydration_data,
+                                        hydration.warn_size,
+                                        &stream_path,
+                                    ) {
+                                        tail.push_str(&m);
+                                    }
+
+                                    if let Some(m) =
+                                        states.filter(|m| diagnostics && !m.sizes.is_empty())
+                                    {
+                                        tail.push_str(&state_size_overlay(&m.sizes));
+                                    }
+                                    if let Some(snapshot) = metrics
+                                        .as_ref()
+                                        .map(|m| m.snapshot())
+                                        .filter(|m| !m.is_empty())
+                                    {
+                                        tail.push_str(&bridge_metrics_overlay(&snapshot));
+                                    }
+                                    if let Some(m) = render_profile.filter(|m| !m.is_empty()) {
+                                        tail.push_str(&render_profile_overlay(&m));
+                                    }
+                                    if let Some(ref m) = auto_refresh_script {
+                                        tail.push_str(m);
+                                    }
+
+                                    tail.push_str(&shell_end);
+                                    sender.send_data(tail.into()).await
+                                };
+
+                                let result = AssertUnwindSafe(stream_body).catch_unwind().await;
+                                match result {
+                                    Ok(Ok(())) => {}
+                                    // The connection is closed by the browser.
+                                    Ok(Err(_)) => {}
+                                    // The status has been sent, so the response is aborted instead.
+                                    Err(_) => {
+                                        tracing::error!("failed to stream {}", stream_path);
+                                        sender.abort();
+                                    }
+                                }
+                            }
+                            .instrument(tracing::Span::current()),
+                        );
+                    }
+
+                    let mut resp = Response::new(body);
+                    resp.headers_mut().insert(
+                        CONTENT_TYPE,
+                        HeaderValue::from_static("text/html; charset=utf-8"),
+                    );
+                    resp
+                } else {
+                    let mut body_s = String::new();
+                    let mut helmet_tags = Vec::new();
+                    let mut strict_overlay = None;
+                    let mut profile_overlay = None;
+
+                    if !props.is_client_only() {
+                        // The first render of strict mode only writes to a discarded helmet.
+                        let strict_s = match strict_mode {
+                            true => Some(
+                                yew::LocalServerRenderer::<StackableRoot<COMP, CTX, BCTX>>::with_props(
+                                    StackableRootProps {
+                                        server_app_props: props.clone(),
+                                        helmet_writer: render_static().1,
+                                        bridge: bridge.clone(),
+                                        bridge_metadata: bridge_metadata.clone(),
+                                        render_profile: None,
+                                        response_control: None,
+                                        hydration_data: HydrationData::default(),
+                                    },
+                                )
+                                .render()
+                                .await,
+                            ),
+                            false => None,
+                        };
+
+                        let (reader, writer) = render_static();
+                        let render_profile = diagnostics.then(RenderProfile::new);
+                        let render_start = Instant::now();
+
+                        body_s =
+                            yew::LocalServerRenderer::<StackableRoot<COMP, CTX, BCTX>>::with_props(
+                                StackableRootProps {
+                                    server_app_props: props,
+                                    helmet_writer: writer,
+                                    bridge,
+                                    bridge_metadata,
+                                    render_profile: render_profile.clone(),
+                                    response_control: Some(response_control.clone()),
+                                    hydration_data: hydration_data.clone(),
+                                },
+                            )
+                            .render()
+                            .await;
+
+                        if let Some(ref m) = render_metrics {
+                            m.record_render(render_start.elapsed());
+                        }
+
+                        helmet_tags = reader.render().await;
+                        let _ = write!(
+                            &mut head_s,
+                            r#"<meta name="stackable-mode" content="hydrate">"#
+                        );
+
+                        if let Some(m) = render_profile.filter(|m| !m.is_empty()) {
+                            profile_overlay = Some(render_profile_overlay(&m));
+                        }
+
+                        if let Some(ref m) = strict_s {
+                            let _ = write!(
+                                &mut head_s,
+                                r#"<meta name="{STRICT_MODE_META_NAME}" content="true">"#
+                            );
+
+                            strict_overlay = strict_mode_difference(m, &body_s)
+                                .map(|(first, second)| strict_mode_overlay(&first, &second));
+                        }
+
+                        if let Some(m) = process_prepared_states(
+                            &body_s,
+                            state_compression.as_ref(),
+                            state_secret.as_deref(),
+                        ) {
+                            body_s = m.body;
+                            if let Some(ref meta) = m.meta {
+                                head_s.push_str(meta);
+                            }
+                            state_cookie = m.cookie;
+
+                            if diagnostics && !m.sizes.is_empty() {
+                                body_s.push_str(&state_size_overlay(&m.sizes));
+                            }
+                        }
+                    }
+
+                    if let Some(m) = hydration_script(&hydration_data, hydration.warn_size, &path) {
+                        body_s.push_str(&m);
+                    }
+
+                    if let Some(snapshot) = metrics
+                        .as_ref()
+                        .map(|m| m.snapshot())
+                        .filter(|m| !m.is_empty())
+                    {
+                        body_s.push_str(&bridge_metrics_overlay(&snapshot));
+                    }
+
+                    if let Some(ref m) = strict_overlay {
+                        body_s.push_str(m);
+                    }
+
+                    if let Some(ref m) = profile_overlay {
+                        body_s.push_str(m);
+                    }
+
+                    // With development server, we read index.html every time.
+                    if let Some(ref m) = auto_refresh_script {
+                        body_s.push_str(m);
+                    }
+
+                    reply::html(
+                        index_html
+                            .render(helmet_tags, html_attrs.map(|m| m.1), head_s, body_s)
+                            .await?,
+                    )
+                    .into_response()
+                };
+
+                if let Some(m) = state_cookie.and_then(|m| HeaderValue::from_str(&m).ok()) {
+                    // The key is only valid for this response.
+                    resp.headers_mut().append(SET_COOKIE, m);
+                    resp.headers_mut()
+                        .insert(CACHE_CONTROL, HeaderValue::from_static("no-store"));
+                }
+
+                if let Some(m) = preload {
+                    for asset in m.entry.iter().chain(m.route_assets(&path)) {
+                        if let Ok(m) = HeaderValue::from_str(&asset.to_link_header()) {
+                            resp.headers_mut().append(LINK, m);
+                        }
+                    }
+                }
+
+                Ok::<_, IndexHtmlError>(controlled_response(&response_control, resp))
+            };
+
+            let create_render_inner =
+                move |props: ServerAppProps<()>,
+                      tx: sync_oneshot::Sender<Result<Response, ErrorReport>>| async move {
+                    let path = match props.raw_queries() {
+                        "" => props.path().to_string(),
+                        m => format!("{}?{}", props.path(), m),
+                    };
+                    let fallback_props = (fallback_mode == FallbackMode::IndexHtml)
+                        .then(|| props.clone().client_only());
+
+                    let result = match AssertUnwindSafe(render_inner(props)).catch_unwind().await {
+                        Ok(Ok(m)) => Ok(m),
+                        Ok(Err(e)) => Err(ErrorReport::new(path.clone(), format!("{e:?}"))),
+                        Err(e) => Err(ErrorReport::from_panic(path.clone(), e)),
+                    };
+
+                    // Pages that are not found are rendered in the browser or answered without a
+                    // page, if configured.
+                    let result = match result {
+                        Ok(m) if m.status() == StatusCode::NOT_FOUND => match fallback_props {
+                            Some(props) => render_inner(props)
+                                .await
+                                .map_err(|e| ErrorReport::new(path, format!("{e:?}"))),
+                            None if fallback_mode == FallbackMode::NotFound => {
+                                Ok(reply::with_status("", StatusCode::NOT_FOUND).into_response())
+                            }
+                            None => Ok(m),
+                        },
+                        m => m,
+                    };
+
+                    let _ = tx.send(result);
+                };
+
+            let render_html = move |props: ServerAppProps<()>| async move {
+                let (tx, rx) = sync_oneshot::channel::<Result<Response, ErrorReport>>();
+                let path = props.path().to_string();
+                let span = tracing::info_span!("render", path = %path);
+
+                match render_pool {
+                    Some(ref pool) => {
+                        pool.spawn_pinned(move || create_render_inner(props, tx).instrument(span))
+                    }
+                    // We spawn into a local runtime early for higher efficiency.
+                    None => match LocalHandle::try_current() {
+                        Some(handle) => {
+                            handle.spawn_local(create_render_inner(props, tx).instrument(span))
+                        }
+                        None => Runtime::default()
+                            .spawn_pinned(move || create_render_inner(props, tx).instrument(span)),
+                    },
+                }
+
+                let report = match rx.await {
+                    Ok(Ok(m)) => return m,
+                    Ok(Err(e)) => e,
+                    Err(_) => ErrorReport::new(path, "renderer exited unexpectedly"),
+                };
+
+                if let Some(ref m) = dashboard {
+                    m.record_error(&report);
+                }
+                error_page.render(&report, diagnostics)
+            };
+
+            let f = warp::get()
+                .and(reject_prefixes(not_found_prefixes))
+                .and(warp::path::full())
+                .and(
+                    warp::query::raw()
+                        .or_else(|_| async move { Ok::<_, Rejection>((String::new(),)) }),
+                )
+                .and(header::optional::<String>("accept-language"))
+                .and(warp::cookie::optional::<String>(LOCALE_COOKIE_NAME))
+                .and(warp::cookie::optional::<String>(Bridge::CSRF_COOKIE))
+                .and(load_session(session_manager.clone()))
+                .and(header::headers_cloned())
+                .and(warp::addr::remote())
+                .then(
+                    move |path: FullPath,
+                          raw_queries,
+                          accept_language: Option<String>,
+                          locale_cookie: Option<String>,
+                          csrf_cookie: Option<String>,
+                          session: Option<Session>,
+                          headers: HeaderMap,
+                          remote_addr: Option<SocketAddr>| {
+                        let locales = props_index_htmls.locales(path.as_str());
+                        let locale = locales
+                            .as_ref()
+                            .and_then(|m| {
+                                m.negotiate(locale_cookie.as_deref(), accept_language.as_deref())
+                            })
+                            .map(|m| m.to_string());
+                        let images = props_index_htmls.image_manifest(path.as_str());
+                        let request = request_context(
+                            &headers,
+                            remote_addr,
+                            client_ip_header.as_deref(),
+                            locale.as_deref(),
+                        );
+
+                        // Pages are specific to a user once a session is stored, or if the
+                        // request carries credentials that the render can read.
+                        let render_cache = render_cache.clone().filter(|_| {
+                            session.as_ref().and_then(|m| m.id()).is_none()
+                                && RenderCache::is_shareable(&headers)
+                        });
+                        let cache_path = path.as_str().to_string();
+                        let cache_key =
+                            RenderCache::key(path.as_str(), &raw_queries, locale.as_deref());
+                        // The render limit also keeps renders, to serve them to shed requests.
+                        let render_cache_control =
+                            (render_cache.is_some() || serve_stale).then(RenderCacheControl::new);
+                        let stale_control = render_cache_control.clone();
+
+                        let props = ServerAppProps::from_warp_request(
+                            path,
+                            raw_queries,
+                            locales,
+                            locale,
+                            images,
+                            session.clone(),
+                            render_cache_control.clone(),
+                            request,
+                            props_app_state.clone(),
+                        );
+                        let render_html = render_html.clone();
+                        let session_manager = session_manager.clone();
+                        let issue_csrf = csrf && csrf_cookie.is_none();
+
+                        let resp = async move {
+                            let (cache, control) = match (render_cache, render_cache_control) {
+                                (Some(cache), Some(control)) => (cache, control),
+                                _ => {
+                                    let resp = render_html(props).await;
+                                    return save_session(session_manager, session, resp).await;
+                                }
+                            };
+
+                            if let Some(m) = cache.load(&cache_key).await {
+                                return m;
+                            }
+
+                            let resp = render_html(props).await;
+                            let resp = save_session(session_manager, session, resp).await;
+                            cache.apply(&control).await;
+
+                            cache.store(&cache_key, &cache_path, &control, resp).await
+                        };
+
+                        // The token is issued after the render cache, so cached pages are shared.
+                        async move {
+                            let mut resp = resp.await;
+                            // Read by the render limit, which does not keep pages that are not
+                            // cacheable.
+                            if let Some(m) = stale_control {
+                                resp.extensions_mut().insert(m);
+                            }
+                            if issue_csrf {
+                                issue_csrf_token(&mut resp);
+                            }
+
+                            resp
+                        }
+                    },
+                );
+
+            Some(f)
         }
 
         fn create_refresh_filter(
+            &self,
         ) -> impl Clone + Send + Filter<Extract = (Response,), Error = Rejection> {
-            warp::path::path("_refresh")
+            let allowed_origins = self.allowed_origins.clone();
+
+            exact_path(&self.refresh_path)
+                .and(header::optional::<String>("origin"))
                 .and(warp::ws())
-                .then(|m: Ws| async move {
-                    m.on_upgrade(|mut ws| async move {
-                        let read_refresh = {
-                            || async move {
-                                while let Some(m) = ws.next().await {
-                                    let m = match m {
-                                        Ok(m) => m,
-                                        Err(e) => {
-                                            tracing::error!("receive message error: {:?}", e);
-
-                                            if let Err(e) = ws.close().await {
+                .then(move |origin: Option<String>, m: Ws| {
+                    let origin_allowed =
+                        is_origin_allowed(allowed_origins.as_deref(), origin.as_deref());
+
+                    async move {
+                        if !origin_allowed {
+                            tracing::warn!(?origin, "rejected websocket connection from origin");
+                            return reply::with_status("", StatusCode::FORBIDDEN).into_response();
+                        }
+
+                        m.on_upgrade(|mut ws| async move {
+                            let read_refresh = {
+                                || async move {
+                                    while let Some(m) = ws.next().await {
+                                        let m = match m {
+                                            Ok(m) => m,
+                                            Err(e) => {
+                                                tracing::error!("receive message error: {:?}", e);
+
+                                                if let Err(e) = ws.close().await {
+                                                    tracing::error!(
+                                                        "failed to close websocket: {:?}",
+                                                        e
+                                                    );
+                                                }
+
+                                                return;
+                                            }
+                                        };
+
+                                        if m.is_ping() || m.is_pong() {
+                                            continue;
+                                        }
+
+                                        let m = match m.to_str() {
+                                            Ok(m) => m,
+                                            Err(_) => {
                                                 tracing::error!(
-                                                    "failed to close websocket: {:?}",
-                                                    e
+                                                    "received unknown message: {:?}",
+                                                    m
                                                 );
+                                                return;
                                             }
+                                        };
 
-                                            return;
+                                        if let Ok(m) = serde_json::from_str::<RefreshMessage>(m) {
+                                            m.log();
+                                            continue;
                                         }
-                                    };
 
-                                    if m.is_ping() || m.is_pong() {
-                                        continue;
-                                    }
+                                        // Ping client if string matches.
+                                        // Otherwise, tell the client to reload the page.
+                                        let message_to_send = if m == SERVER_ID.as_str() {
+                                            Message::ping("")
+                                        } else {
+                                            Message::text("restart")
+                                        };
 
-                                    let m = match m.to_str() {
-                                        Ok(m) => m,
-                                        Err(_) => {
-                                            tracing::error!("received unknown message: {:?}", m);
+                                        if let Err(e) = ws.send(message_to_send).await {
+                                            tracing::error!("error sending message: {:?}", e);
                                             return;
                                         }
-                                    };
-
-                                    // Ping client if string matches.
-                                    // Otherwise, tell the client to reload the page.
-                                    let message_to_send = if m == SERVER_ID.as_str() {
-                                        Message::ping("")
-                                    } else {
-                                        Message::text("restart")
-                                    };
-
-                                    if let Err(e) = ws.send(message_to_send).await {
-                                        tracing::error!("error sending message: {:?}", e);
-                                        return;
                                     }
                                 }
-                            }
-                        };
+                            };
 
-                        match LocalHandle::try_current() {
-                            Some(handle) => handle.spawn_local(read_refresh()),
-                            // TODO: Allow Overriding Runtime with Endpoint.
-                            None => Runtime::default().spawn_pinned(read_refresh),
-                        }
-                    })
-                    .into_response()
+                            match LocalHandle::try_current() {
+                                Some(handle) => handle.spawn_local(read_refresh()),
+                                // TODO: Allow Overriding Runtime with Endpoint.
+                                None => Runtime::default().spawn_pinned(read_refresh),
+                            }
+                        })
+                        .into_response()
+                    }
                 })
         }
 
         fn create_bridge_filter(
             &self,
         ) -> Option<impl Clone + Send + Filter<Extract = (Response,), Error = Rejection>> {
-            let bridge = self.bridge.clone()?;
+            let mut bridge = self.bridge.clone()?;
+            if let Some(m) = self.metrics.clone() {
+                bridge = bridge.with_metrics(m);
+            }
+            if let Some(m) = self.mocks.clone() {
+                bridge = bridge.with_mocks(m);
+            }
+            if let Some(m) = self.recorder.clone() {
+                bridge = bridge.with_recorder(m);
+            }
+            let bridge_path = self
+                .bridge_path
+                .clone()
+                .unwrap_or_else(|| bridge.path().to_string());
+
+            let stream_bridge = bridge.clone();
+            let upload_bridge = bridge.clone();
+            let session_manager = self.session_manager();
+            let render_cache = self.render_cache.clone();
+            let csrf = self.csrf;
+            let app_state = self.app_state.clone();
+            let stream_app_state = self.app_state.clone();
+            let upload_app_state = self.app_state.clone();
+
+            // A frontend from a different build may send requests that this bridge resolves
+            // differently, it is asked to reload instead.
+            let build_id = BuildInfo::current_id();
+            let reload_required_f = warp::post()
+                .and(header::optional::<String>(Bridge::BUILD_ID_HEADER))
+                .and_then(move |frontend_build_id: Option<String>| {
+                    let is_skewed = match (build_id.as_deref(), frontend_build_id.as_deref()) {
+                        (Some(m), Some(n)) => m != n,
+                        _ => false,
+                    };
+
+                    async move {
+                        if !is_skewed {
+                            return Err(not_found());
+                        }
+
+                        tracing::debug!(
+                            ?frontend_build_id,
+                            "rejected bridge request from a different build"
+                        );
+
+                        Ok(reply::with_header(
+                            reply::with_status("", StatusCode::CONFLICT),
+                            Bridge::RELOAD_REQUIRED_HEADER,
+                            "true",
+                        )
+                        .into_response())
+                    }
+                });
 
             let http_bridge_f = warp::post()
                 .and(header::exact_ignore_case(
@@ -369,58 +2082,400 @@ mod feat_warp_filter {
                     "application/x-bincode",
                 ))
                 .and(header::optional("authorization"))
+                .and(warp::cookie::optional::<String>(Bridge::CSRF_COOKIE))
+                .and(header::optional::<String>(Bridge::CSRF_HEADER))
                 .and(bytes())
-                .then(move |token: Option<String>, input: Bytes| {
-                    let bridge = bridge.clone();
-                    let (tx, rx) = sync_oneshot::channel();
+                .and(load_session(session_manager.clone()))
+                .then(
+                    move |token: Option<String>,
+                          csrf_cookie: Option<String>,
+                          csrf_header: Option<String>,
+                          input: Bytes,
+                          session: Option<Session>| {
+                        let bridge = bridge.clone();
+                        let csrf_rejected = csrf
+                            && !is_csrf_verified(csrf_cookie.as_deref(), csrf_header.as_deref());
+                        let (tx, rx) = sync_oneshot::channel();
+                        let span = tracing::info_span!("bridge");
+                        let session_manager = session_manager.clone();
+                        let resolver_session = session.clone();
+                        let render_cache = render_cache.clone();
+                        let render_cache_control =
+                            render_cache.as_ref().map(|_| RenderCacheControl::new());
+                        let resolver_render_cache_control = render_cache_control.clone();
+                        let app_state = app_state.clone();
+
+                        let resolve_encoded = move || async move {
+                            let meta = match bridge_metadata(token, app_state) {
+                                Some(mut m) => {
+                                    if let Some(session) = resolver_session {
+                                        m = m.with_session(session);
+                                    }
+                                    if let Some(control) = resolver_render_cache_control {
+                                        m = m.with_render_cache(control);
+                                    }
+                                    if csrf_rejected {
+                                        m = m.with_csrf_rejected();
+                                    }
+                                    m
+                                }
+                                None => {
+                                    let reply = reply::with_status("", StatusCode::BAD_REQUEST)
+                                        .into_response();
+
+                                    let _ = tx.send(reply);
+                                    return;
+                                }
+                            };
+
+                            let content = bridge
+                                .connect(meta)
+                                .and_then(|m| async move { m.resolve_encoded(&input).await })
+                                .await;
+
+                            let reply = match content {
+                                Ok(m) => {
+                                    reply::with_header(m, "content-type", "application/x-bincode")
+                                        .into_response()
+                                }
+                                Err(e) => bridge_error_reply(e),
+                            };
+
+                            let _ = tx.send(reply);
+                        };
+
+                        match LocalHandle::try_current() {
+                            Some(handle) => handle.spawn_local(resolve_encoded().instrument(span)),
+                            // TODO: Allow Overriding Runtime with Endpoint.
+                            None => Runtime::default()
+                                .spawn_pinned(move || resolve_encoded().instrument(span)),
+                        }
+
+                        async move {
+                            let resp = rx.await.expect("failed to resolve the bridge request");
+                            if let (Some(cache), Some(control)) =
+                                (render_cache, render_cache_control)
+                            {
+                                cache.apply(&control).await;
+                            }
 
-                    let resolve_encoded = move || async move {
-                        let mut meta = BridgeMetadata::<()>::new();
+                            save_session(session_manager, session, resp).await
+                        }
+                    },
+                );
 
-                        if let Some(m) = token {
-                            if !m.starts_with("Bearer ") {
+            let stream_bridge_f = warp::post()
+                .and(header::exact_ignore_case(
+                    "content-type",
+                    "application/x-bincode",
+                ))
+                .and(header::exact_ignore_case("accept", "application/x-ndjson"))
+                .and(header::optional("authorization"))
+                .and(bytes())
+                .then(move |token: Option<String>, input: Bytes| {
+                    let bridge = stream_bridge.clone();
+                    let (tx, rx) = sync_oneshot::channel();
+                    let (line_tx, line_rx) = mpsc::channel::<String>(16);
+                    let span = tracing::info_span!("bridge_stream");
+                    let app_state = stream_app_state.clone();
+
+                    let resolve_stream = move || async move {
+                        let meta = match bridge_metadata(token, app_state) {
+                            Some(m) => m,
+                            None => {
                                 let reply =
                                     reply::with_status("", StatusCode::BAD_REQUEST).into_response();
 
                                 let _ = tx.send(reply);
                                 return;
                             }
+                        };
 
-                            meta = meta.with_token(m.split_at(7).1);
-                        }
-
-                        let content = bridge
+                        let lines = bridge
                             .connect(meta)
-                            .and_then(|m| async move { m.resolve_encoded(&input).await })
+                            .and_then(|m| async move { m.resolve_stream_encoded(&input) })
                             .await;
 
-                        let reply = match content {
-                            Ok(m) => reply::with_header(m, "content-type", "application/x-bincode")
-                                .into_response(),
-                            Err(BridgeError::Encoding(_))
-                            | Err(BridgeError::InvalidIndex(_))
-                            | Err(BridgeError::InvalidType(_)) => {
-                                reply::with_status("", StatusCode::BAD_REQUEST).into_response()
-                            }
-                            Err(BridgeError::Network(_)) => {
-                                reply::with_status("", StatusCode::INTERNAL_SERVER_ERROR)
-                                    .into_response()
+                        let mut lines = match lines {
+                            Ok(m) => m,
+                            Err(e) => {
+                                let _ = tx.send(bridge_error_reply(e));
+                                return;
                             }
                         };
 
-                        let _ = tx.send(reply);
+                        // Lines are sent to the client as they are resolved.
+                        let body = Body::wrap_stream(futures::stream::unfold(
+                            line_rx,
+                            |mut rx| async move {
+                                rx.recv().await.map(|m| (Ok::<_, Infallible>(m), rx))
+                            },
+                        ));
+                        let _ = tx.send(
+                            reply::with_header(
+                                Response::new(body),
+                                "content-type",
+                                "application/x-ndjson",
+                            )
+                            .into_response(),
+                        );
+
+                        while let Some(m) = lines.next().await {
+                            match m {
+                                Ok(m) => {
+                                    // The client has disconnected.
+                                    if line_tx.send(m).await.is_err() {
+                                        return;
+                                    }
+                                }
+                                Err(e) => {
+                                    tracing::error!("failed to resolve stream: {:?}", e);
+
+                                    // The client surfaces the error instead of seeing the stream
+                                    // end.
+                                    let _ = line_tx.send(Bridge::stream_error_line(&e)).await;
+                                    return;
+                                }
+                            }
+                        }
                     };
 
                     match LocalHandle::try_current() {
-                        Some(handle) => handle.spawn_local(resolve_encoded()),
+                        Some(handle) => handle.spawn_local(resolve_stream().instrument(span)),
                         // TODO: Allow Overriding Runtime with Endpoint.
-                        None => Runtime::default().spawn_pinned(resolve_encoded),
+                        None => Runtime::default()
+                            .spawn_pinned(move || resolve_stream().instrument(span)),
                     }
 
                     async move { rx.await.expect("failed to resolve the bridge request") }
                 });
 
-            Some(warp::path::path("_bridge").and(http_bridge_f))
+            let upload_bridge_f = warp::post()
+                .and(header::exact_ignore_case(
+                    "content-type",
+                    "application/x-bincode",
+                ))
+                .and(header::exact_ignore_case(
+                    "accept",
+                    "application/x-stackable-upload",
+                ))
+                .and(header::optional("authorization"))
+                .and(warp::cookie::optional::<String>(Bridge::CSRF_COOKIE))
+                .and(header::optional::<String>(Bridge::CSRF_HEADER))
+                .and(bytes())
+                .then(
+                    move |token: Option<String>,
+                          csrf_cookie: Option<String>,
+                          csrf_header: Option<String>,
+                          input: Bytes| {
+                        let bridge = upload_bridge.clone();
+                        let (tx, rx) = sync_oneshot::channel();
+                        let span = tracing::info_span!("bridge_upload");
+                        let csrf_rejected = csrf
+                            && !is_csrf_verified(csrf_cookie.as_deref(), csrf_header.as_deref());
+                        let app_state = upload_app_state.clone();
+
+                        let resolve_upload = move || async move {
+                            let meta = match bridge_metadata(token, app_state) {
+                                Some(m) if csrf_rejected => m.with_csrf_rejected(),
+                                Some(m) => m,
+                                None => {
+                                    let reply = reply::with_status("", StatusCode::BAD_REQUEST)
+                                        .into_response();
+
+                                    let _ = tx.send(reply);
+                                    return;
+                                }
+                            };
+
+                            // The reply is delayed until the chunk has been taken by the resolver.
+                            let content = bridge
+                                .connect(meta)
+                                .and_then(|m| async move { m.resolve_upload_encoded(&input).await })
+                                .await;
+
+                            let reply = match content {
+                                Ok(m) => {
+                                    reply::with_header(m, "content-type", "application/x-bincode")
+                                        .into_response()
+                                }
+                                Err(e) => bridge_error_reply(e),
+                            };
+
+                            let _ = tx.send(reply);
+                        };
+
+                        match LocalHandle::try_current() {
+                            Some(handle) => handle.spawn_local(resolve_upload().instrument(span)),
+                            // TODO: Allow Overriding Runtime with Endpoint.
+                            None => Runtime::default()
+                                .spawn_pinned(move || resolve_upload().instrument(span)),
+                        }
+
+                        async move { rx.await.expect("failed to resolve the bridge request") }
+                    },
+                );
+
+            let resolve_f = stream_bridge_f
+                .or(upload_bridge_f)
+                .unify()
+                .or(http_bridge_f)
+                .unify();
+
+            // Bridge requests carry the session of the user, so they are rejected from origins
+            // that are not allowed, the same as the websocket of auto refresh.
+            let allowed_origins = self.allowed_origins.clone();
+            let origin_rejected_f =
+                header::optional::<String>("origin").and_then(move |origin: Option<String>| {
+                    let origin_allowed =
+                        is_origin_allowed(allowed_origins.as_deref(), origin.as_deref());
+
+                    async move {
+                        if origin_allowed {
+                            return Err(not_found());
+                        }
+
+                        tracing::warn!(?origin, "rejected bridge request from origin");
+
+                        Ok(reply::with_status("", StatusCode::FORBIDDEN).into_response())
+                    }
+                });
+
+            let bridge_f = origin_rejected_f
+                .or(reload_required_f)
+                .unify()
+                .or(limit(
+                    self.bridge_limit
+                        .clone()
+                        .map(|m| m.with_metrics(self.metrics.clone())),
+                    resolve_f,
+                ))
+                .unify();
+
+            // Preflight requests are answered by the CORS filter, so it is only applied to the
+            // path of the bridge.
+            let bridge_f = match self.cors {
+                Some(ref m) => bridge_f
+                    .with(bridge_cors(m))
+                    .map(Reply::into_response)
+                    .boxed(),
+                None => bridge_f.boxed(),
+            };
+
+            Some(exact_path(&bridge_path).and(bridge_f))
+        }
+
+        /// Serves a service worker that unregisters itself at the service worker path of every
+        /// frontend, so service workers of production builds do not serve stale assets.
+        fn create_noop_service_worker_filter(
+        ) -> impl Clone + Send + Filter<Extract = (Response,), Error = Rejection> {
+            warp::get()
+                .and(warp::path::full())
+                .and_then(|m: FullPath| async move {
+                    match m.as_str().rsplit('/').next() == Some(SERVICE_WORKER_FILE_NAME) {
+                        true => Ok(()),
+                        false => Err(not_found()),
+                    }
+                })
+                .untuple_one()
+                .map(|| {
+                    let mut resp = reply::with_header(
+                        NOOP_SERVICE_WORKER,
+                        "content-type",
+                        "text/javascript; charset=utf-8",
+                    )
+                    .into_response();
+                    resp.headers_mut()
+                        .insert(CACHE_CONTROL, HeaderValue::from_static("no-store"));
+
+                    resp
+                })
+        }
+
+        fn create_metrics_filter(
+            &self,
+        ) -> Option<impl Clone + Send + Filter<Extract = (Response,), Error = Rejection>> {
+            let metrics = self.metrics.clone()?;
+
+            let f = warp::get()
+                .and(exact_path(&self.metrics_path))
+                .map(move || {
+                    reply::with_header(
+                        metrics.render_prometheus(),
+                        "content-type",
+                        "text/plain; version=0.0.4",
+                    )
+                    .into_response()
+                });
+
+            Some(f)
+        }
+
+        fn create_dashboard_filter(
+            &self,
+        ) -> Option<impl Clone + Send + Filter<Extract = (Response,), Error = Rejection>> {
+            let dashboard = self.dashboard.clone()?;
+            let api_routes: Arc<[String]> = self.api_routes().into();
+            let mount_paths: Arc<[String]> = self
+                .frontend
+                .as_ref()
+                .map(|m| m.mount_paths())
+                .unwrap_or_default()
+                .into();
+            let contract = Arc::new(self.bridge_contract());
+
+            let f = warp::get().and(exact_path(DASHBOARD_PATH)).then(move || {
+                let dashboard = dashboard.clone();
+                let api_routes = api_routes.clone();
+                let mount_paths = mount_paths.clone();
+                let contract = contract.clone();
+
+                async move {
+                    let status = dashboard.status().await;
+                    let mut resp = reply::html(dashboard.render(
+                        status.as_ref(),
+                        &api_routes,
+                        &mount_paths,
+                        &contract,
+                    ))
+                    .into_response();
+                    resp.headers_mut()
+                        .insert(CACHE_CONTROL, HeaderValue::from_static("no-store"));
+
+                    resp
+                }
+            });
+
+            Some(f)
+        }
+
+        /// Serves health checks and metrics with [`admin_warp_filter`](Self::admin_warp_filter)
+        /// instead of the filter of the endpoint.
+        pub fn with_admin(mut self, config: &AdminConfig) -> Self {
+            self.admin = Some(config.clone());
+
+            self
+        }
+
+        /// Returns the filter of the admin listener, if it is enabled with
+        /// [`with_admin`](Self::with_admin).
+        ///
+        /// It responds to the health path with `200 OK`, and serves metrics if they are enabled.
+        pub fn admin_warp_filter(
+            &self,
+        ) -> Option<impl Clone + Send + Filter<Extract = (Response,), Error = Rejection>> {
+            let admin = self.admin.as_ref()?;
+
+            let mut routes = warp::get()
+                .and(exact_path(&admin.health_path))
+                .map(|| reply::with_status("OK", StatusCode::OK).into_response())
+                .boxed();
+
+            if let Some(m) = self.create_metrics_filter() {
+                routes = routes.or(m).unify().boxed();
+            }
+
+            Some(routes)
         }
 
         pub fn with_frontend(mut self, frontend: Frontend) -> Self {
@@ -429,11 +2484,179 @@ mod feat_warp_filter {
             self
         }
 
+        /// Sets the page to respond with when server-side rendering fails.
+        pub fn with_error_page(mut self, error_page: ErrorPage) -> Self {
+            self.error_page = error_page;
+
+            self
+        }
+
+        /// Renders a detailed error page with the panic message, backtrace and route when
+        /// server-side rendering fails.
+        ///
+        /// This is enabled by the development server and should not be used in production.
+        pub fn with_diagnostics(mut self) -> Self {
+            install_panic_hook();
+            self.diagnostics = true;
+
+            self
+        }
+
+        /// Renders pages on dedicated worker threads.
+        ///
+        /// # Panics
+        ///
+        /// Panics if the worker threads cannot be created.
+        pub fn with_render_config(mut self, config: &RenderConfig) -> Self {
+            self.render_pool =
+                Some(RenderPool::new(config).expect("failed to create render runtime"));
+
+            self
+        }
+
+        /// Reads the address of the client from a header that a reverse proxy sets, e.g.:
+        /// `X-Forwarded-For`.
+        ///
+        /// Clients can send any value, so this should only be set if the server is only reachable
+        /// through the proxy.
+        pub fn with_client_ip_header<S>(mut self, name: S) -> Self
+        where
+            S: Into<String>,
+        {
+            self.client_ip_header = Some(name.into());
+
+            self
+        }
+
+        /// Enables or disables CSRF protection of bridge mutations and uploads, which is enabled by
+        /// default.
+        ///
+        /// Pages are served with a token in a cookie, mutations and uploads are rejected unless
+        /// they carry the token in a header. Disable it only if the bridge is called by clients
+        /// other than the frontend.
+        pub fn with_csrf_protection(mut self, enabled: bool) -> Self {
+            self.csrf = enabled;
+
+            self
+        }
+
+        /// Sets the format of hydration data and the size that a warning is logged over.
+        pub fn with_hydration_config(mut self, config: &HydrationConfig) -> Self {
+            self.hydration = config.clone();
+
+            self
+        }
+
+        /// Sets what paths that are not routes of the frontend are answered with.
+        ///
+        /// By default, pages are served as rendered, which is usually the not-found route of the
+        /// router with `404 Not Found`.
+        pub fn with_fallback_config(mut self, config: &FallbackConfig) -> Self {
+            self.fallback = config.clone();
+
+            self
+        }
+
+        /// Caches rendered pages.
+        ///
+        /// Pages of routes in the configuration of the cache are cached, other pages are cached if
+        /// they are marked cacheable with [`ServerAppProps::render_cache`]. Pages and resolvers can
+        /// invalidate cached pages, which is applied after their responses.
+        pub fn with_render_cache(mut self, cache: RenderCache) -> Self {
+            self.render_cache = Some(cache);
+
+            self
+        }
+
+        /// Limits the number of pages rendered and bridge requests resolved at the same time.
+        ///
+        /// Each class is limited separately and assets are never limited, so static files are
+        /// served while pages are rendering. Requests that cannot be queued are shed with
+        /// `503 Service Unavailable` and counted in metrics.
+        pub fn with_request_limits(mut self, limits: &RequestLimits) -> Self {
+            self.render_limit = limits
+                .render
+                .as_ref()
+                .map(|m| ConcurrencyLimiter::new("render", m));
+            // Bridge requests are not idempotent, so stale responses are never served.
+            self.bridge_limit = limits.bridge.as_ref().map(|m| {
+                ConcurrencyLimiter::new(
+                    "bridge",
+                    &ConcurrencyLimit {
+                        serve_stale: false,
+                        ..m.clone()
+                    },
+                )
+            });
+
+            self
+        }
+
+        /// Allows frontends and clients served from other origins to call the bridge.
+        ///
+        /// # Panics
+        ///
+        /// Panics if an origin, a method or a header in the configuration is invalid.
+        pub fn with_cors(mut self, config: &CorsConfig) -> Self {
+            self.cors = Some(config.clone());
+
+            self
+        }
+
+        /// Limits the rate of requests of each client.
+        ///
+        /// Clients are identified by their address, which is read from the client ip header if it
+        /// is set, or by a header of the rule. Rejected requests are counted in metrics.
+        pub fn with_rate_limit(mut self, config: &RateLimitConfig) -> Self {
+            self.rate_limiter = Some(RateLimiter::new(config));
+
+            self
+        }
+
+        /// Renders each page twice and shows an overlay if the renders differ.
+        ///
+        /// Components that read global state, time or randomness during rendering produce
+        /// different markup between renders, which otherwise only shows up as hydration
+        /// mismatches. The frontend performs the same check before hydration.
+        pub fn with_strict_mode(mut self) -> Self {
+            self.strict_mode = true;
+
+            self
+        }
+
+        /// Streams pages while they are rendered.
+        ///
+        /// The head of `index.html` is sent before components are rendered, and the html of each
+        /// component is sent when its suspense resolves. Helmet tags are sent after the body, so
+        /// crawlers that do not run scripts only see them if streaming is disabled. Errors after
+        /// the head is sent abort the response instead of rendering the error page. Statuses and
+        /// redirects of components must be set before the first component suspends.
+        ///
+        /// Pages are not streamed in strict mode.
+        pub fn with_streaming(mut self) -> Self {
+            self.streaming = true;
+
+            self
+        }
+
         pub fn into_warp_filter(
             self,
         ) -> impl Clone + Send + Filter<Extract = (impl Reply + Send,), Error = Rejection> {
             let bridge_f = self.create_bridge_filter();
-            let index_html_f = self.create_index_filter();
+            // Metrics are served by the admin listener if it is enabled.
+            let metrics_f = self
+                .create_metrics_filter()
+                .filter(|_| self.admin.is_none());
+            let request_metrics = self.metrics.clone();
+            let dashboard_f = self.create_dashboard_filter();
+            let index_html_f = self.create_index_filter().map(|m| {
+                limit(
+                    self.render_limit
+                        .clone()
+                        .map(|m| m.with_metrics(self.metrics.clone())),
+                    m,
+                )
+            });
 
             let Self { frontend, .. } = self;
 
@@ -448,19 +2671,70 @@ mod feat_warp_filter {
                 routes = routes.or(m).unify().boxed();
             }
 
+            if let Some(m) = metrics_f {
+                routes = routes.or(m).unify().boxed();
+            }
+
+            if let Some(m) = dashboard_f {
+                routes = routes.or(m).unify().boxed();
+            }
+
             if let Some(m) = frontend {
                 routes = routes.or(m.into_warp_filter()).unify().boxed();
             }
 
             if self.auto_refresh {
-                routes = routes.or(Self::create_refresh_filter()).unify().boxed();
+                routes = routes
+                    .or(self.create_refresh_filter())
+                    .unify()
+                    .or(Self::create_noop_service_worker_filter())
+                    .unify()
+                    .boxed();
             }
 
             if let Some(m) = index_html_f {
                 routes = routes.or(m).unify().boxed();
             }
 
-            routes.with(log::custom(|info| {
+            let routes = guard(self.guards.clone(), routes);
+
+            let routes = rate_limit(
+                self.rate_limiter
+                    .clone()
+                    .map(|m| m.with_metrics(self.metrics.clone())),
+                self.client_ip_header.clone(),
+                routes,
+            );
+
+            // Requests are wrapped in a span so spans from rendering and the bridge are grouped,
+            // it continues the trace of the caller if the request has a `traceparent` header.
+            let routes = routes.with(warp::trace(|info| {
+                let span = tracing::info_span!(
+                    "request",
+                    method = %info.method(),
+                    path = %info.path(),
+                    version = ?info.version(),
+                    remote.addr = ?info.remote_addr(),
+                );
+                crate::trace::set_parent(
+                    &span,
+                    info.request_headers()
+                        .get(TRACEPARENT_HEADER)
+                        .and_then(|m| m.to_str().ok()),
+                );
+
+                span
+            }));
+
+            routes.with(log::custom(move |info| {
+                if let Some(ref m) = request_metrics {
+                    m.record_request(
+                        info.method().as_str(),
+                        info.status().as_u16(),
+                        info.elapsed(),
+                    );
+                }
+
                 // We emit a custom span so it won't interfere with warp's default tracing event.
                 tracing::info!(target: "stackable_backend::endpoint::trace",
                 remote_addr = ?info.remote_addr(),
@@ -477,21 +2751,122 @@ mod feat_warp_filter {
 
 #[cfg(feature = "tower-service")]
 mod feat_tower_service {
+    use std::collections::BTreeMap;
     use std::convert::Infallible;
     use std::future::Future;
+    use std::sync::Arc;
 
+    use bytes::Bytes;
+    use http::status::StatusCode;
+    use hyper::body::HttpBody;
     use hyper::{Body, Request, Response};
-    use tower::Service;
+    use stackable_core::server::VirtualHost;
+    use tower::util::BoxCloneService;
+    use tower::{BoxError, Layer, Service, ServiceExt};
 
     use super::*;
+    use crate::vhost::VirtualHosts;
+
+    /// Converts the body of a response from a layer into a hyper body.
+    fn into_body<B>(body: B) -> Body
+    where
+        B: 'static + Send + HttpBody,
+        B::Data: Send + Into<Bytes>,
+        B::Error: Into<BoxError>,
+    {
+        let chunks = futures::stream::unfold(Box::pin(body), |mut body| async move {
+            body.data()
+                .await
+                .map(|m| (m.map(Into::into).map_err(Into::into), body))
+        });
+
+        Body::wrap_stream::<_, Bytes, BoxError>(chunks)
+    }
+
     impl<COMP, CTX, BCTX> Endpoint<COMP, CTX, BCTX>
     where
         COMP: BaseComponent<Properties = ServerAppProps<CTX>>,
         CTX: 'static,
         BCTX: 'static,
     {
+        /// Wraps the service of the endpoint with a tower layer, e.g.: compression or timeouts.
+        ///
+        /// Layers that are added later wrap layers that are added earlier. Errors of the layer are
+        /// responded with `500 Internal Server Error`.
+        pub fn with_layer<L, B, E>(mut self, layer: L) -> Self
+        where
+            L: 'static + Send + Layer<BoxService>,
+            L::Service:
+                'static + Clone + Send + Service<Request<Body>, Response = Response<B>, Error = E>,
+            <L::Service as Service<Request<Body>>>::Future: 'static + Send,
+            B: 'static + Send + HttpBody,
+            B::Data: Send + Into<Bytes>,
+            B::Error: Into<BoxError>,
+            E: Into<BoxError>,
+        {
+            self.layers.push(Box::new(move |svc| {
+                let svc = layer.layer(svc).map_result(|m| {
+                    let resp = match m {
+                        Ok(m) => m.map(into_body),
+                        Err(e) => {
+                            let e: BoxError = e.into();
+                            tracing::error!("failed to handle request: {}", e);
+
+                            let mut resp = Response::new(Body::empty());
+                            *resp.status_mut() = StatusCode::INTERNAL_SERVER_ERROR;
+                            resp
+                        }
+                    };
+
+                    Ok::<_, Infallible>(resp)
+                });
+
+                BoxCloneService::new(svc)
+            }));
+
+            self
+        }
+
+        /// Serves hostnames from frontends or path prefixes, e.g.: `admin.example.com` from the
+        /// frontend mounted at `/admin`.
+        ///
+        /// Virtual hosts are applied by the tower service, not the warp filter.
+        pub fn with_virtual_hosts(mut self, vhosts: &BTreeMap<String, VirtualHost>) -> Self {
+            self.vhosts = vhosts.clone();
+
+            self
+        }
+
+        /// Serves a virtual host at another hostname, e.g.: `admin.localhost` for
+        /// `admin.example.com` in development.
+        pub fn with_host_alias<A, H>(mut self, alias: A, host: H) -> Self
+        where
+            A: Into<String>,
+            H: Into<String>,
+        {
+            self.host_aliases.push((alias.into(), host.into()));
+
+            self
+        }
+
+        /// Returns the service of the admin listener, if it is enabled with `with_admin`.
+        pub fn admin_tower_service(
+            &self,
+        ) -> Option<
+            impl 'static
+                + Clone
+                + Service<
+                    Request<Body>,
+                    Response = Response<Body>,
+                    Error = Infallible,
+                    Future = impl 'static + Send + Future<Output = Result<Response<Body>, Infallible>>,
+                >,
+        > {
+            self.admin_warp_filter().map(warp::service)
+        }
+
         pub fn into_tower_service(
-            self,
+            mut self,
         ) -> impl 'static
                + Clone
                + Service<
@@ -500,8 +2875,25 @@ mod feat_tower_service {
             Error = Infallible,
             Future = impl 'static + Send + Future<Output = Result<Response<Body>, Infallible>>,
         > {
+            let layers = std::mem::take(&mut self.layers);
+            let vhosts = Arc::new(VirtualHosts::new(
+                &self.vhosts,
+                &self.host_aliases,
+                &self
+                    .frontend
+                    .as_ref()
+                    .map(|m| m.mounts())
+                    .unwrap_or_default(),
+                self.api_routes(),
+            ));
             let routes = self.into_warp_filter();
-            warp::service(routes)
+
+            // Layers see requests as they were sent, routes see the paths of virtual hosts.
+            let svc = warp::service(routes).map_request(move |req| vhosts.rewrite(req));
+
+            layers
+                .into_iter()
+                .fold(BoxCloneService::new(svc), |svc, layer| layer(svc))
         }
     }
 }
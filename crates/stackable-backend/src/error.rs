@@ -7,3 +7,98 @@ pub enum ServerAppError {
 }
 
 pub type ServerAppResult<T> = Result<T, ServerAppError>;
+
+#[cfg(feature = "warp-filter")]
+#[derive(Error, Debug)]
+pub(crate) enum IndexHtmlError {
+    #[error("failed to read index.html")]
+    Read(#[from] std::io::Error),
+    #[error("failed to render index.html")]
+    Rewrite(#[from] lol_html::errors::RewritingError),
+    #[error("index.html does not have a body placeholder to stream into")]
+    MissingBody,
+}
+
+#[cfg(feature = "warp-filter")]
+pub(crate) type IndexHtmlResult<T> = Result<T, IndexHtmlError>;
+
+#[cfg(feature = "warp-filter")]
+#[derive(Error, Debug)]
+pub enum SessionError {
+    #[error("invalid session id")]
+    InvalidId,
+    #[error("session store is not enabled: {}", .0)]
+    Unsupported(&'static str),
+    #[error("failed to access session store")]
+    Io(#[from] std::io::Error),
+    #[error("failed to encode / decode session")]
+    Encoding(#[from] bincode::Error),
+    #[cfg(feature = "session-redis")]
+    #[error("failed to communicate with redis")]
+    Redis(#[from] redis::RedisError),
+}
+
+#[cfg(feature = "warp-filter")]
+pub type SessionResult<T> = Result<T, SessionError>;
+
+#[cfg(feature = "warp-filter")]
+#[derive(Error, Debug)]
+pub enum RenderCacheError {
+    #[error("render cache store is not enabled: {}", .0)]
+    Unsupported(&'static str),
+    #[error("failed to encode / decode cached page")]
+    Encoding(#[from] bincode::Error),
+    #[cfg(feature = "render-cache-redis")]
+    #[error("failed to communicate with redis")]
+    Redis(#[from] redis::RedisError),
+}
+
+#[cfg(feature = "warp-filter")]
+pub type RenderCacheResult<T> = Result<T, RenderCacheError>;
+
+#[cfg(feature = "warp-filter")]
+#[derive(Error, Debug)]
+pub enum AppStateError {
+    #[error("failed to initialize {}", .name)]
+    Initialize {
+        name: &'static str,
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+}
+
+#[cfg(feature = "warp-filter")]
+pub type AppStateResult<T> = Result<T, AppStateError>;
+
+#[cfg(feature = "tls")]
+#[derive(Error, Debug)]
+pub enum TlsError {
+    #[error("failed to serve over TLS")]
+    Io(#[from] std::io::Error),
+    #[error("no private key is found in {}", .0.display())]
+    MissingKey(std::path::PathBuf),
+    #[error("invalid certificate or private key")]
+    Rustls(#[from] rustls::Error),
+    #[cfg(feature = "http3")]
+    #[error("failed to establish HTTP/3 connection")]
+    Connection(#[from] quinn::ConnectionError),
+    #[cfg(feature = "http3")]
+    #[error("failed to communicate with HTTP/3")]
+    Http3(#[from] h3::Error),
+}
+
+#[cfg(feature = "tls")]
+pub type TlsResult<T> = Result<T, TlsError>;
+
+#[cfg(feature = "test")]
+#[derive(Error, Debug)]
+pub enum TestServerError {
+    #[error("failed to start test server")]
+    Io(#[from] std::io::Error),
+    #[error("failed to communicate with test server")]
+    Http(#[from] hyper::Error),
+    #[error("invalid uri: {}", .0)]
+    InvalidUri(String),
+}
+
+#[cfg(feature = "test")]
+pub type TestServerResult<T> = Result<T, TestServerError>;
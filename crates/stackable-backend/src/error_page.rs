@@ -0,0 +1,184 @@
+use std::any::Any;
+use std::backtrace::Backtrace;
+use std::cell::RefCell;
+use std::fmt::Write;
+use std::panic;
+use std::sync::{Arc, Once};
+
+use http::status::StatusCode;
+use warp::reply::{html, with_status, Response};
+use warp::Reply;
+
+use crate::utils::escape_html;
+
+thread_local! {
+    static LAST_BACKTRACE: RefCell<Option<String>> = RefCell::new(None);
+}
+
+/// Installs a panic hook that records the backtrace of the last panic on the current thread.
+///
+/// The previous hook is still called, so panics are printed as usual. A panic of a render only
+/// fails the request, the report is responded with `500 Internal Server Error`.
+pub(crate) fn install_panic_hook() {
+    static INSTALL: Once = Once::new();
+
+    INSTALL.call_once(|| {
+        let prev_hook = panic::take_hook();
+
+        panic::set_hook(Box::new(move |info| {
+            let backtrace = Backtrace::force_capture().to_string();
+            LAST_BACKTRACE.with(|m| *m.borrow_mut() = Some(backtrace));
+
+            prev_hook(info);
+        }));
+    });
+}
+
+/// A report of a failed server-side rendering.
+#[derive(Debug, Clone)]
+pub(crate) struct ErrorReport {
+    path: String,
+    message: String,
+    backtrace: Option<String>,
+}
+
+impl ErrorReport {
+    pub fn new<P, M>(path: P, message: M) -> Self
+    where
+        P: Into<String>,
+        M: Into<String>,
+    {
+        Self {
+            path: path.into(),
+            message: message.into(),
+            backtrace: None,
+        }
+    }
+
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Creates a report from a panic payload caught on the current thread.
+    pub fn from_panic<P>(path: P, payload: Box<dyn Any + Send>) -> Self
+    where
+        P: Into<String>,
+    {
+        let message = match payload.downcast::<String>() {
+            Ok(m) => *m,
+            Err(payload) => match payload.downcast::<&'static str>() {
+                Ok(m) => (*m).to_string(),
+                Err(_) => "Box<dyn Any>".to_string(),
+            },
+        };
+
+        Self {
+            path: path.into(),
+            message: format!("renderer panicked: {message}"),
+            backtrace: LAST_BACKTRACE.with(|m| m.borrow_mut().take()),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Inner {
+    Default,
+    Html(Arc<str>),
+}
+
+/// The page to respond with when server-side rendering fails.
+///
+/// When the endpoint has diagnostics enabled (the development server), a detailed page with the
+/// error message, backtrace and route is rendered instead.
+#[derive(Debug, Clone)]
+pub struct ErrorPage {
+    inner: Inner,
+}
+
+impl Default for ErrorPage {
+    fn default() -> Self {
+        Self {
+            inner: Inner::Default,
+        }
+    }
+}
+
+impl ErrorPage {
+    /// Creates an error page that responds with the provided html.
+    pub fn new_html<S>(s: S) -> Self
+    where
+        S: AsRef<str>,
+    {
+        Self {
+            inner: Inner::Html(s.as_ref().into()),
+        }
+    }
+
+    fn render_diagnostics(report: &ErrorReport) -> String {
+        let mut s = String::new();
+
+        let _ = write!(
+            &mut s,
+            r#"<!doctype html>
+<html>
+<head>
+    <meta charset="utf-8">
+    <title>Server-side Rendering Error</title>
+    <style>
+        body {{ font-family: sans-serif; margin: 2rem; color: #1f2328; }}
+        h1 {{ color: #cf222e; }}
+        pre {{ background: #f6f8fa; padding: 1rem; overflow: auto; }}
+    </style>
+</head>
+<body>
+    <h1>Server-side Rendering Error</h1>
+    <p>Route: <code>{}</code></p>
+    <pre>{}</pre>"#,
+            escape_html(&report.path),
+            escape_html(&report.message),
+        );
+
+        if let Some(ref m) = report.backtrace {
+            let _ = write!(
+                &mut s,
+                r#"
+    <h2>Backtrace</h2>
+    <pre>{}</pre>"#,
+                escape_html(m)
+            );
+        }
+
+        s.push_str(
+            r#"
+    <p>This page is only shown by the development server.</p>
+</body>
+</html>"#,
+        );
+
+        s
+    }
+
+    pub(crate) fn render(&self, report: &ErrorReport, diagnostics: bool) -> Response {
+        match report.backtrace {
+            Some(ref m) => {
+                tracing::error!(path = %report.path, backtrace = %m, "{}", report.message)
+            }
+            None => tracing::error!(path = %report.path, "{}", report.message),
+        }
+
+        let content = if diagnostics {
+            Self::render_diagnostics(report)
+        } else {
+            match self.inner {
+                Inner::Default => "<h1>500 Internal Server Error</h1>".to_string(),
+                Inner::Html(ref m) => m.to_string(),
+            }
+        };
+
+        with_status(html(content), StatusCode::INTERNAL_SERVER_ERROR).into_response()
+    }
+}
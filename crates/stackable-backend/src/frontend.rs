@@ -1,18 +1,37 @@
 use std::borrow::Cow;
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::convert::Infallible;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use std::{fmt, str};
+use std::time::{Duration, Instant, UNIX_EPOCH};
+use std::{env, fmt, str};
 
 use bounce::helmet::HelmetTag;
+use bytes::Bytes;
 use lol_html::{doc_comments, element, rewrite_str, Settings};
 use rust_embed::{EmbeddedFile, RustEmbed};
+use stackable_core::assets::{is_hashed_file_name, AssetManifest};
+use stackable_core::build_info::BuildInfo;
+use stackable_core::frontend::FrontendMount;
+use stackable_core::images::ImageManifest;
+use stackable_core::index_html::{substitute_placeholders, ENV_PREFIX};
+use stackable_core::locales::{HtmlAttrs, LocaleManifest};
+use stackable_core::preload::PreloadManifest;
 use tokio::fs;
 use warp::filters::fs::File;
 use warp::filters::BoxedFilter;
-use warp::path::Tail;
-use warp::reply::{with_header, Response};
+use warp::http::header::{
+    HeaderValue, ACCEPT_RANGES, CACHE_CONTROL, CONTENT_LENGTH, CONTENT_RANGE, CONTENT_TYPE, ETAG,
+    LAST_MODIFIED,
+};
+use warp::http::StatusCode;
+use warp::hyper::Body;
+use warp::path::{Peek, Tail};
+use warp::reply::Response;
 use warp::{Filter, Rejection, Reply};
 
+use crate::error::IndexHtmlResult;
 use crate::utils::ThreadLocalLazy;
 
 type GetFileFn = Box<dyn Send + Fn(&str) -> Option<EmbeddedFile>>;
@@ -34,9 +53,14 @@ impl fmt::Debug for Inner {
     }
 }
 
+/// The built frontend that the server renders pages with and serves assets from.
+///
+/// If the build directory contains `frontends.json`, each frontend listed is served from its
+/// subdirectory at its mount path.
 #[derive(Debug, Clone)]
 pub struct Frontend {
     inner: Inner,
+    subdir: Option<Arc<str>>,
 }
 
 impl Frontend {
@@ -48,9 +72,15 @@ impl Frontend {
 
         Self {
             inner: Inner::Path(p),
+            subdir: None,
         }
     }
 
+    /// Serves a frontend embedded into the server binary.
+    ///
+    /// `stackctl build` sets `--cfg stackable_embedded_frontend` and `STACKABLE_FRONTEND_BUILD_DIR`
+    /// for the embedding. Neither is set by the development server, which passes the build
+    /// directory at runtime instead, so changes to the frontend do not rebuild the backend.
     pub fn new_embedded<E>() -> Self
     where
         E: RustEmbed,
@@ -59,41 +89,252 @@ impl Frontend {
 
         Self {
             inner: Inner::Embed { get_file },
+            subdir: None,
         }
     }
 
-    pub(crate) fn into_warp_filter(self) -> BoxedFilter<(Response,)> {
+    fn with_subdir(&self, name: &str) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            subdir: Some(name.into()),
+        }
+    }
+
+    /// Returns the path of a file in the build directory of this frontend.
+    fn file_path(subdir: Option<&str>, path: &str) -> String {
+        match subdir {
+            Some(m) => format!("{m}/{path}"),
+            None => path.to_string(),
+        }
+    }
+
+    fn read_file(&self, path: &str) -> Option<String> {
+        let path = Self::file_path(self.subdir.as_deref(), path);
+
         match self.inner {
-            Inner::Path(m) => warp::fs::dir(m)
-                .then(|m: File| async move { m.into_response() })
-                .boxed(),
-            Inner::Embed { get_file } => warp::path::tail()
-                .and_then(move |path: Tail| {
-                    let get_file = get_file.clone();
-                    async move {
-                        let get_file = get_file.get();
-
-                        let asset = get_file(path.as_str()).ok_or_else(warp::reject::not_found)?;
-                        let mime = mime_guess::from_path(path.as_str()).first_or_octet_stream();
-
-                        Ok::<_, Rejection>(
-                            with_header(
-                                warp::hyper::Response::new(asset.data),
-                                "content-type",
-                                mime.as_ref(),
-                            )
-                            .into_response(),
-                        )
+            Inner::Path(ref m) => std::fs::read_to_string(m.join(path)).ok(),
+            Inner::Embed { ref get_file } => {
+                let file = (get_file.get())(&path)?;
+                Some(String::from_utf8_lossy(&file.data).into_owned())
+            }
+        }
+    }
+
+    /// Reads the frontends written by stackctl, longest mount path first.
+    pub(crate) fn mounts(&self) -> Vec<FrontendMount> {
+        let mut mounts = self
+            .read_file(FrontendMount::FILE_NAME)
+            .and_then(|m| FrontendMount::list_from_json(&m).ok())
+            .unwrap_or_default();
+        mounts.sort_by_key(|m| Reverse(m.mount_path.trim_end_matches('/').len()));
+
+        mounts
+    }
+
+    /// Returns the mount paths of the frontends, `/` if there is only one frontend.
+    pub(crate) fn mount_paths(&self) -> Vec<String> {
+        let mounts = self.mounts();
+
+        match mounts.is_empty() {
+            true => vec!["/".to_string()],
+            false => mounts.into_iter().map(|m| m.mount_path).collect(),
+        }
+    }
+
+    /// Reads the locales written by stackctl, if localization is enabled.
+    fn locales(&self) -> Option<Arc<LocaleManifest>> {
+        self.read_file(LocaleManifest::FILE_NAME)
+            .and_then(|m| LocaleManifest::from_json(&m).ok())
+            .map(Arc::new)
+    }
+
+    /// Reads the image manifest written by `stackctl build`, if `[build.images]` is enabled.
+    fn image_manifest(&self) -> Option<Arc<ImageManifest>> {
+        self.read_file(ImageManifest::FILE_NAME)
+            .and_then(|m| ImageManifest::from_json(&m).ok())
+            .map(Arc::new)
+    }
+
+    /// Reads the preload manifest written by `stackctl build`.
+    fn preload_manifest(&self) -> Option<Arc<PreloadManifest>> {
+        self.read_file(PreloadManifest::FILE_NAME)
+            .and_then(|m| PreloadManifest::from_json(&m).ok())
+            .map(Arc::new)
+    }
+
+    /// Reads the subresource integrity hashes of the current build, if it is a release build.
+    fn integrity(&self) -> Option<Arc<BTreeMap<String, String>>> {
+        self.asset_manifest()?
+            .builds
+            .into_iter()
+            .find(|m| m.superseded_at.is_none())
+            .map(|m| m.integrity)
+            .filter(|m| !m.is_empty())
+            .map(Arc::new)
+    }
+
+    /// Reads the asset manifest written by `stackctl build`.
+    fn asset_manifest(&self) -> Option<AssetManifest> {
+        self.read_file(AssetManifest::FILE_NAME)
+            .and_then(|m| AssetManifest::from_json(&m).ok())
+    }
+
+    pub(crate) fn into_warp_filter(self) -> BoxedFilter<(Response,)> {
+        let mounts = self.mounts();
+        if mounts.is_empty() {
+            return self.create_assets_filter();
+        }
+
+        let mut routes = warp::any()
+            .and_then(|| async move { Err::<Response, Rejection>(warp::reject::not_found()) })
+            .boxed();
+
+        for mount in mounts {
+            let mut f = warp::any().boxed();
+            for segment in mount.mount_path.split('/').filter(|m| !m.is_empty()) {
+                f = f.and(warp::path(segment.to_string())).boxed();
+            }
+
+            // The mount path itself is rendered by the server.
+            let f = f
+                .and(warp::path::peek())
+                .and_then(|m: Peek| async move {
+                    match m.as_str().is_empty() {
+                        true => Err(warp::reject::not_found()),
+                        false => Ok(()),
                     }
                 })
-                .boxed(),
+                .untuple_one()
+                .and(self.with_subdir(&mount.name).create_assets_filter());
+            routes = routes.or(f).unify().boxed();
         }
+
+        routes
+    }
+
+    fn create_assets_filter(self) -> BoxedFilter<(Response,)> {
+        let asset_manifest = self.asset_manifest().map(Arc::new);
+        let subdir = self.subdir;
+
+        let files_f = match self.inner {
+            Inner::Path(m) => {
+                let dir = match subdir {
+                    Some(ref s) => m.join(s.as_ref()),
+                    None => m,
+                };
+
+                // Files are streamed from disk and range requests are handled by warp.
+                warp::fs::dir(dir)
+                    .then(|m: File| async move {
+                        let is_wasm = m.path().extension().map(|m| m == "wasm").unwrap_or(false);
+                        let mut resp = m.into_response();
+                        if is_wasm {
+                            resp.headers_mut()
+                                .insert(CONTENT_TYPE, HeaderValue::from_static(WASM_MIME));
+                        }
+
+                        resp
+                    })
+                    .boxed()
+            }
+            Inner::Embed { get_file } => {
+                let asset_manifest = asset_manifest.clone();
+
+                warp::path::tail()
+                    .and(warp::header::optional::<String>("range"))
+                    .and(warp::header::optional::<String>("if-range"))
+                    .and_then(
+                        move |path: Tail, range: Option<String>, if_range: Option<String>| {
+                            let get_file = get_file.clone();
+                            let subdir = subdir.clone();
+                            let asset_manifest = asset_manifest.clone();
+                            async move {
+                                let get_file = get_file.get();
+
+                                let asset =
+                                    get_file(&Self::file_path(subdir.as_deref(), path.as_str()))
+                                        .ok_or_else(warp::reject::not_found)?;
+                                let etag = asset_manifest
+                                    .as_ref()
+                                    .and_then(|m| m.hash(path.as_str()))
+                                    .map(|m| format!("\"{m}\""));
+                                let last_modified = asset.metadata.last_modified();
+
+                                // A range is only served if the asset has not changed since the
+                                // client received the rest of it.
+                                let range = range.filter(|_| {
+                                    if_range.as_deref().map_or(true, |m| {
+                                        if_range_matches(m, etag.as_deref(), last_modified)
+                                    })
+                                });
+
+                                Ok::<_, Rejection>(embedded_response(
+                                    asset.data,
+                                    path.as_str(),
+                                    range.as_deref(),
+                                    last_modified,
+                                ))
+                            }
+                        },
+                    )
+                    .boxed()
+            }
+        };
+
+        let asset_manifest = match asset_manifest {
+            Some(m) => m,
+            None => return files_f,
+        };
+        let deployed_at = Instant::now();
+        let hash_manifest = asset_manifest.clone();
+
+        // The path is peeked, so the files filter still receives it.
+        warp::path::peek()
+            .and_then(move |path: Peek| {
+                let asset_manifest = asset_manifest.clone();
+                async move {
+                    if path.as_str() == AssetManifest::FILE_NAME {
+                        return Err(warp::reject::not_found());
+                    }
+
+                    // Assets of previous builds are served for the grace period after a deploy.
+                    if asset_manifest.is_previous_asset(path.as_str())
+                        && deployed_at.elapsed().as_secs() > asset_manifest.grace_period
+                    {
+                        return Err(warp::reject::not_found());
+                    }
+
+                    Ok::<_, Rejection>(())
+                }
+            })
+            .untuple_one()
+            .and(warp::path::peek())
+            .and(warp::header::optional::<String>("if-none-match"))
+            .and(warp::header::optional::<String>("if-modified-since"))
+            .and(files_f)
+            .map(
+                move |path: Peek,
+                      if_none_match: Option<String>,
+                      if_modified_since: Option<String>,
+                      resp: Response| {
+                    cache_response(
+                        resp,
+                        path.as_str(),
+                        hash_manifest.hash(path.as_str()),
+                        if_none_match.as_deref(),
+                        if_modified_since.as_deref(),
+                    )
+                },
+            )
+            .boxed()
     }
 
-    pub(crate) fn index_html(&self) -> IndexHtml {
+    fn index_html(&self) -> IndexHtml {
+        let path = Self::file_path(self.subdir.as_deref(), "index.html");
+
         match self.inner {
-            Inner::Path(ref m) => IndexHtml::Path(m.join("index.html").into()),
-            Inner::Embed { ref get_file } => (get_file.get())("index.html")
+            Inner::Path(ref m) => IndexHtml::Path(m.join(path).into()),
+            Inner::Embed { ref get_file } => (get_file.get())(&path)
                 .map(|m| m.data)
                 .as_deref()
                 .map(String::from_utf8_lossy)
@@ -102,6 +343,331 @@ impl Frontend {
                 .expect("index.html not found!"),
         }
     }
+
+    pub(crate) fn index_htmls(&self) -> IndexHtmls {
+        let mounts = self.mounts();
+
+        let inner = match mounts.is_empty() {
+            true => vec![(
+                None,
+                self.index_html(),
+                self.locales(),
+                self.preload_manifest(),
+                self.image_manifest(),
+                self.integrity(),
+            )],
+            false => mounts
+                .into_iter()
+                .map(|m| {
+                    let frontend = self.with_subdir(&m.name);
+                    (
+                        Some(m),
+                        frontend.index_html(),
+                        frontend.locales(),
+                        frontend.preload_manifest(),
+                        frontend.image_manifest(),
+                        frontend.integrity(),
+                    )
+                })
+                .collect(),
+        };
+
+        IndexHtmls {
+            inner: inner.into(),
+        }
+    }
+}
+
+/// The content type that browsers require to compile wasm modules while they are downloaded.
+const WASM_MIME: &str = "application/wasm";
+
+/// The size of chunks that embedded assets are sent in.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// The part of an asset requested with a `Range` header.
+#[derive(Debug, PartialEq, Eq)]
+enum ByteRange {
+    Full,
+    Partial { start: usize, end: usize },
+    Unsatisfiable,
+}
+
+impl ByteRange {
+    /// Parses a `Range` header for an asset of `len` bytes.
+    ///
+    /// Headers that are invalid or request multiple ranges are ignored and the full asset is
+    /// served, as permitted by RFC 9110.
+    fn parse(header: &str, len: usize) -> Self {
+        let range = match header.trim().strip_prefix("bytes=") {
+            Some(m) if !m.contains(',') => m.trim(),
+            _ => return Self::Full,
+        };
+        let (start, end) = match range.split_once('-') {
+            Some(m) => m,
+            None => return Self::Full,
+        };
+
+        // `bytes=-500` requests the last 500 bytes.
+        if start.is_empty() {
+            return match end.parse::<usize>() {
+                Ok(0) => Self::Unsatisfiable,
+                Ok(_) if len == 0 => Self::Unsatisfiable,
+                Ok(m) => Self::Partial {
+                    start: len.saturating_sub(m),
+                    end: len - 1,
+                },
+                Err(_) => Self::Full,
+            };
+        }
+
+        let start = match start.parse::<usize>() {
+            Ok(m) => m,
+            Err(_) => return Self::Full,
+        };
+        let end = match end {
+            "" => len.saturating_sub(1),
+            m => match m.parse::<usize>() {
+                Ok(m) => m.min(len.saturating_sub(1)),
+                Err(_) => return Self::Full,
+            },
+        };
+
+        if start >= len {
+            Self::Unsatisfiable
+        } else if end < start {
+            Self::Full
+        } else {
+            Self::Partial { start, end }
+        }
+    }
+}
+
+/// Creates the response of an embedded asset, with the part of the asset in the `Range` header.
+/// Adds the cache headers of an asset of a build, and responds with `304 Not Modified` if the
+/// browser has the same version.
+///
+/// Files with a content hash in their names are cached indefinitely, other files, e.g.:
+/// `index.html`, are revalidated with their `ETag` or `Last-Modified`.
+fn cache_response(
+    mut resp: Response,
+    path: &str,
+    hash: Option<&str>,
+    if_none_match: Option<&str>,
+    if_modified_since: Option<&str>,
+) -> Response {
+    // Conditional requests with `If-Modified-Since` may be responded by warp.
+    if !resp.status().is_success() && resp.status() != StatusCode::NOT_MODIFIED {
+        return resp;
+    }
+
+    let cache_control = match is_hashed_file_name(path) {
+        true => "public, max-age=31536000, immutable",
+        false => "no-cache",
+    };
+    resp.headers_mut()
+        .insert(CACHE_CONTROL, HeaderValue::from_static(cache_control));
+
+    let etag = hash.map(|m| format!("\"{m}\""));
+    if let Some(m) = etag.as_deref().and_then(|m| HeaderValue::from_str(m).ok()) {
+        resp.headers_mut().insert(ETAG, m);
+    }
+
+    // `If-None-Match` takes precedence over `If-Modified-Since`.
+    let not_modified = match (if_none_match, etag.as_deref()) {
+        (Some(m), Some(etag)) => m
+            .split(',')
+            .map(|m| m.trim())
+            .any(|m| m == "*" || m.trim_start_matches("W/") == etag),
+        (Some(_), None) => false,
+        (None, _) => {
+            let last_modified = resp
+                .headers()
+                .get(LAST_MODIFIED)
+                .and_then(|m| m.to_str().ok())
+                .and_then(|m| httpdate::parse_http_date(m).ok());
+            let since = if_modified_since.and_then(|m| httpdate::parse_http_date(m).ok());
+
+            matches!((last_modified, since), (Some(m), Some(since)) if m <= since)
+        }
+    };
+
+    if not_modified && resp.status().is_success() {
+        *resp.status_mut() = StatusCode::NOT_MODIFIED;
+        *resp.body_mut() = Body::empty();
+        resp.headers_mut().remove(CONTENT_LENGTH);
+        resp.headers_mut().remove(CONTENT_RANGE);
+    }
+
+    resp
+}
+
+/// Returns whether the validator of an `If-Range` header matches an asset.
+///
+/// Entity tags are compared strongly and dates must match the modification time exactly, as
+/// required by RFC 9110.
+fn if_range_matches(if_range: &str, etag: Option<&str>, last_modified: Option<u64>) -> bool {
+    let if_range = if_range.trim();
+
+    if if_range.starts_with('"') {
+        return etag == Some(if_range);
+    }
+    if if_range.starts_with("W/") {
+        return false;
+    }
+
+    match (httpdate::parse_http_date(if_range), last_modified) {
+        (Ok(m), Some(last_modified)) => m == UNIX_EPOCH + Duration::from_secs(last_modified),
+        _ => false,
+    }
+}
+
+/// Creates a body that is sent in chunks, so large assets are not written to the connection as a
+/// single buffer.
+///
+/// The chunks are slices of `data`, so nothing is copied.
+fn chunked_body(data: Bytes) -> Body {
+    let len = data.len();
+    if len <= CHUNK_SIZE {
+        return data.into();
+    }
+
+    let chunks = (0..len)
+        .step_by(CHUNK_SIZE)
+        .map(move |start| Ok::<_, Infallible>(data.slice(start..(start + CHUNK_SIZE).min(len))));
+
+    Body::wrap_stream(futures::stream::iter(chunks))
+}
+
+fn embedded_response(
+    data: Cow<'static, [u8]>,
+    path: &str,
+    range: Option<&str>,
+    last_modified: Option<u64>,
+) -> Response {
+    let mime = match path.ends_with(".wasm") {
+        true => WASM_MIME.into(),
+        false => mime_guess::from_path(path)
+            .first_or_octet_stream()
+            .to_string(),
+    };
+    // Assets embedded in release builds are borrowed from the binary.
+    let data = match data {
+        Cow::Borrowed(m) => Bytes::from_static(m),
+        Cow::Owned(m) => Bytes::from(m),
+    };
+    let len = data.len();
+
+    let mut resp = match range.map(|m| ByteRange::parse(m, len)) {
+        None | Some(ByteRange::Full) => {
+            let mut resp = Response::new(chunked_body(data));
+            resp.headers_mut()
+                .insert(CONTENT_LENGTH, HeaderValue::from(len));
+
+            resp
+        }
+        Some(ByteRange::Partial { start, end }) => {
+            let mut resp = Response::new(chunked_body(data.slice(start..=end)));
+            *resp.status_mut() = StatusCode::PARTIAL_CONTENT;
+            resp.headers_mut()
+                .insert(CONTENT_LENGTH, HeaderValue::from(end + 1 - start));
+            if let Ok(m) = HeaderValue::from_str(&format!("bytes {start}-{end}/{len}")) {
+                resp.headers_mut().insert(CONTENT_RANGE, m);
+            }
+
+            resp
+        }
+        Some(ByteRange::Unsatisfiable) => {
+            let mut resp = Response::default();
+            *resp.status_mut() = StatusCode::RANGE_NOT_SATISFIABLE;
+            if let Ok(m) = HeaderValue::from_str(&format!("bytes */{len}")) {
+                resp.headers_mut().insert(CONTENT_RANGE, m);
+            }
+
+            return resp;
+        }
+    };
+
+    if let Ok(m) = HeaderValue::from_str(&mime) {
+        resp.headers_mut().insert(CONTENT_TYPE, m);
+    }
+    resp.headers_mut()
+        .insert(ACCEPT_RANGES, HeaderValue::from_static("bytes"));
+
+    let last_modified = last_modified
+        .map(|m| httpdate::fmt_http_date(UNIX_EPOCH + Duration::from_secs(m)))
+        .and_then(|m| HeaderValue::from_str(&m).ok());
+    if let Some(m) = last_modified {
+        resp.headers_mut().insert(LAST_MODIFIED, m);
+    }
+
+    resp
+}
+
+type IndexHtmlEntry = (
+    Option<FrontendMount>,
+    IndexHtml,
+    Option<Arc<LocaleManifest>>,
+    Option<Arc<PreloadManifest>>,
+    Option<Arc<ImageManifest>>,
+    Option<Arc<BTreeMap<String, String>>>,
+);
+
+/// The `index.html` of each frontend, longest mount path first.
+#[derive(Clone)]
+pub(crate) struct IndexHtmls {
+    inner: Arc<[IndexHtmlEntry]>,
+}
+
+impl IndexHtmls {
+    /// Paths outside of all mount paths are rendered with the frontend of the shortest mount path.
+    fn entry(&self, path: &str) -> &IndexHtmlEntry {
+        self.inner
+            .iter()
+            .find(|(mount, ..)| mount.as_ref().map(|m| m.contains(path)).unwrap_or(true))
+            .or_else(|| self.inner.last())
+            .expect("no frontend is available")
+    }
+
+    /// Returns the `index.html` of the frontend that serves the path.
+    pub fn select(&self, path: &str) -> &IndexHtml {
+        &self.entry(path).1
+    }
+
+    /// Returns the locales of the frontend that serves the path.
+    pub fn locales(&self, path: &str) -> Option<Arc<LocaleManifest>> {
+        self.entry(path).2.clone()
+    }
+
+    /// Returns the preload manifest of the frontend that serves the path.
+    pub fn preload_manifest(&self, path: &str) -> Option<&PreloadManifest> {
+        self.entry(path).3.as_deref()
+    }
+
+    /// Returns the subresource integrity hash of an asset, by its url.
+    pub fn integrity(&self, path: &str, href: &str) -> Option<&str> {
+        let (mount, .., integrity) = self.entry(path);
+        let public_url = mount
+            .as_ref()
+            .map(|m| m.mount_path.trim_end_matches('/'))
+            .unwrap_or_default();
+
+        let rel_path = href.strip_prefix(public_url)?.trim_start_matches('/');
+        integrity.as_ref()?.get(rel_path).map(|m| m.as_str())
+    }
+
+    /// Returns the image manifest of the frontend that serves the path.
+    pub fn image_manifest(&self, path: &str) -> Option<Arc<ImageManifest>> {
+        self.entry(path).4.clone()
+    }
+}
+
+/// Returns the value of a placeholder of `index.html` that is not known when the frontend is
+/// built, e.g.: `{{ build_id }}` of the development server.
+fn placeholder_value(name: &str) -> Option<String> {
+    match name {
+        "build_id" => BuildInfo::current_id(),
+        m => env::var(m.strip_prefix(ENV_PREFIX)?).ok(),
+    }
 }
 
 #[derive(Clone)]
@@ -111,24 +677,28 @@ pub(crate) enum IndexHtml {
 }
 
 impl IndexHtml {
-    async fn read_content(&self) -> Cow<'_, str> {
+    async fn read_content(&self) -> IndexHtmlResult<Cow<'_, str>> {
         match self {
-            IndexHtml::Path(p) => fs::read_to_string(&p)
-                .await
-                .map(Cow::from)
-                .expect("TODO: implement failure."),
-
-            IndexHtml::Embedded(ref s) => s.as_ref().into(),
+            IndexHtml::Path(p) => Ok(fs::read_to_string(&p).await.map(Cow::from)?),
+            IndexHtml::Embedded(ref s) => Ok(s.as_ref().into()),
         }
     }
 
-    pub async fn render<I, H, B>(&self, tags: I, head_s: H, body_s: B) -> String
+    /// Renders `index.html`, `html_attrs` of the locale are overridden by attributes in helmet.
+    pub async fn render<I, H, B>(
+        &self,
+        tags: I,
+        html_attrs: Option<HtmlAttrs>,
+        head_s: H,
+        body_s: B,
+    ) -> IndexHtmlResult<String>
     where
         I: IntoIterator<Item = HelmetTag>,
         H: Into<String>,
         B: AsRef<str>,
     {
         let mut head_s = head_s.into();
+        let mut html_attrs = html_attrs;
         let body_s = body_s.as_ref();
 
         let mut html_tag = None;
@@ -148,13 +718,21 @@ impl IndexHtml {
             }
         }
 
-        let index_html_s = self.read_content().await;
+        let mut index_html_s = self.read_content().await?;
+        if index_html_s.contains("{{") {
+            index_html_s = substitute_placeholders(&index_html_s, placeholder_value).into();
+        }
 
-        rewrite_str(
+        let s = rewrite_str(
             &index_html_s,
             Settings {
                 element_content_handlers: vec![
                     element!("html", |h| {
+                        if let Some(m) = html_attrs.take() {
+                            h.set_attribute("lang", &m.lang)?;
+                            h.set_attribute("dir", m.dir.as_str())?;
+                        }
+
                         if let Some(HelmetTag::Html { attrs }) = html_tag.take() {
                             for (k, v) in attrs {
                                 h.set_attribute(k.as_ref(), v.as_ref())?;
@@ -186,7 +764,8 @@ impl IndexHtml {
                 })],
                 ..Default::default()
             },
-        )
-        .expect("failed to render html")
+        )?;
+
+        Ok(s)
     }
 }
@@ -0,0 +1,135 @@
+//! Guards check requests under a path before they are handled, e.g.: authentication.
+//!
+//! A guard either lets a request through or responds to it instead of the endpoint.
+
+use std::fmt;
+use std::future::Future;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use futures::future::BoxFuture;
+use futures::FutureExt;
+use http::status::StatusCode;
+use http::{HeaderMap, Method, Request};
+use warp::filters::BoxedFilter;
+use warp::path::FullPath;
+use warp::reject::not_found;
+use warp::reply::Response;
+use warp::{header, reply, Filter, Rejection, Reply};
+
+type GuardFn = Arc<dyn Send + Sync + Fn(Request<()>) -> BoxFuture<'static, Result<(), Response>>>;
+
+#[derive(Clone)]
+pub(crate) struct Guard {
+    prefix: String,
+    check: GuardFn,
+}
+
+impl fmt::Debug for Guard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Guard")
+            .field("prefix", &self.prefix)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Guard {
+    pub fn new<F, Fut>(prefix: String, check: F) -> Self
+    where
+        F: 'static + Send + Sync + Fn(Request<()>) -> Fut,
+        Fut: 'static + Send + Future<Output = Result<(), Response>>,
+    {
+        Self {
+            prefix,
+            check: Arc::new(move |m| check(m).boxed()),
+        }
+    }
+}
+
+/// Creates the request that is passed to guards, the address of the client is inserted as an
+/// extension if it is known.
+fn guard_request(
+    method: &Method,
+    path: &FullPath,
+    raw_queries: &str,
+    headers: &HeaderMap,
+    remote_addr: Option<SocketAddr>,
+) -> Option<Request<()>> {
+    let uri = match raw_queries {
+        "" => path.as_str().to_string(),
+        m => format!("{}?{}", path.as_str(), m),
+    };
+
+    let mut req = Request::builder()
+        .method(method.clone())
+        .uri(uri)
+        .body(())
+        .ok()?;
+    *req.headers_mut() = headers.clone();
+    if let Some(m) = remote_addr {
+        req.extensions_mut().insert(m);
+    }
+
+    Some(req)
+}
+
+/// Runs the guards that match the path of a request before a filter handles it.
+///
+/// Guards are run in the order they are added.
+pub(crate) fn guard<F>(guards: Vec<Guard>, f: F) -> BoxedFilter<(Response,)>
+where
+    F: 'static + Clone + Send + Sync + Filter<Extract = (Response,), Error = Rejection>,
+{
+    if guards.is_empty() {
+        return f.boxed();
+    }
+
+    let guards: Arc<[Guard]> = guards.into();
+
+    // Responds if a guard denies the request, requests that pass are handled by the filter.
+    let denied_f = warp::method()
+        .and(warp::path::full())
+        .and(warp::query::raw().or_else(|_| async move { Ok::<_, Rejection>((String::new(),)) }))
+        .and(header::headers_cloned())
+        .and(warp::addr::remote())
+        .and_then(
+            move |method: Method,
+                  path: FullPath,
+                  raw_queries: String,
+                  headers: HeaderMap,
+                  remote_addr: Option<SocketAddr>| {
+                let guards = guards.clone();
+
+                async move {
+                    for m in guards
+                        .iter()
+                        .filter(|m| path.as_str().starts_with(&m.prefix))
+                    {
+                        let req = match guard_request(
+                            &method,
+                            &path,
+                            &raw_queries,
+                            &headers,
+                            remote_addr,
+                        ) {
+                            Some(m) => m,
+                            // A request that cannot be checked is not let through.
+                            None => {
+                                return Ok(
+                                    reply::with_status("", StatusCode::BAD_REQUEST).into_response()
+                                )
+                            }
+                        };
+
+                        if let Err(resp) = (m.check)(req).await {
+                            return Ok(resp);
+                        }
+                    }
+
+                    Err(not_found())
+                }
+            },
+        );
+
+    denied_f.or(f).unify().boxed()
+}
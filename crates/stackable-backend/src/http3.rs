@@ -0,0 +1,121 @@
+//! HTTP/3 over QUIC, served on the UDP port of the same number as HTTPS.
+//!
+//! Browsers only connect with HTTP/3 after an HTTPS response advertises it with `Alt-Svc`.
+
+use std::convert::Infallible;
+use std::future::Future;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use bytes::{Buf, Bytes, BytesMut};
+use h3::server::RequestStream;
+use http::HeaderValue;
+use hyper::body::HttpBody;
+use hyper::{Body, Request, Response};
+use stackable_core::server::TlsConfig;
+use tower::{Service, ServiceExt};
+
+use crate::error::TlsResult;
+use crate::tls::server_config;
+
+/// Returns the value of `Alt-Svc` that advertises HTTP/3 on a port.
+pub(crate) fn alt_svc(port: u16) -> HeaderValue {
+    HeaderValue::from_str(&format!("h3=\":{port}\"; ma=86400"))
+        .expect("failed to create alt-svc header")
+}
+
+/// Serves a service with HTTP/3 until the endpoint is closed.
+pub(crate) async fn serve<S, F>(addr: SocketAddr, config: &TlsConfig, svc: S) -> TlsResult<()>
+where
+    S: Service<Request<Body>, Response = Response<Body>, Error = Infallible, Future = F>
+        + Clone
+        + Send
+        + 'static,
+    F: Future<Output = Result<Response<Body>, Infallible>> + Send + 'static,
+{
+    let mut crypto = server_config(config)?;
+    crypto.alpn_protocols = vec![b"h3".to_vec()];
+
+    let endpoint =
+        quinn::Endpoint::server(quinn::ServerConfig::with_crypto(Arc::new(crypto)), addr)?;
+
+    while let Some(connecting) = endpoint.accept().await {
+        let svc = svc.clone();
+
+        tokio::spawn(async move {
+            if let Err(e) = serve_connection(connecting, svc).await {
+                tracing::debug!("HTTP/3 connection failed: {}", e);
+            }
+        });
+    }
+
+    Ok(())
+}
+
+async fn serve_connection<S, F>(connecting: quinn::Connecting, svc: S) -> TlsResult<()>
+where
+    S: Service<Request<Body>, Response = Response<Body>, Error = Infallible, Future = F>
+        + Clone
+        + Send
+        + 'static,
+    F: Future<Output = Result<Response<Body>, Infallible>> + Send + 'static,
+{
+    let conn = connecting.await?;
+    let mut conn = h3::server::Connection::<_, Bytes>::new(h3_quinn::Connection::new(conn)).await?;
+
+    while let Some((req, stream)) = conn.accept().await? {
+        let svc = svc.clone();
+
+        tokio::spawn(async move {
+            if let Err(e) = serve_request(req, stream, svc).await {
+                tracing::debug!("HTTP/3 request failed: {}", e);
+            }
+        });
+    }
+
+    Ok(())
+}
+
+async fn serve_request<S, F>(
+    req: Request<()>,
+    mut stream: RequestStream<h3_quinn::BidiStream<Bytes>, Bytes>,
+    svc: S,
+) -> TlsResult<()>
+where
+    S: Service<Request<Body>, Response = Response<Body>, Error = Infallible, Future = F>,
+    F: Future<Output = Result<Response<Body>, Infallible>>,
+{
+    // Bodies of requests are small, the bridge limits them before they are read anyway.
+    let mut body = BytesMut::new();
+    while let Some(m) = stream.recv_data().await? {
+        body.extend_from_slice(m.chunk());
+    }
+
+    let (parts, ()) = req.into_parts();
+    let resp = match svc
+        .oneshot(Request::from_parts(parts, Body::from(body.freeze())))
+        .await
+    {
+        Ok(m) => m,
+        Err(e) => match e {},
+    };
+
+    let (parts, mut body) = resp.into_parts();
+    stream
+        .send_response(Response::from_parts(parts, ()))
+        .await?;
+
+    while let Some(m) = body.data().await {
+        match m {
+            Ok(m) => stream.send_data(m).await?,
+            Err(e) => {
+                tracing::debug!("failed to read response body: {}", e);
+                break;
+            }
+        }
+    }
+
+    stream.finish().await?;
+
+    Ok(())
+}
@@ -1,25 +1,64 @@
 #![deny(clippy::all)]
 #![deny(missing_debug_implementations)]
 
+#[cfg(feature = "warp-filter")]
+mod app_state;
 #[cfg(feature = "cli")]
 mod cli;
+#[cfg(feature = "warp-filter")]
+mod dashboard;
 mod endpoint;
 #[cfg(feature = "warp-filter")]
+mod error_page;
+#[cfg(feature = "warp-filter")]
 mod frontend;
+#[cfg(feature = "warp-filter")]
+mod guard;
+#[cfg(feature = "http3")]
+mod http3;
+#[cfg(feature = "warp-filter")]
+mod limits;
+pub mod metrics;
+#[cfg(feature = "warp-filter")]
+mod prepared_state;
 mod props;
+#[cfg(feature = "warp-filter")]
+mod rate_limit;
+#[cfg(feature = "cli")]
+mod recorder;
+#[cfg(feature = "warp-filter")]
+pub mod render_cache;
+#[cfg(feature = "warp-filter")]
+mod render_pool;
 mod root;
 #[cfg(feature = "hyper-server")]
 mod server;
+#[cfg(feature = "warp-filter")]
+pub mod session;
+#[cfg(feature = "warp-filter")]
+mod tasks;
+#[cfg(feature = "test")]
+pub mod test;
+#[cfg(feature = "tls")]
+mod tls;
 pub mod trace;
+#[cfg(feature = "tower-service")]
+mod vhost;
 
+#[cfg(feature = "warp-filter")]
+pub use app_state::AppStateBuilder;
 #[cfg(feature = "cli")]
 pub use cli::Cli;
 pub use endpoint::Endpoint;
 #[cfg(feature = "warp-filter")]
+pub use error_page::ErrorPage;
+#[cfg(feature = "warp-filter")]
 pub use frontend::Frontend;
 pub use props::ServerAppProps;
 #[cfg(feature = "hyper-server")]
 pub use server::Server;
+#[cfg(feature = "warp-filter")]
+pub use tasks::Tasks;
 
 pub mod error;
 pub mod utils;
@@ -0,0 +1,366 @@
+//! Concurrency limits of each class of requests, so expensive requests cannot hold up others.
+//!
+//! Requests that cannot be queued are shed with `503 Service Unavailable`, so latency stays
+//! bounded when the server is saturated.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use http::header::{HeaderValue, SET_COOKIE};
+use http::status::StatusCode;
+use http::HeaderMap;
+use stackable_bridge::Bridge;
+use stackable_core::locales::LOCALE_COOKIE_NAME;
+use stackable_core::render_cache::RenderCacheControl;
+use stackable_core::server::ConcurrencyLimit;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use warp::filters::BoxedFilter;
+use warp::reject::Reject;
+use warp::reply::Response;
+use warp::{reply, Filter, Rejection, Reply};
+
+use crate::metrics::Metrics;
+use crate::render_cache::RenderCache;
+
+/// The number of stale renders that are kept for each class, the least recently used render is
+/// removed when it is full.
+const STALE_CAPACITY: usize = 256;
+
+/// The time that a stale render is kept for.
+const STALE_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// The request could not be queued or has waited for longer than the queue timeout.
+#[derive(Debug)]
+struct Overloaded {
+    key: Option<String>,
+}
+
+impl Reject for Overloaded {}
+
+/// The number of requests that are waiting for a permit.
+#[derive(Debug)]
+struct Queued<'a> {
+    queued: &'a AtomicUsize,
+}
+
+impl<'a> Queued<'a> {
+    fn enter(queued: &'a AtomicUsize, max_queued: Option<usize>) -> Option<Self> {
+        let prev = queued.fetch_add(1, Ordering::SeqCst);
+        let m = Self { queued };
+
+        match max_queued {
+            Some(max) if prev >= max => None,
+            _ => Some(m),
+        }
+    }
+}
+
+impl Drop for Queued<'_> {
+    fn drop(&mut self) {
+        self.queued.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct ConcurrencyLimiter {
+    class: &'static str,
+    permits: Arc<Semaphore>,
+    queued: Arc<AtomicUsize>,
+    max_queued: Option<usize>,
+    queue_timeout: Option<Duration>,
+    retry_after: u64,
+    stale: Option<RenderCache>,
+    metrics: Option<Metrics>,
+}
+
+impl ConcurrencyLimiter {
+    pub fn new(class: &'static str, config: &ConcurrencyLimit) -> Self {
+        Self {
+            class,
+            permits: Arc::new(Semaphore::new(config.max_concurrent.max(1))),
+            queued: Arc::default(),
+            max_queued: config.max_queued,
+            queue_timeout: config.queue_timeout_ms.map(Duration::from_millis),
+            retry_after: config.retry_after,
+            stale: config
+                .serve_stale
+                .then(|| RenderCache::in_memory(STALE_CAPACITY, STALE_TTL)),
+            metrics: None,
+        }
+    }
+
+    /// Returns `true` if stale renders are served to shed requests.
+    pub fn serves_stale(&self) -> bool {
+        self.stale.is_some()
+    }
+
+    /// Counts shed requests in metrics.
+    pub fn with_metrics(mut self, metrics: Option<Metrics>) -> Self {
+        self.metrics = metrics;
+
+        self
+    }
+
+    /// Waits until the request can be handled, the permit is released when it is dropped.
+    ///
+    /// Returns `None` if the request should be shed.
+    async fn acquire(&self) -> Option<OwnedSemaphorePermit> {
+        if let Ok(m) = self.permits.clone().try_acquire_owned() {
+            return Some(m);
+        }
+
+        let _queued = Queued::enter(&self.queued, self.max_queued)?;
+        let permits = self.permits.clone();
+
+        // The semaphore is never closed.
+        match self.queue_timeout {
+            Some(m) => tokio::time::timeout(m, permits.acquire_owned())
+                .await
+                .ok()?
+                .ok(),
+            None => permits.acquire_owned().await.ok(),
+        }
+    }
+
+    /// Keeps a successful render of a URL, so it can be served to shed requests.
+    ///
+    /// Requests without a key carry credentials, their renders are not kept. Neither are pages
+    /// that are marked as not cacheable with [`RenderCacheControl::no_cache`].
+    async fn store(&self, key: Option<String>, mut resp: Response) -> Response {
+        let (stale, key) = match (self.stale.as_ref(), key) {
+            (Some(stale), Some(key)) => (stale, key),
+            _ => return resp,
+        };
+
+        let no_cache = resp
+            .extensions()
+            .get::<RenderCacheControl>()
+            .and_then(|m| m.is_cacheable())
+            == Some(false);
+        if no_cache {
+            return resp;
+        }
+
+        // The CSRF token is issued to each browser, so it is kept out of the stale render.
+        let csrf_cookies = take_csrf_cookies(resp.headers_mut());
+        let mut resp = stale.store_page(&key, &[], STALE_TTL, resp).await;
+        for m in csrf_cookies {
+            resp.headers_mut().append(SET_COOKIE, m);
+        }
+
+        resp
+    }
+
+    /// Responds to a shed request with a stale render if there is one, or
+    /// `503 Service Unavailable`.
+    async fn shed(&self, key: Option<&str>) -> Response {
+        let stale = match (self.stale.as_ref(), key) {
+            (Some(stale), Some(key)) => stale.load(key).await,
+            _ => None,
+        };
+
+        if let Some(ref m) = self.metrics {
+            m.record_shed(self.class, stale.is_some());
+        }
+
+        if let Some(mut resp) = stale {
+            resp.headers_mut().remove("x-stackable-cache");
+            resp.headers_mut()
+                .insert("x-stackable-stale", HeaderValue::from_static("true"));
+
+            return resp;
+        }
+
+        reply::with_header(
+            reply::with_status("Service Unavailable", StatusCode::SERVICE_UNAVAILABLE),
+            "retry-after",
+            self.retry_after.to_string(),
+        )
+        .into_response()
+    }
+}
+
+/// Removes the `Set-Cookie` headers of the CSRF token, and returns them.
+fn take_csrf_cookies(headers: &mut HeaderMap) -> Vec<HeaderValue> {
+    let prefix = format!("{}=", Bridge::CSRF_COOKIE);
+    let (csrf, others): (Vec<_>, Vec<_>) = headers
+        .get_all(SET_COOKIE)
+        .iter()
+        .cloned()
+        .partition(|m| m.as_bytes().starts_with(prefix.as_bytes()));
+
+    if !csrf.is_empty() {
+        headers.remove(SET_COOKIE);
+        for m in others {
+            headers.append(SET_COOKIE, m);
+        }
+    }
+
+    csrf
+}
+
+/// Returns the key of stale renders of the request, which is its path, query, locale cookie and
+/// `Accept-Language`.
+///
+/// Requests that may be rendered for a user have no key, see [`RenderCache::is_shareable`].
+fn request_key() -> impl Clone + Send + Filter<Extract = (Option<String>,), Error = Rejection> {
+    warp::path::full()
+        .and(warp::query::raw().or(warp::any().map(String::new)).unify())
+        .and(warp::cookie::optional::<String>(LOCALE_COOKIE_NAME))
+        .and(warp::header::headers_cloned())
+        .map(
+            |path: warp::path::FullPath,
+             query: String,
+             locale_cookie: Option<String>,
+             headers: HeaderMap| {
+                if !RenderCache::is_shareable(&headers) {
+                    return None;
+                }
+
+                let accept_language = headers
+                    .get("accept-language")
+                    .and_then(|m| m.to_str().ok())
+                    .unwrap_or_default();
+                let locale = format!("{}|{accept_language}", locale_cookie.unwrap_or_default());
+
+                Some(RenderCache::key(path.as_str(), &query, Some(&locale)))
+            },
+        )
+}
+
+/// Limits the number of requests that a filter handles at the same time.
+///
+/// Requests that cannot be queued or wait for longer than the queue timeout are shed.
+pub(crate) fn limit<F>(limiter: Option<ConcurrencyLimiter>, f: F) -> BoxedFilter<(Response,)>
+where
+    F: 'static + Clone + Send + Sync + Filter<Extract = (Response,), Error = Rejection>,
+{
+    let limiter = match limiter {
+        Some(m) => m,
+        None => return f.boxed(),
+    };
+
+    let acquire_limiter = limiter.clone();
+    let store_limiter = limiter.clone();
+
+    request_key()
+        .and_then(move |key: Option<String>| {
+            let limiter = acquire_limiter.clone();
+            async move {
+                match limiter.acquire().await {
+                    Some(m) => Ok((key, m)),
+                    None => Err(warp::reject::custom(Overloaded { key })),
+                }
+            }
+        })
+        .untuple_one()
+        .and(f)
+        .and_then(
+            move |key: Option<String>, _permit: OwnedSemaphorePermit, resp: Response| {
+                let limiter = store_limiter.clone();
+                async move { Ok::<_, Rejection>(limiter.store(key, resp).await) }
+            },
+        )
+        .recover(move |e: Rejection| {
+            let limiter = limiter.clone();
+            let overloaded = e.find::<Overloaded>().map(|m| m.key.clone());
+            async move {
+                match overloaded {
+                    Some(m) => Ok(limiter.shed(m.as_deref()).await),
+                    None => Err(e),
+                }
+            }
+        })
+        .unify()
+        .boxed()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limiter() -> ConcurrencyLimiter {
+        ConcurrencyLimiter::new(
+            "render",
+            &ConcurrencyLimit {
+                max_concurrent: 1,
+                max_queued: Some(0),
+                queue_timeout_ms: None,
+                retry_after: 1,
+                serve_stale: true,
+            },
+        )
+    }
+
+    /// A page with a CSRF token, as the index filter responds to a request without cookies.
+    fn page(
+        no_cache: bool,
+    ) -> impl Clone + Send + Sync + Filter<Extract = (Response,), Error = Rejection> {
+        warp::any().map(move || {
+            let mut resp = Response::new("page".into());
+            resp.headers_mut().append(
+                SET_COOKIE,
+                HeaderValue::from_static("stackable-csrf=token; Path=/; SameSite=Strict"),
+            );
+
+            let control = RenderCacheControl::new();
+            if no_cache {
+                control.no_cache();
+            }
+            resp.extensions_mut().insert(control);
+
+            resp
+        })
+    }
+
+    #[tokio::test]
+    async fn shed_request_gets_stale_render() {
+        let limiter = limiter();
+        let f = limit(Some(limiter.clone()), page(false));
+
+        let resp = warp::test::request().path("/").reply(&f).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert!(resp.headers().contains_key(SET_COOKIE));
+
+        // The only permit is taken, so the request is shed.
+        let _permit = limiter.permits.clone().try_acquire_owned().unwrap();
+        let resp = warp::test::request().path("/").reply(&f).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(resp.body().as_ref(), b"page");
+        assert_eq!(resp.headers().get("x-stackable-stale").unwrap(), "true");
+        assert!(!resp.headers().contains_key(SET_COOKIE));
+    }
+
+    #[tokio::test]
+    async fn no_cache_render_is_not_kept() {
+        let limiter = limiter();
+        let f = limit(Some(limiter.clone()), page(true));
+
+        let resp = warp::test::request().path("/").reply(&f).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let _permit = limiter.permits.clone().try_acquire_owned().unwrap();
+        let resp = warp::test::request().path("/").reply(&f).await;
+        assert_eq!(resp.status(), StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    #[tokio::test]
+    async fn request_with_credentials_has_no_key() {
+        let key = warp::test::request()
+            .path("/")
+            .header("cookie", "stackable-csrf=token; session=1")
+            .filter(&request_key())
+            .await
+            .unwrap();
+        assert_eq!(key, None);
+
+        let key = warp::test::request()
+            .path("/")
+            .header("cookie", "stackable-csrf=token")
+            .filter(&request_key())
+            .await
+            .unwrap();
+        assert!(key.is_some());
+    }
+}
@@ -0,0 +1,319 @@
+//! Request counts and latencies, render times, latency and error rates of bridge requests and
+//! requests shed by concurrency limits.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use stackable_bridge::metrics::BridgeMetrics;
+
+/// The upper bounds of latency buckets, in seconds.
+const BUCKETS: [f64; 11] = [
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+/// Measurements of a bridge endpoint.
+#[derive(Debug, Clone, Default)]
+pub struct EndpointMetrics {
+    /// The number of resolved requests.
+    pub requests: u64,
+    /// The number of requests that resolved to an error.
+    pub errors: u64,
+    /// The total time spent resolving requests.
+    pub total_duration: Duration,
+    buckets: [u64; BUCKETS.len()],
+}
+
+/// A latency histogram without labels.
+#[derive(Debug, Clone, Default)]
+struct Histogram {
+    count: u64,
+    sum: Duration,
+    buckets: [u64; BUCKETS.len()],
+}
+
+impl Histogram {
+    fn record(&mut self, duration: Duration) {
+        self.count += 1;
+        self.sum += duration;
+        record_buckets(&mut self.buckets, duration);
+    }
+}
+
+/// Counts a duration in each cumulative bucket that contains it.
+fn record_buckets(buckets: &mut [u64; BUCKETS.len()], duration: Duration) {
+    let secs = duration.as_secs_f64();
+    for (bound, count) in BUCKETS.iter().zip(buckets.iter_mut()) {
+        if secs <= *bound {
+            *count += 1;
+        }
+    }
+}
+
+/// Writes the samples of a histogram, `labels` are written before `le`, e.g.: `endpoint="a",`.
+fn write_histogram(
+    s: &mut String,
+    name: &str,
+    labels: &str,
+    buckets: &[u64; BUCKETS.len()],
+    count: u64,
+    sum: Duration,
+) {
+    for (bound, n) in BUCKETS.iter().zip(buckets.iter()) {
+        let _ = writeln!(s, r#"{name}_bucket{{{labels}le="{bound}"}} {n}"#);
+    }
+    let _ = writeln!(s, r#"{name}_bucket{{{labels}le="+Inf"}} {count}"#);
+
+    let labels = labels.trim_end_matches(',');
+    let _ = writeln!(s, "{name}_sum{{{labels}}} {}", sum.as_secs_f64());
+    let _ = writeln!(s, "{name}_count{{{labels}}} {count}");
+}
+
+/// Escapes a label value of the Prometheus text format.
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Returns the method as a label, other methods share a label so clients cannot add labels.
+fn method_label(method: &str) -> &'static str {
+    match method {
+        "GET" => "GET",
+        "HEAD" => "HEAD",
+        "POST" => "POST",
+        "PUT" => "PUT",
+        "PATCH" => "PATCH",
+        "DELETE" => "DELETE",
+        "OPTIONS" => "OPTIONS",
+        _ => "OTHER",
+    }
+}
+
+impl EndpointMetrics {
+    /// Returns the average latency of requests.
+    pub fn mean_duration(&self) -> Duration {
+        match self.requests {
+            0 => Duration::ZERO,
+            // Divided as nanoseconds, as the number of requests can exceed `u32`.
+            m => Duration::from_nanos((self.total_duration.as_nanos() / u128::from(m)) as u64),
+        }
+    }
+
+    /// Returns the upper bound of the bucket that contains the quantile, in seconds.
+    ///
+    /// Returns `None` if the quantile is above the largest bucket.
+    pub fn quantile_upper_bound(&self, quantile: f64) -> Option<f64> {
+        let rank = (self.requests as f64 * quantile).ceil() as u64;
+
+        BUCKETS
+            .iter()
+            .zip(self.buckets.iter())
+            .find(|(_, count)| **count >= rank)
+            .map(|(bound, _)| *bound)
+    }
+}
+
+/// Collects measurements of bridge requests in memory.
+///
+/// Measurements can be rendered in the Prometheus text format, which is served by the endpoint.
+#[derive(Debug, Clone, Default)]
+pub struct Metrics {
+    inner: Arc<Mutex<BTreeMap<&'static str, EndpointMetrics>>>,
+    /// The number of shed requests of each class and outcome.
+    shed: Arc<Mutex<BTreeMap<(&'static str, &'static str), u64>>>,
+    /// The number of requests of each method and status.
+    requests: Arc<Mutex<BTreeMap<(&'static str, u16), u64>>>,
+    request_durations: Arc<Mutex<Histogram>>,
+    render_durations: Arc<Mutex<Histogram>>,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the measurements of all endpoints, ordered by name.
+    pub fn snapshot(&self) -> Vec<(&'static str, EndpointMetrics)> {
+        let inner = self.inner.lock().expect("failed to lock metrics");
+
+        inner.iter().map(|(k, v)| (*k, v.clone())).collect()
+    }
+
+    /// Counts a request that was shed because the queue of its class is full.
+    pub(crate) fn record_shed(&self, class: &'static str, stale: bool) {
+        let outcome = if stale { "stale" } else { "rejected" };
+        let mut shed = self.shed.lock().expect("failed to lock metrics");
+
+        *shed.entry((class, outcome)).or_default() += 1;
+    }
+
+    /// Counts a response and its latency.
+    pub(crate) fn record_request(&self, method: &str, status: u16, duration: Duration) {
+        let mut requests = self.requests.lock().expect("failed to lock metrics");
+        *requests.entry((method_label(method), status)).or_default() += 1;
+
+        self.request_durations
+            .lock()
+            .expect("failed to lock metrics")
+            .record(duration);
+    }
+
+    /// Records the time of a server-side render.
+    pub(crate) fn record_render(&self, duration: Duration) {
+        self.render_durations
+            .lock()
+            .expect("failed to lock metrics")
+            .record(duration);
+    }
+
+    /// Renders the measurements in the Prometheus text format.
+    pub fn render_prometheus(&self) -> String {
+        let mut s = String::new();
+        let snapshot = self.snapshot();
+
+        let requests = self
+            .requests
+            .lock()
+            .expect("failed to lock metrics")
+            .clone();
+        let _ = writeln!(
+            s,
+            "# HELP stackable_http_requests_total The number of responses of the server."
+        );
+        let _ = writeln!(s, "# TYPE stackable_http_requests_total counter");
+        for ((method, status), count) in requests.iter() {
+            let _ = writeln!(
+                s,
+                r#"stackable_http_requests_total{{method="{}",status="{}"}} {}"#,
+                escape_label(method),
+                status,
+                count
+            );
+        }
+
+        let m = self
+            .request_durations
+            .lock()
+            .expect("failed to lock metrics")
+            .clone();
+        let _ = writeln!(
+            s,
+            "# HELP stackable_http_request_duration_seconds The latency of responses of the server."
+        );
+        let _ = writeln!(
+            s,
+            "# TYPE stackable_http_request_duration_seconds histogram"
+        );
+        write_histogram(
+            &mut s,
+            "stackable_http_request_duration_seconds",
+            "",
+            &m.buckets,
+            m.count,
+            m.sum,
+        );
+
+        let m = self
+            .render_durations
+            .lock()
+            .expect("failed to lock metrics")
+            .clone();
+        let _ = writeln!(
+            s,
+            "# HELP stackable_render_duration_seconds The time of server-side renders of pages."
+        );
+        let _ = writeln!(s, "# TYPE stackable_render_duration_seconds histogram");
+        write_histogram(
+            &mut s,
+            "stackable_render_duration_seconds",
+            "",
+            &m.buckets,
+            m.count,
+            m.sum,
+        );
+
+        let _ = writeln!(
+            s,
+            "# HELP stackable_bridge_requests_total The number of resolved bridge requests."
+        );
+        let _ = writeln!(s, "# TYPE stackable_bridge_requests_total counter");
+        for (name, m) in snapshot.iter() {
+            let _ = writeln!(
+                s,
+                r#"stackable_bridge_requests_total{{endpoint="{}"}} {}"#,
+                escape_label(name),
+                m.requests
+            );
+        }
+
+        let _ = writeln!(
+            s,
+            "# HELP stackable_bridge_errors_total The number of bridge requests that resolved to \
+             an error."
+        );
+        let _ = writeln!(s, "# TYPE stackable_bridge_errors_total counter");
+        for (name, m) in snapshot.iter() {
+            let _ = writeln!(
+                s,
+                r#"stackable_bridge_errors_total{{endpoint="{}"}} {}"#,
+                escape_label(name),
+                m.errors
+            );
+        }
+
+        let _ = writeln!(
+            s,
+            "# HELP stackable_bridge_request_duration_seconds The latency of bridge requests."
+        );
+        let _ = writeln!(
+            s,
+            "# TYPE stackable_bridge_request_duration_seconds histogram"
+        );
+        for (name, m) in snapshot.iter() {
+            write_histogram(
+                &mut s,
+                "stackable_bridge_request_duration_seconds",
+                &format!(r#"endpoint="{}","#, escape_label(name)),
+                &m.buckets,
+                m.requests,
+                m.total_duration,
+            );
+        }
+
+        let shed = self.shed.lock().expect("failed to lock metrics").clone();
+        let _ = writeln!(
+            s,
+            "# HELP stackable_shed_requests_total The number of requests shed by concurrency \
+             limits."
+        );
+        let _ = writeln!(s, "# TYPE stackable_shed_requests_total counter");
+        for ((class, outcome), count) in shed.iter() {
+            let _ = writeln!(
+                s,
+                r#"stackable_shed_requests_total{{class="{}",outcome="{}"}} {}"#,
+                escape_label(class),
+                escape_label(outcome),
+                count
+            );
+        }
+
+        s
+    }
+}
+
+impl BridgeMetrics for Metrics {
+    fn record(&self, name: &'static str, duration: Duration, is_err: bool) {
+        let mut inner = self.inner.lock().expect("failed to lock metrics");
+        let m = inner.entry(name).or_default();
+
+        m.requests += 1;
+        if is_err {
+            m.errors += 1;
+        }
+        m.total_duration += duration;
+        record_buckets(&mut m.buckets, duration);
+    }
+}
@@ -0,0 +1,221 @@
+//! Post-processing of the state that components prepare during server-side rendering.
+//!
+//! Yew embeds the prepared state of each component as a script tag after its html.
+
+use std::io::{self, Write};
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use rand::Rng;
+use stackable_core::cipher::{StateKey, ENCRYPTED_ATTR, KEY_ID_META_NAME};
+use stackable_core::compression::{CompressionAlgorithm, StateCompressionConfig, COMPRESSED_ATTR};
+
+use crate::utils::random_str_with_len;
+
+const OPEN_TAG: &str = r#"<script type="application/x-yew-comp-state">"#;
+const OPEN_TAG_START: &str = r#"<script type="application/x-yew-comp-state""#;
+const CLOSE_TAG: &str = "</script>";
+
+/// Replaces the content of each prepared state in the body.
+///
+/// The mapping function returns the attributes to add to the script tag and the new content.
+pub(crate) fn map_prepared_states<F>(body: &str, mut f: F) -> String
+where
+    F: FnMut(&str) -> (String, String),
+{
+    let mut s = String::with_capacity(body.len());
+    let mut rest = body;
+
+    while let Some(start) = rest.find(OPEN_TAG) {
+        let content_start = start + OPEN_TAG.len();
+        let content_len = match rest[content_start..].find(CLOSE_TAG) {
+            Some(m) => m,
+            None => break,
+        };
+
+        let (attrs, content) = f(&rest[content_start..content_start + content_len]);
+
+        s.push_str(&rest[..start]);
+        s.push_str(OPEN_TAG_START);
+        if !attrs.is_empty() {
+            s.push(' ');
+            s.push_str(&attrs);
+        }
+        s.push('>');
+        s.push_str(&content);
+
+        rest = &rest[content_start + content_len..];
+    }
+
+    s.push_str(rest);
+
+    s
+}
+
+/// Compresses a payload.
+///
+/// Payloads are base64 encoded by Yew, so they are decoded before compression.
+fn compress(algorithm: CompressionAlgorithm, s: &str) -> io::Result<String> {
+    let data = STANDARD
+        .decode(s)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+    let compressed = match algorithm {
+        CompressionAlgorithm::Gzip => {
+            let mut w = GzEncoder::new(Vec::new(), Compression::default());
+            w.write_all(&data)?;
+            w.finish()?
+        }
+        CompressionAlgorithm::Brotli => {
+            let mut w = brotli::CompressorWriter::new(Vec::new(), 4096, 9, 22);
+            w.write_all(&data)?;
+            w.into_inner()
+        }
+    };
+
+    Ok(STANDARD.encode(compressed))
+}
+
+/// The result of processing the prepared states of a response.
+#[derive(Debug, Default)]
+pub(crate) struct ProcessedStates {
+    pub body: String,
+    /// The meta tag that carries the key id, if states are encrypted.
+    pub meta: Option<String>,
+    /// The cookie that delivers the key to the browser, if states are encrypted.
+    pub cookie: Option<String>,
+    /// The size of each state before and after compression, if states are compressed.
+    pub sizes: Vec<(usize, usize)>,
+}
+
+/// Compresses and / or encrypts prepared states with a key derived for a response.
+pub(crate) struct StateProcessor {
+    compression: Option<StateCompressionConfig>,
+    key: Option<StateKey>,
+    /// The meta tag that carries the key id, if states are encrypted.
+    pub meta: Option<String>,
+    /// The cookie that delivers the key to the browser, if states are encrypted.
+    pub cookie: Option<String>,
+    /// The size of each state before and after compression, if states are compressed.
+    pub sizes: Vec<(usize, usize)>,
+}
+
+impl StateProcessor {
+    /// Returns `None` if states are neither compressed nor encrypted.
+    pub fn new(
+        compression: Option<&StateCompressionConfig>,
+        secret: Option<&[u8]>,
+    ) -> Option<Self> {
+        if compression.is_none() && secret.is_none() {
+            return None;
+        }
+
+        let mut meta = None;
+        let mut cookie = None;
+        let key = secret.map(|m| {
+            let key_id = random_str_with_len(16);
+            let key = StateKey::derive(m, &key_id);
+
+            meta = Some(format!(
+                r#"<meta name="{KEY_ID_META_NAME}" content="{key_id}">"#
+            ));
+            cookie = Some(format!(
+                "{}={}; Path=/; Max-Age=60; SameSite=Strict",
+                StateKey::cookie_name(&key_id),
+                key.encode()
+            ));
+
+            key
+        });
+
+        Some(Self {
+            compression: compression.cloned(),
+            key,
+            meta,
+            cookie,
+            sizes: Vec::new(),
+        })
+    }
+
+    /// Processes the prepared states in a body, which must not end in an incomplete state.
+    pub fn process(&mut self, body: &str) -> String {
+        let mut rng = rand::thread_rng();
+
+        map_prepared_states(body, |m| {
+            let mut attrs = Vec::new();
+            let mut content = m.to_string();
+
+            if let Some(ref config) = self.compression {
+                let original_len = content.len();
+
+                if original_len >= config.threshold {
+                    match compress(config.algorithm, &content) {
+                        // Compression may not help with small or random payloads.
+                        Ok(m) if m.len() < original_len => {
+                            attrs.push(format!(
+                                r#"{COMPRESSED_ATTR}="{}""#,
+                                config.algorithm.as_str()
+                            ));
+                            content = m;
+                        }
+                        Ok(_) => {}
+                        Err(e) => tracing::warn!("failed to compress state: {}", e),
+                    }
+                }
+
+                self.sizes.push((original_len, content.len()));
+            }
+
+            if let Some(ref key) = self.key {
+                attrs.push(ENCRYPTED_ATTR.to_string());
+                content = key.encrypt(rng.gen(), &content);
+            }
+
+            (attrs.join(" "), content)
+        })
+    }
+}
+
+/// Returns the length of the part of a streamed body that has no incomplete prepared state.
+///
+/// The rest is held back until the next chunk, so each state is processed as a whole.
+pub(crate) fn complete_len(body: &str) -> usize {
+    if let Some(start) = body.rfind(OPEN_TAG) {
+        if !body[start..].contains(CLOSE_TAG) {
+            return start;
+        }
+    }
+
+    // The open tag may be split between chunks.
+    (1..OPEN_TAG.len())
+        .rev()
+        .filter_map(|n| body.len().checked_sub(n))
+        .find(|&i| body.is_char_boundary(i) && OPEN_TAG.starts_with(&body[i..]))
+        .unwrap_or(body.len())
+}
+
+/// Compresses and / or encrypts the prepared states in the body.
+///
+/// States are encrypted with a key derived for this response. Returns `None` if the body has no
+/// prepared state.
+pub(crate) fn process_prepared_states(
+    body: &str,
+    compression: Option<&StateCompressionConfig>,
+    secret: Option<&[u8]>,
+) -> Option<ProcessedStates> {
+    if !body.contains(OPEN_TAG) {
+        return None;
+    }
+
+    let mut processor = StateProcessor::new(compression, secret)?;
+    let body = processor.process(body);
+
+    Some(ProcessedStates {
+        body,
+        meta: processor.meta,
+        cookie: processor.cookie,
+        sizes: processor.sizes,
+    })
+}
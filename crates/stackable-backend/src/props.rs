@@ -1,6 +1,12 @@
 use std::sync::Arc;
 
 use serde::{Deserialize, Serialize};
+use stackable_bridge::request::RequestContext;
+use stackable_core::app_state::AppState;
+use stackable_core::images::ImageManifest;
+use stackable_core::locales::LocaleManifest;
+use stackable_core::render_cache::RenderCacheControl;
+use stackable_core::session::Session;
 use yew::Properties;
 
 use crate::error::ServerAppResult;
@@ -27,6 +33,13 @@ impl Path {
 pub struct Inner {
     path: Path,
     raw_queries: String,
+    locales: Option<Arc<LocaleManifest>>,
+    locale: Option<String>,
+    images: Option<Arc<ImageManifest>>,
+    session: Option<Session>,
+    render_cache: Option<RenderCacheControl>,
+    request: RequestContext,
+    app_state: AppState,
 }
 
 #[derive(Properties, Debug)]
@@ -52,6 +65,47 @@ impl<T> ServerAppProps<T> {
         &self.inner.raw_queries
     }
 
+    /// Returns the locales of the frontend, if localization is enabled in `stackable.toml`.
+    pub fn locales(&self) -> Option<&LocaleManifest> {
+        self.inner.locales.as_deref()
+    }
+
+    /// Returns the locale negotiated from the locale cookie and `Accept-Language`.
+    pub fn locale(&self) -> Option<&str> {
+        self.inner.locale.as_deref()
+    }
+
+    /// Returns the variants of images in the public directory, if `[build.images]` is enabled in
+    /// `stackable.toml`.
+    pub fn images(&self) -> Option<&ImageManifest> {
+        self.inner.images.as_deref()
+    }
+
+    /// Returns the session of the request, if sessions are enabled in `[server.session]`.
+    ///
+    /// Changes are saved after the page is rendered.
+    pub fn session(&self) -> Option<&Session> {
+        self.inner.session.as_ref()
+    }
+
+    /// Returns the headers, cookies, client address and locale of the request.
+    ///
+    /// Components can read it with `use_request_context` of stackable-bridge.
+    pub fn request(&self) -> &RequestContext {
+        &self.inner.request
+    }
+
+    /// Returns the render cache of the request, if it is enabled in `[server.render-cache]` or
+    /// stale renders are served by `[server.limits]`.
+    pub fn render_cache(&self) -> Option<&RenderCacheControl> {
+        self.inner.render_cache.as_ref()
+    }
+
+    /// Returns the values that are shared by all requests of the server.
+    pub fn app_state(&self) -> &AppState {
+        &self.inner.app_state
+    }
+
     pub fn context(&self) -> &T {
         &self.context
     }
@@ -100,11 +154,29 @@ mod feat_warp_filter {
     use super::*;
 
     impl ServerAppProps<()> {
-        pub(crate) fn from_warp_request(path: FullPath, raw_queries: String) -> Self {
+        #[allow(clippy::too_many_arguments)]
+        pub(crate) fn from_warp_request(
+            path: FullPath,
+            raw_queries: String,
+            locales: Option<Arc<LocaleManifest>>,
+            locale: Option<String>,
+            images: Option<Arc<ImageManifest>>,
+            session: Option<Session>,
+            render_cache: Option<RenderCacheControl>,
+            request: RequestContext,
+            app_state: AppState,
+        ) -> Self {
             Self {
                 inner: Inner {
                     path: Path::Warp(path),
                     raw_queries,
+                    locales,
+                    locale,
+                    images,
+                    session,
+                    render_cache,
+                    request,
+                    app_state,
                 }
                 .into(),
                 context: ().into(),
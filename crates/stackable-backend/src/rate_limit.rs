@@ -0,0 +1,394 @@
+//! Token bucket rate limits of each client, so a single client cannot exhaust the server.
+//!
+//! Each client has a bucket for each rule, which is refilled at the rate of the rule. Requests
+//! are rejected with `429 Too Many Requests` while the bucket is empty.
+
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use http::status::StatusCode;
+use http::HeaderMap;
+use stackable_core::server::{RateLimit, RateLimitConfig};
+use warp::filters::BoxedFilter;
+use warp::path::FullPath;
+use warp::reject::Reject;
+use warp::reply::Response;
+use warp::{header, reply, Filter, Rejection, Reply};
+
+use crate::metrics::Metrics;
+use crate::utils::client_ip;
+
+/// The number of buckets after which buckets that have been refilled are removed.
+///
+/// If too many buckets are still in use, the buckets that were used least recently are removed
+/// until a quarter of the buckets are free.
+const MAX_BUCKETS: usize = 10000;
+
+/// The client has sent more requests than the limit allows.
+#[derive(Debug)]
+struct RateLimited {
+    retry_after: u64,
+}
+
+impl Reject for RateLimited {}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+#[derive(Debug)]
+struct Rule {
+    /// The prefix of the rule, or `None` for the global limit.
+    prefix: Option<String>,
+    /// The number of tokens that are added each second.
+    rate: f64,
+    burst: f64,
+    header: Option<String>,
+}
+
+impl Rule {
+    fn new(prefix: Option<String>, limit: &RateLimit) -> Self {
+        Self {
+            prefix,
+            rate: f64::from(limit.requests) / limit.period_secs.max(1) as f64,
+            burst: f64::from(limit.burst.unwrap_or(limit.requests).max(1)),
+            header: limit.header.clone(),
+        }
+    }
+
+    /// Returns the time it takes to refill an empty bucket.
+    fn refill_time(&self) -> Duration {
+        Duration::from_secs_f64(self.burst / self.rate.max(f64::EPSILON))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct RateLimiter {
+    rules: Arc<Vec<Rule>>,
+    buckets: Arc<Mutex<HashMap<(usize, String), Bucket>>>,
+    metrics: Option<Metrics>,
+}
+
+impl RateLimiter {
+    pub fn new(config: &RateLimitConfig) -> Self {
+        let mut rules = config
+            .rules
+            .iter()
+            .map(|m| Rule::new(Some(m.prefix.clone()), &m.limit))
+            .collect::<Vec<_>>();
+        // The rule with the longest prefix is matched first, the global limit is always matched.
+        rules.sort_by(|a, b| {
+            b.prefix
+                .as_ref()
+                .map(|m| m.len())
+                .cmp(&a.prefix.as_ref().map(|m| m.len()))
+        });
+
+        if let Some(ref m) = config.global {
+            rules.push(Rule::new(None, m));
+        }
+
+        Self {
+            rules: rules.into(),
+            buckets: Arc::default(),
+            metrics: None,
+        }
+    }
+
+    /// Counts rejected requests in metrics.
+    pub fn with_metrics(mut self, metrics: Option<Metrics>) -> Self {
+        self.metrics = metrics;
+
+        self
+    }
+
+    /// Takes a token from the buckets of the client.
+    ///
+    /// Requests are counted by the rule with the longest matching prefix and by the global limit.
+    /// Each is counted by the address of the client, and by the value of the header of the rule
+    /// if it is present. The address is always counted, as clients choose the values of headers
+    /// and could send a new value with each request.
+    ///
+    /// Returns the number of seconds until a token is available if a bucket is empty.
+    fn check(
+        &self,
+        path: &str,
+        ip: &str,
+        header: impl Fn(&str) -> Option<String>,
+    ) -> Result<(), u64> {
+        self.check_at(path, ip, header, Instant::now())
+    }
+
+    fn check_at(
+        &self,
+        path: &str,
+        ip: &str,
+        header: impl Fn(&str) -> Option<String>,
+        now: Instant,
+    ) -> Result<(), u64> {
+        let prefix_rule = self.rules.iter().enumerate().find(|(_, m)| {
+            m.prefix
+                .as_deref()
+                .map(|prefix| path.starts_with(prefix))
+                .unwrap_or(false)
+        });
+        let global_rule = self
+            .rules
+            .iter()
+            .enumerate()
+            .find(|(_, m)| m.prefix.is_none());
+
+        let mut keys = Vec::new();
+        for (idx, rule) in prefix_rule.into_iter().chain(global_rule) {
+            keys.push((idx, format!("ip:{ip}")));
+            if let Some(m) = rule.header.as_deref().and_then(&header) {
+                keys.push((idx, format!("header:{m}")));
+            }
+        }
+
+        let mut buckets = self.buckets.lock().expect("failed to lock rate limits");
+
+        if buckets.len() + keys.len() > MAX_BUCKETS && keys.iter().any(|m| !buckets.contains_key(m))
+        {
+            self.evict(&mut buckets, now);
+        }
+
+        let mut retry_after = None;
+        for key in keys.iter() {
+            let rule = &self.rules[key.0];
+            let bucket = buckets.entry(key.clone()).or_insert(Bucket {
+                tokens: rule.burst,
+                updated: now,
+            });
+
+            let elapsed = now.saturating_duration_since(bucket.updated).as_secs_f64();
+            bucket.tokens = (bucket.tokens + elapsed * rule.rate).min(rule.burst);
+            bucket.updated = now;
+
+            if bucket.tokens < 1.0 {
+                let m = ((1.0 - bucket.tokens) / rule.rate.max(f64::EPSILON)).ceil() as u64;
+                retry_after = retry_after.max(Some(m));
+            }
+        }
+
+        if let Some(m) = retry_after {
+            return Err(m);
+        }
+
+        // Tokens are only taken if no bucket is empty.
+        for key in keys.iter() {
+            if let Some(m) = buckets.get_mut(key) {
+                m.tokens -= 1.0;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Removes buckets that have been refilled, then the buckets that were used least recently
+    /// until a quarter of the buckets are free.
+    ///
+    /// Buckets are removed in batches, so the buckets are not scanned for each new client.
+    fn evict(&self, buckets: &mut HashMap<(usize, String), Bucket>, now: Instant) {
+        // Buckets that have been refilled are the same as new buckets.
+        let rules = &self.rules;
+        buckets.retain(|(idx, _), m| {
+            now.saturating_duration_since(m.updated) < rules[*idx].refill_time()
+        });
+
+        let target = MAX_BUCKETS - MAX_BUCKETS / 4;
+        if buckets.len() <= target {
+            return;
+        }
+
+        let mut updated = buckets.values().map(|m| m.updated).collect::<Vec<_>>();
+        let (_, cutoff, _) = updated.select_nth_unstable(buckets.len() - target - 1);
+        let cutoff = *cutoff;
+
+        buckets.retain(|_, m| m.updated > cutoff);
+    }
+}
+
+/// Limits the rate of requests that a filter handles for each client.
+pub(crate) fn rate_limit<F>(
+    limiter: Option<RateLimiter>,
+    client_ip_header: Option<String>,
+    f: F,
+) -> BoxedFilter<(Response,)>
+where
+    F: 'static + Clone + Send + Sync + Filter<Extract = (Response,), Error = Rejection>,
+{
+    let limiter = match limiter {
+        Some(m) => m,
+        None => return f.boxed(),
+    };
+
+    let check_limiter = limiter.clone();
+
+    warp::path::full()
+        .and(header::headers_cloned())
+        .and(warp::addr::remote())
+        .and_then(
+            move |path: FullPath, headers: HeaderMap, remote_addr: Option<SocketAddr>| {
+                let ip = client_ip(&headers, remote_addr, client_ip_header.as_deref())
+                    .map(|m| m.to_string())
+                    .unwrap_or_default();
+                let result = check_limiter.check(path.as_str(), &ip, |header| {
+                    headers
+                        .get(header)
+                        .and_then(|m| m.to_str().ok())
+                        .map(|m| m.to_string())
+                });
+
+                async move {
+                    result.map_err(|retry_after| warp::reject::custom(RateLimited { retry_after }))
+                }
+            },
+        )
+        .untuple_one()
+        .and(f)
+        .recover(move |e: Rejection| {
+            let metrics = limiter.metrics.clone();
+            async move {
+                let retry_after = match e.find::<RateLimited>() {
+                    Some(m) => m.retry_after.max(1),
+                    None => return Err(e),
+                };
+
+                if let Some(ref metrics) = metrics {
+                    metrics.record_shed("rate_limit", false);
+                }
+
+                Ok(reply::with_header(
+                    reply::with_status("Too Many Requests", StatusCode::TOO_MANY_REQUESTS),
+                    "retry-after",
+                    retry_after.to_string(),
+                )
+                .into_response())
+            }
+        })
+        .unify()
+        .boxed()
+}
+
+#[cfg(test)]
+mod tests {
+    use stackable_core::server::RateLimitRule;
+
+    use super::*;
+
+    fn limit(requests: u32, burst: u32) -> RateLimit {
+        RateLimit {
+            requests,
+            period_secs: 1,
+            burst: Some(burst),
+            header: None,
+        }
+    }
+
+    fn rule(prefix: &str, limit: RateLimit) -> RateLimitRule {
+        RateLimitRule {
+            prefix: prefix.to_string(),
+            limit,
+        }
+    }
+
+    #[test]
+    fn global_limit_applies_to_rules() {
+        let limiter = RateLimiter::new(&RateLimitConfig {
+            global: Some(limit(1, 2)),
+            rules: vec![rule("/api", limit(1, 10))],
+        });
+        let now = Instant::now();
+
+        assert_eq!(limiter.check_at("/api/a", "ip", |_| None, now), Ok(()));
+        assert_eq!(limiter.check_at("/api/a", "ip", |_| None, now), Ok(()));
+        assert_eq!(limiter.check_at("/api/a", "ip", |_| None, now), Err(1));
+        assert_eq!(limiter.check_at("/other", "ip", |_| None, now), Err(1));
+    }
+
+    #[test]
+    fn longest_prefix_rule_applies() {
+        let limiter = RateLimiter::new(&RateLimitConfig {
+            global: Some(limit(1, 10)),
+            rules: vec![rule("/api", limit(1, 1)), rule("/api/public", limit(1, 3))],
+        });
+        let now = Instant::now();
+
+        for _ in 0..3 {
+            assert_eq!(limiter.check_at("/api/public", "ip", |_| None, now), Ok(()));
+        }
+        assert_eq!(limiter.check_at("/api/public", "ip", |_| None, now), Err(1));
+
+        assert_eq!(
+            limiter.check_at("/api/private", "ip", |_| None, now),
+            Ok(())
+        );
+        assert_eq!(
+            limiter.check_at("/api/private", "ip", |_| None, now),
+            Err(1)
+        );
+    }
+
+    #[test]
+    fn tokens_are_not_taken_from_other_buckets_when_one_is_empty() {
+        let limiter = RateLimiter::new(&RateLimitConfig {
+            global: Some(limit(1, 2)),
+            rules: vec![rule("/api", limit(1, 1))],
+        });
+        let now = Instant::now();
+
+        assert_eq!(limiter.check_at("/api", "ip", |_| None, now), Ok(()));
+        assert_eq!(limiter.check_at("/api", "ip", |_| None, now), Err(1));
+        assert_eq!(limiter.check_at("/other", "ip", |_| None, now), Ok(()));
+    }
+
+    #[test]
+    fn buckets_are_refilled() {
+        let limiter = RateLimiter::new(&RateLimitConfig {
+            global: Some(RateLimit {
+                period_secs: 10,
+                ..limit(5, 1)
+            }),
+            rules: Vec::new(),
+        });
+        let now = Instant::now();
+
+        assert_eq!(limiter.check_at("/", "ip", |_| None, now), Ok(()));
+        assert_eq!(limiter.check_at("/", "ip", |_| None, now), Err(2));
+        assert_eq!(
+            limiter.check_at("/", "ip", |_| None, now + Duration::from_secs(1)),
+            Err(1)
+        );
+        assert_eq!(
+            limiter.check_at("/", "ip", |_| None, now + Duration::from_secs(2)),
+            Ok(())
+        );
+        assert_eq!(limiter.check_at("/", "other", |_| None, now), Ok(()));
+    }
+
+    #[test]
+    fn header_does_not_bypass_address() {
+        let limiter = RateLimiter::new(&RateLimitConfig {
+            global: Some(RateLimit {
+                header: Some("x-api-key".to_string()),
+                ..limit(1, 1)
+            }),
+            rules: Vec::new(),
+        });
+        let now = Instant::now();
+
+        assert_eq!(
+            limiter.check_at("/", "ip", |_| Some("a".to_string()), now),
+            Ok(())
+        );
+        assert_eq!(
+            limiter.check_at("/", "ip", |_| Some("b".to_string()), now),
+            Err(1)
+        );
+    }
+}
@@ -0,0 +1,52 @@
+//! Records the results of bridge queries and mutations to a file, for `stackctl serve --record`.
+
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use stackable_bridge::recorder::BridgeRecorder;
+use stackable_core::mock::{Mock, MockFile};
+
+/// A recorder that writes results as a [`MockFile`], which stackctl replays with
+/// `stackctl serve --replay`.
+#[derive(Debug)]
+pub(crate) struct FileRecorder {
+    path: PathBuf,
+    file: Mutex<MockFile>,
+}
+
+impl FileRecorder {
+    /// Opens a recording, results are added to the recording if the file exists.
+    pub fn open(path: PathBuf) -> io::Result<Self> {
+        let file = match fs::read_to_string(&path) {
+            Ok(m) => serde_json::from_str(&m)?,
+            Err(e) if e.kind() == ErrorKind::NotFound => MockFile::default(),
+            Err(e) => return Err(e),
+        };
+
+        Ok(Self {
+            path,
+            file: Mutex::new(file),
+        })
+    }
+}
+
+impl BridgeRecorder for FileRecorder {
+    fn record(&self, mock: Mock) {
+        let mut file = self.file.lock().expect("failed to lock recording");
+
+        // A later result of the same request replaces the earlier one.
+        file.mocks
+            .retain(|m| m.name != mock.name || m.input != mock.input);
+        file.mocks.push(mock);
+
+        let written = serde_json::to_string_pretty(&*file)
+            .map_err(io::Error::from)
+            .and_then(|m| fs::write(&self.path, m));
+
+        if let Err(e) = written {
+            tracing::error!("failed to write recording: {}", e);
+        }
+    }
+}
@@ -0,0 +1,149 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+
+use super::RenderCacheStore;
+use crate::error::RenderCacheResult;
+
+#[derive(Debug)]
+struct Entry {
+    data: Vec<u8>,
+    tags: Vec<String>,
+    expires_at: Instant,
+    /// The position of the page in `order`.
+    used_at: u64,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    entries: HashMap<String, Entry>,
+    /// Keys of pages from the least recently used.
+    order: BTreeMap<u64, String>,
+    tags: HashMap<String, HashSet<String>>,
+    clock: u64,
+}
+
+impl Inner {
+    fn tick(&mut self) -> u64 {
+        self.clock += 1;
+        self.clock
+    }
+
+    fn remove(&mut self, key: &str) {
+        let entry = match self.entries.remove(key) {
+            Some(m) => m,
+            None => return,
+        };
+
+        self.order.remove(&entry.used_at);
+        for tag in entry.tags {
+            if let Some(keys) = self.tags.get_mut(&tag) {
+                keys.remove(key);
+                if keys.is_empty() {
+                    self.tags.remove(&tag);
+                }
+            }
+        }
+    }
+}
+
+/// A render cache store that keeps pages in memory.
+///
+/// The least recently used page is removed when the store is full.
+#[derive(Debug)]
+pub struct MemoryRenderCacheStore {
+    capacity: usize,
+    inner: Mutex<Inner>,
+}
+
+impl MemoryRenderCacheStore {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            inner: Mutex::default(),
+        }
+    }
+}
+
+#[async_trait]
+impl RenderCacheStore for MemoryRenderCacheStore {
+    async fn load(&self, key: &str) -> RenderCacheResult<Option<Vec<u8>>> {
+        let mut inner = self.inner.lock().expect("failed to lock cached pages");
+
+        let expired = match inner.entries.get(key) {
+            Some(m) => m.expires_at <= Instant::now(),
+            None => return Ok(None),
+        };
+        if expired {
+            inner.remove(key);
+            return Ok(None);
+        }
+
+        let used_at = inner.tick();
+        let entry = inner.entries.get_mut(key).expect("page is cached");
+        let last_used_at = std::mem::replace(&mut entry.used_at, used_at);
+        let data = entry.data.clone();
+
+        inner.order.remove(&last_used_at);
+        inner.order.insert(used_at, key.to_string());
+
+        Ok(Some(data))
+    }
+
+    async fn store(
+        &self,
+        key: &str,
+        data: &[u8],
+        tags: &[String],
+        ttl: Duration,
+    ) -> RenderCacheResult<()> {
+        if self.capacity == 0 {
+            return Ok(());
+        }
+
+        let mut inner = self.inner.lock().expect("failed to lock cached pages");
+
+        inner.remove(key);
+        while inner.entries.len() >= self.capacity {
+            let oldest = match inner.order.values().next() {
+                Some(m) => m.clone(),
+                None => break,
+            };
+            inner.remove(&oldest);
+        }
+
+        let used_at = inner.tick();
+        for tag in tags {
+            inner
+                .tags
+                .entry(tag.clone())
+                .or_default()
+                .insert(key.to_string());
+        }
+        inner.order.insert(used_at, key.to_string());
+        inner.entries.insert(
+            key.to_string(),
+            Entry {
+                data: data.to_vec(),
+                tags: tags.to_vec(),
+                expires_at: Instant::now() + ttl,
+                used_at,
+            },
+        );
+
+        Ok(())
+    }
+
+    async fn invalidate(&self, tag: &str) -> RenderCacheResult<()> {
+        let mut inner = self.inner.lock().expect("failed to lock cached pages");
+
+        let keys = inner.tags.remove(tag).unwrap_or_default();
+        for key in keys {
+            inner.remove(&key);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,255 @@
+//! Caching of server-rendered pages.
+//!
+//! A [`RenderCacheStore`] keeps encoded pages with an expiry time and the keys that invalidate
+//! them. Pages are cached per path, queries and locale.
+
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use http::header::{HeaderName, HeaderValue, AUTHORIZATION, COOKIE, SET_COOKIE};
+use http::status::StatusCode;
+use http::HeaderMap;
+use serde::{Deserialize, Serialize};
+use stackable_bridge::Bridge;
+use stackable_core::locales::LOCALE_COOKIE_NAME;
+use stackable_core::preload::route_matches;
+use stackable_core::render_cache::RenderCacheControl;
+use stackable_core::server::{RenderCacheConfig, RenderCacheStoreConfig};
+use warp::hyper::body::HttpBody;
+use warp::reply::Response;
+use warp::Reply;
+
+use crate::error::{RenderCacheError, RenderCacheResult};
+
+mod memory;
+#[cfg(feature = "render-cache-redis")]
+mod redis;
+
+pub use memory::MemoryRenderCacheStore;
+
+#[cfg(feature = "render-cache-redis")]
+pub use self::redis::RedisRenderCacheStore;
+
+/// A backend that caches rendered pages.
+#[async_trait]
+pub trait RenderCacheStore: 'static + Send + Sync + fmt::Debug {
+    /// Loads a page, returns `None` if the page is not cached or has expired.
+    async fn load(&self, key: &str) -> RenderCacheResult<Option<Vec<u8>>>;
+
+    /// Stores a page that expires after `ttl` and is invalidated by any of `tags`.
+    async fn store(
+        &self,
+        key: &str,
+        data: &[u8],
+        tags: &[String],
+        ttl: Duration,
+    ) -> RenderCacheResult<()>;
+
+    /// Removes all pages with a tag.
+    async fn invalidate(&self, tag: &str) -> RenderCacheResult<()>;
+}
+
+/// Creates a render cache store from the render cache configuration.
+pub fn create_store(config: &RenderCacheConfig) -> RenderCacheResult<Arc<dyn RenderCacheStore>> {
+    match config.store {
+        RenderCacheStoreConfig::Memory => {
+            Ok(Arc::new(MemoryRenderCacheStore::new(config.capacity)))
+        }
+        #[cfg(feature = "render-cache-redis")]
+        RenderCacheStoreConfig::Redis { ref url } => Ok(Arc::new(RedisRenderCacheStore::new(url)?)),
+        #[cfg(not(feature = "render-cache-redis"))]
+        RenderCacheStoreConfig::Redis { .. } => Err(RenderCacheError::Unsupported("redis")),
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedPage {
+    headers: Vec<(String, Vec<u8>)>,
+    body: Vec<u8>,
+}
+
+/// A cache of server-rendered pages.
+///
+/// Clones share the same store, so a clone can be kept to invalidate pages outside of requests.
+#[derive(Debug, Clone)]
+pub struct RenderCache {
+    store: Arc<dyn RenderCacheStore>,
+    routes: Arc<[String]>,
+    ttl: Duration,
+}
+
+impl RenderCache {
+    pub fn new(store: Arc<dyn RenderCacheStore>, config: &RenderCacheConfig) -> Self {
+        Self {
+            store,
+            routes: config.routes.clone().into(),
+            ttl: Duration::from_secs(config.ttl),
+        }
+    }
+
+    /// Creates a cache in memory without routes, pages are only cached with
+    /// [`store_page`](Self::store_page).
+    pub(crate) fn in_memory(capacity: usize, ttl: Duration) -> Self {
+        Self {
+            store: Arc::new(MemoryRenderCacheStore::new(capacity)),
+            routes: Vec::new().into(),
+            ttl,
+        }
+    }
+
+    /// Removes cached pages of a path, e.g.: `/posts/1`, or of a key that pages have added.
+    ///
+    /// Pages of a path are removed with all queries and locales.
+    pub async fn invalidate(&self, key: &str) -> RenderCacheResult<()> {
+        self.store.invalidate(key).await
+    }
+
+    /// Returns the key that a page is cached at.
+    pub(crate) fn key(path: &str, raw_queries: &str, locale: Option<&str>) -> String {
+        let mut key = path.to_string();
+        if !raw_queries.is_empty() {
+            key.push('?');
+            key.push_str(raw_queries);
+        }
+        if let Some(m) = locale {
+            key.push('#');
+            key.push_str(m);
+        }
+
+        key
+    }
+
+    /// Returns `true` if a page rendered for a request can be shared with other users.
+    ///
+    /// Requests with authorization or cookies other than the CSRF token and the locale may be
+    /// rendered for a user.
+    pub(crate) fn is_shareable(headers: &HeaderMap) -> bool {
+        if headers.contains_key(AUTHORIZATION) {
+            return false;
+        }
+
+        headers.get_all(COOKIE).iter().all(|m| match m.to_str() {
+            Ok(m) => m
+                .split(';')
+                .map(|m| m.trim())
+                .filter(|m| !m.is_empty())
+                .all(|m| {
+                    let name = m.split_once('=').map(|(name, _)| name).unwrap_or(m);
+                    name == Bridge::CSRF_COOKIE || name == LOCALE_COOKIE_NAME
+                }),
+            Err(_) => false,
+        })
+    }
+
+    /// Returns a cached page, errors are logged and treated as misses.
+    pub(crate) async fn load(&self, key: &str) -> Option<Response> {
+        let data = match self.store.load(key).await {
+            Ok(m) => m?,
+            Err(e) => {
+                tracing::warn!("failed to load cached page: {}", e);
+                return None;
+            }
+        };
+
+        let page: CachedPage = match bincode::deserialize(&data) {
+            Ok(m) => m,
+            Err(e) => {
+                tracing::warn!("failed to decode cached page: {}", e);
+                return None;
+            }
+        };
+
+        let mut resp = Response::new(page.body.into());
+        for (name, value) in page.headers {
+            if let (Ok(name), Ok(value)) = (
+                HeaderName::from_bytes(name.as_bytes()),
+                HeaderValue::from_bytes(&value),
+            ) {
+                resp.headers_mut().append(name, value);
+            }
+        }
+        resp.headers_mut()
+            .insert("x-stackable-cache", HeaderValue::from_static("hit"));
+
+        Some(resp)
+    }
+
+    /// Caches a rendered page if it is cacheable.
+    pub(crate) async fn store(
+        &self,
+        key: &str,
+        path: &str,
+        control: &RenderCacheControl,
+        resp: Response,
+    ) -> Response {
+        let cacheable = control
+            .is_cacheable()
+            .unwrap_or_else(|| self.routes.iter().any(|m| route_matches(m, path)));
+        if !cacheable {
+            return resp;
+        }
+
+        let mut tags = control.keys();
+        tags.push(path.to_string());
+
+        self.store_page(key, &tags, control.ttl().unwrap_or(self.ttl), resp)
+            .await
+    }
+
+    /// Caches a page that expires after `ttl` and is invalidated by any of `tags`.
+    ///
+    /// Streamed pages and responses that are not successful or set cookies are not cached.
+    pub(crate) async fn store_page(
+        &self,
+        key: &str,
+        tags: &[String],
+        ttl: Duration,
+        resp: Response,
+    ) -> Response {
+        if resp.status() != StatusCode::OK
+            || resp.headers().contains_key(SET_COOKIE)
+            || resp.body().size_hint().exact().is_none()
+        {
+            return resp;
+        }
+
+        let (parts, body) = resp.into_parts();
+        let body = match warp::hyper::body::to_bytes(body).await {
+            Ok(m) => m,
+            Err(e) => {
+                tracing::error!("failed to read rendered page: {}", e);
+                return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+            }
+        };
+
+        let page = CachedPage {
+            headers: parts
+                .headers
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.as_bytes().to_vec()))
+                .collect(),
+            body: body.to_vec(),
+        };
+
+        let result = match bincode::serialize(&page) {
+            Ok(m) => self.store.store(key, &m, tags, ttl).await,
+            Err(e) => Err(e.into()),
+        };
+        if let Err(e) = result {
+            tracing::warn!("failed to cache page: {}", e);
+        }
+
+        Response::from_parts(parts, body.into())
+    }
+
+    /// Applies the invalidations of a request.
+    pub(crate) async fn apply(&self, control: &RenderCacheControl) {
+        for key in control.take_invalidated() {
+            if let Err(e) = self.invalidate(&key).await {
+                tracing::warn!("failed to invalidate cached pages of {}: {}", key, e);
+            }
+        }
+    }
+}
@@ -0,0 +1,98 @@
+use std::time::Duration;
+
+use ::redis::{cmd, pipe, Client};
+use async_trait::async_trait;
+
+use super::RenderCacheStore;
+use crate::error::RenderCacheResult;
+
+/// A render cache store that keeps pages in a Redis server.
+///
+/// Expiry is handled by Redis, tags expire with the last page that they are added to. This
+/// requires Redis 7 or later.
+#[derive(Debug)]
+pub struct RedisRenderCacheStore {
+    client: Client,
+}
+
+impl RedisRenderCacheStore {
+    pub fn new(url: &str) -> RenderCacheResult<Self> {
+        Ok(Self {
+            client: Client::open(url)?,
+        })
+    }
+
+    fn key(key: &str) -> String {
+        format!("stackable:render:{key}")
+    }
+
+    fn tag_key(tag: &str) -> String {
+        format!("stackable:render-tag:{tag}")
+    }
+}
+
+#[async_trait]
+impl RenderCacheStore for RedisRenderCacheStore {
+    async fn load(&self, key: &str) -> RenderCacheResult<Option<Vec<u8>>> {
+        let mut conn = self.client.get_async_connection().await?;
+
+        Ok(cmd("GET")
+            .arg(Self::key(key))
+            .query_async(&mut conn)
+            .await?)
+    }
+
+    async fn store(
+        &self,
+        key: &str,
+        data: &[u8],
+        tags: &[String],
+        ttl: Duration,
+    ) -> RenderCacheResult<()> {
+        let mut conn = self.client.get_async_connection().await?;
+        let ttl = u64::try_from(ttl.as_millis()).unwrap_or(u64::MAX);
+
+        let mut p = pipe();
+        p.cmd("SET")
+            .arg(Self::key(key))
+            .arg(data)
+            .arg("PX")
+            .arg(ttl)
+            .ignore();
+        for tag in tags {
+            p.cmd("SADD").arg(Self::tag_key(tag)).arg(key).ignore();
+            // GT only extends the expiry, so tags outlive their pages.
+            p.cmd("PEXPIRE")
+                .arg(Self::tag_key(tag))
+                .arg(ttl)
+                .arg("GT")
+                .ignore();
+            p.cmd("PEXPIRE")
+                .arg(Self::tag_key(tag))
+                .arg(ttl)
+                .arg("NX")
+                .ignore();
+        }
+        p.query_async::<_, ()>(&mut conn).await?;
+
+        Ok(())
+    }
+
+    async fn invalidate(&self, tag: &str) -> RenderCacheResult<()> {
+        let mut conn = self.client.get_async_connection().await?;
+
+        let keys: Vec<String> = cmd("SMEMBERS")
+            .arg(Self::tag_key(tag))
+            .query_async(&mut conn)
+            .await?;
+
+        let mut p = pipe();
+        for key in keys.iter() {
+            p.cmd("DEL").arg(Self::key(key)).ignore();
+        }
+        p.cmd("DEL").arg(Self::tag_key(tag)).ignore();
+        p.query_async::<_, ()>(&mut conn).await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,33 @@
+//! A dedicated runtime that renders pages, so expensive pages cannot delay other requests.
+
+use std::future::Future;
+use std::io;
+
+use stackable_core::server::RenderConfig;
+use yew::platform::Runtime;
+
+#[derive(Debug, Clone)]
+pub(crate) struct RenderPool {
+    runtime: Runtime,
+}
+
+impl RenderPool {
+    pub fn new(config: &RenderConfig) -> io::Result<Self> {
+        let mut builder = Runtime::builder();
+        if let Some(m) = config.workers {
+            builder.worker_threads(m);
+        }
+
+        Ok(Self {
+            runtime: builder.build()?,
+        })
+    }
+
+    pub fn spawn_pinned<F, Fut>(&self, create_task: F)
+    where
+        F: 'static + Send + FnOnce() -> Fut,
+        Fut: 'static + Future<Output = ()>,
+    {
+        self.runtime.spawn_pinned(create_task);
+    }
+}
@@ -3,6 +3,10 @@ use std::rc::Rc;
 
 use bounce::helmet::{HelmetBridge, StaticWriter};
 use bounce::{use_atom_setter, BounceRoot};
+use stackable_bridge::hydration::{HydrationData, HydrationDataState};
+use stackable_bridge::profiler::{RenderProfile, RenderProfileState};
+use stackable_bridge::request::RequestContextState;
+use stackable_bridge::response::{ResponseControl, ResponseControlState};
 use stackable_bridge::state::{BridgeMetadataState, BridgeState};
 use stackable_bridge::{Bridge, BridgeMetadata};
 use yew::prelude::*;
@@ -17,6 +21,11 @@ pub(crate) struct StackableRootProps<CTX, BCTX> {
     pub server_app_props: ServerAppProps<CTX>,
     pub bridge: Bridge,
     pub bridge_metadata: Rc<BridgeMetadata<BCTX>>,
+    /// Records render durations of profiled components, only set by the development server.
+    pub render_profile: Option<RenderProfile>,
+    /// Receives the status that components set, not set for discarded renders.
+    pub response_control: Option<ResponseControl>,
+    pub hydration_data: HydrationData,
 }
 
 impl<CTX, BCTX> PartialEq for StackableRootProps<CTX, BCTX> {
@@ -25,6 +34,9 @@ impl<CTX, BCTX> PartialEq for StackableRootProps<CTX, BCTX> {
             && self.server_app_props == other.server_app_props
             && self.bridge == other.bridge
             && Rc::ptr_eq(&self.bridge_metadata, &other.bridge_metadata)
+            && self.render_profile == other.render_profile
+            && self.response_control == other.response_control
+            && self.hydration_data == other.hydration_data
     }
 }
 
@@ -35,6 +47,9 @@ impl<CTX, BCTX> Clone for StackableRootProps<CTX, BCTX> {
             server_app_props: self.server_app_props.clone(),
             bridge: self.bridge.clone(),
             bridge_metadata: self.bridge_metadata.clone(),
+            render_profile: self.render_profile.clone(),
+            response_control: self.response_control.clone(),
+            hydration_data: self.hydration_data.clone(),
         }
     }
 }
@@ -50,6 +65,9 @@ where
         server_app_props,
         bridge,
         bridge_metadata,
+        render_profile,
+        response_control,
+        hydration_data,
         ..
     } = props.clone();
 
@@ -65,11 +83,28 @@ where
 
     let set_bridge = use_atom_setter::<BridgeState>();
     let set_bridge_metadata = use_atom_setter::<BridgeMetadataState<BCTX>>();
+    let set_render_profile = use_atom_setter::<RenderProfileState>();
+    let set_request_context = use_atom_setter::<RequestContextState>();
+    let set_response_control = use_atom_setter::<ResponseControlState>();
+    let set_hydration_data = use_atom_setter::<HydrationDataState>();
+    let request_context = Rc::new(server_app_props.request().clone());
 
     use_memo(
         move |_| {
             set_bridge(BridgeState { inner: bridge });
             set_bridge_metadata(BridgeMetadataState::from(bridge_metadata));
+            set_render_profile(RenderProfileState {
+                inner: render_profile,
+            });
+            set_request_context(RequestContextState {
+                inner: request_context,
+            });
+            set_response_control(ResponseControlState {
+                inner: response_control,
+            });
+            set_hydration_data(HydrationDataState {
+                inner: hydration_data,
+            });
         },
         (),
     );
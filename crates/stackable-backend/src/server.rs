@@ -1,16 +1,31 @@
 use std::convert::Infallible;
+use std::fmt;
 use std::future::Future;
+#[cfg(feature = "tls")]
+use std::io;
 use std::net::SocketAddr;
+use std::time::Duration;
 
-use futures::TryStream;
+use futures::channel::oneshot;
+use futures::future::{self, BoxFuture, Either};
+use futures::{FutureExt, TryStream};
 use hyper::body::HttpBody;
 use hyper::server::accept::Accept;
 use hyper::server::conn::AddrIncoming;
 use hyper::{Body, Request, Response};
+#[cfg(feature = "tls")]
+use stackable_core::server::TlsConfig;
 use tokio::io::{AsyncRead, AsyncWrite};
+#[cfg(feature = "tls")]
+use tokio::net::{TcpListener, TcpStream};
+#[cfg(feature = "tls")]
+use tokio_rustls::server::TlsStream;
 use tower::Service;
 use yew::platform::Runtime;
 
+#[cfg(feature = "tls")]
+use crate::error::TlsResult;
+
 // An executor to process requests on the Yew runtime.
 //
 // By spawning requests on the Yew runtime,
@@ -33,10 +48,31 @@ where
     }
 }
 
+type ShutdownHook = Box<dyn Send + FnOnce() -> BoxFuture<'static, ()>>;
+
+/// How the server shuts down.
+#[derive(Default)]
+pub(crate) struct Shutdown {
+    signal: Option<BoxFuture<'static, ()>>,
+    timeout: Option<Duration>,
+    hooks: Vec<ShutdownHook>,
+}
+
+impl fmt::Debug for Shutdown {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Shutdown")
+            .field("graceful", &self.signal.is_some())
+            .field("timeout", &self.timeout)
+            .field("hooks", &self.hooks.len())
+            .finish()
+    }
+}
+
 #[derive(Debug)]
 pub struct Server<I> {
     inner: hyper::server::Builder<I>,
     rt: Option<Runtime>,
+    shutdown: Shutdown,
 }
 
 impl<I> Server<I> {
@@ -44,9 +80,21 @@ impl<I> Server<I> {
         Server {
             inner: hyper::server::Server::bind(&addr.into()),
             rt: None,
+            shutdown: Shutdown::default(),
         }
     }
 
+    /// Serves with a listener that is already bound, e.g.: to an ephemeral port.
+    ///
+    /// This must be called in the context of a tokio runtime.
+    pub fn from_tcp(listener: std::net::TcpListener) -> hyper::Result<Server<AddrIncoming>> {
+        Ok(Server {
+            inner: hyper::server::Server::from_tcp(listener)?,
+            rt: None,
+            shutdown: Shutdown::default(),
+        })
+    }
+
     pub fn from_stream<S, A, T, E>(stream: S) -> Server<impl Accept<Conn = T, Error = E>>
     where
         S: TryStream<Ok = T, Error = E, Item = Result<T, E>> + Send,
@@ -56,8 +104,89 @@ impl<I> Server<I> {
         Server {
             inner: hyper::server::Server::builder(hyper::server::accept::from_stream(stream)),
             rt: None,
+            shutdown: Shutdown::default(),
         }
     }
+
+    /// Serves over TLS, HTTP/2 is negotiated with ALPN if it is enabled.
+    ///
+    /// This must be called in the context of a tokio runtime.
+    #[cfg(feature = "tls")]
+    pub async fn bind_tls(
+        addr: impl Into<SocketAddr> + 'static,
+        config: &TlsConfig,
+    ) -> TlsResult<Server<impl Accept<Conn = TlsStream<TcpStream>, Error = io::Error>>> {
+        let listener = TcpListener::bind(addr.into()).await?;
+        let incoming = crate::tls::incoming(listener, config)?;
+
+        Ok(Server {
+            inner: hyper::server::Server::builder(hyper::server::accept::from_stream(incoming)),
+            rt: None,
+            shutdown: Shutdown::default(),
+        })
+    }
+
+    /// Stops accepting connections when `signal` resolves, and waits for requests in flight.
+    ///
+    /// If a timeout is set, requests that are still in flight after it are dropped.
+    pub fn with_graceful_shutdown<F>(mut self, signal: F, timeout: Option<Duration>) -> Self
+    where
+        F: 'static + Send + Future<Output = ()>,
+    {
+        self.shutdown = self.shutdown.with_signal(signal, timeout);
+
+        self
+    }
+
+    /// Runs a hook after the server has stopped, hooks are run in the order they are added.
+    pub fn with_shutdown_hook<F, Fut>(mut self, hook: F) -> Self
+    where
+        F: 'static + Send + FnOnce() -> Fut,
+        Fut: 'static + Send + Future<Output = ()>,
+    {
+        self.shutdown = self.shutdown.with_hook(hook);
+
+        self
+    }
+
+    pub(crate) fn with_shutdown(mut self, shutdown: Shutdown) -> Self {
+        self.shutdown = shutdown;
+
+        self
+    }
+}
+
+impl Shutdown {
+    pub(crate) fn with_signal<F>(mut self, signal: F, timeout: Option<Duration>) -> Self
+    where
+        F: 'static + Send + Future<Output = ()>,
+    {
+        self.signal = Some(signal.boxed());
+        self.timeout = timeout;
+
+        self
+    }
+
+    pub(crate) fn with_hook<F, Fut>(mut self, hook: F) -> Self
+    where
+        F: 'static + Send + FnOnce() -> Fut,
+        Fut: 'static + Send + Future<Output = ()>,
+    {
+        self.hooks.push(Box::new(move || hook().boxed()));
+
+        self
+    }
+
+    /// Runs a hook before other hooks.
+    pub(crate) fn with_first_hook<F, Fut>(mut self, hook: F) -> Self
+    where
+        F: 'static + Send + FnOnce() -> Fut,
+        Fut: 'static + Send + Future<Output = ()>,
+    {
+        self.hooks.insert(0, Box::new(move || hook().boxed()));
+
+        self
+    }
 }
 impl<I> Server<I>
 where
@@ -105,13 +234,51 @@ where
         BD: Send + 'static,
         BE: Into<Box<dyn std::error::Error + Send + Sync>>,
     {
-        let Self { inner, rt } = self;
+        let Self {
+            inner,
+            rt,
+            shutdown,
+        } = self;
 
-        inner
+        let server = inner
             .executor(Executor {
                 inner: rt.unwrap_or_default(),
             })
-            .serve(make_svc)
-            .await
+            .serve(make_svc);
+
+        let result = match shutdown.signal {
+            Some(signal) => {
+                let (tx, rx) = oneshot::channel::<()>();
+                let server = server.with_graceful_shutdown(async move {
+                    signal.await;
+                    tracing::info!("shutting down, waiting for requests in flight");
+                    let _ = tx.send(());
+                });
+
+                // The timeout starts when the signal is received.
+                let timeout = shutdown.timeout;
+                let deadline = async move {
+                    match (rx.await, timeout) {
+                        (Ok(()), Some(m)) => tokio::time::sleep(m).await,
+                        _ => future::pending().await,
+                    }
+                };
+
+                match future::select(Box::pin(server), Box::pin(deadline)).await {
+                    Either::Left((m, _)) => m,
+                    Either::Right(_) => {
+                        tracing::warn!("requests in flight are dropped after the shutdown timeout");
+                        Ok(())
+                    }
+                }
+            }
+            None => server.await,
+        };
+
+        for hook in shutdown.hooks {
+            hook().await;
+        }
+
+        result
     }
 }
@@ -0,0 +1,130 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use http::header::{HeaderValue, CACHE_CONTROL, SET_COOKIE};
+use rand::RngCore;
+use stackable_core::cipher::StateKey;
+use stackable_core::server::SessionConfig;
+use stackable_core::session::Session;
+use warp::reply::Response;
+
+use super::SessionStore;
+use crate::utils::random_str_with_len;
+
+/// The key id that the key of session cookies is derived with.
+const COOKIE_KEY_ID: &str = "session-cookie";
+
+/// Loads the session of a request with the id in its cookie, and saves it after the response.
+///
+/// The id is encrypted with a key derived from the server secret, so cookies cannot be forged.
+#[derive(Debug, Clone)]
+pub(crate) struct SessionManager {
+    store: Arc<dyn SessionStore>,
+    key: StateKey,
+    cookie_name: Arc<str>,
+    max_age: Duration,
+    secure: bool,
+}
+
+impl SessionManager {
+    pub fn new(store: Arc<dyn SessionStore>, config: &SessionConfig, secret: &[u8]) -> Self {
+        Self {
+            store,
+            key: StateKey::derive(secret, COOKIE_KEY_ID),
+            cookie_name: config.cookie_name.as_str().into(),
+            max_age: Duration::from_secs(config.max_age),
+            secure: config.secure,
+        }
+    }
+
+    /// Returns the value of the session cookie in a `cookie` header.
+    fn cookie_value<'a>(&self, cookies: &'a str) -> Option<&'a str> {
+        cookies.split(';').find_map(|m| {
+            let (name, value) = m.trim().split_once('=')?;
+            (name == &*self.cookie_name).then_some(value)
+        })
+    }
+
+    /// Loads the session of a request, a new session is returned if the cookie is missing or
+    /// invalid, or the session has expired.
+    pub async fn load(&self, cookies: Option<&str>) -> Session {
+        let id = match cookies
+            .and_then(|m| self.cookie_value(m))
+            .and_then(|m| self.key.decrypt(m))
+        {
+            Some(m) => m,
+            None => return Session::new(),
+        };
+
+        match self.store.load(&id).await {
+            Ok(Some(m)) => Session::from_stored(id, &m).unwrap_or_else(|e| {
+                tracing::warn!("failed to decode session: {}", e);
+                Session::new()
+            }),
+            Ok(None) => Session::new(),
+            Err(e) => {
+                tracing::warn!("failed to load session: {}", e);
+                Session::new()
+            }
+        }
+    }
+
+    fn cookie(&self, value: &str, max_age: u64) -> Option<HeaderValue> {
+        let secure = if self.secure { "; Secure" } else { "" };
+
+        HeaderValue::from_str(&format!(
+            "{}={}; Path=/; Max-Age={}; HttpOnly; SameSite=Lax{}",
+            self.cookie_name, value, max_age, secure
+        ))
+        .ok()
+    }
+
+    /// Saves a session that has changed and sets its cookie on the response.
+    pub async fn save(&self, session: &Session, resp: &mut Response) {
+        if !session.is_changed() {
+            return;
+        }
+
+        let old_id = session.id();
+        let is_replaced = session.is_destroyed() || session.is_renewed();
+
+        if let Some(ref m) = old_id.as_ref().filter(|_| is_replaced) {
+            if let Err(e) = self.store.remove(m).await {
+                tracing::warn!("failed to remove session: {}", e);
+            }
+        }
+
+        let cookie = if session.is_destroyed() || session.is_empty() {
+            old_id.and_then(|_| self.cookie("", 0))
+        } else {
+            let id = match old_id {
+                Some(m) if !is_replaced => m,
+                _ => random_str_with_len(32),
+            };
+
+            let data = match session.to_bytes() {
+                Ok(m) => m,
+                Err(e) => {
+                    tracing::warn!("failed to encode session: {}", e);
+                    return;
+                }
+            };
+
+            if let Err(e) = self.store.store(&id, &data, self.max_age).await {
+                tracing::warn!("failed to store session: {}", e);
+                return;
+            }
+
+            let mut nonce = [0; 12];
+            rand::thread_rng().fill_bytes(&mut nonce);
+            self.cookie(&self.key.encrypt(nonce, &id), self.max_age.as_secs())
+        };
+
+        if let Some(m) = cookie {
+            resp.headers_mut().append(SET_COOKIE, m);
+            // Responses that set a session must not be shared by caches.
+            resp.headers_mut()
+                .insert(CACHE_CONTROL, HeaderValue::from_static("no-store"));
+        }
+    }
+}
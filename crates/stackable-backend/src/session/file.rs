@@ -0,0 +1,123 @@
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tokio::fs;
+
+use super::{expires_at, is_expired, validate_id, SessionStore};
+use crate::error::SessionResult;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Record {
+    expires_at: u64,
+    data: Vec<u8>,
+}
+
+/// A session store that keeps each session as a file in a directory.
+#[derive(Debug)]
+pub struct FileSessionStore {
+    dir: PathBuf,
+}
+
+impl FileSessionStore {
+    pub fn new<P>(dir: P) -> Self
+    where
+        P: Into<PathBuf>,
+    {
+        Self { dir: dir.into() }
+    }
+
+    fn path(&self, id: &str) -> SessionResult<PathBuf> {
+        validate_id(id)?;
+
+        Ok(self.dir.join(format!("{id}.session")))
+    }
+
+    async fn read_record(path: &Path) -> SessionResult<Option<Record>> {
+        let content = match fs::read(path).await {
+            Ok(m) => m,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+
+        Ok(Some(bincode::deserialize(&content)?))
+    }
+
+    async fn remove_file(path: &Path) -> SessionResult<()> {
+        match fs::remove_file(path).await {
+            Ok(_) => Ok(()),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+#[async_trait]
+impl SessionStore for FileSessionStore {
+    async fn load(&self, id: &str) -> SessionResult<Option<Vec<u8>>> {
+        let path = self.path(id)?;
+
+        match Self::read_record(&path).await? {
+            Some(m) if is_expired(m.expires_at) => {
+                Self::remove_file(&path).await?;
+                Ok(None)
+            }
+            Some(m) => Ok(Some(m.data)),
+            None => Ok(None),
+        }
+    }
+
+    async fn store(&self, id: &str, data: &[u8], max_age: Duration) -> SessionResult<()> {
+        let path = self.path(id)?;
+
+        let record = Record {
+            expires_at: expires_at(max_age),
+            data: data.to_vec(),
+        };
+
+        fs::create_dir_all(&self.dir).await?;
+        fs::write(&path, bincode::serialize(&record)?).await?;
+
+        Ok(())
+    }
+
+    async fn remove(&self, id: &str) -> SessionResult<()> {
+        let path = self.path(id)?;
+
+        Self::remove_file(&path).await
+    }
+
+    async fn purge_expired(&self) -> SessionResult<usize> {
+        let mut entries = match fs::read_dir(&self.dir).await {
+            Ok(m) => m,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(0),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut purged = 0;
+
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+
+            if path.extension().and_then(|m| m.to_str()) != Some("session") {
+                continue;
+            }
+
+            // Files that cannot be decoded are also purged.
+            let expired = match Self::read_record(&path).await {
+                Ok(Some(m)) => is_expired(m.expires_at),
+                Ok(None) => false,
+                Err(_) => true,
+            };
+
+            if expired {
+                Self::remove_file(&path).await?;
+                purged += 1;
+            }
+        }
+
+        Ok(purged)
+    }
+}
@@ -0,0 +1,66 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use async_trait::async_trait;
+
+use super::{expires_at, is_expired, validate_id, SessionStore};
+use crate::error::SessionResult;
+
+/// A session store that keeps sessions in memory.
+///
+/// Sessions are lost when the server restarts.
+#[derive(Debug, Default)]
+pub struct MemorySessionStore {
+    sessions: Mutex<HashMap<String, (u64, Vec<u8>)>>,
+}
+
+impl MemorySessionStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl SessionStore for MemorySessionStore {
+    async fn load(&self, id: &str) -> SessionResult<Option<Vec<u8>>> {
+        validate_id(id)?;
+        let mut sessions = self.sessions.lock().expect("failed to lock sessions");
+
+        match sessions.get(id) {
+            Some((expires_at, _)) if is_expired(*expires_at) => {
+                sessions.remove(id);
+                Ok(None)
+            }
+            Some((_, data)) => Ok(Some(data.clone())),
+            None => Ok(None),
+        }
+    }
+
+    async fn store(&self, id: &str, data: &[u8], max_age: Duration) -> SessionResult<()> {
+        validate_id(id)?;
+        let mut sessions = self.sessions.lock().expect("failed to lock sessions");
+
+        sessions.insert(id.to_string(), (expires_at(max_age), data.to_vec()));
+
+        Ok(())
+    }
+
+    async fn remove(&self, id: &str) -> SessionResult<()> {
+        validate_id(id)?;
+        let mut sessions = self.sessions.lock().expect("failed to lock sessions");
+
+        sessions.remove(id);
+
+        Ok(())
+    }
+
+    async fn purge_expired(&self) -> SessionResult<usize> {
+        let mut sessions = self.sessions.lock().expect("failed to lock sessions");
+
+        let len = sessions.len();
+        sessions.retain(|_, (expires_at, _)| !is_expired(*expires_at));
+
+        Ok(len - sessions.len())
+    }
+}
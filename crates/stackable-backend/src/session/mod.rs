@@ -0,0 +1,90 @@
+//! Session storage.
+//!
+//! A [`SessionStore`] persists the encoded content of a session with an expiry time. The id of
+//! the session of a request is carried in an encrypted cookie.
+
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use async_trait::async_trait;
+use stackable_core::server::{SessionConfig, SessionStoreConfig};
+
+use crate::error::{SessionError, SessionResult};
+
+mod cookie;
+mod file;
+mod memory;
+#[cfg(feature = "session-redis")]
+mod redis;
+
+pub(crate) use cookie::SessionManager;
+pub use file::FileSessionStore;
+pub use memory::MemorySessionStore;
+
+#[cfg(feature = "session-redis")]
+pub use self::redis::RedisSessionStore;
+
+/// A backend that stores sessions.
+#[async_trait]
+pub trait SessionStore: 'static + Send + Sync + fmt::Debug {
+    /// Loads a session, returns `None` if the session does not exist or has expired.
+    async fn load(&self, id: &str) -> SessionResult<Option<Vec<u8>>>;
+
+    /// Stores a session that expires after `max_age`.
+    async fn store(&self, id: &str, data: &[u8], max_age: Duration) -> SessionResult<()>;
+
+    /// Removes a session.
+    async fn remove(&self, id: &str) -> SessionResult<()>;
+
+    /// Removes all expired sessions and returns the number of sessions removed.
+    async fn purge_expired(&self) -> SessionResult<usize>;
+}
+
+/// Creates a session store from the session configuration.
+pub fn create_store(config: &SessionConfig) -> SessionResult<Arc<dyn SessionStore>> {
+    match config.store {
+        SessionStoreConfig::Memory => Ok(Arc::new(MemorySessionStore::new())),
+        SessionStoreConfig::File { ref path } => Ok(Arc::new(FileSessionStore::new(path))),
+        #[cfg(feature = "session-redis")]
+        SessionStoreConfig::Redis { ref url } => Ok(Arc::new(RedisSessionStore::new(url)?)),
+        #[cfg(not(feature = "session-redis"))]
+        SessionStoreConfig::Redis { .. } => Err(SessionError::Unsupported("redis")),
+    }
+}
+
+/// Session ids are used as file names and keys, so only alphanumeric ids are accepted.
+fn validate_id(id: &str) -> SessionResult<()> {
+    if id.is_empty() || !id.chars().all(|m| m.is_ascii_alphanumeric()) {
+        return Err(SessionError::InvalidId);
+    }
+
+    Ok(())
+}
+
+fn expires_at(max_age: Duration) -> u64 {
+    (SystemTime::now() + max_age)
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|m| m.as_secs())
+        .unwrap_or(0)
+}
+
+fn is_expired(expires_at: u64) -> bool {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|m| m.as_secs() >= expires_at)
+        .unwrap_or(true)
+}
+
+/// Purges expired sessions periodically.
+pub(crate) async fn purge_periodically(store: Arc<dyn SessionStore>, interval: Duration) {
+    loop {
+        tokio::time::sleep(interval).await;
+
+        match store.purge_expired().await {
+            Ok(0) => {}
+            Ok(m) => tracing::debug!("purged {} expired sessions", m),
+            Err(e) => tracing::warn!("failed to purge expired sessions: {:?}", e),
+        }
+    }
+}
@@ -0,0 +1,68 @@
+use std::time::Duration;
+
+use ::redis::{cmd, Client};
+use async_trait::async_trait;
+
+use super::{validate_id, SessionStore};
+use crate::error::SessionResult;
+
+/// A session store that keeps sessions in a Redis server.
+///
+/// Expiry is handled by Redis.
+#[derive(Debug)]
+pub struct RedisSessionStore {
+    client: Client,
+}
+
+impl RedisSessionStore {
+    pub fn new(url: &str) -> SessionResult<Self> {
+        Ok(Self {
+            client: Client::open(url)?,
+        })
+    }
+
+    fn key(id: &str) -> SessionResult<String> {
+        validate_id(id)?;
+
+        Ok(format!("stackable:session:{id}"))
+    }
+}
+
+#[async_trait]
+impl SessionStore for RedisSessionStore {
+    async fn load(&self, id: &str) -> SessionResult<Option<Vec<u8>>> {
+        let key = Self::key(id)?;
+        let mut conn = self.client.get_async_connection().await?;
+
+        Ok(cmd("GET").arg(key).query_async(&mut conn).await?)
+    }
+
+    async fn store(&self, id: &str, data: &[u8], max_age: Duration) -> SessionResult<()> {
+        let key = Self::key(id)?;
+        let mut conn = self.client.get_async_connection().await?;
+
+        cmd("SET")
+            .arg(key)
+            .arg(data)
+            .arg("PX")
+            .arg(u64::try_from(max_age.as_millis()).unwrap_or(u64::MAX))
+            .query_async::<_, ()>(&mut conn)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn remove(&self, id: &str) -> SessionResult<()> {
+        let key = Self::key(id)?;
+        let mut conn = self.client.get_async_connection().await?;
+
+        cmd("DEL").arg(key).query_async::<_, ()>(&mut conn).await?;
+
+        Ok(())
+    }
+
+    async fn purge_expired(&self) -> SessionResult<usize> {
+        // Redis removes expired keys by itself.
+        Ok(0)
+    }
+}
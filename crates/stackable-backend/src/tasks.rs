@@ -0,0 +1,213 @@
+//! Background tasks and periodic jobs that run alongside the server.
+//!
+//! A panic in a task or a run of a job is logged and does not affect the server or other jobs.
+
+use std::future::Future;
+use std::panic::AssertUnwindSafe;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use futures::future::{self, BoxFuture, Either};
+use futures::FutureExt;
+use stackable_core::dev::ScheduledJob;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use tokio::time::{interval_at, Instant, MissedTickBehavior};
+
+/// The shortest interval of a job, shorter intervals are raised to it.
+const MIN_JOB_INTERVAL: Duration = Duration::from_millis(100);
+
+type JobFn = Arc<dyn Send + Sync + Fn() -> BoxFuture<'static, ()>>;
+
+struct Job {
+    name: String,
+    interval: Duration,
+    run: JobFn,
+}
+
+#[derive(Default)]
+struct State {
+    started: bool,
+    /// Jobs that are started with the tasks.
+    pending: Vec<Job>,
+    jobs: Vec<ScheduledJob>,
+    handles: Vec<JoinHandle<()>>,
+}
+
+struct Inner {
+    state: Mutex<State>,
+    stopped: watch::Sender<bool>,
+}
+
+/// A handle to the background tasks of an application.
+///
+/// Handles are cheap to clone, so they can be passed to resolvers in the context of the
+/// application.
+#[derive(Clone)]
+pub struct Tasks {
+    inner: Arc<Inner>,
+}
+
+impl std::fmt::Debug for Tasks {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Tasks")
+            .field("jobs", &self.jobs())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Default for Tasks {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Tasks {
+    pub fn new() -> Self {
+        let (stopped, _) = watch::channel(false);
+
+        Self {
+            inner: Arc::new(Inner {
+                state: Mutex::default(),
+                stopped,
+            }),
+        }
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, State> {
+        self.inner.state.lock().expect("failed to lock tasks")
+    }
+
+    fn is_stopped(&self) -> bool {
+        *self.inner.stopped.borrow()
+    }
+
+    /// Registers a job that runs every `interval`, starting one interval after the tasks are
+    /// started.
+    ///
+    /// A run that takes longer than the interval delays the next run. Intervals shorter than
+    /// 100ms, including zero, are raised to 100ms.
+    pub fn add_job<F, Fut>(&self, name: impl Into<String>, interval: Duration, f: F)
+    where
+        F: 'static + Send + Sync + Fn() -> Fut,
+        Fut: 'static + Send + Future<Output = ()>,
+    {
+        let name = name.into();
+        if interval < MIN_JOB_INTERVAL {
+            tracing::warn!(
+                "interval of job {} is shorter than {:?}, using {:?}",
+                name,
+                MIN_JOB_INTERVAL,
+                MIN_JOB_INTERVAL
+            );
+        }
+        let interval = interval.max(MIN_JOB_INTERVAL);
+
+        let job = Job {
+            name,
+            interval,
+            run: Arc::new(move || f().boxed()),
+        };
+
+        let mut state = self.state();
+        state.jobs.push(ScheduledJob {
+            name: job.name.clone(),
+            interval,
+        });
+
+        if state.started {
+            let handle = self.start_job(job);
+            state.handles.push(handle);
+        } else {
+            state.pending.push(job);
+        }
+    }
+
+    /// Spawns a task that runs in the background.
+    ///
+    /// Tasks spawned after shutdown has started are not run.
+    pub fn spawn<F>(&self, name: impl Into<String>, f: F)
+    where
+        F: 'static + Send + Future<Output = ()>,
+    {
+        let name = name.into();
+
+        if self.is_stopped() {
+            tracing::warn!("task {} is not run, the server is shutting down", name);
+            return;
+        }
+
+        let handle = tokio::spawn(async move {
+            if AssertUnwindSafe(f).catch_unwind().await.is_err() {
+                tracing::error!("task {} panicked", name);
+            }
+        });
+
+        let mut state = self.state();
+        state.handles.retain(|m| !m.is_finished());
+        state.handles.push(handle);
+    }
+
+    /// Returns the registered jobs.
+    pub fn jobs(&self) -> Vec<ScheduledJob> {
+        self.state().jobs.clone()
+    }
+
+    /// Starts registered jobs, this must be called in a tokio runtime.
+    ///
+    /// This is called by [`Cli`](crate::Cli) when the server starts.
+    pub fn start(&self) {
+        let mut state = self.state();
+        if state.started {
+            return;
+        }
+        state.started = true;
+
+        for job in std::mem::take(&mut state.pending) {
+            let handle = self.start_job(job);
+            state.handles.push(handle);
+        }
+    }
+
+    fn start_job(&self, job: Job) -> JoinHandle<()> {
+        let mut stopped = self.inner.stopped.subscribe();
+
+        tokio::spawn(async move {
+            let mut interval = interval_at(Instant::now() + job.interval, job.interval);
+            interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+            while !*stopped.borrow() {
+                let tick = Box::pin(interval.tick());
+                let stop = Box::pin(stopped.changed());
+                if let Either::Right(_) = future::select(tick, stop).await {
+                    break;
+                }
+
+                if AssertUnwindSafe((job.run)()).catch_unwind().await.is_err() {
+                    tracing::error!("job {} panicked", job.name);
+                }
+            }
+        })
+    }
+
+    /// Stops jobs and waits for tasks and runs of jobs in progress.
+    ///
+    /// If a timeout is set, tasks that are still running after it are left behind.
+    pub async fn shutdown(&self, timeout: Option<Duration>) {
+        self.inner.stopped.send_replace(true);
+
+        let handles = std::mem::take(&mut self.state().handles);
+        let all = future::join_all(handles);
+
+        match timeout {
+            Some(m) => {
+                if tokio::time::timeout(m, all).await.is_err() {
+                    tracing::warn!("background tasks are still running after the shutdown timeout");
+                }
+            }
+            None => {
+                all.await;
+            }
+        }
+    }
+}
@@ -0,0 +1,231 @@
+//! A server for integration tests, which serves an endpoint on an ephemeral port without
+//! stackctl.
+//!
+//! ```ignore
+//! let server = TestServer::spawn(Endpoint::<ServerApp>::new().with_bridge(create_bridge()))?;
+//!
+//! let resp = server.get("/").await?;
+//! assert!(resp.text().contains("Welcome"));
+//! ```
+
+use std::net::{SocketAddr, TcpListener};
+use std::path::PathBuf;
+use std::thread;
+
+use bytes::Bytes;
+use futures::channel::oneshot;
+use futures::future::{select, Either};
+use http::{HeaderMap, Request, StatusCode, Uri};
+use hyper::client::HttpConnector;
+use hyper::{Body, Client};
+use serde::de::DeserializeOwned;
+use yew::BaseComponent;
+
+use crate::error::{TestServerError, TestServerResult};
+use crate::utils::random_str;
+use crate::{Endpoint, Frontend, Server, ServerAppProps};
+
+/// The `index.html` of the frontend that [`TestServer::spawn`] serves.
+pub const DEFAULT_INDEX_HTML: &str =
+    r#"<!DOCTYPE html><html><head><meta charset="utf-8"></head><body></body></html>"#;
+
+/// A frontend build directory that is removed when the server stops.
+#[derive(Debug)]
+struct TempDist {
+    path: PathBuf,
+}
+
+impl TempDist {
+    fn new(index_html: &str) -> TestServerResult<Self> {
+        let path = std::env::temp_dir().join(format!("stackable-test-{}", random_str()));
+        std::fs::create_dir_all(&path)?;
+        std::fs::write(path.join("index.html"), index_html)?;
+
+        Ok(Self { path })
+    }
+}
+
+impl Drop for TempDist {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.path);
+    }
+}
+
+/// A response read by the client of a [`TestServer`].
+#[derive(Debug)]
+pub struct TestResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: Bytes,
+}
+
+impl TestResponse {
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.body).into_owned()
+    }
+
+    pub fn json<T>(&self) -> serde_json::Result<T>
+    where
+        T: DeserializeOwned,
+    {
+        serde_json::from_slice(&self.body)
+    }
+}
+
+/// A server that serves an endpoint on an ephemeral port of the loopback interface.
+///
+/// The server runs on its own thread and runtime, so it keeps serving while a test blocks. The
+/// client requires a tokio runtime, e.g.: `#[tokio::test]`. The server stops when it is dropped.
+#[derive(Debug)]
+pub struct TestServer {
+    addr: SocketAddr,
+    client: Client<HttpConnector>,
+    shutdown: Option<oneshot::Sender<()>>,
+    _dist: Option<TempDist>,
+}
+
+impl TestServer {
+    /// Starts a server with a frontend that only contains [`DEFAULT_INDEX_HTML`].
+    pub fn spawn<COMP, CTX, BCTX>(endpoint: Endpoint<COMP, CTX, BCTX>) -> TestServerResult<Self>
+    where
+        COMP: BaseComponent<Properties = ServerAppProps<CTX>>,
+        CTX: 'static,
+        BCTX: 'static,
+    {
+        Self::spawn_with_index_html(endpoint, DEFAULT_INDEX_HTML)
+    }
+
+    /// Starts a server with a frontend that only contains the `index.html`, e.g.: one with the
+    /// elements that pages are rendered into.
+    pub fn spawn_with_index_html<COMP, CTX, BCTX>(
+        endpoint: Endpoint<COMP, CTX, BCTX>,
+        index_html: &str,
+    ) -> TestServerResult<Self>
+    where
+        COMP: BaseComponent<Properties = ServerAppProps<CTX>>,
+        CTX: 'static,
+        BCTX: 'static,
+    {
+        let dist = TempDist::new(index_html)?;
+        let endpoint = endpoint.with_frontend(Frontend::new_path(&dist.path));
+
+        let mut server = Self::spawn_inner(endpoint)?;
+        server._dist = Some(dist);
+
+        Ok(server)
+    }
+
+    /// Starts a server with the frontend of the endpoint, e.g.: a frontend built by trunk.
+    pub fn spawn_with_frontend<COMP, CTX, BCTX>(
+        endpoint: Endpoint<COMP, CTX, BCTX>,
+        frontend: Frontend,
+    ) -> TestServerResult<Self>
+    where
+        COMP: BaseComponent<Properties = ServerAppProps<CTX>>,
+        CTX: 'static,
+        BCTX: 'static,
+    {
+        Self::spawn_inner(endpoint.with_frontend(frontend))
+    }
+
+    fn spawn_inner<COMP, CTX, BCTX>(endpoint: Endpoint<COMP, CTX, BCTX>) -> TestServerResult<Self>
+    where
+        COMP: BaseComponent<Properties = ServerAppProps<CTX>>,
+        CTX: 'static,
+        BCTX: 'static,
+    {
+        let listener = TcpListener::bind(("127.0.0.1", 0))?;
+        listener.set_nonblocking(true)?;
+        let addr = listener.local_addr()?;
+
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+        let server = {
+            let _guard = rt.enter();
+            Server::<()>::from_tcp(listener)?
+        };
+        let svc = endpoint.into_tower_service();
+
+        let (tx, rx) = oneshot::channel();
+        thread::Builder::new()
+            .name(format!("stackable-test-server-{}", addr.port()))
+            .spawn(move || {
+                rt.block_on(async move {
+                    let serve = Box::pin(server.serve_service(svc));
+
+                    if let Either::Left((Err(e), _)) = select(serve, rx).await {
+                        tracing::error!("test server failed: {}", e);
+                    }
+                })
+            })?;
+
+        Ok(Self {
+            addr,
+            client: Client::new(),
+            shutdown: Some(tx),
+            _dist: None,
+        })
+    }
+
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Returns the url of a path on the server, e.g.: `http://127.0.0.1:49152/about`.
+    pub fn url(&self, path: &str) -> String {
+        format!("http://{}/{}", self.addr, path.trim_start_matches('/'))
+    }
+
+    /// Sends a request to the server, a relative uri is resolved against the server.
+    pub async fn request(&self, mut req: Request<Body>) -> TestServerResult<TestResponse> {
+        if req.uri().authority().is_none() {
+            let path = req
+                .uri()
+                .path_and_query()
+                .map(|m| m.as_str())
+                .unwrap_or("/");
+            let uri = self
+                .url(path)
+                .parse::<Uri>()
+                .map_err(|_| TestServerError::InvalidUri(path.to_string()))?;
+            *req.uri_mut() = uri;
+        }
+
+        let resp = self.client.request(req).await?;
+        let (parts, body) = resp.into_parts();
+
+        Ok(TestResponse {
+            status: parts.status,
+            headers: parts.headers,
+            body: hyper::body::to_bytes(body).await?,
+        })
+    }
+
+    pub async fn get(&self, path: &str) -> TestServerResult<TestResponse> {
+        let req = Request::get(path)
+            .body(Body::empty())
+            .map_err(|_| TestServerError::InvalidUri(path.to_string()))?;
+
+        self.request(req).await
+    }
+
+    pub async fn post<B>(&self, path: &str, body: B) -> TestServerResult<TestResponse>
+    where
+        B: Into<Body>,
+    {
+        let req = Request::post(path)
+            .body(body.into())
+            .map_err(|_| TestServerError::InvalidUri(path.to_string()))?;
+
+        self.request(req).await
+    }
+}
+
+impl Drop for TestServer {
+    fn drop(&mut self) {
+        if let Some(m) = self.shutdown.take() {
+            let _ = m.send(());
+        }
+    }
+}
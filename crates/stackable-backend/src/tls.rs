@@ -0,0 +1,92 @@
+//! TLS of the production server, HTTP/2 is negotiated with ALPN.
+
+use std::fs::File;
+use std::io::{self, BufReader};
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures::{stream, Stream, StreamExt};
+use rustls_pemfile::Item;
+use stackable_core::server::TlsConfig;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::time::timeout;
+use tokio_rustls::server::TlsStream;
+use tokio_rustls::TlsAcceptor;
+
+use crate::error::{TlsError, TlsResult};
+
+/// Connections that are dropped if the client does not finish the handshake in time.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The number of handshakes performed at the same time.
+const MAX_HANDSHAKES: usize = 128;
+
+/// Reads the certificate chain and the private key of a TLS configuration.
+///
+/// ALPN protocols are not set, as they differ between HTTPS and HTTP/3.
+pub(crate) fn server_config(config: &TlsConfig) -> TlsResult<rustls::ServerConfig> {
+    let certs = rustls_pemfile::certs(&mut BufReader::new(File::open(&config.cert)?))?
+        .into_iter()
+        .map(rustls::Certificate)
+        .collect::<Vec<_>>();
+
+    let key = rustls_pemfile::read_all(&mut BufReader::new(File::open(&config.key)?))?
+        .into_iter()
+        .find_map(|m| match m {
+            Item::PKCS8Key(m) | Item::RSAKey(m) | Item::ECKey(m) => Some(rustls::PrivateKey(m)),
+            _ => None,
+        })
+        .ok_or_else(|| TlsError::MissingKey(config.key.clone()))?;
+
+    Ok(rustls::ServerConfig::builder()
+        .with_safe_defaults()
+        .with_no_client_auth()
+        .with_single_cert(certs, key)?)
+}
+
+/// Accepts TLS connections of a listener.
+///
+/// Connections that fail the handshake are dropped, so they do not stop the server.
+pub(crate) fn incoming(
+    listener: TcpListener,
+    config: &TlsConfig,
+) -> TlsResult<impl Stream<Item = io::Result<TlsStream<TcpStream>>> + Send> {
+    let mut server_config = server_config(config)?;
+    server_config.alpn_protocols = if config.http2 {
+        vec![b"h2".to_vec(), b"http/1.1".to_vec()]
+    } else {
+        vec![b"http/1.1".to_vec()]
+    };
+    let acceptor = TlsAcceptor::from(Arc::new(server_config));
+
+    let connections = stream::unfold(listener, |listener| async move {
+        let conn = listener.accept().await;
+        Some((conn, listener))
+    });
+
+    Ok(connections
+        .map(move |conn| {
+            let acceptor = acceptor.clone();
+            async move {
+                let (stream, _) = conn?;
+
+                match timeout(HANDSHAKE_TIMEOUT, acceptor.accept(stream)).await {
+                    Ok(m) => m,
+                    Err(_) => Err(io::Error::new(
+                        io::ErrorKind::TimedOut,
+                        "TLS handshake timed out",
+                    )),
+                }
+            }
+        })
+        .buffer_unordered(MAX_HANDSHAKES)
+        .filter_map(|conn| async move {
+            match conn {
+                Ok(m) => Some(Ok(m)),
+                Err(e) => {
+                    tracing::debug!("failed to accept TLS connection: {}", e);
+                    None
+                }
+            }
+        }))
+}
@@ -1,7 +1,8 @@
 use std::env;
 
 use console::style;
-use stackable_core::dev::StackctlMetadata;
+use stackable_core::dev::{LogRecord, StackctlMetadata};
+use stackable_core::observability::{ObservabilityConfig, TRACEPARENT_HEADER};
 use tracing::field::Visit;
 use tracing::{Level, Subscriber};
 use tracing_subscriber::filter::filter_fn;
@@ -86,35 +87,231 @@ where
     }
 }
 
+/// A layer that writes events to stderr as JSON lines, which stackctl renders.
+#[derive(Debug, Default)]
+struct StructuredLog {}
+
+impl<S> Layer<S> for StructuredLog
+where
+    S: Subscriber + for<'lookup> LookupSpan<'lookup>,
+{
+    fn on_event(&self, event: &tracing::Event<'_>, ctx: Context<'_, S>) {
+        #[derive(Default)]
+        struct Values {
+            message: String,
+            fields: Vec<(String, String)>,
+        }
+
+        impl Visit for Values {
+            fn record_str(&mut self, field: &tracing::field::Field, value: &str) {
+                match field.name() {
+                    "message" => self.message = value.to_string(),
+                    m => self.fields.push((m.to_string(), value.to_string())),
+                }
+            }
+
+            fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+                match field.name() {
+                    "message" => self.message = format!("{:?}", value),
+                    m => self.fields.push((m.to_string(), format!("{:?}", value))),
+                }
+            }
+        }
+
+        let mut values = Values::default();
+        event.record(&mut values);
+
+        let spans = ctx
+            .event_scope(event)
+            .map(|m| m.from_root().map(|m| m.name().to_string()).collect())
+            .unwrap_or_default();
+
+        let record = LogRecord {
+            level: event.metadata().level().to_string(),
+            target: event.metadata().target().to_string(),
+            message: values.message,
+            fields: values.fields,
+            spans,
+        };
+
+        if let Ok(m) = record.to_json() {
+            eprintln!("{m}");
+        }
+    }
+}
+
+/// Reads the observability configuration.
+///
+/// The development server passes the configuration from `stackable.toml`, otherwise exporting is
+/// enabled if `OTEL_EXPORTER_OTLP_ENDPOINT` is set.
+fn observability_config(meta: Option<&StackctlMetadata>) -> Option<ObservabilityConfig> {
+    let config = match meta {
+        Some(m) => m.observability.clone(),
+        None => ObservabilityConfig {
+            enabled: env::var_os("OTEL_EXPORTER_OTLP_ENDPOINT").is_some(),
+            ..Default::default()
+        },
+    };
+
+    config.enabled.then_some(config)
+}
+
+/// Returns a layer that exports spans to an OpenTelemetry collector.
+#[cfg(feature = "otlp")]
+fn otlp_layer<S>(config: &ObservabilityConfig) -> Option<impl Layer<S>>
+where
+    S: Subscriber + for<'lookup> LookupSpan<'lookup>,
+{
+    use opentelemetry::sdk::{trace, Resource};
+    use opentelemetry::KeyValue;
+    use opentelemetry_otlp::WithExportConfig;
+
+    let endpoint = config
+        .otlp_endpoint
+        .clone()
+        .or_else(|| env::var("OTEL_EXPORTER_OTLP_ENDPOINT").ok())
+        .unwrap_or_else(|| ObservabilityConfig::DEFAULT_OTLP_ENDPOINT.to_string());
+    let service_name = config
+        .service_name
+        .clone()
+        .or_else(|| env::var("OTEL_SERVICE_NAME").ok())
+        .or_else(|| {
+            env::current_exe()
+                .ok()
+                .and_then(|m| m.file_stem().map(|m| m.to_string_lossy().to_string()))
+        })
+        .unwrap_or_else(|| "stackable".to_string());
+
+    let tracer = opentelemetry_otlp::new_pipeline()
+        .tracing()
+        .with_exporter(
+            opentelemetry_otlp::new_exporter()
+                .tonic()
+                .with_endpoint(endpoint),
+        )
+        .with_trace_config(
+            trace::config().with_resource(Resource::new(vec![KeyValue::new(
+                "service.name",
+                service_name,
+            )])),
+        )
+        .install_batch(opentelemetry::runtime::Tokio);
+
+    match tracer {
+        Ok(m) => Some(tracing_opentelemetry::layer().with_tracer(m)),
+        Err(e) => {
+            eprintln!("failed to initialise OpenTelemetry exporter: {e}");
+            None
+        }
+    }
+}
+
+#[cfg(not(feature = "otlp"))]
+fn otlp_layer<S>(_config: &ObservabilityConfig) -> Option<tracing_subscriber::layer::Identity>
+where
+    S: Subscriber + for<'lookup> LookupSpan<'lookup>,
+{
+    eprintln!("observability is enabled, but stackable-backend is built without the otlp feature");
+
+    None
+}
+
+/// Sets the parent of a span to the trace context of an incoming `traceparent` header.
+#[cfg(feature = "otlp")]
+pub(crate) fn set_parent(span: &tracing::Span, traceparent: Option<&str>) {
+    use std::collections::HashMap;
+
+    use opentelemetry::propagation::TextMapPropagator;
+    use opentelemetry::sdk::propagation::TraceContextPropagator;
+    use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+    if let Some(m) = traceparent {
+        let carrier = HashMap::from([(TRACEPARENT_HEADER.to_string(), m.to_string())]);
+        span.set_parent(TraceContextPropagator::new().extract(&carrier));
+    }
+}
+
+#[cfg(not(feature = "otlp"))]
+pub(crate) fn set_parent(_span: &tracing::Span, _traceparent: Option<&str>) {}
+
+/// Returns the `traceparent` header of the current span.
+///
+/// Resolvers can send it with requests to other services, so their spans are connected to the
+/// request that is being rendered. Returns `None` if spans are not exported.
+#[cfg(feature = "otlp")]
+pub fn current_traceparent() -> Option<String> {
+    use std::collections::HashMap;
+
+    use opentelemetry::propagation::TextMapPropagator;
+    use opentelemetry::sdk::propagation::TraceContextPropagator;
+    use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+    let mut carrier = HashMap::new();
+    TraceContextPropagator::new().inject_context(&tracing::Span::current().context(), &mut carrier);
+
+    carrier.remove(TRACEPARENT_HEADER)
+}
+
+#[cfg(not(feature = "otlp"))]
+pub fn current_traceparent() -> Option<String> {
+    None
+}
+
+/// Flushes spans that have not been exported yet.
+///
+/// This should be called before the server exits.
+pub fn shutdown() {
+    #[cfg(feature = "otlp")]
+    opentelemetry::global::shutdown_tracer_provider();
+}
+
 /// Initialise tracing with default settings.
+///
+/// If observability is enabled, spans are also exported to an OpenTelemetry collector.
+/// This must be called within a tokio runtime.
 pub fn init_default<S>(var_name: S)
 where
     S: Into<String>,
 {
     let var_name = var_name.into();
-    let env_filter = EnvFilter::builder()
-        .with_default_directive(Level::INFO.into())
-        .with_env_var(var_name)
-        .from_env_lossy();
+    let meta = env::var(StackctlMetadata::ENV_NAME)
+        .ok()
+        .and_then(|m| StackctlMetadata::from_json(&m).ok());
+
+    // The filter of stackctl takes precedence under development server.
+    let env_filter = EnvFilter::builder().with_default_directive(Level::INFO.into());
+    let env_filter = match meta.as_ref().and_then(|m| m.log_filter.as_deref()) {
+        Some(m) => env_filter.parse_lossy(m),
+        None => env_filter.with_env_var(var_name).from_env_lossy(),
+    };
+
+    let otlp_layer = observability_config(meta.as_ref()).and_then(|m| otlp_layer(&m));
+
+    match meta {
+        Some(ref m) => {
+            // access logs are processed by the access log layer
+            let not_access =
+                || filter_fn(|metadata| metadata.target() != "stackable_backend::endpoint::trace");
 
-    match env::var(StackctlMetadata::ENV_NAME) {
-        Ok(_) => {
             // Register pretty logging if under development server.
             tracing_subscriber::registry()
+                .with(otlp_layer)
                 .with(pretty_access())
-                .with(
+                .with((!m.structured_logs).then(|| {
                     tracing_subscriber::fmt::layer()
                         .compact()
-                        // access logs are processed by the access log layer
-                        .with_filter(filter_fn(|metadata| {
-                            metadata.target() != "stackable_backend::endpoint::trace"
-                        })),
+                        .with_filter(not_access())
+                }))
+                .with(
+                    m.structured_logs
+                        .then(|| StructuredLog::default().with_filter(not_access())),
                 )
                 .with(env_filter)
                 .init();
         }
-        Err(_) => {
+        None => {
             tracing_subscriber::registry()
+                .with(otlp_layer)
                 .with(tracing_subscriber::fmt::layer().compact())
                 .with(env_filter)
                 .init();
@@ -4,12 +4,53 @@ pub use self::thread_local::ThreadLocalLazy;
 
 #[cfg(feature = "warp-filter")]
 pub(crate) fn random_str() -> String {
+    random_str_with_len(7)
+}
+
+#[cfg(feature = "warp-filter")]
+pub(crate) fn random_str_with_len(len: usize) -> String {
     use rand::distributions::Alphanumeric;
     use rand::Rng;
 
     rand::thread_rng()
         .sample_iter(&Alphanumeric)
-        .take(7)
+        .take(len)
         .map(char::from)
         .collect()
 }
+
+/// Escapes a string so it can be safely embedded in html.
+#[cfg(feature = "warp-filter")]
+pub(crate) fn escape_html(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#x27;"),
+            m => escaped.push(m),
+        }
+    }
+
+    escaped
+}
+
+/// Returns the address of the client, from the header that a reverse proxy sets if it is
+/// configured.
+#[cfg(feature = "warp-filter")]
+pub(crate) fn client_ip(
+    headers: &http::HeaderMap,
+    remote_addr: Option<std::net::SocketAddr>,
+    client_ip_header: Option<&str>,
+) -> Option<std::net::IpAddr> {
+    // Proxies append the address that connected to them, so the last one is trusted.
+    client_ip_header
+        .and_then(|m| headers.get_all(m).iter().last())
+        .and_then(|m| m.to_str().ok())
+        .and_then(|m| m.rsplit(',').next())
+        .and_then(|m| m.trim().parse().ok())
+        .or_else(|| remote_addr.map(|m| m.ip()))
+}
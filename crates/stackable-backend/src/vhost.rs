@@ -0,0 +1,130 @@
+//! Virtual hosts, which serve hostnames from path prefixes of the server.
+//!
+//! Paths are rewritten before requests reach the routes of the endpoint, so frontends, guards and
+//! rate limits see the prefixed path.
+
+use std::collections::{BTreeMap, HashMap};
+
+use hyper::header::HOST;
+use hyper::http::uri::PathAndQuery;
+use hyper::{Body, Request, Uri};
+use stackable_core::frontend::FrontendMount;
+use stackable_core::server::VirtualHost;
+
+/// The path prefixes of virtual hosts by their hostnames.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct VirtualHosts {
+    prefixes: HashMap<String, String>,
+    /// Routes of the server, which are served on every host.
+    api_routes: Vec<String>,
+}
+
+/// Returns the hostname of a request in lowercase, without the port.
+fn hostname(req: &Request<Body>) -> Option<String> {
+    let host = req
+        .headers()
+        .get(HOST)
+        .and_then(|m| m.to_str().ok())
+        .or_else(|| req.uri().host())?;
+
+    // IPv6 addresses are not hostnames.
+    if host.starts_with('[') {
+        return None;
+    }
+
+    let host = host.split(':').next().unwrap_or(host);
+
+    Some(host.trim_end_matches('.').to_ascii_lowercase())
+}
+
+/// Returns `true` if the path is the prefix or a path under it.
+fn has_prefix(path: &str, prefix: &str) -> bool {
+    match path.strip_prefix(prefix.trim_end_matches('/')) {
+        Some(m) => m.is_empty() || m.starts_with('/'),
+        None => false,
+    }
+}
+
+impl VirtualHosts {
+    /// Resolves the prefixes of virtual hosts, aliases are pairs of the alias and the hostname of
+    /// a virtual host.
+    ///
+    /// Virtual hosts with a frontend that is not mounted are skipped with a warning.
+    pub fn new(
+        vhosts: &BTreeMap<String, VirtualHost>,
+        aliases: &[(String, String)],
+        mounts: &[FrontendMount],
+        api_routes: Vec<String>,
+    ) -> Self {
+        let mut prefixes = HashMap::new();
+
+        for (host, vhost) in vhosts.iter() {
+            let prefix = match (vhost.frontend.as_deref(), vhost.prefix.as_deref()) {
+                (Some(name), _) => match mounts.iter().find(|m| m.name == name) {
+                    Some(m) => m.mount_path.clone(),
+                    None => {
+                        tracing::warn!("frontend {} of virtual host {} is not mounted", name, host);
+                        continue;
+                    }
+                },
+                (None, Some(m)) => m.to_string(),
+                (None, None) => {
+                    tracing::warn!("virtual host {} has no frontend or prefix", host);
+                    continue;
+                }
+            };
+
+            prefixes.insert(
+                host.to_ascii_lowercase(),
+                format!("/{}", prefix.trim_matches('/')),
+            );
+        }
+
+        for (alias, host) in aliases.iter() {
+            match prefixes.get(&host.to_ascii_lowercase()).cloned() {
+                Some(m) => {
+                    prefixes.insert(alias.to_ascii_lowercase(), m);
+                }
+                None => tracing::warn!("host alias {} is not a virtual host: {}", alias, host),
+            }
+        }
+
+        Self {
+            prefixes,
+            api_routes,
+        }
+    }
+
+    /// Adds the prefix of the virtual host of a request to its path.
+    pub fn rewrite(&self, mut req: Request<Body>) -> Request<Body> {
+        let prefix = match hostname(&req).and_then(|m| self.prefixes.get(&m)) {
+            Some(m) => m,
+            None => return req,
+        };
+
+        let path = req.uri().path();
+        if has_prefix(path, prefix) || self.api_routes.iter().any(|m| has_prefix(path, m)) {
+            return req;
+        }
+
+        let path = match path {
+            "/" => prefix.to_string(),
+            m => format!("{}{}", prefix.trim_end_matches('/'), m),
+        };
+        let path_and_query = match req.uri().query() {
+            Some(m) => format!("{path}?{m}"),
+            None => path,
+        };
+
+        let mut parts = req.uri().clone().into_parts();
+        parts.path_and_query = match path_and_query.parse::<PathAndQuery>() {
+            Ok(m) => Some(m),
+            Err(_) => return req,
+        };
+        if let Ok(m) = Uri::from_parts(parts) {
+            *req.uri_mut() = m;
+        }
+
+        req
+    }
+}
@@ -1,9 +1,19 @@
 use std::sync::Arc;
 
+use stackable_core::app_state::AppState;
+use stackable_core::render_cache::RenderCacheControl;
+use stackable_core::session::Session;
+
 #[derive(Debug, Default)]
 pub struct BridgeMetadata<CTX = ()> {
     token: Option<Arc<str>>,
     context: Arc<CTX>,
+    session: Option<Session>,
+    render_cache: Option<RenderCacheControl>,
+    app_state: AppState,
+    csrf_rejected: bool,
+    #[cfg(feature = "resolvable")]
+    span: Option<tracing::Span>,
 }
 
 impl<CTX> BridgeMetadata<CTX> {
@@ -26,9 +36,72 @@ impl<CTX> BridgeMetadata<CTX> {
         BridgeMetadata {
             token: self.token,
             context: context.into(),
+            session: self.session,
+            render_cache: self.render_cache,
+            app_state: self.app_state,
+            csrf_rejected: self.csrf_rejected,
+            #[cfg(feature = "resolvable")]
+            span: self.span,
         }
     }
 
+    /// Sets the session of the request, which is saved after the response.
+    pub fn with_session(mut self, session: Session) -> Self {
+        self.session = Some(session);
+        self
+    }
+
+    /// Returns the session of the request, if sessions are enabled on the server.
+    pub fn session(&self) -> Option<&Session> {
+        self.session.as_ref()
+    }
+
+    /// Sets the render cache of the request, invalidations are applied after the response.
+    pub fn with_render_cache(mut self, render_cache: RenderCacheControl) -> Self {
+        self.render_cache = Some(render_cache);
+        self
+    }
+
+    /// Returns the render cache of the request, if the render cache is enabled on the server.
+    pub fn render_cache(&self) -> Option<&RenderCacheControl> {
+        self.render_cache.as_ref()
+    }
+
+    /// Sets the values that are shared by all requests of the server.
+    pub fn with_app_state(mut self, app_state: AppState) -> Self {
+        self.app_state = app_state;
+        self
+    }
+
+    /// Returns the values that are shared by all requests of the server.
+    pub fn app_state(&self) -> &AppState {
+        &self.app_state
+    }
+
+    /// Marks the request as failing CSRF verification, mutations and uploads are rejected.
+    pub fn with_csrf_rejected(mut self) -> Self {
+        self.csrf_rejected = true;
+        self
+    }
+
+    #[cfg_attr(not(feature = "resolvable"), allow(dead_code))]
+    pub(crate) fn is_csrf_rejected(&self) -> bool {
+        self.csrf_rejected
+    }
+
+    /// Sets the parent of the spans of resolvers during server-side rendering, e.g.: the span of
+    /// the request that renders the page.
+    #[cfg(feature = "resolvable")]
+    pub fn with_span(mut self, span: tracing::Span) -> Self {
+        self.span = Some(span);
+        self
+    }
+
+    #[cfg(feature = "resolvable")]
+    pub(crate) fn span(&self) -> Option<&tracing::Span> {
+        self.span.as_ref()
+    }
+
     pub fn context(&self) -> &CTX {
         self.context.as_ref()
     }
@@ -42,6 +115,11 @@ impl<CTX> BridgeMetadata<CTX> {
         Self {
             token: self.token.clone(),
             context: self.context.clone(),
+            session: self.session.clone(),
+            render_cache: self.render_cache.clone(),
+            app_state: self.app_state.clone(),
+            csrf_rejected: self.csrf_rejected,
+            span: self.span.clone(),
         }
     }
 }
@@ -7,8 +7,10 @@ use std::sync::Arc;
 use bounce::Selector;
 use serde::{Deserialize, Serialize};
 
+use crate::contract::{BridgeContract, ContractEntry};
 use crate::error::{BridgeError, BridgeResult};
 use crate::types::{MutationResult, QueryResult};
+use crate::upload::{UploadChunk, UploadReply};
 
 mod metadata;
 
@@ -20,33 +22,83 @@ struct Incoming<'a> {
     input: &'a [u8],
 }
 
+/// The last line of a stream that has failed on the server, so the browser can tell a failed
+/// stream from a stream that has ended.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct StreamErrorFrame {
+    stream_error: String,
+}
+
 #[derive(Default)]
 pub struct BridgeBuilder {
+    path: Option<String>,
+    contract: BridgeContract,
     #[cfg(feature = "resolvable")]
     ctx_id: Option<TypeId>,
     #[cfg(feature = "resolvable")]
     resolvers: Resolvers,
+    #[cfg(feature = "resolvable")]
+    stream_resolvers: StreamResolvers,
+    #[cfg(feature = "resolvable")]
+    upload_resolvers: UploadResolvers,
+    #[cfg(feature = "resolvable")]
+    uploads: Uploads,
     #[cfg(not(feature = "resolvable"))]
     query_ids: Vec<TypeId>,
     #[cfg(not(feature = "resolvable"))]
+    stream_ids: Vec<TypeId>,
+    #[cfg(not(feature = "resolvable"))]
+    upload_ids: Vec<TypeId>,
+    #[cfg(not(feature = "resolvable"))]
     read_token: Option<ReadToken>,
 }
 
 impl BridgeBuilder {
-    pub fn add_query<T>(self) -> Self
+    pub fn add_query<T>(mut self) -> Self
     where
         T: 'static + BridgedQuery,
     {
+        self.contract
+            .queries
+            .push(ContractEntry::new::<T, T::Input, T::Error>("query"));
+
         self.add_query_impl::<T>()
     }
 
-    pub fn add_mutation<T>(self) -> Self
+    pub fn add_mutation<T>(mut self) -> Self
     where
         T: 'static + BridgedMutation,
     {
+        self.contract
+            .queries
+            .push(ContractEntry::new::<T, T::Input, T::Error>("mutation"));
+
         self.add_mutation_impl::<T>()
     }
 
+    pub fn add_stream<T>(mut self) -> Self
+    where
+        T: 'static + BridgedStream,
+    {
+        self.contract
+            .streams
+            .push(ContractEntry::new::<T, T::Input, T::Error>("stream"));
+
+        self.add_stream_impl::<T>()
+    }
+
+    pub fn add_upload<T>(mut self) -> Self
+    where
+        T: 'static + BridgedUpload,
+    {
+        self.contract
+            .uploads
+            .push(ContractEntry::new::<T, T::Input, T::Error>("upload"));
+
+        self.add_upload_impl::<T>()
+    }
+
     pub fn with_token_selector<T>(self) -> Self
     where
         T: 'static + Selector + AsRef<str>,
@@ -54,6 +106,18 @@ impl BridgeBuilder {
         self.with_token_selector_impl::<T>()
     }
 
+    /// Sets the path that the bridge is mounted at.
+    ///
+    /// Defaults to `/_bridge`. In the browser, the path that the server renders pages with is
+    /// used instead, if any.
+    pub fn with_path<S>(mut self, path: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.path = Some(path.into());
+        self
+    }
+
     pub fn build(self) -> Bridge {
         static ID: AtomicUsize = AtomicUsize::new(0);
         let id = ID.fetch_add(1, Ordering::AcqRel);
@@ -61,6 +125,10 @@ impl BridgeBuilder {
         Bridge {
             inner: self.into(),
             id,
+            #[cfg(feature = "resolvable")]
+            metrics: Vec::new(),
+            #[cfg(feature = "resolvable")]
+            mocking: Mocking::default(),
         }
     }
 }
@@ -74,59 +142,515 @@ impl fmt::Debug for BridgeBuilder {
 #[cfg(feature = "resolvable")]
 mod feat_resolvable {
     use std::any::Any;
+    use std::collections::HashMap;
     use std::rc::Rc;
-    use std::sync::Arc;
+    use std::sync::{Arc, Mutex};
+    use std::time::{Duration, Instant};
 
     use bounce::BounceStates;
-    use futures::future::LocalBoxFuture;
-    use futures::FutureExt;
+    use futures::channel::{mpsc, oneshot};
+    use futures::future::{self, poll_fn, Either, LocalBoxFuture};
+    use futures::stream::LocalBoxStream;
+    use futures::{FutureExt, SinkExt, StreamExt};
+    use serde::de::DeserializeOwned;
+    use tracing::Instrument;
+
+    use stackable_core::mock::{Mock, Mocks};
 
     use super::*;
+    use crate::metrics::BridgeMetrics;
+    use crate::recorder::BridgeRecorder;
     pub(super) use crate::resolvers::{
         MutationResolver as BridgedMutation, QueryResolver as BridgedQuery,
+        StreamResolver as BridgedStream, UploadResolver as BridgedUpload,
     };
+    use crate::upload::{UploadMessage, UploadStream};
+
+    /// Uploads that have not received a chunk for this duration are aborted, as are uploads whose
+    /// resolver has not taken a chunk for this duration.
+    const UPLOAD_TIMEOUT: Duration = Duration::from_secs(600);
+
+    /// The number of uploads that can be pending at the same time, new uploads are rejected
+    /// when it is reached.
+    const MAX_PENDING_UPLOADS: usize = 1024;
+
+    /// The encoded output of a resolver and whether the resolver has returned an error.
+    type Resolved = (Vec<u8>, bool);
+
+    /// Resolvers are stored with their type names, which are used as names in metrics, and
+    /// whether they are mutations.
+    pub(super) type Resolvers = Vec<(
+        &'static str,
+        bool,
+        Arc<
+            dyn Send
+                + Sync
+                + Fn(&dyn Any, &[u8], &Mocking) -> LocalBoxFuture<'static, BridgeResult<Resolved>>,
+        >,
+    )>;
+
+    pub(super) type StreamResolvers = Vec<(
+        &'static str,
+        Arc<
+            dyn Send
+                + Sync
+                + Fn(
+                    &dyn Any,
+                    &[u8],
+                )
+                    -> BridgeResult<LocalBoxStream<'static, BridgeResult<(String, bool)>>>,
+        >,
+    )>;
 
-    pub(super) type Resolvers = Vec<
+    /// Upload resolvers are stored with their type names and size limits.
+    pub(super) type UploadResolvers = Vec<(
+        &'static str,
+        Option<u64>,
         Arc<
-            dyn Send + Sync + Fn(&dyn Any, &[u8]) -> LocalBoxFuture<'static, BridgeResult<Vec<u8>>>,
+            dyn Send
+                + Sync
+                + Fn(&dyn Any, &[u8], UploadStream) -> LocalBoxFuture<'static, BridgeResult<Resolved>>,
         >,
-    >;
+    )>;
+
+    pub(super) type Recorders = Vec<Arc<dyn BridgeMetrics>>;
+
+    fn record(
+        recorders: &[Arc<dyn BridgeMetrics>],
+        name: &'static str,
+        start: Instant,
+        is_err: bool,
+    ) {
+        let duration = start.elapsed();
+
+        for m in recorders {
+            m.record(name, duration, is_err);
+        }
+    }
+
+    /// Records a stream when it is dropped, whether it has finished or the client has disconnected.
+    struct StreamRecord {
+        recorders: Recorders,
+        name: &'static str,
+        start: Instant,
+        is_err: bool,
+    }
+
+    impl Drop for StreamRecord {
+        fn drop(&mut self) {
+            record(&self.recorders, self.name, self.start, self.is_err);
+        }
+    }
+
+    pub(super) type Uploads = Mutex<HashMap<String, PendingUpload>>;
+
+    pub(super) struct PendingUpload {
+        /// The session or token that has started the upload, chunks of others are rejected.
+        owner: Option<String>,
+        offset: u64,
+        /// The sender is taken while a chunk is being delivered to the resolver.
+        tx: Option<mpsc::Sender<UploadMessage>>,
+        result: oneshot::Receiver<BridgeResult<Vec<u8>>>,
+        last_active: Instant,
+        limit: Option<u64>,
+    }
+
+    fn encode_reply(reply: &UploadReply) -> BridgeResult<Vec<u8>> {
+        bincode::serialize(reply).map_err(BridgeError::Encoding)
+    }
+
+    /// The mocks that resolve queries and mutations instead of their resolvers and the recorder
+    /// of their results.
+    #[derive(Clone, Default)]
+    pub(super) struct Mocking {
+        mocks: Option<Arc<Mocks>>,
+        recorder: Option<Arc<dyn BridgeRecorder>>,
+    }
+
+    impl Mocking {
+        /// Returns the result of the mock that matches the input of a query or mutation.
+        ///
+        /// Mocks that cannot be decoded into the output or error of the query or mutation are
+        /// logged and skipped. If the mocks are strict, requests that match no mock fail with
+        /// [`BridgeError::NotMocked`].
+        fn resolve<T, I, E>(
+            &self,
+            input: &I,
+            into_error: fn(BridgeError) -> E,
+        ) -> Option<Result<T, E>>
+        where
+            T: 'static + DeserializeOwned,
+            I: Serialize,
+            E: DeserializeOwned,
+        {
+            let mocks = self.mocks.as_deref()?;
+            let name = std::any::type_name::<T>();
+            let not_mocked = || {
+                mocks
+                    .is_strict()
+                    .then(|| Err(into_error(BridgeError::NotMocked(name))))
+            };
+
+            let input = match serde_json::to_value(input) {
+                Ok(m) => m,
+                Err(_) => return not_mocked(),
+            };
+            let mock = match mocks.find(name, &input) {
+                Some(m) => m,
+                None => return not_mocked(),
+            };
+
+            let result = match mock.error {
+                Some(ref m) => serde_json::from_value(m.clone()).map(Err),
+                None => serde_json::from_value(mock.output.clone()).map(Ok),
+            };
+
+            match result {
+                Ok(m) => {
+                    tracing::debug!("resolved {} with mock {}", name, mock.name);
+                    Some(m)
+                }
+                Err(e) => {
+                    tracing::error!("failed to decode mock {}: {}", mock.name, e);
+                    not_mocked()
+                }
+            }
+        }
+
+        /// Records the result of a resolver.
+        fn record<T, I, E>(&self, input: &I, result: Result<&T, &E>)
+        where
+            T: 'static + Serialize,
+            I: Serialize,
+            E: Serialize,
+        {
+            let recorder = match self.recorder {
+                Some(ref m) => m,
+                None => return,
+            };
+
+            let encoded = serde_json::to_value(input).and_then(|input| {
+                let (output, error) = match result {
+                    Ok(m) => (serde_json::to_value(m)?, None),
+                    Err(e) => (serde_json::Value::Null, Some(serde_json::to_value(e)?)),
+                };
+
+                Ok(Mock {
+                    name: std::any::type_name::<T>().to_string(),
+                    input: Some(input),
+                    output,
+                    error,
+                })
+            });
+
+            match encoded {
+                Ok(m) => recorder.record(m),
+                Err(e) => tracing::error!("failed to record {}: {}", std::any::type_name::<T>(), e),
+            }
+        }
+    }
 
     impl Bridge {
         pub(crate) fn read_token(&self, _states: &BounceStates) -> Option<Rc<dyn AsRef<str>>> {
             None
         }
+
+        /// Adds a recorder that receives the latency and outcome of bridge requests resolved by
+        /// the server.
+        pub fn with_metrics<M>(mut self, metrics: M) -> Self
+        where
+            M: 'static + BridgeMetrics,
+        {
+            self.metrics.push(Arc::new(metrics));
+
+            self
+        }
+
+        /// Resolves queries and mutations that match a mock with the mock, e.g.: for
+        /// `stackctl serve --mock`.
+        pub fn with_mocks(mut self, mocks: Mocks) -> Self {
+            self.mocking.mocks = Some(Arc::new(mocks));
+
+            self
+        }
+
+        /// Sets the recorder that receives the results of queries and mutations resolved by
+        /// their resolvers, e.g.: for `stackctl serve --record`.
+        pub fn with_recorder<R>(mut self, recorder: R) -> Self
+        where
+            R: 'static + BridgeRecorder,
+        {
+            self.mocking.recorder = Some(Arc::new(recorder));
+
+            self
+        }
     }
 
     impl<CTX> ConnectedBridge<CTX>
     where
         CTX: 'static,
     {
+        /// Returns the span of a resolver.
+        ///
+        /// Its parent is the span in the metadata, as queries of a render may be polled outside of
+        /// the span of the render.
+        fn resolver_span<T>(&self, kind: &'static str) -> tracing::Span {
+            let parent = self
+                .metadata
+                .span()
+                .cloned()
+                .unwrap_or_else(tracing::Span::current);
+
+            tracing::info_span!(parent: &parent, "bridge_resolve", kind, name = std::any::type_name::<T>())
+        }
+
         pub(crate) async fn resolve_query<T>(&self, input: &T::Input) -> QueryResult<T>
         where
             T: 'static + BridgedQuery<Context = CTX>,
         {
-            T::resolve(&self.metadata, input).await
+            let mocking = &self.inner.mocking;
+            if let Some(m) = mocking.resolve::<T, _, T::Error>(input, T::into_query_error) {
+                return m.map(Rc::new);
+            }
+
+            let result = T::resolve(&self.metadata, input)
+                .instrument(self.resolver_span::<T>("query"))
+                .await;
+            mocking.record(input, result.as_deref());
+
+            result
         }
 
         pub(crate) async fn resolve_mutation<T>(&self, input: &T::Input) -> MutationResult<T>
         where
             T: 'static + BridgedMutation<Context = CTX>,
         {
-            T::resolve(&self.metadata, input).await
+            let mocking = &self.inner.mocking;
+            if let Some(m) = mocking.resolve::<T, _, T::Error>(input, T::into_mutation_error) {
+                return m.map(Rc::new);
+            }
+
+            let result = T::resolve(&self.metadata, input)
+                .instrument(self.resolver_span::<T>("mutation"))
+                .await;
+            mocking.record(input, result.as_deref());
+
+            result
         }
 
         pub async fn resolve_encoded(&self, incoming: &[u8]) -> BridgeResult<Vec<u8>> {
             let incoming: Incoming<'_> = bincode::deserialize(incoming)?;
 
-            let resolver = self
+            let (name, mutation, resolver) =
+                self.inner
+                    .inner
+                    .resolvers
+                    .get(incoming.query_index)
+                    .ok_or(BridgeError::InvalidIndex(incoming.query_index))?;
+
+            if *mutation && self.metadata.is_csrf_rejected() {
+                return Err(BridgeError::CsrfRejected);
+            }
+
+            let start = Instant::now();
+            let resolved = resolver(&self.metadata, incoming.input, &self.inner.mocking).await;
+            let is_err = resolved.as_ref().map(|m| m.1).unwrap_or(true);
+            record(&self.inner.metrics, name, start, is_err);
+
+            resolved.map(|m| m.0)
+        }
+
+        /// Resolves a stream, each item is a line of json.
+        pub fn resolve_stream_encoded(
+            &self,
+            incoming: &[u8],
+        ) -> BridgeResult<LocalBoxStream<'static, BridgeResult<String>>> {
+            let incoming: Incoming<'_> = bincode::deserialize(incoming)?;
+
+            let (name, resolver) = self
                 .inner
                 .inner
-                .resolvers
+                .stream_resolvers
                 .get(incoming.query_index)
                 .ok_or(BridgeError::InvalidIndex(incoming.query_index))?;
 
-            resolver(&self.metadata, incoming.input).await
+            let mut stream_record = StreamRecord {
+                recorders: self.inner.metrics.clone(),
+                name,
+                start: Instant::now(),
+                is_err: false,
+            };
+
+            let lines = match resolver(&self.metadata, incoming.input) {
+                Ok(m) => m,
+                Err(e) => {
+                    stream_record.is_err = true;
+                    return Err(e);
+                }
+            };
+
+            Ok(lines
+                .map(move |m| {
+                    let is_err = m.as_ref().map(|m| m.1).unwrap_or(true);
+                    stream_record.is_err = stream_record.is_err || is_err;
+
+                    m.map(|m| m.0)
+                })
+                .boxed_local())
+        }
+
+        /// Resolves a chunk of an upload.
+        ///
+        /// The reply is sent after the resolver has taken the chunk, so the browser cannot send
+        /// chunks faster than the resolver consumes them.
+        pub async fn resolve_upload_encoded(&self, incoming: &[u8]) -> BridgeResult<Vec<u8>> {
+            let chunk: UploadChunk<'_> = bincode::deserialize(incoming)?;
+            let uploads = &self.inner.inner.uploads;
+
+            if self.metadata.is_csrf_rejected() {
+                return Err(BridgeError::CsrfRejected);
+            }
+
+            let owner = self
+                .metadata
+                .session()
+                .and_then(|m| m.id())
+                .or_else(|| self.metadata.token().map(|m| m.to_string()));
+
+            let mut tx = {
+                let mut uploads = uploads.lock().expect("failed to lock uploads");
+                // Dropping the sender of an expired upload aborts it.
+                uploads.retain(|_, m| m.tx.is_none() || m.last_active.elapsed() < UPLOAD_TIMEOUT);
+
+                if !uploads.contains_key(chunk.upload_id) {
+                    let input = match chunk.input {
+                        Some(m) if chunk.offset == 0 => m,
+                        _ => return encode_reply(&UploadReply::Mismatch { offset: 0 }),
+                    };
+
+                    if uploads.len() >= MAX_PENDING_UPLOADS {
+                        return Err(BridgeError::Aborted);
+                    }
+
+                    let (name, limit, resolver) = self
+                        .inner
+                        .inner
+                        .upload_resolvers
+                        .get(chunk.query_index)
+                        .ok_or(BridgeError::InvalidIndex(chunk.query_index))?;
+
+                    let (tx, rx) = mpsc::channel(0);
+                    let (result_tx, result_rx) = oneshot::channel();
+                    let resolve = resolver(&self.metadata, input, UploadStream::new(rx));
+                    let recorders = self.inner.metrics.clone();
+                    yew::platform::spawn_local(async move {
+                        let start = Instant::now();
+                        let resolved = resolve.await;
+                        let is_err = resolved.as_ref().map(|m| m.1).unwrap_or(true);
+                        record(&recorders, name, start, is_err);
+
+                        let _ = result_tx.send(resolved.map(|m| m.0));
+                    });
+
+                    uploads.insert(
+                        chunk.upload_id.to_string(),
+                        PendingUpload {
+                            owner: owner.clone(),
+                            offset: 0,
+                            tx: Some(tx),
+                            result: result_rx,
+                            last_active: Instant::now(),
+                            limit: *limit,
+                        },
+                    );
+                }
+
+                let upload = uploads
+                    .get_mut(chunk.upload_id)
+                    .expect("failed to find upload");
+
+                // An upload started with a session or token only accepts chunks with the same
+                // session or token, the offset of the upload is not revealed to others.
+                if upload.owner.is_some() && upload.owner != owner {
+                    return encode_reply(&UploadReply::Mismatch { offset: 0 });
+                }
+
+                // Another chunk is being delivered, the browser should retry later.
+                if upload.offset != chunk.offset || upload.tx.is_none() {
+                    return encode_reply(&UploadReply::Mismatch {
+                        offset: upload.offset,
+                    });
+                }
+
+                // Dropping the sender aborts the resolver.
+                if let Some(limit) = upload
+                    .limit
+                    .filter(|m| chunk.offset + chunk.data.len() as u64 > *m)
+                {
+                    uploads.remove(chunk.upload_id);
+                    return encode_reply(&UploadReply::TooLarge { limit });
+                }
+
+                upload.last_active = Instant::now();
+                upload.tx.take().expect("failed to take sender")
+            };
+
+            let deliver = async {
+                tx.send(UploadMessage::Chunk(chunk.data.to_vec())).await?;
+
+                if chunk.last {
+                    tx.send(UploadMessage::End).await
+                } else {
+                    // Waits until the resolver has taken the chunk.
+                    poll_fn(|cx| tx.poll_ready(cx)).await
+                }
+            };
+
+            let accepted = match future::select(
+                deliver.boxed_local(),
+                yew::platform::time::sleep(UPLOAD_TIMEOUT).boxed_local(),
+            )
+            .await
+            {
+                Either::Left((m, _)) => m.is_ok(),
+                Either::Right(_) => {
+                    // The resolver has stopped taking chunks, dropping the sender aborts it.
+                    uploads
+                        .lock()
+                        .expect("failed to lock uploads")
+                        .remove(chunk.upload_id);
+
+                    return Err(BridgeError::Aborted);
+                }
+            };
+
+            let upload = {
+                let mut uploads = uploads.lock().expect("failed to lock uploads");
+
+                if accepted && !chunk.last {
+                    let upload = uploads
+                        .get_mut(chunk.upload_id)
+                        .ok_or(BridgeError::Aborted)?;
+
+                    upload.offset += chunk.data.len() as u64;
+                    upload.last_active = Instant::now();
+                    upload.tx = Some(tx);
+
+                    return encode_reply(&UploadReply::Accepted {
+                        offset: upload.offset,
+                    });
+                }
+
+                // The upload is completed or the resolver has returned early.
+                uploads
+                    .remove(chunk.upload_id)
+                    .ok_or(BridgeError::Aborted)?
+            };
+
+            drop(tx);
+            let output = upload.result.await.map_err(|_| BridgeError::Aborted)??;
+
+            encode_reply(&UploadReply::Completed(output))
         }
     }
 
@@ -144,25 +668,40 @@ mod feat_resolvable {
             }
             self.ctx_id = Some(ctx_id);
 
-            let resolver = Arc::new(|metadata: &dyn Any, input: &[u8]| {
+            let resolver = Arc::new(|metadata: &dyn Any, input: &[u8], mocking: &Mocking| {
                 let input = match bincode::deserialize::<T::Input>(input) {
                     Ok(m) => m,
                     Err(e) => return async move { Err(BridgeError::Encoding(e)) }.boxed_local(),
                 };
 
+                if let Some(result) = mocking.resolve::<T, _, T::Error>(&input, T::into_query_error)
+                {
+                    return async move {
+                        bincode::serialize(&result.as_ref())
+                            .map(|m| (m, result.is_err()))
+                            .map_err(BridgeError::Encoding)
+                    }
+                    .boxed_local();
+                }
+
                 let metadata = metadata
                     .downcast_ref::<BridgeMetadata<T::Context>>()
                     .expect("failed to downcast the context!")
                     .duplicate();
+                let mocking = mocking.clone();
 
                 async move {
                     let result = T::resolve(&metadata, &input).await;
-                    bincode::serialize(&result.as_deref()).map_err(BridgeError::Encoding)
+                    mocking.record(&input, result.as_deref());
+                    bincode::serialize(&result.as_deref())
+                        .map(|m| (m, result.is_err()))
+                        .map_err(BridgeError::Encoding)
                 }
                 .boxed_local()
             });
 
-            self.resolvers.push(resolver);
+            self.resolvers
+                .push((std::any::type_name::<T>(), false, resolver));
             self
         }
 
@@ -179,25 +718,117 @@ mod feat_resolvable {
             }
             self.ctx_id = Some(ctx_id);
 
-            let resolver = Arc::new(|metadata: &dyn Any, input: &[u8]| {
+            let resolver = Arc::new(|metadata: &dyn Any, input: &[u8], mocking: &Mocking| {
                 let input = match bincode::deserialize::<T::Input>(input) {
                     Ok(m) => m,
                     Err(e) => return async move { Err(BridgeError::Encoding(e)) }.boxed_local(),
                 };
 
+                if let Some(result) =
+                    mocking.resolve::<T, _, T::Error>(&input, T::into_mutation_error)
+                {
+                    return async move {
+                        bincode::serialize(&result.as_ref())
+                            .map(|m| (m, result.is_err()))
+                            .map_err(BridgeError::Encoding)
+                    }
+                    .boxed_local();
+                }
+
                 let metadata = metadata
                     .downcast_ref::<BridgeMetadata<T::Context>>()
                     .expect("failed to downcast the context!")
                     .duplicate();
+                let mocking = mocking.clone();
 
                 async move {
                     let result = T::resolve(&metadata, &input).await;
-                    bincode::serialize(&result.as_deref()).map_err(BridgeError::Encoding)
+                    mocking.record(&input, result.as_deref());
+                    bincode::serialize(&result.as_deref())
+                        .map(|m| (m, result.is_err()))
+                        .map_err(BridgeError::Encoding)
+                }
+                .boxed_local()
+            });
+
+            self.resolvers
+                .push((std::any::type_name::<T>(), true, resolver));
+
+            self
+        }
+
+        pub(super) fn add_stream_impl<T>(mut self) -> Self
+        where
+            T: 'static + BridgedStream,
+        {
+            let ctx_id = TypeId::of::<T::Context>();
+            if let Some(m) = self.ctx_id {
+                assert_eq!(
+                    m, ctx_id,
+                    "all resolvers should have the same context type!"
+                );
+            }
+            self.ctx_id = Some(ctx_id);
+
+            let resolver = Arc::new(|metadata: &dyn Any, input: &[u8]| -> BridgeResult<_> {
+                let input = bincode::deserialize::<T::Input>(input)?;
+
+                let metadata = metadata
+                    .downcast_ref::<BridgeMetadata<T::Context>>()
+                    .expect("failed to downcast the context!")
+                    .duplicate();
+
+                let items = T::resolve(metadata, input).map(|m| -> BridgeResult<(String, bool)> {
+                    let mut line = serde_json::to_string(&m)?;
+                    line.push('\n');
+
+                    Ok((line, m.is_err()))
+                });
+
+                Ok(items.boxed_local())
+            });
+
+            self.stream_resolvers
+                .push((std::any::type_name::<T>(), resolver));
+
+            self
+        }
+
+        pub(super) fn add_upload_impl<T>(mut self) -> Self
+        where
+            T: 'static + BridgedUpload,
+        {
+            let ctx_id = TypeId::of::<T::Context>();
+            if let Some(m) = self.ctx_id {
+                assert_eq!(
+                    m, ctx_id,
+                    "all resolvers should have the same context type!"
+                );
+            }
+            self.ctx_id = Some(ctx_id);
+
+            let resolver = Arc::new(|metadata: &dyn Any, input: &[u8], chunks: UploadStream| {
+                let input = match bincode::deserialize::<T::Input>(input) {
+                    Ok(m) => m,
+                    Err(e) => return async move { Err(BridgeError::Encoding(e)) }.boxed_local(),
+                };
+
+                let metadata = metadata
+                    .downcast_ref::<BridgeMetadata<T::Context>>()
+                    .expect("failed to downcast the context!")
+                    .duplicate();
+
+                async move {
+                    let result = T::resolve(&metadata, &input, chunks).await;
+                    bincode::serialize(&result.as_deref())
+                        .map(|m| (m, result.is_err()))
+                        .map_err(BridgeError::Encoding)
                 }
                 .boxed_local()
             });
 
-            self.resolvers.push(resolver);
+            self.upload_resolvers
+                .push((std::any::type_name::<T>(), T::MAX_SIZE, resolver));
 
             self
         }
@@ -217,15 +848,97 @@ use feat_resolvable::*;
 mod not_feat_resolvable {
     use std::rc::Rc;
 
+    use std::time::Duration;
+
     use bounce::{BounceStates, Selector};
-    use gloo_net::http::Request;
+    use futures::stream::{self, LocalBoxStream};
+    use futures::{pin_mut, Stream, StreamExt};
+    use gloo_net::http::{Request, Response};
     use js_sys::Uint8Array;
+    use wasm_bindgen::JsCast;
+    use web_sys::HtmlDocument;
+
+    use stackable_core::build_info::BUILD_ID;
 
     use super::*;
-    pub(super) use crate::types::{BridgedMutation, BridgedQuery};
+    pub(super) use crate::types::{BridgedMutation, BridgedQuery, BridgedStream, BridgedUpload};
+    use crate::types::{StreamResult, UploadResult};
+
+    /// The number of times a chunk is retried before an upload fails.
+    const UPLOAD_RETRIES: usize = 5;
+
+    /// Splits chunks of a response into lines.
+    fn split_lines<S>(chunks: S) -> impl Stream<Item = BridgeResult<String>>
+    where
+        S: 'static + Stream<Item = BridgeResult<Vec<u8>>>,
+    {
+        stream::unfold(
+            (chunks.boxed_local(), Vec::new(), false),
+            |(mut chunks, mut buf, mut done)| async move {
+                loop {
+                    if let Some(pos) = buf.iter().position(|m| *m == b'\n') {
+                        let line = buf.drain(..=pos).collect::<Vec<_>>();
+                        let line = String::from_utf8_lossy(&line).trim().to_string();
+
+                        if line.is_empty() {
+                            continue;
+                        }
+
+                        return Some((Ok(line), (chunks, buf, done)));
+                    }
+
+                    if done {
+                        let line = String::from_utf8_lossy(&buf).trim().to_string();
+                        buf.clear();
+
+                        return match line.is_empty() {
+                            true => None,
+                            false => Some((Ok(line), (chunks, buf, done))),
+                        };
+                    }
+
+                    match chunks.next().await {
+                        Some(Ok(m)) => buf.extend(m),
+                        Some(Err(e)) => {
+                            buf.clear();
+                            return Some((Err(e), (chunks, buf, true)));
+                        }
+                        None => {
+                            done = true;
+                        }
+                    }
+                }
+            },
+        )
+    }
 
     pub(super) type ReadToken = Box<dyn Fn(&BounceStates) -> Rc<dyn AsRef<str>>>;
 
+    /// Reads the CSRF token that the server has issued with the page, if any.
+    fn csrf_token() -> Option<String> {
+        let cookies = web_sys::window()?
+            .document()?
+            .dyn_into::<HtmlDocument>()
+            .ok()?
+            .cookie()
+            .ok()?;
+
+        cookies
+            .split(';')
+            .filter_map(|m| m.trim().split_once('='))
+            .find(|(name, _)| *name == Bridge::CSRF_COOKIE)
+            .map(|(_, value)| value.to_string())
+    }
+
+    /// Reads the path of the bridge that the server has rendered the page with, if any.
+    fn rendered_path() -> Option<String> {
+        web_sys::window()?
+            .document()?
+            .query_selector(&format!(r#"meta[name="{}"]"#, Bridge::PATH_META_NAME))
+            .ok()??
+            .get_attribute("content")
+    }
+
     impl Bridge {
         pub(crate) fn read_token(&self, states: &BounceStates) -> Option<Rc<dyn AsRef<str>>> {
             self.inner.read_token.as_ref().map(|m| m(states))
@@ -233,7 +946,57 @@ mod not_feat_resolvable {
     }
 
     impl<CTX> ConnectedBridge<CTX> {
-        async fn resolve_encoded(&self, type_id: TypeId, input: &[u8]) -> BridgeResult<Vec<u8>> {
+        /// Creates a request to the bridge with the token and the build id of the frontend.
+        fn create_request(&self) -> Request {
+            // The path configured on the server takes precedence over the path of the bridge.
+            let path = rendered_path();
+            let mut req = Request::post(path.as_deref().unwrap_or_else(|| self.inner.path()));
+
+            if let Some(m) = self.metadata.token() {
+                req = req.header("authorization", &format!("Bearer {}", m));
+            }
+
+            if let Some(m) = BUILD_ID {
+                req = req.header(Bridge::BUILD_ID_HEADER, m);
+            }
+
+            req
+        }
+
+        /// Creates a request that changes state on the server, which carries the CSRF token.
+        fn create_mutating_request(&self) -> Request {
+            let req = self.create_request();
+
+            match csrf_token() {
+                Some(m) => req.header(Bridge::CSRF_HEADER, &m),
+                None => req,
+            }
+        }
+
+        /// Sends the request and notifies the application if the server has been updated.
+        async fn send_request(req: Request) -> BridgeResult<Response> {
+            let resp = req.send().await?;
+
+            if resp.headers().get(Bridge::RELOAD_REQUIRED_HEADER).is_some() {
+                if let Some(m) = web_sys::window() {
+                    if let Ok(e) = web_sys::Event::new(Bridge::RELOAD_REQUIRED_EVENT) {
+                        let _ = m.dispatch_event(&e);
+                    }
+                }
+
+                return Err(BridgeError::ReloadRequired);
+            }
+
+            Ok(resp)
+        }
+
+        /// Resolves a query or a mutation, mutations carry the CSRF token.
+        async fn resolve_encoded(
+            &self,
+            type_id: TypeId,
+            input: &[u8],
+            mutation: bool,
+        ) -> BridgeResult<Vec<u8>> {
             let idx = self
                 .inner
                 .inner
@@ -252,17 +1015,287 @@ mod not_feat_resolvable {
             let incoming = bincode::serialize(&incoming)?;
 
             let input = Uint8Array::from(incoming.as_slice());
-            let mut req = Request::post("/_bridge")
+            let req = if mutation {
+                self.create_mutating_request()
+            } else {
+                self.create_request()
+            };
+            let req = req
                 .header("content-type", "application/x-bincode")
                 .body(input);
 
-            if let Some(m) = self.metadata.token() {
-                req = req.header("authorization", &format!("Bearer {}", m));
+            let resp = Self::send_request(req).await?;
+
+            resp.binary().await.map_err(|m| m.into())
+        }
+
+        async fn resolve_stream_encoded(
+            &self,
+            type_id: TypeId,
+            input: &[u8],
+        ) -> BridgeResult<LocalBoxStream<'static, BridgeResult<String>>> {
+            let idx = self
+                .inner
+                .inner
+                .stream_ids
+                .iter()
+                .enumerate()
+                .find(|(_, m)| **m == type_id)
+                .ok_or(BridgeError::InvalidType(type_id))?
+                .0;
+
+            let incoming = Incoming {
+                query_index: idx,
+                input,
+            };
+
+            let incoming = bincode::serialize(&incoming)?;
+
+            let input = Uint8Array::from(incoming.as_slice());
+            let req = self
+                .create_request()
+                .header("content-type", "application/x-bincode")
+                .header("accept", "application/x-ndjson")
+                .body(input);
+
+            let resp = Self::send_request(req).await?;
+
+            if !resp.ok() {
+                return Err(BridgeError::Network(gloo_net::Error::GlooError(format!(
+                    "server responded with status {}",
+                    resp.status()
+                ))));
             }
 
-            let resp = req.send().await?;
+            match resp.body() {
+                Some(m) => {
+                    let chunks = wasm_streams::ReadableStream::from_raw(m.unchecked_into())
+                        .into_stream()
+                        .map(|m| {
+                            m.map(|m| Uint8Array::new(&m).to_vec()).map_err(|e| {
+                                BridgeError::Network(gloo_net::Error::GlooError(format!(
+                                    "failed to read response: {:?}",
+                                    e
+                                )))
+                            })
+                        });
+
+                    Ok(split_lines(chunks).boxed_local())
+                }
+                // Browsers that do not support streaming responses, we read the entire response.
+                None => {
+                    let lines = resp
+                        .text()
+                        .await?
+                        .lines()
+                        .filter(|m| !m.trim().is_empty())
+                        .map(|m| Ok(m.to_string()))
+                        .collect::<Vec<_>>();
+
+                    Ok(stream::iter(lines).boxed_local())
+                }
+            }
+        }
 
-            resp.binary().await.map_err(|m| m.into())
+        /// Resolves a stream, errors of the bridge are kept apart from errors of the resolver.
+        pub(crate) async fn resolve_stream_raw<T>(
+            &self,
+            input: &T::Input,
+        ) -> BridgeResult<LocalBoxStream<'static, BridgeResult<StreamResult<T>>>>
+        where
+            T: 'static + BridgedStream,
+        {
+            let input = bincode::serialize(&input).map_err(BridgeError::Encoding)?;
+            let type_id = TypeId::of::<T>();
+
+            let lines = self.resolve_stream_encoded(type_id, &input).await?;
+
+            Ok(lines
+                .map(|m| {
+                    m.and_then(|m| match serde_json::from_str::<StreamResult<T>>(&m) {
+                        Ok(m) => Ok(m),
+                        Err(e) => match serde_json::from_str::<StreamErrorFrame>(&m) {
+                            Ok(m) => Err(BridgeError::StreamFailed(m.stream_error)),
+                            Err(_) => Err(BridgeError::Json(e)),
+                        },
+                    })
+                })
+                .boxed_local())
+        }
+
+        pub(crate) async fn resolve_stream<T>(
+            &self,
+            input: &T::Input,
+        ) -> LocalBoxStream<'static, StreamResult<T>>
+        where
+            T: 'static + BridgedStream,
+        {
+            match self.resolve_stream_raw::<T>(input).await {
+                Ok(m) => m
+                    .map(|m| m.map_err(T::into_stream_error).and_then(|m| m))
+                    .boxed_local(),
+                Err(e) => stream::once(async move { Err(T::into_stream_error(e)) }).boxed_local(),
+            }
+        }
+
+        async fn send_upload_chunk(&self, chunk: &[u8]) -> BridgeResult<UploadReply> {
+            let input = Uint8Array::from(chunk);
+            let req = self
+                .create_mutating_request()
+                .header("content-type", "application/x-bincode")
+                .header("accept", "application/x-stackable-upload")
+                .body(input);
+
+            let resp = Self::send_request(req).await?;
+
+            if !resp.ok() {
+                return Err(BridgeError::Network(gloo_net::Error::GlooError(format!(
+                    "server responded with status {}",
+                    resp.status()
+                ))));
+            }
+
+            Ok(bincode::deserialize(&resp.binary().await?)?)
+        }
+
+        /// Sends chunks to the server one at a time.
+        ///
+        /// A chunk that fails to send is retried from the offset that the server has received.
+        async fn resolve_upload_encoded<S>(
+            &self,
+            type_id: TypeId,
+            input: &[u8],
+            chunks: S,
+            on_progress: &dyn Fn(u64),
+        ) -> BridgeResult<Vec<u8>>
+        where
+            S: Stream<Item = Vec<u8>>,
+        {
+            let idx = self
+                .inner
+                .inner
+                .upload_ids
+                .iter()
+                .enumerate()
+                .find(|(_, m)| **m == type_id)
+                .ok_or(BridgeError::InvalidType(type_id))?
+                .0;
+
+            // Chunks are accepted by the id, so it must not be guessable by others.
+            let mut random = [0; 16];
+            web_sys::window()
+                .and_then(|m| m.crypto().ok())
+                .and_then(|m| m.get_random_values_with_u8_array(&mut random).ok())
+                .ok_or(BridgeError::Aborted)?;
+            let upload_id: String = random.iter().map(|m| format!("{m:02x}")).collect();
+
+            let chunks = chunks.peekable();
+            pin_mut!(chunks);
+
+            // An empty upload is sent as a single empty chunk.
+            let mut data = chunks.next().await.unwrap_or_default();
+            let mut offset = 0;
+
+            loop {
+                let last = chunks.as_mut().peek().await.is_none();
+                let chunk = bincode::serialize(&UploadChunk {
+                    query_index: idx,
+                    upload_id: &upload_id,
+                    offset,
+                    input: (offset == 0).then_some(input),
+                    data: &data,
+                    last,
+                })?;
+
+                let mut retries = 0;
+                let next_offset = offset + data.len() as u64;
+
+                loop {
+                    let reply = match self.send_upload_chunk(&chunk).await {
+                        Ok(m) => m,
+                        Err(BridgeError::Network(_)) if retries < UPLOAD_RETRIES => {
+                            retries += 1;
+                            yew::platform::time::sleep(Duration::from_millis(500 * retries as u64))
+                                .await;
+                            continue;
+                        }
+                        Err(e) => return Err(e),
+                    };
+
+                    match reply {
+                        UploadReply::Completed(m) => return Ok(m),
+                        UploadReply::TooLarge { limit } => {
+                            return Err(BridgeError::TooLarge(limit))
+                        }
+                        UploadReply::Accepted { offset: m } => {
+                            offset = m;
+                            on_progress(offset);
+                            break;
+                        }
+                        // The chunk was received before the last request failed.
+                        UploadReply::Mismatch { offset: m } if m == next_offset => {
+                            offset = m;
+                            on_progress(offset);
+                            break;
+                        }
+                        // The last request is still being processed.
+                        UploadReply::Mismatch { offset: m }
+                            if m == offset && retries < UPLOAD_RETRIES =>
+                        {
+                            retries += 1;
+                            yew::platform::time::sleep(Duration::from_millis(500 * retries as u64))
+                                .await;
+                        }
+                        UploadReply::Mismatch { offset: m } => {
+                            return Err(BridgeError::OffsetMismatch(m))
+                        }
+                    }
+                }
+
+                // The server has received the last chunk without completing the upload.
+                if last {
+                    return Err(BridgeError::Aborted);
+                }
+
+                data = chunks.next().await.expect("failed to read chunk");
+            }
+        }
+
+        /// Uploads a stream of chunks, the resolver on the server receives them as they arrive.
+        pub async fn resolve_upload<T, S>(&self, input: &T::Input, chunks: S) -> UploadResult<T>
+        where
+            T: 'static + BridgedUpload,
+            S: Stream<Item = Vec<u8>>,
+        {
+            self.resolve_upload_with_progress::<T, S, _>(input, chunks, |_| {})
+                .await
+        }
+
+        /// Uploads a stream of chunks, `on_progress` is called with the number of bytes that the
+        /// server has taken after each chunk.
+        pub async fn resolve_upload_with_progress<T, S, F>(
+            &self,
+            input: &T::Input,
+            chunks: S,
+            on_progress: F,
+        ) -> UploadResult<T>
+        where
+            T: 'static + BridgedUpload,
+            S: Stream<Item = Vec<u8>>,
+            F: Fn(u64),
+        {
+            let inner = move || async move {
+                let input = bincode::serialize(&input).map_err(BridgeError::Encoding)?;
+                let type_id = TypeId::of::<T>();
+
+                let output = self
+                    .resolve_upload_encoded(type_id, &input, chunks, &on_progress)
+                    .await?;
+                bincode::deserialize::<std::result::Result<T, T::Error>>(&output)
+                    .map_err(BridgeError::Encoding)
+            };
+
+            inner().await.map_err(T::into_upload_error)?.map(Rc::new)
         }
 
         pub(crate) async fn resolve_query<T>(&self, input: &T::Input) -> QueryResult<T>
@@ -273,7 +1306,7 @@ mod not_feat_resolvable {
                 let input = bincode::serialize(&input).map_err(BridgeError::Encoding)?;
                 let type_id = TypeId::of::<T>();
 
-                let output = self.resolve_encoded(type_id, &input).await?;
+                let output = self.resolve_encoded(type_id, &input, false).await?;
                 bincode::deserialize::<std::result::Result<T, T::Error>>(&output)
                     .map_err(BridgeError::Encoding)
             };
@@ -289,7 +1322,7 @@ mod not_feat_resolvable {
                 let input = bincode::serialize(&input).map_err(BridgeError::Encoding)?;
                 let type_id = TypeId::of::<T>();
 
-                let output = self.resolve_encoded(type_id, &input).await?;
+                let output = self.resolve_encoded(type_id, &input, true).await?;
                 bincode::deserialize::<std::result::Result<T, T::Error>>(&output)
                     .map_err(BridgeError::Encoding)
             };
@@ -319,6 +1352,26 @@ mod not_feat_resolvable {
             self
         }
 
+        pub(super) fn add_stream_impl<T>(mut self) -> Self
+        where
+            T: 'static + BridgedStream,
+        {
+            let type_id = TypeId::of::<T>();
+            self.stream_ids.push(type_id);
+
+            self
+        }
+
+        pub(super) fn add_upload_impl<T>(mut self) -> Self
+        where
+            T: 'static + BridgedUpload,
+        {
+            let type_id = TypeId::of::<T>();
+            self.upload_ids.push(type_id);
+
+            self
+        }
+
         pub(super) fn with_token_selector_impl<T>(mut self) -> Self
         where
             T: 'static + Selector + AsRef<str>,
@@ -341,6 +1394,10 @@ use not_feat_resolvable::*;
 pub struct Bridge {
     inner: Arc<BridgeBuilder>,
     id: usize,
+    #[cfg(feature = "resolvable")]
+    metrics: Recorders,
+    #[cfg(feature = "resolvable")]
+    mocking: Mocking,
 }
 
 impl fmt::Debug for Bridge {
@@ -365,10 +1422,44 @@ impl Hash for Bridge {
 }
 
 impl Bridge {
+    /// The header that carries the build id of the frontend in bridge requests.
+    pub const BUILD_ID_HEADER: &str = "x-stackable-build-id";
+    /// The header the server responds with when the frontend is from a different build.
+    pub const RELOAD_REQUIRED_HEADER: &str = "x-stackable-reload-required";
+    /// The event dispatched on `window` when the server requires the frontend to reload.
+    pub const RELOAD_REQUIRED_EVENT: &str = "stackable-reload-required";
+    /// The cookie that carries the CSRF token issued by the server.
+    pub const CSRF_COOKIE: &str = "stackable-csrf";
+    /// The header that mutations and uploads copy the CSRF token into.
+    pub const CSRF_HEADER: &str = "x-stackable-csrf";
+    /// The meta tag that carries the path of the bridge configured on the server.
+    pub const PATH_META_NAME: &str = "stackable-bridge-path";
+
     pub fn builder() -> BridgeBuilder {
         BridgeBuilder::default()
     }
 
+    /// Returns the line that ends a stream which has failed on the server.
+    pub fn stream_error_line(e: &BridgeError) -> String {
+        let frame = StreamErrorFrame {
+            stream_error: e.to_string(),
+        };
+        let mut line = serde_json::to_string(&frame).expect("failed to serialise stream error");
+        line.push('\n');
+
+        line
+    }
+
+    /// Returns the path that the bridge is mounted at.
+    pub fn path(&self) -> &str {
+        self.inner.path.as_deref().unwrap_or("/_bridge")
+    }
+
+    /// Returns the types registered with the bridge.
+    pub fn contract(&self) -> &BridgeContract {
+        &self.inner.contract
+    }
+
     pub async fn connect<CTX>(
         self,
         metadata: BridgeMetadata<CTX>,
@@ -399,6 +1490,10 @@ impl Clone for Bridge {
         Self {
             inner: self.inner.clone(),
             id: self.id,
+            #[cfg(feature = "resolvable")]
+            metrics: self.metrics.clone(),
+            #[cfg(feature = "resolvable")]
+            mocking: self.mocking.clone(),
         }
     }
 }
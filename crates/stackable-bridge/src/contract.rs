@@ -0,0 +1,130 @@
+//! The types registered with a bridge, used to check that the browser and the server agree.
+//!
+//! Requests refer to types by their index, so a frontend and a backend built from different
+//! versions of a bridge can send requests to the wrong resolver.
+
+use serde::{Deserialize, Serialize};
+use serde_reflection::{Tracer, TracerConfig};
+
+/// A type registered with the bridge.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContractEntry {
+    /// The type path of the query, mutation, stream or upload.
+    pub name: String,
+    /// A hash of the serde schemas of the output, input and error.
+    pub schema_hash: String,
+}
+
+impl ContractEntry {
+    /// Creates an entry of a type, whose output is the type itself.
+    pub(crate) fn new<T, I, E>(kind: &str) -> Self
+    where
+        T: for<'de> Deserialize<'de>,
+        I: for<'de> Deserialize<'de>,
+        E: for<'de> Deserialize<'de>,
+    {
+        let name = std::any::type_name::<T>();
+
+        let mut tracer = Tracer::new(TracerConfig::default());
+        let output = trace::<T>(&mut tracer);
+        let input = trace::<I>(&mut tracer);
+        let error = trace::<E>(&mut tracer);
+        // The formats of the named types that the schemas refer to, e.g.: fields of structs.
+        let registry = serde_json::to_string(&tracer.registry_unchecked()).unwrap_or_default();
+
+        Self {
+            name: name.to_string(),
+            schema_hash: schema_hash(&[kind, name, &output, &input, &error, &registry]),
+        }
+    }
+}
+
+/// Returns the serde schema of a type.
+///
+/// Types that cannot be traced, e.g.: types that validate their values when they are
+/// deserialized, are described by their type names.
+fn trace<T>(tracer: &mut Tracer) -> String
+where
+    T: for<'de> Deserialize<'de>,
+{
+    tracer
+        .trace_simple_type::<T>()
+        .ok()
+        .and_then(|(format, _)| serde_json::to_string(&format).ok())
+        .unwrap_or_else(|| std::any::type_name::<T>().to_string())
+}
+
+/// The types registered with a bridge, in the order they are indexed in requests.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BridgeContract {
+    /// Queries and mutations, they share the same indices.
+    pub queries: Vec<ContractEntry>,
+    pub streams: Vec<ContractEntry>,
+    pub uploads: Vec<ContractEntry>,
+}
+
+impl BridgeContract {
+    /// If set, applications write the contract of their bridge to this path and exit.
+    pub const ENV_NAME: &str = "STACKABLE_BRIDGE_CONTRACT";
+    /// The contract of the backend, written to the build directory by `stackctl build`.
+    pub const FILE_NAME: &str = "bridge-contract.json";
+
+    pub fn from_json(s: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(s)
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Compares the contract of a frontend with the contract of a backend.
+    ///
+    /// Returns a description of each incompatibility. Types that only the backend registers are
+    /// allowed.
+    pub fn check(&self, backend: &Self) -> Vec<String> {
+        let mut problems = Vec::new();
+
+        let kinds = [
+            ("query / mutation", &self.queries, &backend.queries),
+            ("stream", &self.streams, &backend.streams),
+            ("upload", &self.uploads, &backend.uploads),
+        ];
+
+        for (kind, frontend, backend) in kinds {
+            for (index, entry) in frontend.iter().enumerate() {
+                match backend.get(index) {
+                    None => problems.push(format!(
+                        "{kind} {} (index {index}) is not registered in the backend",
+                        entry.name
+                    )),
+                    Some(m) if m.name != entry.name => problems.push(format!(
+                        "{kind} {} (index {index}) is registered as {} in the backend",
+                        entry.name, m.name
+                    )),
+                    Some(m) if m.schema_hash != entry.schema_hash => problems.push(format!(
+                        "{kind} {} (index {index}) has a different schema in the backend",
+                        entry.name
+                    )),
+                    Some(_) => {}
+                }
+            }
+        }
+
+        problems
+    }
+}
+
+/// A FNV-1a hash, which is stable across compilers and platforms.
+fn schema_hash(parts: &[&str]) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+
+    for part in parts {
+        // The separator prevents ("ab", "c") and ("a", "bc") from colliding.
+        for b in part.bytes().chain(std::iter::once(0)) {
+            hash ^= u64::from(b);
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+    }
+
+    format!("{:016x}", hash)
+}
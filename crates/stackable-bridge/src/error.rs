@@ -1,5 +1,6 @@
 use std::any::TypeId;
 
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -8,9 +9,183 @@ pub enum BridgeError {
     Network(#[from] gloo_net::Error),
     #[error("failed to encode / decode content")]
     Encoding(#[from] bincode::Error),
+    #[error("failed to encode / decode json")]
+    Json(#[from] serde_json::Error),
     #[error("failed to find type with index: {}", .0)]
     InvalidIndex(usize),
     #[error("failed to find type: {:?}", .0)]
     InvalidType(TypeId),
+    #[error("upload has been aborted")]
+    Aborted,
+    #[error("upload cannot be resumed, server expects offset: {}", .0)]
+    OffsetMismatch(u64),
+    #[error("upload is larger than the limit of {} bytes", .0)]
+    TooLarge(u64),
+    #[error("server has been updated, reload required")]
+    ReloadRequired,
+    #[error("request is rejected by csrf protection")]
+    CsrfRejected,
+    #[error("failed to find a mock of {}", .0)]
+    NotMocked(&'static str),
+    #[error("stream has failed on the server: {}", .0)]
+    StreamFailed(String),
 }
 pub type BridgeResult<T> = Result<T, BridgeError>;
+
+/// The code of errors that are caused by failures to communicate with the server.
+const NETWORK_CODE: &str = "network";
+/// The code of errors that are converted from other errors on the server.
+const INTERNAL_CODE: &str = "internal";
+
+/// An error of a server function that is sent to the browser.
+///
+/// It carries an HTTP status code, a machine-readable code and a message that can be shown to
+/// users. Errors converted from `anyhow::Error` or with [`ServerError::from_error`] are logged and
+/// sent as `500 internal`, so details of the error are not exposed to the browser.
+#[derive(Error, Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[error("{message} ({status} {code})")]
+pub struct ServerError {
+    status: u16,
+    code: String,
+    message: String,
+}
+
+/// The state of the page that a [`ServerError`] maps to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ServerErrorKind {
+    /// The server cannot be reached, the request can be retried.
+    Network,
+    /// The input is invalid (400, 413, 422).
+    Invalid,
+    /// The user is not signed in (401).
+    Unauthenticated,
+    /// The user is not allowed to perform the request (403).
+    Forbidden,
+    /// The resource does not exist (404, 410).
+    NotFound,
+    /// The request conflicts with the current state of the resource (409).
+    Conflict,
+    /// The server is busy, the request can be retried (429, 503).
+    Unavailable,
+    /// Any other error.
+    Internal,
+}
+
+impl ServerError {
+    pub fn new<C, M>(status: u16, code: C, message: M) -> Self
+    where
+        C: Into<String>,
+        M: Into<String>,
+    {
+        Self {
+            status,
+            code: code.into(),
+            message: message.into(),
+        }
+    }
+
+    pub fn bad_request<M>(message: M) -> Self
+    where
+        M: Into<String>,
+    {
+        Self::new(400, "bad_request", message)
+    }
+
+    pub fn unauthorized<M>(message: M) -> Self
+    where
+        M: Into<String>,
+    {
+        Self::new(401, "unauthorized", message)
+    }
+
+    pub fn forbidden<M>(message: M) -> Self
+    where
+        M: Into<String>,
+    {
+        Self::new(403, "forbidden", message)
+    }
+
+    pub fn not_found<M>(message: M) -> Self
+    where
+        M: Into<String>,
+    {
+        Self::new(404, "not_found", message)
+    }
+
+    /// Logs an error on the server and returns an error that does not expose its details.
+    pub fn from_error<E>(e: E) -> Self
+    where
+        E: std::error::Error,
+    {
+        #[cfg(feature = "resolvable")]
+        tracing::error!("server function failed: {e}");
+        #[cfg(not(feature = "resolvable"))]
+        let _ = e;
+
+        Self::internal()
+    }
+
+    fn internal() -> Self {
+        Self::new(500, INTERNAL_CODE, "internal server error")
+    }
+
+    pub fn status(&self) -> u16 {
+        self.status
+    }
+
+    pub fn code(&self) -> &str {
+        &self.code
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Returns the state of the page that this error maps to.
+    pub fn kind(&self) -> ServerErrorKind {
+        if self.code == NETWORK_CODE {
+            return ServerErrorKind::Network;
+        }
+
+        match self.status {
+            400 | 413 | 422 => ServerErrorKind::Invalid,
+            401 => ServerErrorKind::Unauthenticated,
+            403 => ServerErrorKind::Forbidden,
+            404 | 410 => ServerErrorKind::NotFound,
+            409 => ServerErrorKind::Conflict,
+            429 | 503 => ServerErrorKind::Unavailable,
+            _ => ServerErrorKind::Internal,
+        }
+    }
+
+    /// Returns `true` if the request may succeed if it is sent again.
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self.kind(),
+            ServerErrorKind::Network | ServerErrorKind::Unavailable
+        )
+    }
+}
+
+/// Converts failures to communicate with the server, this can be used in `into_query_error`
+/// and the other conversions of server functions.
+impl From<BridgeError> for ServerError {
+    fn from(e: BridgeError) -> Self {
+        match e {
+            BridgeError::ReloadRequired => Self::new(503, "reload_required", e.to_string()),
+            BridgeError::TooLarge(_) => Self::new(413, "too_large", e.to_string()),
+            BridgeError::CsrfRejected => Self::new(403, "csrf_rejected", e.to_string()),
+            BridgeError::StreamFailed(_) => Self::internal(),
+            _ => Self::new(503, NETWORK_CODE, e.to_string()),
+        }
+    }
+}
+
+#[cfg(feature = "resolvable")]
+impl From<anyhow::Error> for ServerError {
+    fn from(e: anyhow::Error) -> Self {
+        tracing::error!("server function failed: {e:?}");
+
+        Self::internal()
+    }
+}
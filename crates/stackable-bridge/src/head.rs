@@ -0,0 +1,82 @@
+//! Title, description and OpenGraph tags of pages.
+//!
+//! Tags are written with the helmet of bounce, which the server renders into `index.html` and the
+//! browser updates after hydration, so pages render the same tags on both sides.
+
+use bounce::helmet::Helmet;
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq, Debug, Clone)]
+pub struct PageMetaProps {
+    #[prop_or_default]
+    pub title: Option<AttrValue>,
+    #[prop_or_default]
+    pub description: Option<AttrValue>,
+    /// The absolute URL of the page, used as the canonical link and `og:url`.
+    #[prop_or_default]
+    pub url: Option<AttrValue>,
+    /// The absolute URL of the image shown when the page is shared.
+    #[prop_or_default]
+    pub image: Option<AttrValue>,
+    /// The `og:type` of the page.
+    #[prop_or(AttrValue::Static("website"))]
+    pub kind: AttrValue,
+    #[prop_or_default]
+    pub site_name: Option<AttrValue>,
+    /// Asks crawlers not to index the page.
+    #[prop_or_default]
+    pub noindex: bool,
+    /// Additional tags, e.g.: `<meta property="article:author" content="..." />`.
+    #[prop_or_default]
+    pub children: Children,
+}
+
+/// Sets the title, the description and OpenGraph tags of a page.
+///
+/// The title and the description are also used for `og:title` and `og:description`, a Twitter
+/// card is added for pages with an image.
+#[function_component]
+pub fn PageMeta(props: &PageMetaProps) -> Html {
+    let meta_name = |name: &'static str, content: &AttrValue| {
+        html! { <meta name={name} content={content.clone()} /> }
+    };
+    let meta_property = |property: &'static str, content: &AttrValue| {
+        html! { <meta property={property} content={content.clone()} /> }
+    };
+
+    let mut tags = Vec::new();
+
+    if let Some(ref m) = props.title {
+        tags.push(html! { <title>{m.clone()}</title> });
+        tags.push(meta_property("og:title", m));
+    }
+    if let Some(ref m) = props.description {
+        tags.push(meta_name("description", m));
+        tags.push(meta_property("og:description", m));
+    }
+    if let Some(ref m) = props.url {
+        tags.push(html! { <link rel="canonical" href={m.clone()} /> });
+        tags.push(meta_property("og:url", m));
+    }
+    if let Some(ref m) = props.image {
+        tags.push(meta_property("og:image", m));
+        tags.push(meta_name(
+            "twitter:card",
+            &AttrValue::Static("summary_large_image"),
+        ));
+    }
+    if let Some(ref m) = props.site_name {
+        tags.push(meta_property("og:site_name", m));
+    }
+    tags.push(meta_property("og:type", &props.kind));
+    if props.noindex {
+        tags.push(meta_name("robots", &AttrValue::Static("noindex")));
+    }
+
+    html! {
+        <Helmet>
+            {for tags}
+            {for props.children.iter()}
+        </Helmet>
+    }
+}
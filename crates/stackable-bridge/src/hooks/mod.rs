@@ -1,5 +1,11 @@
 mod use_bridged_mutation;
 mod use_bridged_query;
+mod use_bridged_stream;
+mod use_bridged_subscription;
+mod use_bridged_upload;
 
 pub use use_bridged_mutation::{use_bridged_mutation, UseBridgedMutationHandle};
 pub use use_bridged_query::{use_bridged_query, UseBridgedQueryHandle};
+pub use use_bridged_stream::{use_bridged_stream, UseBridgedStreamHandle};
+pub use use_bridged_subscription::{use_bridged_subscription, UseBridgedSubscriptionHandle};
+pub use use_bridged_upload::{use_bridged_upload, UseBridgedUploadHandle};
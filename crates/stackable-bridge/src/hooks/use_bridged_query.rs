@@ -160,6 +160,12 @@ where
     }
 }
 
+/// Resolves a bridged query, suspending the component until it is resolved.
+///
+/// During server-side rendering the resolver is called directly and the result is embedded into
+/// the page, so the browser does not resolve it again during hydration. In the browser, queries
+/// are resolved over the bridge, results are cached and components that use the same input
+/// share one request.
 #[hook]
 pub fn use_bridged_query<Q>(input: Rc<Q::Input>) -> SuspensionResult<UseBridgedQueryHandle<Q>>
 where
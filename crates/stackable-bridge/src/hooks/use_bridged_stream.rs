@@ -0,0 +1,217 @@
+use std::cell::Cell;
+use std::fmt;
+use std::rc::Rc;
+
+use bounce::{use_atom_value, use_selector_value};
+use yew::prelude::*;
+
+#[cfg(feature = "resolvable")]
+use crate::resolvers::StreamResolver as BridgedStream;
+use crate::state::{BridgeState, BridgeTokenState};
+#[cfg(not(feature = "resolvable"))]
+use crate::types::BridgedStream;
+
+struct StreamState<T>
+where
+    T: BridgedStream,
+{
+    items: Vec<Rc<T>>,
+    error: Option<T::Error>,
+    finished: bool,
+}
+
+impl<T> Default for StreamState<T>
+where
+    T: BridgedStream,
+{
+    fn default() -> Self {
+        Self {
+            items: Vec::new(),
+            error: None,
+            finished: false,
+        }
+    }
+}
+
+impl<T> PartialEq for StreamState<T>
+where
+    T: BridgedStream,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.items == other.items && self.error == other.error && self.finished == other.finished
+    }
+}
+
+// Streams are not resolved on the server.
+#[cfg_attr(feature = "resolvable", allow(dead_code))]
+enum StreamAction<T>
+where
+    T: BridgedStream,
+{
+    Reset,
+    Push(T),
+    Error(T::Error),
+    Finish,
+}
+
+impl<T> Reducible for StreamState<T>
+where
+    T: BridgedStream,
+{
+    type Action = StreamAction<T>;
+
+    fn reduce(self: Rc<Self>, action: Self::Action) -> Rc<Self> {
+        match action {
+            StreamAction::Reset => Self::default(),
+            StreamAction::Push(m) => {
+                let mut items = self.items.clone();
+                items.push(m.into());
+
+                Self {
+                    items,
+                    error: None,
+                    finished: false,
+                }
+            }
+            StreamAction::Error(e) => Self {
+                items: self.items.clone(),
+                error: Some(e),
+                finished: true,
+            },
+            StreamAction::Finish => Self {
+                items: self.items.clone(),
+                error: None,
+                finished: true,
+            },
+        }
+        .into()
+    }
+}
+
+/// A handle returned by [`use_bridged_stream`].
+pub struct UseBridgedStreamHandle<T>
+where
+    T: BridgedStream + 'static,
+{
+    inner: UseReducerHandle<StreamState<T>>,
+}
+
+impl<T> UseBridgedStreamHandle<T>
+where
+    T: BridgedStream + 'static,
+{
+    /// Returns the items that have been received so far.
+    pub fn items(&self) -> &[Rc<T>] {
+        &self.inner.items
+    }
+
+    /// Returns the error that ended the stream, if any.
+    pub fn error(&self) -> Option<&T::Error> {
+        self.inner.error.as_ref()
+    }
+
+    /// Returns `true` if the stream has ended.
+    pub fn is_finished(&self) -> bool {
+        self.inner.finished
+    }
+}
+
+impl<T> Clone for UseBridgedStreamHandle<T>
+where
+    T: BridgedStream + 'static,
+{
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T> fmt::Debug for UseBridgedStreamHandle<T>
+where
+    T: BridgedStream + fmt::Debug + 'static,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UseBridgedStreamHandle")
+            .field("items", &self.items())
+            .field("error", &self.error())
+            .field("finished", &self.is_finished())
+            .finish()
+    }
+}
+
+/// Resolves a bridged stream, items are appended to the handle as they arrive.
+///
+/// Streams are only resolved in the browser, the handle has no items during server-side
+/// rendering. The stream is restarted when the input changes.
+#[hook]
+pub fn use_bridged_stream<T>(input: Rc<T::Input>) -> UseBridgedStreamHandle<T>
+where
+    T: 'static + BridgedStream,
+{
+    let bridge = use_atom_value::<BridgeState>();
+    let token = use_selector_value::<BridgeTokenState>();
+    let state = use_reducer(StreamState::<T>::default);
+
+    {
+        let dispatcher = state.dispatcher();
+        use_effect_with_deps(
+            move |input| {
+                let cancelled = Rc::new(Cell::new(false));
+
+                #[cfg(not(feature = "resolvable"))]
+                {
+                    use futures::StreamExt;
+
+                    let input = input.clone();
+                    let cancelled = cancelled.clone();
+
+                    yew::platform::spawn_local(async move {
+                        dispatcher.dispatch(StreamAction::Reset);
+
+                        let mut meta = crate::BridgeMetadata::<()>::new();
+                        if let Some(ref m) = token.inner {
+                            meta = meta.with_token(m);
+                        }
+
+                        let connected = match bridge.inner.clone().connect(meta).await {
+                            Ok(m) => m,
+                            Err(e) => {
+                                dispatcher.dispatch(StreamAction::Error(T::into_stream_error(e)));
+                                return;
+                            }
+                        };
+
+                        let mut items = connected.resolve_stream::<T>(&input).await;
+
+                        while let Some(m) = items.next().await {
+                            // The input has changed or the component has been destroyed.
+                            if cancelled.get() {
+                                return;
+                            }
+
+                            match m {
+                                Ok(m) => dispatcher.dispatch(StreamAction::Push(m)),
+                                Err(e) => {
+                                    dispatcher.dispatch(StreamAction::Error(e));
+                                    return;
+                                }
+                            }
+                        }
+
+                        if !cancelled.get() {
+                            dispatcher.dispatch(StreamAction::Finish);
+                        }
+                    });
+                }
+                #[cfg(feature = "resolvable")]
+                let _ = (input, dispatcher, bridge, token);
+
+                move || cancelled.set(true)
+            },
+            input,
+        );
+    }
+
+    UseBridgedStreamHandle { inner: state }
+}
@@ -0,0 +1,250 @@
+use std::fmt;
+use std::rc::Rc;
+#[cfg(not(feature = "resolvable"))]
+use std::time::Duration;
+
+use bounce::{use_atom_value, use_selector_value};
+use futures::channel::oneshot;
+use yew::prelude::*;
+
+#[cfg(feature = "resolvable")]
+use crate::resolvers::StreamResolver as BridgedStream;
+use crate::state::{BridgeState, BridgeTokenState};
+#[cfg(not(feature = "resolvable"))]
+use crate::types::BridgedStream;
+
+/// The longest delay between reconnections.
+#[cfg(not(feature = "resolvable"))]
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(30);
+
+struct SubscriptionState<T>
+where
+    T: BridgedStream,
+{
+    latest: Option<Rc<T>>,
+    error: Option<T::Error>,
+    connected: bool,
+}
+
+impl<T> Default for SubscriptionState<T>
+where
+    T: BridgedStream,
+{
+    fn default() -> Self {
+        Self {
+            latest: None,
+            error: None,
+            connected: false,
+        }
+    }
+}
+
+impl<T> PartialEq for SubscriptionState<T>
+where
+    T: BridgedStream,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.latest == other.latest
+            && self.error == other.error
+            && self.connected == other.connected
+    }
+}
+
+// Subscriptions are not resolved on the server.
+#[cfg_attr(feature = "resolvable", allow(dead_code))]
+enum SubscriptionAction<T>
+where
+    T: BridgedStream,
+{
+    Reset,
+    Connected,
+    Disconnected,
+    Push(T),
+    Error(T::Error),
+}
+
+impl<T> Reducible for SubscriptionState<T>
+where
+    T: BridgedStream,
+{
+    type Action = SubscriptionAction<T>;
+
+    fn reduce(self: Rc<Self>, action: Self::Action) -> Rc<Self> {
+        match action {
+            SubscriptionAction::Reset => Self::default(),
+            SubscriptionAction::Connected => Self {
+                latest: self.latest.clone(),
+                error: None,
+                connected: true,
+            },
+            SubscriptionAction::Disconnected => Self {
+                latest: self.latest.clone(),
+                error: None,
+                connected: false,
+            },
+            SubscriptionAction::Push(m) => Self {
+                latest: Some(m.into()),
+                error: None,
+                connected: true,
+            },
+            SubscriptionAction::Error(e) => Self {
+                latest: self.latest.clone(),
+                error: Some(e),
+                connected: false,
+            },
+        }
+        .into()
+    }
+}
+
+/// A handle returned by [`use_bridged_subscription`].
+pub struct UseBridgedSubscriptionHandle<T>
+where
+    T: BridgedStream + 'static,
+{
+    inner: UseReducerHandle<SubscriptionState<T>>,
+}
+
+impl<T> UseBridgedSubscriptionHandle<T>
+where
+    T: BridgedStream + 'static,
+{
+    /// Returns the latest item, which is kept while the subscription reconnects.
+    pub fn latest(&self) -> Option<&Rc<T>> {
+        self.inner.latest.as_ref()
+    }
+
+    /// Returns the error that ended the subscription, if any.
+    pub fn error(&self) -> Option<&T::Error> {
+        self.inner.error.as_ref()
+    }
+
+    /// Returns `true` if the subscription is connected to the server.
+    pub fn is_connected(&self) -> bool {
+        self.inner.connected
+    }
+}
+
+impl<T> Clone for UseBridgedSubscriptionHandle<T>
+where
+    T: BridgedStream + 'static,
+{
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T> fmt::Debug for UseBridgedSubscriptionHandle<T>
+where
+    T: BridgedStream + fmt::Debug + 'static,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UseBridgedSubscriptionHandle")
+            .field("latest", &self.latest())
+            .field("error", &self.error())
+            .field("connected", &self.is_connected())
+            .finish()
+    }
+}
+
+/// Subscribes to a bridged stream, the handle holds the latest item.
+///
+/// The subscription is reconnected with an increasing delay when the stream ends or the
+/// connection is lost, and ends when the resolver returns an error. Subscriptions are only
+/// resolved in the browser. The subscription is restarted when the input changes.
+#[hook]
+pub fn use_bridged_subscription<T>(input: Rc<T::Input>) -> UseBridgedSubscriptionHandle<T>
+where
+    T: 'static + BridgedStream,
+{
+    let bridge = use_atom_value::<BridgeState>();
+    let token = use_selector_value::<BridgeTokenState>();
+    let state = use_reducer(SubscriptionState::<T>::default);
+
+    {
+        let dispatcher = state.dispatcher();
+        use_effect_with_deps(
+            move |input| {
+                // Dropped by the destructor, which ends the subscription at once.
+                let (cancel_tx, cancel_rx) = oneshot::channel::<()>();
+
+                #[cfg(not(feature = "resolvable"))]
+                {
+                    use futures::future::{self, FutureExt};
+                    use futures::StreamExt;
+
+                    use crate::BridgeError;
+
+                    let input = input.clone();
+
+                    let subscribe = async move {
+                        dispatcher.dispatch(SubscriptionAction::Reset);
+                        let mut retries = 0;
+
+                        loop {
+                            let mut meta = crate::BridgeMetadata::<()>::new();
+                            if let Some(ref m) = token.inner {
+                                meta = meta.with_token(m);
+                            }
+
+                            let items = match bridge.inner.clone().connect(meta).await {
+                                Ok(m) => m.resolve_stream_raw::<T>(&input).await,
+                                Err(e) => Err(e),
+                            };
+
+                            let e = match items {
+                                Ok(mut items) => {
+                                    dispatcher.dispatch(SubscriptionAction::Connected);
+
+                                    loop {
+                                        match items.next().await {
+                                            Some(Ok(Ok(m))) => {
+                                                retries = 0;
+                                                dispatcher.dispatch(SubscriptionAction::Push(m));
+                                            }
+                                            Some(Ok(Err(e))) => {
+                                                dispatcher.dispatch(SubscriptionAction::Error(e));
+                                                return;
+                                            }
+                                            Some(Err(e)) => break Some(e),
+                                            None => break None,
+                                        }
+                                    }
+                                }
+                                Err(e) => Some(e),
+                            };
+
+                            if let Some(e @ BridgeError::ReloadRequired) = e {
+                                dispatcher
+                                    .dispatch(SubscriptionAction::Error(T::into_stream_error(e)));
+                                return;
+                            }
+
+                            dispatcher.dispatch(SubscriptionAction::Disconnected);
+
+                            let delay = Duration::from_millis(500 * 2u64.pow(retries.min(6)));
+                            yew::platform::time::sleep(delay.min(MAX_RECONNECT_DELAY)).await;
+                            retries += 1;
+                        }
+                    };
+
+                    // The input has changed or the component has been destroyed, the stream is
+                    // dropped without waiting for the next item. The signal is polled first, so
+                    // no item is dispatched after it.
+                    yew::platform::spawn_local(async move {
+                        future::select(cancel_rx, subscribe.boxed_local()).await;
+                    });
+                }
+                #[cfg(feature = "resolvable")]
+                let _ = (input, dispatcher, bridge, token, cancel_rx);
+
+                move || drop(cancel_tx)
+            },
+            input,
+        );
+    }
+
+    UseBridgedSubscriptionHandle { inner: state }
+}
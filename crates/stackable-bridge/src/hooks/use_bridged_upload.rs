@@ -0,0 +1,160 @@
+use std::fmt;
+use std::rc::Rc;
+
+use bounce::{use_atom_value, use_selector_value};
+use futures::Stream;
+use yew::prelude::*;
+
+#[cfg(feature = "resolvable")]
+use crate::resolvers::UploadResolver as BridgedUpload;
+use crate::state::{BridgeState, BridgeTokenState};
+#[cfg(not(feature = "resolvable"))]
+use crate::types::BridgedUpload;
+use crate::types::UploadResult;
+use crate::upload::{file_chunks, UploadProgress};
+
+/// A handle returned by [`use_bridged_upload`].
+pub struct UseBridgedUploadHandle<T>
+where
+    T: BridgedUpload + 'static,
+{
+    bridge: Rc<BridgeState>,
+    token: Rc<BridgeTokenState>,
+    result: UseStateHandle<Option<UploadResult<T>>>,
+    progress: UseStateHandle<Option<UploadProgress>>,
+}
+
+impl<T> UseBridgedUploadHandle<T>
+where
+    T: BridgedUpload + 'static,
+{
+    /// Uploads a stream of chunks with input.
+    ///
+    /// The next chunk is only read from the stream after the server has taken the previous one.
+    /// Uploads are not resolved during server-side rendering.
+    pub async fn run<S>(&self, input: impl Into<Rc<T::Input>>, chunks: S) -> UploadResult<T>
+    where
+        S: Stream<Item = Vec<u8>>,
+    {
+        self.run_inner(input.into(), chunks, None).await
+    }
+
+    /// Uploads a file with input.
+    ///
+    /// Files larger than the limit of the upload are rejected without sending them.
+    pub async fn run_file(
+        &self,
+        input: impl Into<Rc<T::Input>>,
+        file: &web_sys::File,
+    ) -> UploadResult<T> {
+        let total = file.size() as u64;
+
+        if let Some(limit) = T::MAX_SIZE.filter(|m| total > *m) {
+            let result = Err(T::into_upload_error(crate::BridgeError::TooLarge(limit)));
+            self.result.set(Some(result.clone()));
+
+            return result;
+        }
+
+        self.run_inner(input.into(), file_chunks(file), Some(total))
+            .await
+    }
+
+    async fn run_inner<S>(
+        &self,
+        input: Rc<T::Input>,
+        chunks: S,
+        total: Option<u64>,
+    ) -> UploadResult<T>
+    where
+        S: Stream<Item = Vec<u8>>,
+    {
+        self.result.set(None);
+        self.progress.set(Some(UploadProgress { sent: 0, total }));
+
+        #[cfg(not(feature = "resolvable"))]
+        let result = {
+            let mut meta = crate::BridgeMetadata::<()>::new();
+            if let Some(ref m) = self.token.inner {
+                meta = meta.with_token(m);
+            }
+
+            let progress = self.progress.clone();
+            let on_progress = move |sent| progress.set(Some(UploadProgress { sent, total }));
+
+            match self.bridge.inner.clone().connect(meta).await {
+                Ok(m) => {
+                    m.resolve_upload_with_progress::<T, S, _>(&input, chunks, on_progress)
+                        .await
+                }
+                Err(e) => Err(T::into_upload_error(e)),
+            }
+        };
+        // Uploads are started by the browser, an upload never finishes during server-side
+        // rendering.
+        #[cfg(feature = "resolvable")]
+        let result = {
+            let _ = (input, chunks, total, &self.bridge, &self.token);
+            futures::future::pending::<UploadResult<T>>().await
+        };
+
+        self.result.set(Some(result.clone()));
+
+        result
+    }
+
+    /// Returns the result of the last finished upload (if any).
+    pub fn result(&self) -> Option<&UploadResult<T>> {
+        self.result.as_ref()
+    }
+
+    /// Returns the progress of the last started upload (if any).
+    pub fn progress(&self) -> Option<UploadProgress> {
+        *self.progress
+    }
+}
+
+impl<T> Clone for UseBridgedUploadHandle<T>
+where
+    T: BridgedUpload + 'static,
+{
+    fn clone(&self) -> Self {
+        Self {
+            bridge: self.bridge.clone(),
+            token: self.token.clone(),
+            result: self.result.clone(),
+            progress: self.progress.clone(),
+        }
+    }
+}
+
+impl<T> fmt::Debug for UseBridgedUploadHandle<T>
+where
+    T: BridgedUpload + fmt::Debug + 'static,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UseBridgedUploadHandle")
+            .field("result", &self.result())
+            .field("progress", &self.progress())
+            .finish()
+    }
+}
+
+/// Streams bytes or files to a bridged upload.
+#[hook]
+pub fn use_bridged_upload<T>() -> UseBridgedUploadHandle<T>
+where
+    T: 'static + BridgedUpload,
+{
+    let bridge = use_atom_value::<BridgeState>();
+    let token = use_selector_value::<BridgeTokenState>();
+    let result = use_state_eq(|| None);
+    let progress = use_state_eq(|| None);
+
+    UseBridgedUploadHandle {
+        bridge,
+        token,
+        result,
+        progress,
+    }
+}
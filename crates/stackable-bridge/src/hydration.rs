@@ -0,0 +1,157 @@
+//! Data that components fetch during server-side rendering and read before hydration, so the
+//! browser does not fetch it again.
+//!
+//! Values are written into the page after the body in the format of `[server.hydration]`. Pages
+//! that are rendered in the browser have no hydration data, and values are never encoded by
+//! frontends built for the browser.
+
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::rc::Rc;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use bounce::{use_atom_value, Atom};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use stackable_core::hydration::HydrationFormat;
+use yew::prelude::*;
+
+use crate::BridgeResult;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Payload {
+    format: HydrationFormat,
+    values: BTreeMap<String, serde_json::Value>,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    payload: Payload,
+    /// `true` if values are collected for a page that is rendered on the server.
+    collecting: bool,
+}
+
+/// The hydration data of a page.
+#[derive(Debug, Clone, Default)]
+pub struct HydrationData {
+    inner: Rc<RefCell<Inner>>,
+}
+
+impl PartialEq for HydrationData {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.inner, &other.inner)
+    }
+}
+
+impl HydrationData {
+    /// Collects values for a page that is rendered on the server.
+    pub fn collect(format: HydrationFormat) -> Self {
+        let inner = Inner {
+            payload: Payload {
+                format,
+                values: BTreeMap::new(),
+            },
+            collecting: true,
+        };
+
+        Self {
+            inner: Rc::new(RefCell::new(inner)),
+        }
+    }
+
+    /// Restores the data that the server has written into the page.
+    pub fn from_json(s: &str) -> serde_json::Result<Self> {
+        let inner = Inner {
+            payload: serde_json::from_str(s)?,
+            collecting: false,
+        };
+
+        Ok(Self {
+            inner: Rc::new(RefCell::new(inner)),
+        })
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string(&self.inner.borrow().payload)
+    }
+
+    /// Provides a value for hydration, this does nothing outside of server-side rendering.
+    pub fn provide<T>(&self, key: &str, value: &T) -> BridgeResult<()>
+    where
+        T: Serialize,
+    {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let mut inner = self.inner.borrow_mut();
+            if !inner.collecting {
+                return Ok(());
+            }
+
+            let value = match inner.payload.format {
+                HydrationFormat::Json => serde_json::to_value(value)?,
+                HydrationFormat::Bincode => {
+                    serde_json::Value::String(STANDARD.encode(bincode::serialize(value)?))
+                }
+            };
+            inner.payload.values.insert(key.to_string(), value);
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        let _ = (key, value);
+
+        Ok(())
+    }
+
+    /// Returns a value that the server has provided, or `None` if it has not provided the key or
+    /// the value cannot be decoded as `T`.
+    pub fn get<T>(&self, key: &str) -> Option<T>
+    where
+        T: DeserializeOwned,
+    {
+        let inner = self.inner.borrow();
+        let value = inner.payload.values.get(key)?;
+
+        match inner.payload.format {
+            HydrationFormat::Json => serde_json::from_value(value.clone()).ok(),
+            HydrationFormat::Bincode => {
+                let data = STANDARD.decode(value.as_str()?).ok()?;
+                bincode::deserialize(&data).ok()
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inner.borrow().payload.values.is_empty()
+    }
+
+    /// Returns the encoded size of each value, largest first.
+    pub fn sizes(&self) -> Vec<(String, usize)> {
+        let mut sizes = self
+            .inner
+            .borrow()
+            .payload
+            .values
+            .iter()
+            .map(|(k, v)| (k.clone(), v.to_string().len()))
+            .collect::<Vec<_>>();
+        sizes.sort_by(|a, b| b.1.cmp(&a.1));
+
+        sizes
+    }
+}
+
+/// The hydration data of the current page, set by the server and the frontend.
+#[derive(Atom, PartialEq, Default, Debug)]
+pub struct HydrationDataState {
+    pub inner: HydrationData,
+}
+
+/// Returns the hydration data of the page.
+///
+/// Components provide values after fetching them on the server, and read them in the browser
+/// before fetching.
+#[hook]
+pub fn use_hydration_data() -> HydrationData {
+    use_atom_value::<HydrationDataState>().inner.clone()
+}
@@ -2,12 +2,23 @@
 #![deny(missing_debug_implementations)]
 
 mod bridge;
+pub mod contract;
 mod error;
+pub mod head;
 pub mod hooks;
+pub mod hydration;
+#[cfg(feature = "resolvable")]
+pub mod metrics;
+pub mod profiler;
+#[cfg(feature = "resolvable")]
+pub mod recorder;
+pub mod request;
 #[cfg(feature = "resolvable")]
 pub mod resolvers;
+pub mod response;
 pub mod state;
 pub mod types;
+pub mod upload;
 
 pub use bridge::{Bridge, BridgeBuilder, BridgeMetadata};
-pub use error::{BridgeError, BridgeResult};
+pub use error::{BridgeError, BridgeResult, ServerError, ServerErrorKind};
@@ -0,0 +1,15 @@
+//! Measurements of bridge requests resolved by the server.
+
+use std::time::Duration;
+
+/// Receives the latency and outcome of bridge requests.
+///
+/// Recorders are registered with [`Bridge::with_metrics`](crate::Bridge::with_metrics).
+pub trait BridgeMetrics: Send + Sync {
+    /// Records a resolved request.
+    ///
+    /// `name` is the type path of the query, mutation, stream or upload. For streams and uploads,
+    /// the duration covers the entire exchange. `is_err` is `true` if the resolver has returned
+    /// an error or the request failed to resolve.
+    fn record(&self, name: &'static str, duration: Duration, is_err: bool);
+}
@@ -0,0 +1,166 @@
+//! Render durations of components during server-side rendering, recorded by the development
+//! server.
+//!
+//! Components are recorded if their content is wrapped in [`Profiled`]. The profile is written
+//! as folded stacks, which flamegraph tools like inferno and speedscope can read.
+
+use std::cell::RefCell;
+use std::fmt::Write;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use bounce::{use_atom_value, Atom};
+use yew::prelude::*;
+
+#[derive(Debug)]
+struct Frame {
+    name: AttrValue,
+    started_at: Instant,
+    /// The time spent in profiled children.
+    children: Duration,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    stack: Vec<Frame>,
+    /// Folded stacks and the time spent in the last component of each stack.
+    samples: Vec<(String, Duration)>,
+}
+
+/// The render durations of profiled components in a request.
+#[derive(Debug, Clone, Default)]
+pub struct RenderProfile {
+    inner: Rc<RefCell<Inner>>,
+}
+
+impl PartialEq for RenderProfile {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.inner, &other.inner)
+    }
+}
+
+impl RenderProfile {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn start(&self, name: AttrValue) {
+        self.inner.borrow_mut().stack.push(Frame {
+            name,
+            started_at: Instant::now(),
+            children: Duration::ZERO,
+        });
+    }
+
+    fn end(&self) {
+        let mut inner = self.inner.borrow_mut();
+        let frame = match inner.stack.pop() {
+            Some(m) => m,
+            None => return,
+        };
+
+        let total = frame.started_at.elapsed();
+        let stack = inner
+            .stack
+            .iter()
+            .map(|m| m.name.as_ref())
+            .chain([frame.name.as_ref()])
+            .collect::<Vec<_>>()
+            .join(";");
+
+        inner
+            .samples
+            .push((stack, total.saturating_sub(frame.children)));
+
+        if let Some(m) = inner.stack.last_mut() {
+            m.children += total;
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inner.borrow().samples.is_empty()
+    }
+
+    /// Returns the profiled components with the longest render durations, including children.
+    pub fn slowest(&self, limit: usize) -> Vec<(String, Duration)> {
+        let inner = self.inner.borrow();
+        let mut totals: Vec<(String, Duration)> = Vec::new();
+
+        for (stack, duration) in inner.samples.iter() {
+            // The duration of a component includes the durations of all stacks below it.
+            let mut prefix = String::new();
+            for name in stack.split(';') {
+                if !prefix.is_empty() {
+                    prefix.push(';');
+                }
+                prefix.push_str(name);
+
+                match totals.iter_mut().find(|m| m.0 == prefix) {
+                    Some(m) => m.1 += *duration,
+                    None => totals.push((prefix.clone(), *duration)),
+                }
+            }
+        }
+
+        totals.sort_by(|a, b| b.1.cmp(&a.1));
+        totals.truncate(limit);
+
+        totals
+    }
+
+    /// Writes the profile as folded stacks with durations in microseconds.
+    pub fn to_folded(&self) -> String {
+        let mut s = String::new();
+
+        for (stack, duration) in self.inner.borrow().samples.iter() {
+            let _ = writeln!(s, "{} {}", stack, duration.as_micros());
+        }
+
+        s
+    }
+}
+
+/// The profile of the current request, only set by the development server.
+#[derive(Atom, PartialEq, Default, Debug)]
+pub struct RenderProfileState {
+    pub inner: Option<RenderProfile>,
+}
+
+#[derive(Properties, PartialEq, Debug)]
+pub struct ProfiledProps {
+    /// The name of the component in the profile, e.g.: `Header`.
+    pub name: AttrValue,
+    #[prop_or_default]
+    pub children: Children,
+}
+
+#[function_component]
+fn ProfiledEnd() -> Html {
+    let profile = use_atom_value::<RenderProfileState>();
+
+    if let Some(ref m) = profile.inner {
+        m.end();
+    }
+
+    Html::default()
+}
+
+/// Records the server-side render duration of its children in the development server.
+///
+/// Children are rendered in order, so the duration ends when the marker after them renders.
+#[function_component]
+pub fn Profiled(props: &ProfiledProps) -> Html {
+    let profile = use_atom_value::<RenderProfileState>();
+
+    if let Some(ref m) = profile.inner {
+        m.start(props.name.clone());
+    }
+
+    html! {
+        <>
+            {props.children.clone()}
+            // Rendered in the browser as well, so hydration sees the same components.
+            <ProfiledEnd />
+        </>
+    }
+}
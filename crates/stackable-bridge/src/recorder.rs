@@ -0,0 +1,20 @@
+//! Recordings of queries and mutations resolved by the server.
+
+use stackable_core::mock::Mock;
+
+/// Receives the results of queries and mutations resolved by their resolvers.
+///
+/// Recorders are registered with [`Bridge::with_recorder`](crate::Bridge::with_recorder).
+pub trait BridgeRecorder: Send + Sync {
+    /// Records a result as a mock, which replays it for the same input.
+    fn record(&self, mock: Mock);
+}
+
+impl<T> BridgeRecorder for std::sync::Arc<T>
+where
+    T: ?Sized + BridgeRecorder,
+{
+    fn record(&self, mock: Mock) {
+        self.as_ref().record(mock)
+    }
+}
@@ -0,0 +1,127 @@
+//! The request that a page is rendered for.
+//!
+//! The server provides the request of each page. Pages that are rendered in the browser see the
+//! defaults set with `Renderer::request_context` of stackable-frontend, as the browser has no
+//! access to the request. Values that differ between the server and the browser cause hydration
+//! mismatches, so they should only change content after the page has hydrated.
+
+use std::collections::BTreeMap;
+use std::net::IpAddr;
+use std::rc::Rc;
+
+use bounce::{use_atom_value, Atom};
+use yew::prelude::*;
+
+/// Headers, cookies, the client address and the negotiated locale of a request.
+///
+/// Pages in the render cache are served to other requests, so they should only depend on the
+/// locale.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RequestContext {
+    headers: BTreeMap<String, String>,
+    cookies: BTreeMap<String, String>,
+    client_ip: Option<IpAddr>,
+    locale: Option<String>,
+}
+
+impl RequestContext {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a header, values of the same header are joined with `, `.
+    ///
+    /// Cookies in a `Cookie` header are added as cookies.
+    pub fn with_header<K, V>(mut self, name: K, value: V) -> Self
+    where
+        K: AsRef<str>,
+        V: Into<String>,
+    {
+        let name = name.as_ref().to_ascii_lowercase();
+        let value = value.into();
+
+        if name == "cookie" {
+            for (k, v) in value
+                .split(';')
+                .filter_map(|m| m.trim().split_once('='))
+                .filter(|(k, _)| !k.is_empty())
+            {
+                self.cookies.insert(k.to_string(), v.to_string());
+            }
+        }
+
+        self.headers
+            .entry(name)
+            .and_modify(|m| {
+                m.push_str(", ");
+                m.push_str(&value);
+            })
+            .or_insert(value);
+
+        self
+    }
+
+    pub fn with_cookie<K, V>(mut self, name: K, value: V) -> Self
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        self.cookies.insert(name.into(), value.into());
+        self
+    }
+
+    pub fn with_client_ip(mut self, ip: IpAddr) -> Self {
+        self.client_ip = Some(ip);
+        self
+    }
+
+    pub fn with_locale<S>(mut self, locale: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.locale = Some(locale.into());
+        self
+    }
+
+    /// Returns the value of a header, names are case-insensitive.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .get(&name.to_ascii_lowercase())
+            .map(|m| m.as_str())
+    }
+
+    /// Returns all headers with lowercase names.
+    pub fn headers(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.headers.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+
+    pub fn cookie(&self, name: &str) -> Option<&str> {
+        self.cookies.get(name).map(|m| m.as_str())
+    }
+
+    pub fn cookies(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.cookies.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+
+    /// Returns the address of the client, if the server knows it.
+    pub fn client_ip(&self) -> Option<IpAddr> {
+        self.client_ip
+    }
+
+    /// Returns the locale negotiated from the locale cookie and `Accept-Language`.
+    pub fn locale(&self) -> Option<&str> {
+        self.locale.as_deref()
+    }
+}
+
+/// The request of the current page, set by the server and the frontend.
+#[derive(Atom, PartialEq, Default, Debug)]
+pub struct RequestContextState {
+    pub inner: Rc<RequestContext>,
+}
+
+/// Returns the request that the page is rendered for.
+#[hook]
+pub fn use_request_context() -> Rc<RequestContext> {
+    use_atom_value::<RequestContextState>().inner.clone()
+}
@@ -1,6 +1,11 @@
 use async_trait::async_trait;
+use futures::stream::LocalBoxStream;
 
-use crate::types::{BridgedMutation, BridgedQuery, MutationResult, QueryResult};
+use crate::types::{
+    BridgedMutation, BridgedQuery, BridgedStream, BridgedUpload, MutationResult, QueryResult,
+    StreamResult, UploadResult,
+};
+use crate::upload::UploadStream;
 use crate::BridgeMetadata;
 
 #[async_trait(?Send)]
@@ -22,3 +27,27 @@ pub trait MutationResolver: BridgedMutation {
         input: &Self::Input,
     ) -> MutationResult<Self>;
 }
+
+pub trait StreamResolver: BridgedStream {
+    type Context: 'static;
+
+    fn resolve(
+        meta: BridgeMetadata<Self::Context>,
+        input: Self::Input,
+    ) -> LocalBoxStream<'static, StreamResult<Self>>;
+}
+
+#[async_trait(?Send)]
+pub trait UploadResolver: BridgedUpload {
+    type Context: 'static;
+
+    /// Resolves an upload.
+    ///
+    /// The browser waits for the resolver to take a chunk before it sends the next one. The
+    /// resolver may return before the upload is completed, the remaining chunks are discarded.
+    async fn resolve(
+        meta: &BridgeMetadata<Self::Context>,
+        input: &Self::Input,
+        chunks: UploadStream,
+    ) -> UploadResult<Self>;
+}
@@ -0,0 +1,82 @@
+//! The status of the response to a server-rendered page.
+//!
+//! Pages are answered with `200 OK` unless a component sets another status while rendering, e.g.:
+//! `404 Not Found` for the not found route. In the browser, the handle has no effect.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use bounce::{use_atom_value, Atom};
+use yew::prelude::*;
+
+#[derive(Debug, Default)]
+struct Inner {
+    status: Option<u16>,
+    location: Option<String>,
+}
+
+/// Sets the status and the redirect of the response to a page.
+#[derive(Debug, Clone, Default)]
+pub struct ResponseControl {
+    inner: Rc<RefCell<Inner>>,
+}
+
+impl PartialEq for ResponseControl {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.inner, &other.inner)
+    }
+}
+
+impl ResponseControl {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_status(&self, status: u16) {
+        self.inner.borrow_mut().status = Some(status);
+    }
+
+    /// Answers with `404 Not Found`, the page is still rendered.
+    pub fn not_found(&self) {
+        self.set_status(404);
+    }
+
+    /// Redirects to a location with `301 Moved Permanently` or `302 Found`.
+    ///
+    /// The page is not sent, so the browser navigates to the location without hydrating it.
+    pub fn redirect<S>(&self, location: S, permanent: bool)
+    where
+        S: Into<String>,
+    {
+        let mut inner = self.inner.borrow_mut();
+        inner.status = Some(if permanent { 301 } else { 302 });
+        inner.location = Some(location.into());
+    }
+
+    /// Returns the status, or `None` if no component has set one.
+    pub fn status(&self) -> Option<u16> {
+        self.inner.borrow().status
+    }
+
+    /// Returns the location to redirect to, if a component has redirected.
+    pub fn location(&self) -> Option<String> {
+        self.inner.borrow().location.clone()
+    }
+}
+
+/// The response of the current page, only set by the server.
+#[derive(Atom, PartialEq, Default, Debug)]
+pub struct ResponseControlState {
+    pub inner: Option<ResponseControl>,
+}
+
+/// Returns the handle to the response of the page.
+///
+/// Outside of server-side rendering, a detached handle is returned.
+#[hook]
+pub fn use_response_control() -> ResponseControl {
+    use_atom_value::<ResponseControlState>()
+        .inner
+        .clone()
+        .unwrap_or_default()
+}
@@ -1,6 +1,6 @@
 use std::rc::Rc;
 
-use bounce::Atom;
+use bounce::{Atom, BounceStates, Selector};
 
 use crate::{Bridge, BridgeMetadata};
 
@@ -9,6 +9,24 @@ pub struct BridgeState {
     pub inner: Bridge,
 }
 
+/// The token of the bridge, read with the token selector of the bridge.
+#[derive(PartialEq, Eq, Debug)]
+pub(crate) struct BridgeTokenState {
+    pub inner: Option<Rc<str>>,
+}
+
+impl Selector for BridgeTokenState {
+    fn select(states: &BounceStates) -> Rc<Self> {
+        let bridge = states.get_atom_value::<BridgeState>();
+        let inner = bridge.inner.read_token(states).map(|m| {
+            let m: &str = (*m).as_ref();
+            Rc::from(m)
+        });
+
+        Rc::new(Self { inner })
+    }
+}
+
 #[derive(Atom, Debug)]
 pub struct BridgeMetadataState<CTX> {
     pub(crate) _inner: Option<Rc<BridgeMetadata<CTX>>>,
@@ -36,6 +36,42 @@ pub trait BridgedMutation: Serialize + for<'de> Deserialize<'de> + PartialEq {
 
 pub type MutationResult<T> = std::result::Result<Rc<T>, <T as BridgedMutation>::Error>;
 
+/// A server function that yields items incrementally.
+///
+/// Items are sent to the browser as newline-delimited json as they are resolved.
+pub trait BridgedStream: Serialize + for<'de> Deserialize<'de> + PartialEq {
+    type Input: 'static + Serialize + for<'de> Deserialize<'de> + PartialEq;
+    type Error: 'static + Serialize + for<'de> Deserialize<'de> + Error + PartialEq + Clone;
+
+    #[cold]
+    fn into_stream_error(e: BridgeError) -> Self::Error {
+        panic_network_error(e);
+    }
+}
+
+pub type StreamResult<T> = std::result::Result<T, <T as BridgedStream>::Error>;
+
+/// A server function that consumes bytes streamed from the browser.
+///
+/// The input is sent with the first chunk, the resolver receives the chunks as they arrive.
+pub trait BridgedUpload: Serialize + for<'de> Deserialize<'de> + PartialEq {
+    type Input: 'static + Serialize + for<'de> Deserialize<'de>;
+    type Error: 'static + Serialize + for<'de> Deserialize<'de> + Error + PartialEq + Clone;
+
+    /// The maximum number of bytes of an upload.
+    ///
+    /// The server aborts uploads over the limit before the resolver receives the chunk that
+    /// exceeds it, and files over the limit are rejected in the browser before they are sent.
+    const MAX_SIZE: Option<u64> = None;
+
+    #[cold]
+    fn into_upload_error(e: BridgeError) -> Self::Error {
+        panic_network_error(e);
+    }
+}
+
+pub type UploadResult<T> = std::result::Result<Rc<T>, <T as BridgedUpload>::Error>;
+
 /// A placeholder type until never type lands in std.
 #[derive(thiserror::Error, Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
 #[error("this never happens")]
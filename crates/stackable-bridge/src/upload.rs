@@ -0,0 +1,126 @@
+//! Streaming request bodies from the browser to the server.
+//!
+//! An upload is sent as a sequence of chunks. The server only acknowledges a chunk after the
+//! resolver has accepted it, so the browser never has more than one chunk in flight. Each chunk
+//! carries its offset, which allows the browser to resume after a failed request.
+
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures::channel::mpsc;
+use futures::{future, Stream, StreamExt};
+use js_sys::Uint8Array;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::JsCast;
+
+use crate::error::{BridgeError, BridgeResult};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct UploadChunk<'a> {
+    pub query_index: usize,
+    pub upload_id: &'a str,
+    pub offset: u64,
+    /// The input of the upload, only sent with the first chunk.
+    #[serde(borrow)]
+    pub input: Option<&'a [u8]>,
+    pub data: &'a [u8],
+    pub last: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) enum UploadReply {
+    /// The chunk has been accepted, the offset of the next chunk is returned.
+    Accepted { offset: u64 },
+    /// The offset of the chunk does not match the offset the server expects.
+    Mismatch { offset: u64 },
+    /// The upload has been resolved.
+    Completed(Vec<u8>),
+    /// The upload has been aborted as it is larger than the limit of the upload.
+    TooLarge { limit: u64 },
+}
+
+/// The progress of an upload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UploadProgress {
+    /// The number of bytes that the server has taken.
+    pub sent: u64,
+    /// The size of the upload, if it is known.
+    pub total: Option<u64>,
+}
+
+#[derive(Debug)]
+#[cfg_attr(not(feature = "resolvable"), allow(dead_code))]
+pub(crate) enum UploadMessage {
+    Chunk(Vec<u8>),
+    End,
+}
+
+/// The chunks of an upload, received by the resolver as they arrive.
+///
+/// The stream yields [`BridgeError::Aborted`] if the browser stops sending chunks before the
+/// upload is completed.
+#[derive(Debug)]
+pub struct UploadStream {
+    inner: mpsc::Receiver<UploadMessage>,
+    done: bool,
+}
+
+impl UploadStream {
+    #[cfg_attr(not(feature = "resolvable"), allow(dead_code))]
+    pub(crate) fn new(inner: mpsc::Receiver<UploadMessage>) -> Self {
+        Self { inner, done: false }
+    }
+}
+
+impl Stream for UploadStream {
+    type Item = BridgeResult<Vec<u8>>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.done {
+            return Poll::Ready(None);
+        }
+
+        match Pin::new(&mut self.inner).poll_next(cx) {
+            Poll::Ready(Some(UploadMessage::Chunk(m))) => Poll::Ready(Some(Ok(m))),
+            Poll::Ready(Some(UploadMessage::End)) => {
+                self.done = true;
+                Poll::Ready(None)
+            }
+            Poll::Ready(None) => {
+                self.done = true;
+                Poll::Ready(Some(Err(BridgeError::Aborted)))
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+/// The number of bytes that are read from a file for each chunk.
+const FILE_CHUNK_SIZE: usize = 1024 * 1024;
+
+/// Reads a file as chunks for an upload.
+///
+/// Reading stops at the first part of the file that cannot be read.
+pub fn file_chunks(file: &web_sys::Blob) -> impl Stream<Item = Vec<u8>> {
+    let parts = wasm_streams::ReadableStream::from_raw(file.stream().unchecked_into())
+        .into_stream()
+        .take_while(|m| future::ready(m.is_ok()))
+        .filter_map(|m| future::ready(m.ok().map(|m| Uint8Array::new(&m).to_vec())));
+
+    // Parts of the stream of the browser are usually small, they are sent in larger chunks.
+    parts
+        .chain(futures::stream::once(future::ready(Vec::new())))
+        .scan(Vec::new(), |buf: &mut Vec<u8>, part| {
+            let end = part.is_empty();
+            buf.extend_from_slice(&part);
+
+            let chunk = if end || buf.len() >= FILE_CHUNK_SIZE {
+                Some(std::mem::take(buf))
+            } else {
+                None
+            };
+
+            future::ready(Some(chunk))
+        })
+        .filter_map(|m| future::ready(m.filter(|m| !m.is_empty())))
+}
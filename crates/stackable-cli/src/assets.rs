@@ -0,0 +1,114 @@
+//! Assets of previous frontend builds, kept for the grace period in `release.asset-grace-period`.
+//!
+//! Trunk replaces the frontend build directory, so the previous build is moved aside before the
+//! frontend is built and its assets are copied back afterwards.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use sha2::{Digest, Sha256};
+use stackable_core::assets::{AssetBuild, AssetManifest};
+use stackable_core::build_info::BuildInfo;
+use stackable_core::images::ImageManifest;
+use stackable_core::locales::LocaleManifest;
+use stackable_core::preload::PreloadManifest;
+use stackable_core::pwa::SERVICE_WORKER_FILE_NAME;
+
+use crate::utils::{collect_files, relative_path};
+
+/// Returns `true` for files that are served as assets.
+///
+/// `index.html` and manifests always belong to the current build.
+pub(crate) fn is_asset(rel_path: &str) -> bool {
+    ![
+        "index.html",
+        AssetManifest::FILE_NAME,
+        ImageManifest::FILE_NAME,
+        LocaleManifest::FILE_NAME,
+        PreloadManifest::FILE_NAME,
+        SERVICE_WORKER_FILE_NAME,
+    ]
+    .contains(&rel_path)
+}
+
+/// Copies assets of previous builds within the grace period into the new frontend build and
+/// writes the asset manifest.
+pub(crate) fn retain_previous_assets(
+    previous_dir: &Path,
+    build_dir: &Path,
+    build_info: &BuildInfo,
+    grace_period: u64,
+    integrity: BTreeMap<String, String>,
+) -> Result<AssetManifest> {
+    let mut entries = Vec::new();
+    collect_files(build_dir, &mut entries).context("failed to list frontend assets")?;
+
+    let mut files = Vec::with_capacity(entries.len());
+    let mut hashes = BTreeMap::new();
+    for path in entries {
+        let rel_path = relative_path(&path, build_dir)?;
+
+        // `index.html` is not an asset, but it is also served as a file.
+        let content = fs::read(&path).with_context(|| format!("failed to read {rel_path}"))?;
+        hashes.insert(rel_path.clone(), format!("{:x}", Sha256::digest(&content)));
+
+        if is_asset(&rel_path) {
+            files.push(rel_path);
+        }
+    }
+    files.sort();
+
+    let mut manifest = AssetManifest {
+        grace_period,
+        builds: vec![AssetBuild {
+            build_id: build_info.build_id.clone(),
+            files,
+            integrity,
+            hashes,
+            superseded_at: None,
+        }],
+    };
+
+    let previous_manifest = fs::read_to_string(previous_dir.join(AssetManifest::FILE_NAME))
+        .ok()
+        .and_then(|m| AssetManifest::from_json(&m).ok());
+
+    if let Some(previous_manifest) = previous_manifest.filter(|_| grace_period > 0) {
+        for mut build in previous_manifest.builds {
+            // The build that was current until now.
+            let superseded_at = *build.superseded_at.get_or_insert(build_info.timestamp);
+
+            if superseded_at + grace_period < build_info.timestamp {
+                continue;
+            }
+
+            build.files.retain(|m| previous_dir.join(m).exists());
+
+            for rel_path in build.files.iter() {
+                let target = build_dir.join(rel_path);
+                // Assets are hashed, a file with the same path has the same content.
+                if target.exists() {
+                    continue;
+                }
+
+                if let Some(m) = target.parent() {
+                    fs::create_dir_all(m)?;
+                }
+                fs::copy(previous_dir.join(rel_path), &target)
+                    .with_context(|| format!("failed to keep previous asset {}", rel_path))?;
+            }
+
+            manifest.builds.push(build);
+        }
+    }
+
+    fs::write(
+        build_dir.join(AssetManifest::FILE_NAME),
+        manifest.to_json()?,
+    )
+    .context("failed to write asset manifest")?;
+
+    Ok(manifest)
+}
@@ -0,0 +1,219 @@
+//! A HTTP load generator for `stackctl bench`, which measures the throughput and the latency of
+//! server-side rendered routes.
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use console::style;
+use serde::Serialize;
+use stackable_core::build_info::BuildInfo;
+
+/// Latency percentiles of a route, in milliseconds.
+#[derive(Serialize, Debug, Default)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct Latency {
+    pub p50: f64,
+    pub p90: f64,
+    pub p99: f64,
+    pub max: f64,
+}
+
+impl Latency {
+    fn from_samples(samples: &mut [Duration]) -> Self {
+        if samples.is_empty() {
+            return Self::default();
+        }
+
+        samples.sort_unstable();
+
+        let percentile = |p: usize| {
+            let index = (samples.len() * p / 100).min(samples.len() - 1);
+            samples[index].as_secs_f64() * 1000.0
+        };
+
+        Self {
+            p50: percentile(50),
+            p90: percentile(90),
+            p99: percentile(99),
+            max: percentile(100),
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct RouteReport {
+    pub route: String,
+    pub requests: usize,
+    /// Requests that failed or responded with an error status.
+    pub errors: usize,
+    pub requests_per_sec: f64,
+    pub latency: Latency,
+}
+
+/// The result of a benchmark, written as JSON with `--json` to compare builds.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct Report {
+    /// The build that is benchmarked, if it is known.
+    pub build: Option<BuildInfo>,
+    pub concurrency: usize,
+    /// The duration of the benchmark, in seconds.
+    pub duration: f64,
+    pub requests_per_sec: f64,
+    pub routes: Vec<RouteReport>,
+}
+
+#[derive(Debug, Default)]
+struct Samples {
+    latencies: Vec<Duration>,
+    errors: usize,
+}
+
+/// Requests the routes from `concurrency` connections for a duration.
+///
+/// Each connection requests the routes in turn, so every route is requested about as often.
+pub(crate) async fn run(
+    base_url: &str,
+    routes: &[String],
+    concurrency: usize,
+    duration: Duration,
+) -> Result<Report> {
+    let client = reqwest::ClientBuilder::default()
+        .pool_max_idle_per_host(concurrency)
+        .timeout(Duration::from_secs(30))
+        .build()?;
+    let urls: Arc<[String]> = routes
+        .iter()
+        .map(|m| format!("{}/{}", base_url, m.trim_start_matches('/')))
+        .collect();
+
+    let start_time = Instant::now();
+    let deadline = start_time + duration;
+
+    let workers = (0..concurrency)
+        .map(|worker| {
+            let client = client.clone();
+            let urls = urls.clone();
+
+            tokio::spawn(async move {
+                let mut samples = (0..urls.len())
+                    .map(|_| Samples::default())
+                    .collect::<Vec<_>>();
+
+                for index in (0..urls.len()).cycle().skip(worker % urls.len()) {
+                    if Instant::now() >= deadline {
+                        break;
+                    }
+
+                    let request_start = Instant::now();
+                    // The body is read, so server-side rendering is included in the latency.
+                    let result = match client.get(&urls[index]).send().await {
+                        Ok(m) => match m.error_for_status() {
+                            Ok(m) => m.bytes().await.map(|_| ()),
+                            Err(e) => Err(e),
+                        },
+                        Err(e) => Err(e),
+                    };
+
+                    match result {
+                        Ok(()) => samples[index].latencies.push(request_start.elapsed()),
+                        Err(_) => samples[index].errors += 1,
+                    }
+                }
+
+                samples
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let mut samples = (0..urls.len())
+        .map(|_| Samples::default())
+        .collect::<Vec<_>>();
+    for worker in workers {
+        for (i, m) in worker.await?.into_iter().enumerate() {
+            samples[i].latencies.extend(m.latencies);
+            samples[i].errors += m.errors;
+        }
+    }
+
+    let elapsed = start_time.elapsed().as_secs_f64();
+
+    let routes = routes
+        .iter()
+        .zip(samples.iter_mut())
+        .map(|(route, m)| RouteReport {
+            route: route.clone(),
+            requests: m.latencies.len() + m.errors,
+            errors: m.errors,
+            requests_per_sec: m.latencies.len() as f64 / elapsed,
+            latency: Latency::from_samples(&mut m.latencies),
+        })
+        .collect::<Vec<_>>();
+
+    Ok(Report {
+        build: None,
+        concurrency,
+        duration: elapsed,
+        requests_per_sec: routes.iter().map(|m| m.requests_per_sec).sum(),
+        routes,
+    })
+}
+
+pub(crate) fn print_report(report: &Report) {
+    if let Some(ref m) = report.build {
+        eprintln!("Build ID: {}", m.build_id);
+    }
+    eprintln!(
+        "{} connections for {:.1}s",
+        report.concurrency, report.duration
+    );
+    eprintln!();
+
+    let width = report
+        .routes
+        .iter()
+        .map(|m| m.route.len())
+        .max()
+        .unwrap_or_default()
+        .max("Route".len());
+
+    eprintln!(
+        "{}",
+        style(format!(
+            "{:width$}  {:>10}  {:>8}  {:>10}  {:>10}  {:>10}  {:>10}",
+            "Route", "Req/s", "Errors", "p50 (ms)", "p90 (ms)", "p99 (ms)", "max (ms)"
+        ))
+        .bold()
+    );
+    for m in report.routes.iter() {
+        let errors = if m.errors > 0 {
+            style(m.errors.to_string()).red()
+        } else {
+            style(m.errors.to_string())
+        };
+
+        eprintln!(
+            "{:width$}  {:>10.1}  {:>8}  {:>10.2}  {:>10.2}  {:>10.2}  {:>10.2}",
+            m.route,
+            m.requests_per_sec,
+            errors,
+            m.latency.p50,
+            m.latency.p90,
+            m.latency.p99,
+            m.latency.max
+        );
+    }
+
+    eprintln!();
+    eprintln!(
+        "{}",
+        style(format!(
+            "{:.1} requests/sec in total",
+            report.requests_per_sec
+        ))
+        .green()
+        .bold()
+    );
+}
@@ -0,0 +1,217 @@
+//! Builds every application of a monorepo, performed by `stackctl build-all`.
+//!
+//! Each manifest is built by a separate `stackctl build` process. Cargo locks its target
+//! directory, so builds that share one with `--target-dir` wait for each other instead of
+//! corrupting it.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::Instant;
+
+use anyhow::{bail, Context, Result};
+use console::style;
+use serde::Serialize;
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+use tokio::process::Command;
+use tokio::sync::Semaphore;
+
+use crate::cli::BuildAllCommand;
+use crate::exit::{ErrorKind, Failure};
+use crate::utils::relative_path;
+
+const MANIFEST_FILE_NAME: &str = "stackable.toml";
+
+/// Directories that never contain applications.
+const SKIPPED_DIRS: [&str; 4] = ["target", "node_modules", "build", "dist"];
+
+/// The result of building an application.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "kebab-case")]
+struct AppReport {
+    name: String,
+    manifest_path: PathBuf,
+    succeeded: bool,
+    /// `None` if the build was terminated by a signal or could not be started.
+    exit_code: Option<i32>,
+    duration_secs: f64,
+}
+
+/// Collects the manifests in the directory and its subdirectories.
+fn discover(dir: &Path, manifests: &mut Vec<PathBuf>) -> Result<()> {
+    for entry in fs::read_dir(dir).with_context(|| format!("failed to read {}", dir.display()))? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().into_owned();
+        let path = entry.path();
+
+        if path.is_dir() {
+            if !name.starts_with('.') && !SKIPPED_DIRS.contains(&name.as_str()) {
+                discover(&path, manifests)?;
+            }
+        } else if name == MANIFEST_FILE_NAME {
+            manifests.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+/// Prints each line of the output of a build, prefixed with the name of its application.
+async fn forward_lines<R>(name: &str, source: R)
+where
+    R: AsyncRead + Unpin,
+{
+    let mut lines = BufReader::new(source).lines();
+    while let Ok(Some(line)) = lines.next_line().await {
+        eprintln!("{} {line}", style(format!("[{name}]")).cyan());
+    }
+}
+
+async fn build_app(
+    cmd_args: &BuildAllCommand,
+    lock_args: &[&str],
+    name: String,
+    manifest_path: PathBuf,
+) -> Result<AppReport> {
+    let exe_path = std::env::current_exe().context("failed to find the path of stackctl")?;
+    let workspace_dir = manifest_path
+        .parent()
+        .context("manifest has no parent directory")?;
+
+    let mut proc = Command::new(exe_path);
+    proc.arg("--manifest-path")
+        .arg(MANIFEST_FILE_NAME)
+        .arg("build")
+        .args(lock_args)
+        .current_dir(workspace_dir)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+
+    if cmd_args.release {
+        proc.arg("--release");
+    }
+    if let Some(ref m) = cmd_args.profile {
+        proc.arg("--profile").arg(m);
+    }
+    if let Some(ref m) = cmd_args.env {
+        proc.arg("--env").arg(m);
+    }
+    if let Some(ref m) = cmd_args.target_dir {
+        proc.env("CARGO_TARGET_DIR", m);
+    }
+
+    let start_time = Instant::now();
+    let mut child = proc
+        .spawn()
+        .with_context(|| format!("failed to build {name}"))?;
+
+    let stdout = child.stdout.take().context("failed to read output")?;
+    let stderr = child.stderr.take().context("failed to read output")?;
+    futures::join!(forward_lines(&name, stdout), forward_lines(&name, stderr));
+
+    let status = child.wait().await?;
+
+    Ok(AppReport {
+        name,
+        manifest_path,
+        succeeded: status.success(),
+        exit_code: status.code(),
+        duration_secs: start_time.elapsed().as_secs_f64(),
+    })
+}
+
+/// Builds every application, `lock_args` are `--offline` and `--locked` of stackctl, which are
+/// forwarded to each build.
+pub(crate) async fn run(cmd_args: BuildAllCommand, lock_args: Vec<&'static str>) -> Result<()> {
+    let root = cmd_args
+        .root
+        .canonicalize()
+        .with_context(|| format!("failed to find {}", cmd_args.root.display()))?;
+
+    let mut manifests = Vec::new();
+    discover(&root, &mut manifests)?;
+    manifests.sort();
+
+    if manifests.is_empty() {
+        bail!(Failure::new(
+            ErrorKind::Config,
+            format!("no {MANIFEST_FILE_NAME} is found in {}", root.display())
+        ));
+    }
+
+    let jobs = cmd_args
+        .jobs
+        .unwrap_or_else(|| {
+            std::thread::available_parallelism()
+                .map(|m| m.get())
+                .unwrap_or(1)
+        })
+        .max(1);
+
+    eprintln!(
+        "{}",
+        style(format!(
+            "Building {} applications, {} at a time...",
+            manifests.len(),
+            jobs
+        ))
+        .cyan()
+        .bold()
+    );
+
+    let cmd_args = Arc::new(cmd_args);
+    let lock_args = Arc::new(lock_args);
+    let permits = Arc::new(Semaphore::new(jobs));
+    let mut handles = Vec::new();
+
+    for manifest_path in manifests {
+        let name = match manifest_path.parent().map(|m| relative_path(m, &root)) {
+            Some(Ok(m)) if !m.is_empty() => m,
+            _ => root
+                .file_name()
+                .map(|m| m.to_string_lossy().into_owned())
+                .unwrap_or_else(|| ".".to_string()),
+        };
+
+        let cmd_args = cmd_args.clone();
+        let lock_args = lock_args.clone();
+        let permits = permits.clone();
+
+        handles.push(tokio::spawn(async move {
+            // The semaphore is never closed.
+            let _permit = permits.acquire_owned().await?;
+            build_app(&cmd_args, &lock_args, name, manifest_path).await
+        }));
+    }
+
+    let mut reports = Vec::with_capacity(handles.len());
+    for handle in handles {
+        reports.push(handle.await??);
+    }
+
+    eprintln!();
+    for m in reports.iter() {
+        let status = match m.succeeded {
+            true => style("ok").green().bold(),
+            false => style("failed").red().bold(),
+        };
+        eprintln!("{:>8} {} ({:.2}s)", status, m.name, m.duration_secs);
+    }
+
+    if cmd_args.json {
+        println!("{}", serde_json::to_string_pretty(&reports)?);
+    }
+
+    let failed = reports.iter().filter(|m| !m.succeeded).count();
+    if failed > 0 {
+        bail!(Failure::new(
+            ErrorKind::Build,
+            format!("{failed} of {} applications failed to build", reports.len())
+        ));
+    }
+
+    Ok(())
+}
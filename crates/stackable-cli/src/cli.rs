@@ -3,28 +3,329 @@ use std::sync::Arc;
 
 use anyhow::{Context, Result};
 use clap::{Parser, Subcommand};
+use clap_complete::Shell;
 use tokio::fs;
 
+use crate::exit::{ErrorFormat, ErrorKind, Failure};
+use crate::generate::AuthProvider;
 use crate::manifest::Manifest;
+use crate::packaging::ArchiveFormat;
 
 #[derive(Parser, Debug)]
 pub(crate) struct ServeCommand {
     /// Open browser after the development server is ready.
     #[arg(long)]
     pub open: bool,
+    /// The cargo profile to build artifacts with. [Default: dev]
+    #[arg(long)]
+    pub profile: Option<String>,
     /// The name of the env profile. [Default: the same name as the build profile]
     #[arg(long)]
     pub env: Option<String>,
+    /// The host to listen on, overrides the host in `dev-server.listen`.
+    ///
+    /// Use `0.0.0.0` to make the development server reachable from other machines.
+    #[arg(long)]
+    pub host: Option<String>,
+    /// The port to listen on, overrides the port in `dev-server.listen`.
+    #[arg(long)]
+    pub port: Option<u16>,
+    /// Print a QR code of the network URL, for testing on phones.
+    #[arg(long)]
+    pub qr: bool,
+    /// Resolve bridge queries and mutations that match a mock in `mocks/*.toml` or
+    /// `mocks/*.json` with the mock, instead of the backend.
+    #[arg(long)]
+    pub mock: bool,
+    /// Record the results of bridge queries and mutations to `mocks/recordings/<NAME>.json`.
+    ///
+    /// Results are added to the recording if it exists.
+    #[arg(long, value_name = "NAME", conflicts_with_all = ["mock", "replay"])]
+    pub record: Option<String>,
+    /// Replay a recording of `--record`, bridge queries and mutations that are not in the
+    /// recording fail instead of reaching the backend.
+    #[arg(long, value_name = "NAME", conflicts_with = "mock")]
+    pub replay: Option<String>,
+    /// Run the unit tests of the packages affected by a change after each rebuild.
+    ///
+    /// Packages with `wasm-bindgen-test` as a dev-dependency are tested with
+    /// `wasm-bindgen-test-runner`.
+    #[arg(long)]
+    pub test: bool,
+    /// Serve a virtual host of `[server.vhosts]` at another hostname, e.g.:
+    /// `--host-alias admin.localhost=admin.example.com`.
+    ///
+    /// The alias must resolve to the development server, e.g.: with an entry in `/etc/hosts`.
+    #[arg(long = "host-alias", value_name = "ALIAS=HOST", value_parser = parse_host_alias)]
+    pub host_aliases: Vec<(String, String)>,
+}
+
+/// Parses a host alias of `stackctl serve`, e.g.: `admin.localhost=admin.example.com`.
+fn parse_host_alias(s: &str) -> Result<(String, String)> {
+    let (alias, host) = s
+        .split_once('=')
+        .context("host aliases must be set as ALIAS=HOST")?;
+
+    Ok((alias.trim().to_string(), host.trim().to_string()))
 }
 
 #[derive(Parser, Debug)]
 pub(crate) struct BuildCommand {
     /// Build artifacts in release mode, with optimizations.
+    #[arg(long, conflicts_with = "profile")]
+    pub release: bool,
+    /// Write SHA-256 checksums of artifacts to `SHA256SUMS`.
+    ///
+    /// The checksums are signed if `release.signing-key` is set.
+    #[arg(long)]
+    pub sign: bool,
+    /// The cargo profile to build artifacts with.
+    #[arg(long)]
+    pub profile: Option<String>,
+    /// The name of the env profile. [Default: the same name as the build profile]
+    #[arg(long)]
+    pub env: Option<String>,
+    /// Print the commands, environment variables and artifacts of the build without running it.
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+#[derive(Parser, Debug)]
+pub(crate) struct BuildAllCommand {
+    /// The directory to discover `stackable.toml` files in. [Default: current directory]
+    #[arg(long, value_name = "DIR", default_value = ".")]
+    pub root: PathBuf,
+    /// The number of applications built at the same time. [Default: number of CPU cores]
+    #[arg(short, long)]
+    pub jobs: Option<usize>,
+    /// Build artifacts in release mode, with optimizations.
+    #[arg(long, conflicts_with = "profile")]
+    pub release: bool,
+    /// The cargo profile to build artifacts with.
+    #[arg(long)]
+    pub profile: Option<String>,
+    /// The name of the env profile. [Default: the same name as the build profile]
+    #[arg(long)]
+    pub env: Option<String>,
+    /// The cargo target directory shared by all applications.
+    ///
+    /// Cargo locks the directory, so applications that share it are compiled one at a time.
+    #[arg(long, value_name = "DIR")]
+    pub target_dir: Option<PathBuf>,
+    /// Print the report of all applications as JSON.
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Parser, Debug)]
+pub(crate) struct PackageCommand {
+    /// The format of the archive. [Default: zip for Windows targets, tar-gz otherwise]
+    #[arg(long, value_enum)]
+    pub format: Option<ArchiveFormat>,
+    /// The directory to write the archive to. [Default: dist]
+    #[arg(long, value_name = "DIR")]
+    pub out_dir: Option<PathBuf>,
+    /// Print the archive and the files it would contain without writing it.
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+#[derive(Parser, Debug)]
+pub(crate) struct DiffBuildsCommand {
+    /// The build directory of the previous release.
+    pub old: PathBuf,
+    /// The build directory to compare with. [Default: build]
+    pub new: Option<PathBuf>,
+    /// Print the differences as JSON.
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Parser, Debug)]
+pub(crate) struct FmtCommand {
+    /// Check formatting instead of formatting files.
+    #[arg(long)]
+    pub check: bool,
+}
+
+#[derive(Parser, Debug)]
+pub(crate) struct LintCommand {
+    /// Fail if clippy reports warnings.
+    #[arg(long)]
+    pub deny_warnings: bool,
+}
+
+#[derive(Parser, Debug)]
+pub(crate) struct CheckCommand {
+    /// Pin the version of wasm-bindgen in `Trunk.toml` to the version in `Cargo.lock`.
+    #[arg(long)]
+    pub fix: bool,
+}
+
+#[derive(Parser, Debug)]
+pub(crate) struct InfoCommand {
+    /// Print the information as JSON.
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Parser, Debug)]
+pub(crate) struct TestCommand {
+    /// Check that every query, mutation, stream and upload of the frontend has a matching
+    /// resolver in the backend.
     #[arg(long)]
+    pub contracts: bool,
+}
+
+#[derive(Parser, Debug)]
+pub(crate) struct E2eCommand {
+    /// Build artifacts in release mode, with optimizations.
+    #[arg(long, conflicts_with = "profile")]
     pub release: bool,
+    /// The cargo profile to build artifacts with.
+    #[arg(long)]
+    pub profile: Option<String>,
     /// The name of the env profile. [Default: the same name as the build profile]
     #[arg(long)]
     pub env: Option<String>,
+    /// Test the artifacts of a previous build instead of building them.
+    #[arg(long)]
+    pub skip_build: bool,
+    /// The test command and its arguments. [Default: `e2e.command` in stackable.toml]
+    #[arg(last = true, value_name = "COMMAND")]
+    pub command: Vec<String>,
+}
+
+#[derive(Parser, Debug)]
+pub(crate) struct BenchCommand {
+    /// A route to request, may be repeated. [Default: `bench.routes` in stackable.toml]
+    #[arg(long = "route", value_name = "PATH")]
+    pub routes: Vec<String>,
+    /// The number of seconds that routes are requested for. [Default: `bench.duration`]
+    #[arg(long, value_name = "SECS")]
+    pub duration: Option<u64>,
+    /// The number of connections that send requests at the same time. [Default:
+    /// `bench.concurrency`]
+    #[arg(short, long)]
+    pub concurrency: Option<usize>,
+    /// The name of the env profile. [Default: release]
+    #[arg(long)]
+    pub env: Option<String>,
+    /// Benchmark the artifacts of a previous release build instead of building them.
+    #[arg(long)]
+    pub skip_build: bool,
+    /// Print the report as JSON to stdout.
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Parser, Debug)]
+pub(crate) struct RollbackCommand {
+    /// The id of the build to switch to. [Default: the build before the current build]
+    pub id: Option<u64>,
+    /// List the builds in the history instead.
+    #[arg(long, conflicts_with = "id")]
+    pub list: bool,
+}
+
+#[derive(Parser, Debug)]
+pub(crate) struct CompletionsCommand {
+    /// The shell to print the completion script for.
+    #[arg(value_enum)]
+    pub shell: Shell,
+}
+
+#[derive(Parser, Debug)]
+pub(crate) struct SelfUpdateCommand {
+    /// Only check whether a newer version is available, fails if stackctl is outdated.
+    #[arg(long)]
+    pub check: bool,
+}
+
+#[derive(Subcommand, Debug)]
+pub(crate) enum SelfCommand {
+    /// Update stackctl to the latest release.
+    Update(SelfUpdateCommand),
+}
+
+#[derive(Parser, Debug)]
+pub(crate) struct GenerateAuthCommand {
+    /// The provider that users sign in with.
+    #[arg(long, value_enum, default_value_t = AuthProvider::Password)]
+    pub provider: AuthProvider,
+    /// The crate that is shared by the frontend and the backend, relative to the workspace.
+    #[arg(long, value_name = "DIR", default_value = "api")]
+    pub api_dir: PathBuf,
+    /// The crate of the pages and components, relative to the workspace.
+    #[arg(long, value_name = "DIR", default_value = "view")]
+    pub view_dir: PathBuf,
+    /// Overwrite files that already exist.
+    #[arg(long)]
+    pub force: bool,
+}
+
+/// Parses a variable of a generator, e.g.: `view-dir=crates/view`.
+fn parse_variable(s: &str) -> Result<(String, String)> {
+    let (name, value) = s
+        .split_once('=')
+        .context("variables must be set as KEY=VALUE")?;
+
+    Ok((name.trim().to_string(), value.to_string()))
+}
+
+#[derive(Parser, Debug)]
+pub(crate) struct GenerateTemplateCommand {
+    /// The name of the generated item, e.g.: `user-profile`.
+    pub name: String,
+    /// Set a variable of the generator, e.g.: `--set view-dir=crates/view`.
+    #[arg(long = "set", value_name = "KEY=VALUE", value_parser = parse_variable)]
+    pub variables: Vec<(String, String)>,
+    /// Overwrite files that already exist.
+    #[arg(long)]
+    pub force: bool,
+    /// Print the files that would be created or updated without writing them.
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+#[derive(Subcommand, Debug)]
+pub(crate) enum GenerateCommand {
+    /// Scaffold login and logout on the backend, a session-aware context provider on the
+    /// frontend and the bridged types between them.
+    Auth(GenerateAuthCommand),
+    /// Scaffold a function component.
+    Component(GenerateTemplateCommand),
+    /// Scaffold a page and add it to the router.
+    Page(GenerateTemplateCommand),
+    /// Scaffold a bridged query and register it in the bridge.
+    Endpoint(GenerateTemplateCommand),
+    /// List the built-in generators and the generators in `.stackable/templates/`.
+    List,
+    /// A generator in `.stackable/templates/`.
+    #[command(external_subcommand)]
+    Custom(Vec<String>),
+}
+
+#[derive(Parser, Debug)]
+pub(crate) struct MockRecordingCommand {
+    /// The name of the recording.
+    pub name: String,
+}
+
+#[derive(Subcommand, Debug)]
+pub(crate) enum MockCommand {
+    /// List the recordings in `mocks/recordings/`.
+    List,
+    /// Print the requests of a recording and whether they have failed.
+    Show(MockRecordingCommand),
+    /// Delete a recording.
+    Delete(MockRecordingCommand),
+}
+
+#[derive(Subcommand, Debug)]
+pub(crate) enum SessionsCommand {
+    /// Remove expired sessions from the session store.
+    Purge,
 }
 
 #[derive(Subcommand, Debug)]
@@ -34,6 +335,50 @@ pub(crate) enum CliCommand {
     Serve(ServeCommand),
     /// Build the server and client for final distribution.
     Build(BuildCommand),
+    /// Build every application with a `stackable.toml` in the directory and its subdirectories.
+    BuildAll(BuildAllCommand),
+    /// Package the artifacts of the last build into a versioned archive.
+    Package(PackageCommand),
+    /// Compare the assets, routes, endpoints and dependencies of two builds.
+    DiffBuilds(DiffBuildsCommand),
+    /// Test the application.
+    Test(TestCommand),
+    /// Build the application, start it on an ephemeral port and run end-to-end tests against it.
+    ///
+    /// The url of the server is passed to the test command in `STACKABLE_E2E_URL`.
+    E2e(E2eCommand),
+    /// Build the application in release mode, start it and measure the throughput and latency
+    /// of routes.
+    Bench(BenchCommand),
+    /// Format the frontend and the backend with rustfmt.
+    Fmt(FmtCommand),
+    /// Check formatting and run clippy for the frontend (wasm target) and the backend.
+    Lint(LintCommand),
+    /// Check that the versions of wasm-bindgen and yew are consistent, this is also checked
+    /// before each build.
+    Check(CheckCommand),
+    /// Print the resolved configuration, detected crates and tool versions for bug reports.
+    Info(InfoCommand),
+    /// Manage sessions in the session store configured in `[server.session]`.
+    #[command(subcommand)]
+    Sessions(SessionsCommand),
+    /// Switch the running development server back to a previous successful build.
+    ///
+    /// The history is disabled unless the number of builds that are kept is set in
+    /// `dev-server.history-size`.
+    Rollback(RollbackCommand),
+    /// Manage recordings of `stackctl serve --record`, which are replayed with
+    /// `stackctl serve --replay`.
+    #[command(subcommand)]
+    Mock(MockCommand),
+    /// Scaffold a feature into the application.
+    #[command(subcommand)]
+    Generate(GenerateCommand),
+    /// Print the completion script of a shell, e.g.: `stackctl completions bash`.
+    Completions(CompletionsCommand),
+    /// Manage the stackctl installation.
+    #[command(name = "self", subcommand)]
+    SelfCmd(SelfCommand),
 }
 
 #[derive(Parser, Debug)]
@@ -44,18 +389,40 @@ pub(crate) struct Cli {
     #[arg(short, long, value_name = "FILE", default_value = "stackable.toml")]
     pub manifest_path: PathBuf,
 
+    /// How errors are written to stderr, the exit code is the same for both formats.
+    #[arg(long, value_enum, global = true, default_value_t = ErrorFormat::Human)]
+    pub error_format: ErrorFormat,
+
+    /// Run cargo and trunk without accessing the network.
+    #[arg(long, global = true)]
+    pub offline: bool,
+
+    /// Require `Cargo.lock` to be up to date, cargo and trunk fail instead of updating it.
+    #[arg(long, global = true)]
+    pub locked: bool,
+
     #[command(subcommand)]
     pub command: CliCommand,
 }
 
 impl Cli {
     pub async fn load_manifest(&self) -> Result<Arc<Manifest>> {
-        let manifest_str = fs::read_to_string(&self.manifest_path).await.context(
-            "failed to load manifest, do you have stackable.toml in the current directory?",
-        )?;
+        let manifest_str = fs::read_to_string(&self.manifest_path)
+            .await
+            .context(Failure::new(
+                ErrorKind::Config,
+                "failed to load manifest, do you have stackable.toml in the current directory?",
+            ))?;
+
+        let manifest: Manifest = toml::from_str(&manifest_str).context(Failure::new(
+            ErrorKind::Config,
+            "failed to parse stackable.toml",
+        ))?;
+        manifest.server.validate().context(Failure::new(
+            ErrorKind::Config,
+            "invalid [server] in stackable.toml",
+        ))?;
 
-        toml::from_str(&manifest_str)
-            .map(Arc::new)
-            .context("failed to parse stackable.toml")
+        Ok(Arc::new(manifest))
     }
 }
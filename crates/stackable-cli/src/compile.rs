@@ -0,0 +1,148 @@
+//! Diagnostics reported while the development server builds the frontend and the backend.
+//!
+//! Diagnostics are printed as soon as they are reported and counted in the progress bar, so a
+//! failed build does not need to be repeated to show its errors.
+
+use std::fmt;
+use std::ops::Add;
+
+use cargo_metadata::diagnostic::DiagnosticLevel;
+use cargo_metadata::Message;
+
+use crate::lint::is_summary;
+
+/// The number of errors and warnings reported by a build.
+#[derive(Debug, Default, Clone, Copy)]
+pub(crate) struct DiagnosticCounts {
+    pub errors: usize,
+    pub warnings: usize,
+}
+
+impl DiagnosticCounts {
+    fn record(&mut self, level: &DiagnosticLevel) {
+        match level {
+            DiagnosticLevel::Error | DiagnosticLevel::Ice => self.errors += 1,
+            DiagnosticLevel::Warning => self.warnings += 1,
+            _ => {}
+        }
+    }
+}
+
+impl Add for DiagnosticCounts {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self {
+            errors: self.errors + rhs.errors,
+            warnings: self.warnings + rhs.warnings,
+        }
+    }
+}
+
+impl fmt::Display for DiagnosticCounts {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let plural = |n: usize| if n == 1 { "" } else { "s" };
+
+        write!(
+            f,
+            "{} error{}, {} warning{}",
+            self.errors,
+            plural(self.errors),
+            self.warnings,
+            plural(self.warnings)
+        )
+    }
+}
+
+/// Returns the level of a line that starts a rendered diagnostic, e.g.: `error[E0308]: ...`.
+///
+/// Summaries like `could not compile` are not diagnostics.
+fn header_level(line: &str) -> Option<DiagnosticLevel> {
+    let (level, rest) = if let Some(m) = line.strip_prefix("error") {
+        (DiagnosticLevel::Error, m)
+    } else if let Some(m) = line.strip_prefix("warning") {
+        (DiagnosticLevel::Warning, m)
+    } else {
+        return None;
+    };
+
+    let message = match rest.strip_prefix('[') {
+        Some(m) => m.split_once("]: ")?.1,
+        None => rest.strip_prefix(": ")?,
+    };
+
+    let is_cargo_summary = message.starts_with("could not compile")
+        || (message.contains(" generated ")
+            && (message.ends_with(" warning") || message.ends_with(" warnings")));
+
+    (!is_summary(message) && !is_cargo_summary).then_some(level)
+}
+
+/// Diagnostics in the output of a build.
+///
+/// The output of cargo is read as `--message-format=json-diagnostic-rendered-ansi`. Trunk does not
+/// offer JSON output, so diagnostics are found in its rendered output instead.
+#[derive(Debug)]
+pub(crate) struct DiagnosticStream {
+    json: bool,
+    in_diagnostic: bool,
+    counts: DiagnosticCounts,
+}
+
+impl DiagnosticStream {
+    /// Reads `--message-format=json-diagnostic-rendered-ansi` of cargo.
+    pub fn cargo_json() -> Self {
+        Self {
+            json: true,
+            in_diagnostic: false,
+            counts: DiagnosticCounts::default(),
+        }
+    }
+
+    /// Reads diagnostics that are already rendered, e.g.: by trunk.
+    pub fn rendered() -> Self {
+        Self {
+            json: false,
+            in_diagnostic: false,
+            counts: DiagnosticCounts::default(),
+        }
+    }
+
+    pub fn counts(&self) -> DiagnosticCounts {
+        self.counts
+    }
+
+    /// Reads a line of output and returns the text to print, if it is part of a diagnostic.
+    pub fn push_line(&mut self, line: &str) -> Option<String> {
+        if self.json {
+            let diagnostic = match serde_json::from_str::<Message>(line).ok()? {
+                Message::CompilerMessage(m) => m.message,
+                _ => return None,
+            };
+
+            if is_summary(&diagnostic.message) {
+                return None;
+            }
+            self.counts.record(&diagnostic.level);
+
+            // Keeps an empty line between diagnostics, as cargo does.
+            let rendered = diagnostic.rendered.unwrap_or(diagnostic.message);
+            return Some(format!("{}\n", rendered.trim_end()));
+        }
+
+        let plain = console::strip_ansi_codes(line);
+
+        if let Some(level) = header_level(&plain) {
+            self.counts.record(&level);
+            self.in_diagnostic = true;
+        } else if plain.trim().is_empty() {
+            // Rendered diagnostics end with an empty line.
+            let in_diagnostic = self.in_diagnostic;
+            self.in_diagnostic = false;
+
+            return in_diagnostic.then(String::new);
+        }
+
+        self.in_diagnostic.then(|| line.to_string())
+    }
+}
@@ -0,0 +1,75 @@
+//! Shell completions, printed by `stackctl completions`.
+//!
+//! Cargo profiles and env profiles of the workspace are completed for `--profile` and `--env`.
+//! They are read when the script is generated, so it needs to be generated again when profiles
+//! are added.
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use clap::builder::PossibleValuesParser;
+use clap::CommandFactory;
+use clap_complete::Shell;
+
+use crate::cli::Cli;
+
+/// Returns the built-in cargo profiles and profiles in `[profile]` of the workspace manifest.
+fn cargo_profiles(workspace_dir: &Path) -> BTreeSet<String> {
+    let mut profiles = BTreeSet::from(["dev".to_string(), "release".to_string()]);
+
+    let manifest = fs::read_to_string(workspace_dir.join("Cargo.toml"))
+        .ok()
+        .and_then(|m| toml::from_str::<toml::Value>(&m).ok());
+
+    if let Some(toml::Value::Table(m)) = manifest.as_ref().and_then(|m| m.get("profile")) {
+        profiles.extend(m.keys().cloned());
+    }
+
+    profiles
+}
+
+/// Returns the names of env profiles, e.g.: `staging` for `.env.staging`.
+fn env_profiles(workspace_dir: &Path) -> BTreeSet<String> {
+    fs::read_dir(workspace_dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|m| m.ok())
+        .filter_map(|m| {
+            let file_name = m.file_name().to_string_lossy().into_owned();
+            let name = file_name.strip_prefix(".env.")?;
+            let name = name.strip_suffix(".local").unwrap_or(name);
+
+            (!name.is_empty() && name != "local").then(|| name.to_string())
+        })
+        .collect()
+}
+
+/// Writes the completion script of a shell to stdout.
+pub(crate) fn print(shell: Shell, manifest_path: &Path) {
+    let workspace_dir = manifest_path
+        .parent()
+        .filter(|m| !m.as_os_str().is_empty())
+        .unwrap_or_else(|| Path::new("."));
+
+    let profiles = cargo_profiles(workspace_dir);
+    let envs = env_profiles(workspace_dir)
+        .into_iter()
+        .chain(profiles.iter().cloned())
+        .collect::<BTreeSet<_>>();
+
+    let mut cmd = Cli::command();
+    for name in ["serve", "build", "build-all"] {
+        cmd = cmd.mut_subcommand(name, |m| {
+            m.mut_arg("profile", |m| {
+                m.value_parser(PossibleValuesParser::new(profiles.clone()))
+            })
+            .mut_arg("env", |m| {
+                m.value_parser(PossibleValuesParser::new(envs.clone()))
+            })
+        });
+    }
+
+    clap_complete::generate(shell, &mut cmd, "stackctl", &mut io::stdout());
+}
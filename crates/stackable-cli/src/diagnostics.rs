@@ -0,0 +1,299 @@
+//! Diagnostic bundles that users can attach to issues when stackctl panics or fails, and
+//! diagnoses of common failures.
+//!
+//! Bundles are only written if the user agrees to it and values that may contain secrets are
+//! redacted.
+
+use std::backtrace::Backtrace;
+use std::collections::VecDeque;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+use std::{env, fmt, fs, io, panic};
+
+use anyhow::{Context, Result};
+use console::{style, Term};
+
+use crate::info::tool_version;
+
+const MAX_LOG_LINES: usize = 500;
+const MAX_BUILD_LOG_LINES: usize = 100;
+
+/// Keys in `stackable.toml` whose values are replaced in bundles.
+const SECRET_KEY_PARTS: [&str; 4] = ["secret", "key", "token", "password"];
+
+static LAST_PANIC: Mutex<Option<String>> = Mutex::new(None);
+
+/// The last lines that stackctl has logged, written by a tracing layer.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct RecentLogs {
+    inner: Arc<Mutex<VecDeque<String>>>,
+}
+
+impl RecentLogs {
+    fn lines(&self) -> Vec<String> {
+        self.inner
+            .lock()
+            .map(|m| m.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+}
+
+impl io::Write for RecentLogs {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if let Ok(mut inner) = self.inner.lock() {
+            for line in String::from_utf8_lossy(buf).lines() {
+                if inner.len() >= MAX_LOG_LINES {
+                    inner.pop_front();
+                }
+                inner.push_back(line.to_string());
+            }
+        }
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Records the message and the backtrace of panics, in addition to the default panic hook.
+pub(crate) fn install_panic_hook() {
+    let default_hook = panic::take_hook();
+
+    panic::set_hook(Box::new(move |info| {
+        default_hook(info);
+
+        let report = format!("{info}\n\n{}", Backtrace::force_capture());
+        if let Ok(mut m) = LAST_PANIC.lock() {
+            *m = Some(report);
+        }
+    }));
+}
+
+/// Returns the last panic recorded by the panic hook.
+pub(crate) fn take_panic() -> Option<String> {
+    LAST_PANIC.lock().ok().and_then(|mut m| m.take())
+}
+
+fn redact_paths(s: &str) -> String {
+    match env::var("HOME").or_else(|_| env::var("USERPROFILE")) {
+        Ok(home) if !home.is_empty() => s.replace(&home, "~"),
+        _ => s.to_string(),
+    }
+}
+
+fn redact_value(value: &mut toml::Value) {
+    if let toml::Value::Table(table) = value {
+        for (k, v) in table.iter_mut() {
+            let k = k.to_lowercase();
+            if !v.is_table() && SECRET_KEY_PARTS.iter().any(|m| k.contains(m)) {
+                *v = toml::Value::String("<redacted>".to_string());
+            } else {
+                redact_value(v);
+            }
+        }
+    }
+}
+
+/// Reads `stackable.toml` with values that may contain secrets replaced.
+fn config_snapshot(manifest_path: &Path) -> String {
+    let s = match fs::read_to_string(manifest_path) {
+        Ok(m) => m,
+        Err(e) => return format!("failed to read {}: {e}", manifest_path.display()),
+    };
+
+    match toml::from_str::<toml::Value>(&s) {
+        Ok(mut m) => {
+            redact_value(&mut m);
+            m.to_string()
+        }
+        Err(e) => format!("failed to parse manifest: {e}"),
+    }
+}
+
+/// Returns the last lines of the newest trunk and cargo logs in the data directory.
+fn build_logs(data_dir: &Path) -> Vec<(PathBuf, String)> {
+    let mut logs = Vec::new();
+
+    for dir in ["frontend", "backend"].map(|m| data_dir.join(m)) {
+        let newest = fs::read_dir(&dir)
+            .into_iter()
+            .flatten()
+            .filter_map(|m| m.ok())
+            .filter(|m| m.file_name().to_string_lossy().starts_with("log-stderr-"))
+            .filter_map(|m| Some((m.metadata().ok()?.modified().ok()?, m.path())))
+            .max_by_key(|(modified, _)| *modified);
+
+        if let Some((_, path)) = newest {
+            let content = fs::read_to_string(&path).unwrap_or_default();
+            let lines = content.lines().collect::<Vec<_>>();
+            let tail = lines[lines.len().saturating_sub(MAX_BUILD_LOG_LINES)..].join("\n");
+
+            logs.push((path, tail));
+        }
+    }
+
+    logs
+}
+
+/// Writes a diagnostic bundle into the current directory and returns its path.
+async fn write_bundle(failure: &str, manifest_path: &Path, logs: &RecentLogs) -> Result<PathBuf> {
+    let mut s = String::new();
+
+    let _ = writeln!(s, "# stackctl diagnostic bundle\n");
+    let _ = writeln!(s, "stackctl: {}", env!("CARGO_PKG_VERSION"));
+    let _ = writeln!(s, "platform: {} {}", env::consts::OS, env::consts::ARCH);
+    let _ = writeln!(s, "command: {}", env::args().collect::<Vec<_>>().join(" "));
+
+    let _ = writeln!(s, "\n## Failure\n\n{failure}");
+
+    let _ = writeln!(s, "\n## Tools\n");
+    for m in ["rustc", "cargo", "trunk"] {
+        let version = tool_version(m).await;
+        let _ = writeln!(s, "{m}: {}", version.as_deref().unwrap_or("not found"));
+    }
+
+    let _ = writeln!(
+        s,
+        "\n## stackable.toml\n\n{}",
+        config_snapshot(manifest_path)
+    );
+
+    let _ = writeln!(s, "\n## Recent logs\n");
+    for line in logs.lines() {
+        let _ = writeln!(s, "{line}");
+    }
+
+    if let Some(data_dir) = manifest_path.parent().map(|m| m.join(".stackable")) {
+        for (path, tail) in build_logs(&data_dir) {
+            let _ = writeln!(s, "\n## {}\n\n{tail}", path.display());
+        }
+    }
+
+    let timestamp = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|m| m.as_secs())
+        .unwrap_or_default();
+    let path = env::current_dir()?.join(format!("stackctl-diagnostics-{timestamp}.txt"));
+
+    fs::write(&path, redact_paths(&s))
+        .with_context(|| format!("failed to write {}", path.display()))?;
+
+    Ok(path)
+}
+
+/// Asks whether to write a diagnostic bundle, only if stackctl runs in a terminal.
+pub(crate) async fn offer_bundle(failure: &str, manifest_path: &Path, logs: &RecentLogs) {
+    let term = Term::stderr();
+    if !term.is_term() {
+        return;
+    }
+
+    eprintln!();
+    eprint!(
+        "stackctl has failed. Write a diagnostic bundle that you can attach to an issue? {} ",
+        style("[y/N]").bold()
+    );
+
+    let answer = term.read_line().unwrap_or_default();
+    if !matches!(answer.trim(), "y" | "Y" | "yes") {
+        return;
+    }
+
+    match write_bundle(failure, manifest_path, logs).await {
+        Ok(m) => eprintln!(
+            "Diagnostic bundle is written to {}, please review it before attaching.",
+            style(m.display()).cyan().bold()
+        ),
+        Err(e) => eprintln!("failed to write diagnostic bundle: {e:?}"),
+    }
+}
+
+/// A process that listens on a port.
+#[derive(Debug)]
+pub(crate) struct PortOwner {
+    pid: u32,
+    name: Option<String>,
+}
+
+impl PortOwner {
+    /// Returns the command that stops the process.
+    pub fn kill_command(&self) -> String {
+        match cfg!(windows) {
+            true => format!("taskkill /PID {}", self.pid),
+            false => format!("kill {}", self.pid),
+        }
+    }
+}
+
+impl fmt::Display for PortOwner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.name {
+            Some(ref m) => write!(f, "{} (pid {})", m, self.pid),
+            None => write!(f, "pid {}", self.pid),
+        }
+    }
+}
+
+/// Finds the process that listens on a TCP port with `lsof`, or `netstat` on Windows.
+///
+/// This is best-effort, `None` is returned if the tool is not installed, the process belongs to
+/// another user or the tool does not respond in time.
+pub(crate) async fn port_owner(port: u16) -> Option<PortOwner> {
+    use tokio::process::Command;
+
+    let mut cmd;
+    if cfg!(windows) {
+        cmd = Command::new("netstat");
+        cmd.arg("-ano").arg("-p").arg("TCP");
+    } else {
+        cmd = Command::new("lsof");
+        cmd.arg("-nP")
+            .arg(format!("-iTCP:{port}"))
+            .arg("-sTCP:LISTEN")
+            .arg("-Fpc");
+    }
+
+    let output = tokio::time::timeout(
+        Duration::from_secs(3),
+        cmd.stdin(Stdio::null())
+            .stderr(Stdio::null())
+            .kill_on_drop(true)
+            .output(),
+    )
+    .await
+    .ok()?
+    .ok()?;
+    let output = String::from_utf8_lossy(&output.stdout);
+
+    if cfg!(windows) {
+        // `  TCP    0.0.0.0:8080    0.0.0.0:0    LISTENING    1234`
+        let suffix = format!(":{port}");
+        let pid = output.lines().find_map(|line| {
+            let columns = line.split_whitespace().collect::<Vec<_>>();
+            match columns[..] {
+                [_, local, _, "LISTENING", pid] if local.ends_with(&suffix) => pid.parse().ok(),
+                _ => None,
+            }
+        })?;
+
+        return Some(PortOwner { pid, name: None });
+    }
+
+    // Each field is on its own line, prefixed with its name, e.g.: `p1234` and `cnode`.
+    let mut pid = None;
+    let mut name = None;
+    for line in output.lines() {
+        match line.split_at(line.len().min(1)) {
+            ("p", m) if pid.is_none() => pid = m.parse().ok(),
+            ("c", m) if name.is_none() => name = Some(m.to_string()),
+            _ => {}
+        }
+    }
+
+    Some(PortOwner { pid: pid?, name })
+}
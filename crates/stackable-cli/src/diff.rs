@@ -0,0 +1,287 @@
+//! Differences between two build directories, printed by `stackctl diff-builds`.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use stackable_bridge::contract::BridgeContract;
+use stackable_core::build_info::BuildInfo;
+use stackable_core::frontend::FrontendMount;
+
+use crate::utils::{collect_files, relative_path};
+
+/// The versions of packages in the dependency graph, written by `stackctl build`.
+pub(crate) const DEPENDENCIES_FILE_NAME: &str = "dependencies.json";
+
+/// An asset whose size has changed, `None` if the asset does not exist in a build.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct AssetChange {
+    pub path: String,
+    pub old_size: Option<u64>,
+    pub new_size: Option<u64>,
+}
+
+/// A package whose versions have changed.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct DependencyChange {
+    pub name: String,
+    pub old_versions: Vec<String>,
+    pub new_versions: Vec<String>,
+}
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct Changes {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+impl Changes {
+    fn new(old: BTreeSet<String>, new: BTreeSet<String>) -> Self {
+        Self {
+            added: new.difference(&old).cloned().collect(),
+            removed: old.difference(&new).cloned().collect(),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct BuildDiff {
+    pub old_build_id: Option<String>,
+    pub new_build_id: Option<String>,
+    pub old_total_size: u64,
+    pub new_total_size: u64,
+    pub assets: Vec<AssetChange>,
+    /// The mount paths of frontends.
+    pub routes: Changes,
+    /// Queries, mutations, streams and uploads registered with the bridge.
+    pub endpoints: Changes,
+    pub dependencies: Vec<DependencyChange>,
+}
+
+/// Files that describe a build rather than being part of it.
+fn is_build_metadata(rel_path: &str) -> bool {
+    [
+        BuildInfo::FILE_NAME,
+        BridgeContract::FILE_NAME,
+        DEPENDENCIES_FILE_NAME,
+    ]
+    .contains(&rel_path)
+}
+
+fn asset_sizes(build_dir: &Path) -> Result<BTreeMap<String, u64>> {
+    let mut files = Vec::new();
+    collect_files(build_dir, &mut files)
+        .with_context(|| format!("failed to list artifacts in {}", build_dir.display()))?;
+
+    let mut sizes = BTreeMap::new();
+    for path in files {
+        let rel_path = relative_path(&path, build_dir)?;
+        if is_build_metadata(&rel_path) {
+            continue;
+        }
+
+        sizes.insert(rel_path, fs::metadata(&path)?.len());
+    }
+
+    Ok(sizes)
+}
+
+fn read_build_id(build_dir: &Path) -> Option<String> {
+    let s = fs::read_to_string(build_dir.join(BuildInfo::FILE_NAME)).ok()?;
+
+    BuildInfo::from_json(&s).ok().map(|m| m.build_id)
+}
+
+fn read_routes(build_dir: &Path) -> BTreeSet<String> {
+    let path = build_dir.join("frontend").join(FrontendMount::FILE_NAME);
+
+    match fs::read_to_string(path) {
+        Ok(m) => FrontendMount::list_from_json(&m)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|m| m.mount_path)
+            .collect(),
+        // A single frontend is served at the root.
+        Err(_) => ["/".to_string()].into(),
+    }
+}
+
+fn read_endpoints(build_dir: &Path) -> Result<BTreeSet<String>> {
+    let contract = match fs::read_to_string(build_dir.join(BridgeContract::FILE_NAME)) {
+        Ok(m) => BridgeContract::from_json(&m)?,
+        Err(_) => return Ok(BTreeSet::new()),
+    };
+
+    let kinds = [
+        ("query", contract.queries),
+        ("stream", contract.streams),
+        ("upload", contract.uploads),
+    ];
+
+    Ok(kinds
+        .into_iter()
+        .flat_map(|(kind, entries)| {
+            entries
+                .into_iter()
+                .map(move |m| format!("{kind} {}", m.name))
+        })
+        .collect())
+}
+
+fn read_dependencies(build_dir: &Path) -> Result<BTreeMap<String, Vec<String>>> {
+    match fs::read_to_string(build_dir.join(DEPENDENCIES_FILE_NAME)) {
+        Ok(m) => Ok(serde_json::from_str(&m)?),
+        Err(_) => Ok(BTreeMap::new()),
+    }
+}
+
+impl BuildDiff {
+    pub fn new(old_dir: &Path, new_dir: &Path) -> Result<Self> {
+        let old_sizes = asset_sizes(old_dir)?;
+        let new_sizes = asset_sizes(new_dir)?;
+
+        let paths = old_sizes
+            .keys()
+            .chain(new_sizes.keys())
+            .collect::<BTreeSet<_>>();
+        let assets = paths
+            .into_iter()
+            .map(|m| AssetChange {
+                path: m.clone(),
+                old_size: old_sizes.get(m).copied(),
+                new_size: new_sizes.get(m).copied(),
+            })
+            .filter(|m| m.old_size != m.new_size)
+            .collect();
+
+        let old_dependencies = read_dependencies(old_dir)?;
+        let new_dependencies = read_dependencies(new_dir)?;
+        let names = old_dependencies
+            .keys()
+            .chain(new_dependencies.keys())
+            .collect::<BTreeSet<_>>();
+        let dependencies = names
+            .into_iter()
+            .map(|m| DependencyChange {
+                name: m.clone(),
+                old_versions: old_dependencies.get(m).cloned().unwrap_or_default(),
+                new_versions: new_dependencies.get(m).cloned().unwrap_or_default(),
+            })
+            .filter(|m| m.old_versions != m.new_versions)
+            .collect();
+
+        Ok(Self {
+            old_build_id: read_build_id(old_dir),
+            new_build_id: read_build_id(new_dir),
+            old_total_size: old_sizes.values().sum(),
+            new_total_size: new_sizes.values().sum(),
+            assets,
+            routes: Changes::new(read_routes(old_dir), read_routes(new_dir)),
+            endpoints: Changes::new(read_endpoints(old_dir)?, read_endpoints(new_dir)?),
+            dependencies,
+        })
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Renders the differences for humans.
+    pub fn render(&self) -> String {
+        let mut s = String::new();
+
+        let _ = writeln!(
+            s,
+            "Build: {} -> {}",
+            self.old_build_id.as_deref().unwrap_or("unknown"),
+            self.new_build_id.as_deref().unwrap_or("unknown")
+        );
+        let _ = writeln!(
+            s,
+            "Total size: {} -> {} ({})",
+            format_size(self.old_total_size),
+            format_size(self.new_total_size),
+            format_size_change(self.old_total_size, self.new_total_size)
+        );
+
+        let _ = writeln!(s, "\nAssets:");
+        if self.assets.is_empty() {
+            let _ = writeln!(s, "  no changes");
+        }
+        for m in self.assets.iter() {
+            let _ = match (m.old_size, m.new_size) {
+                (None, Some(n)) => writeln!(s, "  + {} ({})", m.path, format_size(n)),
+                (Some(o), None) => writeln!(s, "  - {} ({})", m.path, format_size(o)),
+                (Some(o), Some(n)) => writeln!(
+                    s,
+                    "  ~ {} ({} -> {}, {})",
+                    m.path,
+                    format_size(o),
+                    format_size(n),
+                    format_size_change(o, n)
+                ),
+                (None, None) => Ok(()),
+            };
+        }
+
+        for (title, changes) in [("Routes", &self.routes), ("Endpoints", &self.endpoints)] {
+            let _ = writeln!(s, "\n{title}:");
+            if changes.is_empty() {
+                let _ = writeln!(s, "  no changes");
+            }
+            for m in changes.added.iter() {
+                let _ = writeln!(s, "  + {m}");
+            }
+            for m in changes.removed.iter() {
+                let _ = writeln!(s, "  - {m}");
+            }
+        }
+
+        let _ = writeln!(s, "\nDependencies:");
+        if self.dependencies.is_empty() {
+            let _ = writeln!(s, "  no changes");
+        }
+        for m in self.dependencies.iter() {
+            let _ = match (m.old_versions.is_empty(), m.new_versions.is_empty()) {
+                (true, _) => writeln!(s, "  + {} {}", m.name, m.new_versions.join(", ")),
+                (_, true) => writeln!(s, "  - {} {}", m.name, m.old_versions.join(", ")),
+                _ => writeln!(
+                    s,
+                    "  ~ {} {} -> {}",
+                    m.name,
+                    m.old_versions.join(", "),
+                    m.new_versions.join(", ")
+                ),
+            };
+        }
+
+        s
+    }
+}
+
+fn format_size(size: u64) -> String {
+    match size {
+        m if m >= 1024 * 1024 => format!("{:.1} MiB", m as f64 / (1024.0 * 1024.0)),
+        m if m >= 1024 => format!("{:.1} KiB", m as f64 / 1024.0),
+        m => format!("{m} B"),
+    }
+}
+
+fn format_size_change(old: u64, new: u64) -> String {
+    match new.cmp(&old) {
+        std::cmp::Ordering::Greater => format!("+{}", format_size(new - old)),
+        std::cmp::Ordering::Less => format!("-{}", format_size(old - new)),
+        std::cmp::Ordering::Equal => "unchanged".to_string(),
+    }
+}
@@ -0,0 +1,68 @@
+//! Output of `--dry-run`, which prints the commands and the file operations of a command
+//! instead of performing them.
+//!
+//! Environment variables set by stackctl are printed with their values. Other variables, e.g.:
+//! from env files, may contain secrets, so only their names are printed.
+
+use std::path::Path;
+
+use console::style;
+use tokio::process::Command;
+
+/// Prefixes of environment variables that are set by stackctl or cargo profiles.
+const SHOWN_ENV_PREFIXES: [&str; 4] = ["STACKABLE_", "CARGO_", "TRUNK_", "RUSTFLAGS"];
+
+/// Quotes an argument for POSIX shells if needed.
+fn quote(s: &str) -> String {
+    let is_plain = !s.is_empty()
+        && s.chars()
+            .all(|m| m.is_ascii_alphanumeric() || "-_./:=@,+%".contains(m));
+
+    match is_plain {
+        true => s.to_string(),
+        false => format!("'{}'", s.replace('\'', r"'\''")),
+    }
+}
+
+/// Prints the working directory, the environment variables and the arguments of a command.
+pub(crate) fn print_command(cmd: &Command) {
+    let cmd = cmd.as_std();
+
+    if let Some(m) = cmd.get_current_dir() {
+        eprintln!("{} cd {}", style("$").dim(), quote(&m.to_string_lossy()));
+    }
+
+    let mut envs = cmd
+        .get_envs()
+        .filter_map(|(name, value)| {
+            let name = name.to_string_lossy();
+            // Removed variables are not printed.
+            let value = value?.to_string_lossy();
+
+            let value = match SHOWN_ENV_PREFIXES.iter().any(|m| name.starts_with(m)) {
+                true => quote(&value),
+                false => "<redacted>".to_string(),
+            };
+
+            Some(format!("{name}={value}"))
+        })
+        .collect::<Vec<_>>();
+    envs.sort();
+
+    let args = std::iter::once(cmd.get_program())
+        .chain(cmd.get_args())
+        .map(|m| quote(&m.to_string_lossy()))
+        .collect::<Vec<_>>();
+
+    let line = envs.into_iter().chain(args).collect::<Vec<_>>().join(" ");
+    eprintln!("{} {}", style("$").dim(), line);
+}
+
+/// Prints a file operation, e.g.: `write` or `remove`.
+pub(crate) fn print_action(action: &str, path: &Path) {
+    eprintln!(
+        "{} {}",
+        style(format!("{action:>8}")).cyan(),
+        path.display()
+    );
+}
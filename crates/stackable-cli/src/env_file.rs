@@ -16,6 +16,10 @@ impl EnvFile {
         Self { name: name.into() }
     }
 
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
     pub fn load<P>(&self, workspace_dir: P) -> HashMap<String, String>
     where
         P: AsRef<Path>,
@@ -52,4 +56,15 @@ impl EnvFile {
 
         envs
     }
+
+    /// Loads the keys that are public, values of other keys are not passed to frontend builds.
+    pub fn load_public<P>(&self, workspace_dir: P, keys: &[String]) -> HashMap<String, String>
+    where
+        P: AsRef<Path>,
+    {
+        let mut envs = self.load(workspace_dir);
+        envs.retain(|k, _| keys.contains(k));
+
+        envs
+    }
 }
@@ -0,0 +1,195 @@
+//! Exit codes and the error output of stackctl, so scripts can tell failures apart without
+//! reading messages.
+//!
+//! | Code | Kind                |
+//! |------|---------------------|
+//! | 1    | `failure`           |
+//! | 2    | invalid arguments   |
+//! | 3    | `build`             |
+//! | 4    | `config`            |
+//! | 5    | `toolchain-missing` |
+//! | 6    | `port-conflict`     |
+//! | 70   | `internal`          |
+//!
+//! Invalid arguments are reported by clap before stackctl starts. `stackctl e2e` exits with the
+//! exit code of the test command if the tests fail.
+
+use std::fmt;
+use std::io;
+use std::process::ExitCode;
+
+use clap::ValueEnum;
+use serde::Serialize;
+
+/// How errors are written to stderr.
+#[derive(ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) enum ErrorFormat {
+    #[default]
+    Human,
+    /// A JSON object per error, with the kind, the exit code, the message and its causes.
+    Json,
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum ErrorKind {
+    /// A failure that is not classified, e.g.: failed tests.
+    Failure,
+    /// The frontend or the backend failed to compile.
+    Build,
+    /// `stackable.toml` or another configuration file is missing or invalid.
+    Config,
+    /// A program that stackctl runs, e.g.: trunk or cargo, is not installed.
+    ToolchainMissing,
+    /// The address of the development server is already in use.
+    PortConflict,
+    /// stackctl has panicked.
+    Internal,
+}
+
+impl ErrorKind {
+    pub fn exit_code(self) -> u8 {
+        match self {
+            Self::Failure => 1,
+            Self::Build => 3,
+            Self::Config => 4,
+            Self::ToolchainMissing => 5,
+            Self::PortConflict => 6,
+            Self::Internal => 70,
+        }
+    }
+
+    /// Returns the kind of an error, errors that are not marked with [`Failure`] are classified
+    /// by their causes.
+    pub fn of(e: &anyhow::Error) -> Self {
+        if let Some(m) = e.downcast_ref::<Failure>() {
+            return m.kind;
+        }
+
+        for cause in e.chain() {
+            if cause.is::<toml::de::Error>() {
+                return Self::Config;
+            }
+
+            if cause
+                .downcast_ref::<io::Error>()
+                .map(|m| m.kind() == io::ErrorKind::AddrInUse)
+                .unwrap_or(false)
+            {
+                return Self::PortConflict;
+            }
+        }
+
+        Self::Failure
+    }
+}
+
+/// An error with a known kind.
+///
+/// It can be returned with `bail!` or added to an error with `context`.
+#[derive(Debug)]
+pub(crate) struct Failure {
+    kind: ErrorKind,
+    message: String,
+    /// Replaces the exit code of the kind, e.g.: the exit code of a test command.
+    exit_code: Option<u8>,
+}
+
+impl Failure {
+    pub fn new<S>(kind: ErrorKind, message: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self {
+            kind,
+            message: message.into(),
+            exit_code: None,
+        }
+    }
+
+    pub fn with_exit_code(mut self, exit_code: u8) -> Self {
+        self.exit_code = Some(exit_code);
+        self
+    }
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for Failure {}
+
+/// Returns the error of a program that could not be started.
+pub(crate) fn spawn_error(program: &str, e: io::Error) -> anyhow::Error {
+    let message = match e.kind() {
+        io::ErrorKind::NotFound => {
+            return anyhow::Error::new(e).context(Failure::new(
+                ErrorKind::ToolchainMissing,
+                format!("{program} is not installed or not in PATH"),
+            ));
+        }
+        _ => format!("failed to run {program}"),
+    };
+
+    anyhow::Error::new(e).context(message)
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "kebab-case")]
+struct ErrorOutput<'a> {
+    kind: ErrorKind,
+    exit_code: u8,
+    message: &'a str,
+    causes: Vec<String>,
+}
+
+/// Writes an error to stderr and returns the exit code of its kind.
+pub(crate) fn report(
+    format: ErrorFormat,
+    kind: ErrorKind,
+    exit_code: u8,
+    message: &str,
+    causes: Vec<String>,
+) -> ExitCode {
+    match format {
+        ErrorFormat::Human => {
+            eprintln!("Error: {message}");
+            if !causes.is_empty() {
+                eprintln!();
+                eprintln!("Caused by:");
+                for (i, cause) in causes.iter().enumerate() {
+                    eprintln!("    {i}: {cause}");
+                }
+            }
+        }
+        ErrorFormat::Json => {
+            let output = ErrorOutput {
+                kind,
+                exit_code,
+                message,
+                causes,
+            };
+            eprintln!(
+                "{}",
+                serde_json::to_string(&output).expect("failed to serialise error")
+            );
+        }
+    }
+
+    ExitCode::from(exit_code)
+}
+
+/// Writes an error returned by a command to stderr and returns its exit code.
+pub(crate) fn report_error(format: ErrorFormat, e: &anyhow::Error) -> ExitCode {
+    let causes = e.chain().skip(1).map(|m| m.to_string()).collect();
+
+    let kind = ErrorKind::of(e);
+    let exit_code = e
+        .downcast_ref::<Failure>()
+        .and_then(|m| m.exit_code)
+        .unwrap_or_else(|| kind.exit_code());
+
+    report(format, kind, exit_code, &e.to_string(), causes)
+}
@@ -0,0 +1,667 @@
+//! Scaffolds of common features, written by `stackctl generate`.
+//!
+//! Generators are described by a `generator.toml`, which lists the files to create from
+//! templates and the lines to insert into existing files, e.g.: a variant of the router enum.
+//! Generators in `.stackable/templates/{name}/` of the workspace are available alongside the
+//! built-in ones and replace built-in ones with the same name.
+//!
+//! Authentication is generated from templates in `templates/auth/` with the same layout as the
+//! files they are written to, `__api_crate__` is replaced with the name of the crate that is
+//! shared by the frontend and the backend.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use clap::ValueEnum;
+use console::style;
+use serde::Deserialize;
+use tokio::fs;
+
+use crate::cli::{GenerateAuthCommand, GenerateTemplateCommand};
+use crate::dry_run;
+use crate::exit::{ErrorKind, Failure};
+use crate::template::{self, Variables};
+use crate::utils::relative_path;
+
+const API_CRATE_PLACEHOLDER: &str = "__api_crate__";
+
+/// The directory of generators of a workspace.
+const CUSTOM_GENERATORS_DIR: &str = ".stackable/templates";
+const GENERATOR_FILE_NAME: &str = "generator.toml";
+
+macro_rules! builtin_generator {
+    ($name:literal, [$($template:literal),*]) => {
+        (
+            $name,
+            include_str!(concat!("../templates/generators/", $name, "/generator.toml")),
+            &[$((
+                $template,
+                include_str!(concat!("../templates/generators/", $name, "/", $template)),
+            )),*],
+        )
+    };
+}
+
+/// The name, the description and the templates of built-in generators.
+#[allow(clippy::type_complexity)]
+const BUILTIN_GENERATORS: [(&str, &str, &[(&str, &str)]); 3] = [
+    builtin_generator!("component", ["component.rs.tpl"]),
+    builtin_generator!("endpoint", ["endpoint.rs.tpl"]),
+    builtin_generator!("page", ["page.rs.tpl"]),
+];
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "kebab-case")]
+struct GeneratorVariable {
+    name: String,
+    /// A template that may use `name` and the variables before it.
+    #[serde(default)]
+    default: Option<String>,
+    #[serde(default)]
+    description: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "kebab-case")]
+struct GeneratedFile {
+    /// The path of the file, relative to the workspace.
+    path: String,
+    /// The template, relative to the directory of the generator.
+    template: String,
+}
+
+/// Content inserted into an existing file.
+///
+/// The content is inserted after or before the first line that contains the marker, or appended
+/// if no marker is set. It is skipped if the file already contains it.
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "kebab-case")]
+struct Insertion {
+    path: String,
+    content: String,
+    #[serde(default)]
+    after: Option<String>,
+    #[serde(default)]
+    before: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "kebab-case")]
+struct GeneratorManifest {
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    variables: Vec<GeneratorVariable>,
+    #[serde(default)]
+    files: Vec<GeneratedFile>,
+    #[serde(default)]
+    inserts: Vec<Insertion>,
+}
+
+#[derive(Debug)]
+enum GeneratorSource {
+    Builtin(&'static [(&'static str, &'static str)]),
+    Dir(PathBuf),
+}
+
+#[derive(Debug)]
+struct Generator {
+    name: String,
+    manifest: GeneratorManifest,
+    source: GeneratorSource,
+}
+
+impl Generator {
+    fn parse(name: &str, content: &str, source: GeneratorSource) -> Result<Self> {
+        let manifest = toml::from_str(content).with_context(|| {
+            format!("failed to parse {GENERATOR_FILE_NAME} of generator {name}")
+        })?;
+
+        Ok(Self {
+            name: name.to_string(),
+            manifest,
+            source,
+        })
+    }
+
+    /// Returns the custom generators of a workspace and the built-in generators that are not
+    /// replaced by them, sorted by name.
+    async fn all(workspace_dir: &Path) -> Result<Vec<Self>> {
+        let mut generators = Vec::new();
+        let custom_dir = workspace_dir.join(CUSTOM_GENERATORS_DIR);
+
+        if custom_dir.is_dir() {
+            let mut entries = fs::read_dir(&custom_dir)
+                .await
+                .with_context(|| format!("failed to read {}", custom_dir.display()))?;
+
+            while let Some(entry) = entries.next_entry().await? {
+                let path = entry.path().join(GENERATOR_FILE_NAME);
+                if !path.is_file() {
+                    continue;
+                }
+
+                let name = entry.file_name().to_string_lossy().into_owned();
+                let content = fs::read_to_string(&path)
+                    .await
+                    .with_context(|| format!("failed to read {}", path.display()))?;
+                generators.push(Self::parse(
+                    &name,
+                    &content,
+                    GeneratorSource::Dir(entry.path()),
+                )?);
+            }
+        }
+
+        for (name, content, templates) in BUILTIN_GENERATORS {
+            if !generators.iter().any(|m| m.name == name) {
+                generators.push(Self::parse(
+                    name,
+                    content,
+                    GeneratorSource::Builtin(templates),
+                )?);
+            }
+        }
+
+        generators.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(generators)
+    }
+
+    fn is_builtin(&self) -> bool {
+        matches!(self.source, GeneratorSource::Builtin(_))
+    }
+
+    async fn template(&self, name: &str) -> Result<String> {
+        match self.source {
+            GeneratorSource::Builtin(templates) => templates
+                .iter()
+                .find(|(m, _)| *m == name)
+                .map(|(_, m)| m.to_string())
+                .with_context(|| format!("generator {} has no template {name}", self.name)),
+            GeneratorSource::Dir(ref m) => {
+                let path = m.join(name);
+                fs::read_to_string(&path)
+                    .await
+                    .with_context(|| format!("failed to read {}", path.display()))
+            }
+        }
+    }
+
+    /// Resolves the variables of the generator, variables set on the command line replace
+    /// their defaults.
+    fn variables(&self, cmd_args: &GenerateTemplateCommand) -> Result<Variables> {
+        let mut vars = Variables::new();
+        vars.insert("name".to_string(), cmd_args.name.clone());
+
+        for m in self.manifest.variables.iter() {
+            let value = match cmd_args.variables.iter().find(|(name, _)| *name == m.name) {
+                Some((_, value)) => value.clone(),
+                None => match m.default {
+                    Some(ref default) => template::render(default, &vars)
+                        .with_context(|| format!("failed to render variable {}", m.name))?,
+                    None => bail!(Failure::new(
+                        ErrorKind::Config,
+                        format!(
+                            "generator {} requires a value of {}, set it with --set {}=...",
+                            self.name, m.name, m.name
+                        )
+                    )),
+                },
+            };
+
+            vars.insert(m.name.clone(), value);
+        }
+
+        for (name, value) in cmd_args.variables.iter() {
+            vars.entry(name.clone()).or_insert_with(|| value.clone());
+        }
+
+        Ok(vars)
+    }
+}
+
+/// Returns the content of a file with the content inserted, `None` if the marker is not found.
+fn insert_content(
+    file: &str,
+    content: &str,
+    after: Option<&str>,
+    before: Option<&str>,
+) -> Option<String> {
+    let content = content.trim_start_matches('\n');
+    let content = match content.ends_with('\n') {
+        true => content.to_string(),
+        false => format!("{content}\n"),
+    };
+
+    let (marker, is_after) = match (after, before) {
+        (Some(m), _) => (m, true),
+        (None, Some(m)) => (m, false),
+        (None, None) => {
+            let separator = match file.is_empty() || file.ends_with('\n') {
+                true => "",
+                false => "\n",
+            };
+            return Some(format!("{file}{separator}{content}"));
+        }
+    };
+
+    let mut output = String::with_capacity(file.len() + content.len());
+    let mut inserted = false;
+
+    for line in file.split_inclusive('\n') {
+        let is_marker = !inserted && line.contains(marker);
+
+        if is_marker && !is_after {
+            output.push_str(&content);
+        }
+        output.push_str(line);
+        if is_marker && is_after {
+            if !line.ends_with('\n') {
+                output.push('\n');
+            }
+            output.push_str(&content);
+        }
+
+        inserted = inserted || is_marker;
+    }
+
+    inserted.then_some(output)
+}
+
+pub(crate) async fn list(workspace_dir: &Path) -> Result<()> {
+    for m in Generator::all(workspace_dir).await? {
+        let kind = match m.is_builtin() {
+            true => "",
+            false => " (custom)",
+        };
+        println!(
+            "{}{kind}: {}",
+            style(&m.name).bold(),
+            m.manifest.description.trim()
+        );
+
+        for var in m.manifest.variables.iter() {
+            let default = var
+                .default
+                .as_ref()
+                .map(|m| format!(" [default: {m}]"))
+                .unwrap_or_default();
+            println!(
+                "    --set {}=... {}{default}",
+                var.name,
+                var.description.as_deref().unwrap_or_default()
+            );
+        }
+    }
+
+    Ok(())
+}
+
+pub(crate) async fn run_template(
+    workspace_dir: &Path,
+    generator_name: &str,
+    cmd_args: &GenerateTemplateCommand,
+) -> Result<()> {
+    let generator = match Generator::all(workspace_dir)
+        .await?
+        .into_iter()
+        .find(|m| m.name == generator_name)
+    {
+        Some(m) => m,
+        None => bail!(Failure::new(
+            ErrorKind::Config,
+            format!(
+                "no generator named {generator_name}, generators can be added to \
+                 {CUSTOM_GENERATORS_DIR}/{generator_name}/{GENERATOR_FILE_NAME}"
+            )
+        )),
+    };
+
+    let vars = generator.variables(cmd_args)?;
+
+    let mut files = Vec::new();
+    for m in generator.manifest.files.iter() {
+        let path = template::render(&m.path, &vars)
+            .with_context(|| format!("failed to render the path of {}", m.template))?;
+        let content = template::render(&generator.template(&m.template).await?, &vars)
+            .with_context(|| format!("failed to render {}", m.template))?;
+
+        files.push((workspace_dir.join(path), content));
+    }
+
+    if !cmd_args.force {
+        let existing = files
+            .iter()
+            .filter(|(path, _)| path.exists())
+            .map(|(path, _)| relative_path(path, workspace_dir).unwrap_or_default())
+            .collect::<Vec<_>>();
+
+        if !existing.is_empty() {
+            bail!(Failure::new(
+                ErrorKind::Config,
+                format!(
+                    "{} already exist, pass --force to overwrite",
+                    existing.join(", ")
+                )
+            ));
+        }
+    }
+
+    for (path, content) in files.iter() {
+        if cmd_args.dry_run {
+            dry_run::print_action("create", path);
+            continue;
+        }
+
+        if let Some(m) = path.parent() {
+            fs::create_dir_all(m)
+                .await
+                .with_context(|| format!("failed to create {}", m.display()))?;
+        }
+        fs::write(path, content)
+            .await
+            .with_context(|| format!("failed to write {}", path.display()))?;
+
+        eprintln!(
+            "{:>12} {}",
+            style("Created").green().bold(),
+            relative_path(path, workspace_dir)?
+        );
+    }
+
+    for m in generator.manifest.inserts.iter() {
+        let path = workspace_dir.join(template::render(&m.path, &vars)?);
+        let content = template::render(&m.content, &vars)?;
+        let after = m
+            .after
+            .as_deref()
+            .map(|m| template::render(m, &vars))
+            .transpose()?;
+        let before = m
+            .before
+            .as_deref()
+            .map(|m| template::render(m, &vars))
+            .transpose()?;
+        let marker = after.as_deref().or(before.as_deref());
+
+        let file = match fs::read_to_string(&path).await {
+            Ok(m) => m,
+            // Content without a marker creates the file, e.g.: a new `mod.rs`.
+            Err(_) if marker.is_none() => String::new(),
+            Err(_) => {
+                eprintln!(
+                    "{} {} does not exist, add the following manually:\n{}",
+                    style("warning:").yellow().bold(),
+                    relative_path(&path, workspace_dir).unwrap_or_default(),
+                    content
+                );
+                continue;
+            }
+        };
+
+        if file.contains(content.trim()) {
+            continue;
+        }
+
+        let updated = match insert_content(&file, &content, after.as_deref(), before.as_deref()) {
+            Some(m) => m,
+            None => {
+                eprintln!(
+                    "{} `{}` is not found in {}, add the following manually:\n{}",
+                    style("warning:").yellow().bold(),
+                    marker.unwrap_or_default(),
+                    relative_path(&path, workspace_dir).unwrap_or_default(),
+                    content
+                );
+                continue;
+            }
+        };
+
+        if cmd_args.dry_run {
+            dry_run::print_action("update", &path);
+            continue;
+        }
+
+        if let Some(m) = path.parent() {
+            fs::create_dir_all(m)
+                .await
+                .with_context(|| format!("failed to create {}", m.display()))?;
+        }
+        fs::write(&path, updated)
+            .await
+            .with_context(|| format!("failed to write {}", path.display()))?;
+
+        eprintln!(
+            "{:>12} {}",
+            style("Updated").green().bold(),
+            relative_path(&path, workspace_dir)?
+        );
+    }
+
+    Ok(())
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum AuthProvider {
+    /// Usernames and passwords that are verified by the backend.
+    Password,
+    /// The OAuth 2.0 device authorization grant, e.g.: GitHub, Google or Microsoft.
+    OauthDevice,
+}
+
+impl AuthProvider {
+    /// Returns the templates of the api crate and the view crate, paths are relative to the
+    /// directory of each crate.
+    fn templates(
+        &self,
+    ) -> (
+        [(&'static str, &'static str); 2],
+        (&'static str, &'static str),
+    ) {
+        macro_rules! template {
+            ($provider:literal, $side:literal, $path:literal) => {
+                (
+                    concat!("src/", $path),
+                    include_str!(concat!(
+                        "../templates/auth/",
+                        $provider,
+                        "/",
+                        $side,
+                        "/",
+                        $path
+                    )),
+                )
+            };
+        }
+
+        match self {
+            Self::Password => (
+                [
+                    template!("password", "api", "auth.rs"),
+                    template!("password", "api", "auth/resolvers.rs"),
+                ],
+                template!("password", "view", "auth.rs"),
+            ),
+            Self::OauthDevice => (
+                [
+                    template!("oauth_device", "api", "auth.rs"),
+                    template!("oauth_device", "api", "auth/resolvers.rs"),
+                ],
+                template!("oauth_device", "view", "auth.rs"),
+            ),
+        }
+    }
+
+    /// Returns the bridged types that need to be registered in the bridge.
+    fn bridge_registrations(&self) -> &'static [&'static str] {
+        match self {
+            Self::Password => &[
+                ".add_query::<CurrentUserQuery>()",
+                ".add_mutation::<LoginMutation>()",
+                ".add_mutation::<LogoutMutation>()",
+            ],
+            Self::OauthDevice => &[
+                ".add_query::<CurrentUserQuery>()",
+                ".add_mutation::<StartDeviceLoginMutation>()",
+                ".add_mutation::<PollDeviceLoginMutation>()",
+                ".add_mutation::<LogoutMutation>()",
+            ],
+        }
+    }
+
+    fn login_component(&self) -> &'static str {
+        match self {
+            Self::Password => "LoginForm",
+            Self::OauthDevice => "LoginButton",
+        }
+    }
+}
+
+/// Returns the name of a crate as it is used in paths, e.g.: `my_api` for `my-api`.
+async fn crate_name(crate_dir: &Path, option: &str) -> Result<String> {
+    let manifest_path = crate_dir.join("Cargo.toml");
+    let content = match fs::read_to_string(&manifest_path).await {
+        Ok(m) => m,
+        Err(_) => bail!(Failure::new(
+            ErrorKind::Config,
+            format!(
+                "{} is not a crate, set its location with {option}",
+                crate_dir.display()
+            )
+        )),
+    };
+
+    let manifest: toml::Value = toml::from_str(&content)
+        .with_context(|| format!("failed to parse {}", manifest_path.display()))?;
+
+    manifest
+        .get("package")
+        .and_then(|m| m.get("name"))
+        .and_then(|m| m.as_str())
+        .map(|m| m.replace('-', "_"))
+        .with_context(|| format!("{} has no package name", manifest_path.display()))
+}
+
+pub(crate) async fn run_auth(workspace_dir: &Path, cmd_args: &GenerateAuthCommand) -> Result<()> {
+    let api_dir = workspace_dir.join(&cmd_args.api_dir);
+    let view_dir = workspace_dir.join(&cmd_args.view_dir);
+
+    let api_crate = crate_name(&api_dir, "--api-dir").await?;
+    crate_name(&view_dir, "--view-dir").await?;
+
+    let (api_templates, view_template) = cmd_args.provider.templates();
+    let files = api_templates
+        .into_iter()
+        .map(|(path, content)| (api_dir.join(path), content))
+        .chain([(view_dir.join(view_template.0), view_template.1)])
+        .map(|(path, content)| (path, content.replace(API_CRATE_PLACEHOLDER, &api_crate)))
+        .collect::<Vec<(PathBuf, String)>>();
+
+    if !cmd_args.force {
+        let existing = files
+            .iter()
+            .filter(|(path, _)| path.exists())
+            .map(|(path, _)| relative_path(path, workspace_dir).unwrap_or_default())
+            .collect::<Vec<_>>();
+
+        if !existing.is_empty() {
+            bail!(Failure::new(
+                ErrorKind::Config,
+                format!(
+                    "{} already exist, pass --force to overwrite",
+                    existing.join(", ")
+                )
+            ));
+        }
+    }
+
+    for (path, content) in files.iter() {
+        if let Some(m) = path.parent() {
+            fs::create_dir_all(m)
+                .await
+                .with_context(|| format!("failed to create {}", m.display()))?;
+        }
+        fs::write(path, content)
+            .await
+            .with_context(|| format!("failed to write {}", path.display()))?;
+
+        eprintln!(
+            "{:>12} {}",
+            style("Created").green().bold(),
+            relative_path(path, workspace_dir)?
+        );
+    }
+
+    let api_dir = cmd_args.api_dir.display();
+    let view_dir = cmd_args.view_dir.display();
+    let registrations = cmd_args
+        .provider
+        .bridge_registrations()
+        .iter()
+        .map(|m| format!("        {m}"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let login_component = cmd_args.provider.login_component();
+
+    let mut api_dependencies = vec![
+        r#"async-trait = "0.1""#,
+        r#"serde = { version = "1", features = ["derive"] }"#,
+        r#"thiserror = "1""#,
+    ];
+    if cmd_args.provider == AuthProvider::OauthDevice {
+        api_dependencies.push(r#"tracing = "0.1""#);
+        api_dependencies.push(
+            r#"reqwest = { version = "0.11", features = ["json"], optional = true }  # add "dep:reqwest" to the resolvable feature"#,
+        );
+    }
+    let api_dependencies = api_dependencies.join("\n    ");
+
+    let provider_steps = match cmd_args.provider {
+        AuthProvider::Password => format!(
+            r#"
+5. Enable the `HtmlInputElement` feature of web-sys in {view_dir}/Cargo.toml.
+
+6. Verify credentials in `verify_credentials` of {api_dir}/src/auth/resolvers.rs.
+"#
+        ),
+        AuthProvider::OauthDevice => r#"
+5. Register an application with the provider and set OAUTH_CLIENT_ID,
+   OAUTH_DEVICE_AUTHORIZATION_URL, OAUTH_TOKEN_URL and OAUTH_USERINFO_URL in .env.
+"#
+        .to_string(),
+    };
+
+    eprintln!(
+        r#"
+{}
+
+1. Export the types and register them in the bridge, in {api_dir}/src/lib.rs:
+
+    mod auth;
+    pub use auth::*;
+
+    Bridge::builder()
+{registrations}
+
+2. Export the components, in {view_dir}/src/lib.rs:
+
+    mod auth;
+    pub use auth::{{use_auth, AuthProvider, {login_component}, LogoutButton}};
+
+   and wrap the application in an <AuthProvider /> under a <Suspense />.
+
+3. Add the dependencies to {api_dir}/Cargo.toml, if they are missing:
+
+    {api_dependencies}
+
+4. Enable sessions in stackable.toml:
+
+    [server.session]
+    store = "file"
+{provider_steps}"#,
+        style("Next steps:").cyan().bold(),
+    );
+
+    Ok(())
+}
@@ -0,0 +1,138 @@
+//! The history of successful development builds, for `stackctl rollback`.
+//!
+//! Builds are copied into `.stackable/history/<id>/` and indexed in `.stackable/history.json`.
+//! `stackctl rollback` writes the id of a build to `.stackable/rollback`, the development server
+//! watches it and restarts with the build without rebuilding.
+
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::utils::{collect_files, relative_path};
+
+const HISTORY_FILE_NAME: &str = "history.json";
+const HISTORY_DIR_NAME: &str = "history";
+/// The file that `stackctl rollback` writes the id of a build to, in the data directory.
+pub(crate) const ROLLBACK_FILE_NAME: &str = "rollback";
+
+/// A successful development build.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct HistoryEntry {
+    pub id: u64,
+    pub built_at: SystemTime,
+    pub frontend_build_dir: PathBuf,
+    pub backend_build_path: PathBuf,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub(crate) struct History {
+    /// Builds, the oldest first.
+    pub builds: Vec<HistoryEntry>,
+    /// The id of the build that the development server runs.
+    #[serde(default)]
+    pub current: Option<u64>,
+}
+
+fn copy_dir(from: &Path, to: &Path) -> Result<()> {
+    let mut files = Vec::new();
+    collect_files(from, &mut files)?;
+
+    for path in files {
+        let target = to.join(relative_path(&path, from)?);
+        if let Some(m) = target.parent() {
+            fs::create_dir_all(m)?;
+        }
+        fs::copy(&path, &target)?;
+    }
+
+    Ok(())
+}
+
+impl History {
+    /// Loads the history in a data directory.
+    pub fn load(data_dir: &Path) -> Result<Self> {
+        match fs::read_to_string(data_dir.join(HISTORY_FILE_NAME)) {
+            Ok(m) => serde_json::from_str(&m).context("failed to parse build history"),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e).context("failed to read build history"),
+        }
+    }
+
+    pub fn save(&self, data_dir: &Path) -> Result<()> {
+        fs::write(
+            data_dir.join(HISTORY_FILE_NAME),
+            serde_json::to_string_pretty(self)?,
+        )
+        .context("failed to write build history")
+    }
+
+    /// Copies a build into the history as the current build, the oldest builds are removed to
+    /// keep at most `size` builds.
+    pub fn push(
+        &mut self,
+        data_dir: &Path,
+        frontend_build_dir: &Path,
+        backend_build_path: &Path,
+        size: usize,
+    ) -> Result<()> {
+        let id = self.builds.last().map(|m| m.id + 1).unwrap_or(1);
+        let dir = data_dir.join(HISTORY_DIR_NAME).join(id.to_string());
+        let _ = fs::remove_dir_all(&dir);
+
+        let entry = HistoryEntry {
+            id,
+            built_at: SystemTime::now(),
+            frontend_build_dir: dir.join("frontend"),
+            backend_build_path: dir.join(
+                backend_build_path
+                    .file_name()
+                    .context("failed to find backend binary name")?,
+            ),
+        };
+
+        copy_dir(frontend_build_dir, &entry.frontend_build_dir)
+            .context("failed to copy frontend into build history")?;
+        fs::copy(backend_build_path, &entry.backend_build_path)
+            .context("failed to copy backend into build history")?;
+
+        self.builds.push(entry);
+        self.current = Some(id);
+
+        let removed = self.builds.len().saturating_sub(size);
+        for m in self.builds.drain(..removed) {
+            let _ = fs::remove_dir_all(data_dir.join(HISTORY_DIR_NAME).join(m.id.to_string()));
+        }
+
+        Ok(())
+    }
+
+    pub fn get(&self, id: u64) -> Option<&HistoryEntry> {
+        self.builds.iter().find(|m| m.id == id)
+    }
+
+    /// Returns the build before the current build.
+    pub fn previous(&self) -> Option<&HistoryEntry> {
+        match self.current {
+            Some(current) => self.builds.iter().rev().find(|m| m.id < current),
+            None => self.builds.last(),
+        }
+    }
+}
+
+/// Formats the time since a build, e.g.: `5m ago`.
+pub(crate) fn format_age(built_at: SystemTime) -> String {
+    let age = SystemTime::now()
+        .duration_since(built_at)
+        .unwrap_or(Duration::ZERO)
+        .as_secs();
+
+    match age {
+        0..=59 => format!("{age}s ago"),
+        60..=3599 => format!("{}m ago", age / 60),
+        _ => format!("{}h ago", age / 3600),
+    }
+}
@@ -0,0 +1,127 @@
+//! Conversion of images in the public directory for `stackctl build`, if `[build.images]` is
+//! enabled.
+//!
+//! Each PNG and JPEG image is resized to the widths in `widths` and encoded in every format in
+//! `formats`. The originals are still copied, so browsers without support of the formats can fall
+//! back to them.
+
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use image::codecs::avif::AvifEncoder;
+use image::codecs::webp::{WebPEncoder, WebPQuality};
+use image::imageops::FilterType;
+use image::{ColorType, ImageEncoder, RgbaImage};
+use stackable_core::images::{ImageFormat, ImageManifest, ImageVariant};
+
+use crate::manifest::Images;
+use crate::utils::{collect_files, relative_path};
+
+const EXTENSIONS: [&str; 3] = ["png", "jpg", "jpeg"];
+
+/// The speed of the AVIF encoder, from 1 (slowest) to 10 (fastest).
+const AVIF_SPEED: u8 = 6;
+
+fn is_image(path: &Path) -> bool {
+    path.extension()
+        .and_then(|m| m.to_str())
+        .map(|m| EXTENSIONS.contains(&m.to_ascii_lowercase().as_str()))
+        .unwrap_or(false)
+}
+
+fn encode<W>(w: W, image: &RgbaImage, format: ImageFormat, quality: u8) -> Result<()>
+where
+    W: Write,
+{
+    let (width, height) = image.dimensions();
+
+    match format {
+        ImageFormat::Avif => AvifEncoder::new_with_speed_quality(w, AVIF_SPEED, quality)
+            .write_image(image.as_raw(), width, height, ColorType::Rgba8)?,
+        ImageFormat::Webp => WebPEncoder::new_with_quality(w, WebPQuality::lossy(quality)).encode(
+            image.as_raw(),
+            width,
+            height,
+            ColorType::Rgba8,
+        )?,
+    }
+
+    Ok(())
+}
+
+/// Converts images of the public directory into the frontend build directory and writes the
+/// image manifest.
+///
+/// Variants are written next to the copy of the original, e.g.: `images/hero-640w.webp` for
+/// `images/hero.png`. Images are never enlarged, widths over the width of an image are skipped.
+pub(crate) fn optimize(
+    public_dir: &Path,
+    build_dir: &Path,
+    config: &Images,
+) -> Result<ImageManifest> {
+    let mut files = Vec::new();
+    collect_files(public_dir, &mut files).context("failed to list public files")?;
+    files.retain(|m| is_image(m));
+    files.sort();
+
+    let mut manifest = ImageManifest::default();
+
+    for path in files {
+        let rel_path = relative_path(&path, public_dir)?;
+        let original = image::open(&path).with_context(|| format!("failed to read {rel_path}"))?;
+
+        let mut widths = config
+            .widths
+            .iter()
+            .map(|m| (*m).min(original.width()))
+            .collect::<Vec<_>>();
+        widths.sort_unstable();
+        widths.dedup();
+
+        let stem = rel_path
+            .rsplit_once('.')
+            .map(|(m, _)| m)
+            .unwrap_or(&rel_path);
+        let mut variants = Vec::new();
+
+        for width in widths {
+            let resized = if width < original.width() {
+                original.resize(width, u32::MAX, FilterType::Lanczos3)
+            } else {
+                original.clone()
+            }
+            .to_rgba8();
+
+            for format in config.formats.iter().copied() {
+                let variant_path = format!("{stem}-{width}w.{}", format.extension());
+                let target = build_dir.join(&variant_path);
+                if let Some(m) = target.parent() {
+                    fs::create_dir_all(m)?;
+                }
+
+                let mut file = BufWriter::new(File::create(&target)?);
+                encode(&mut file, &resized, format, config.quality)
+                    .with_context(|| format!("failed to encode {variant_path}"))?;
+                file.flush()?;
+
+                variants.push(ImageVariant {
+                    href: format!("/{variant_path}"),
+                    format,
+                    width: resized.width(),
+                });
+            }
+        }
+
+        manifest.images.insert(rel_path, variants);
+    }
+
+    fs::write(
+        build_dir.join(ImageManifest::FILE_NAME),
+        manifest.to_json()?,
+    )
+    .context("failed to write image manifest")?;
+
+    Ok(manifest)
+}
@@ -2,6 +2,8 @@ use std::time::Duration;
 
 use indicatif::{ProgressBar, ProgressStyle};
 
+use crate::compile::DiagnosticCounts;
+
 fn create_progress(total_steps: u64) -> ProgressBar {
     let bar = ProgressBar::new(total_steps);
     // Progress Bar needs to be updated in a different thread.
@@ -17,7 +19,7 @@ fn create_progress(total_steps: u64) -> ProgressBar {
 
     bar.set_style(
         ProgressStyle::default_bar()
-            .template("{spinner:.green} {prefix} [{elapsed_precise}] [{bar:20}]")
+            .template("{spinner:.green} {prefix} [{elapsed_precise}] [{bar:20}] {msg}")
             .expect("failed to parse template")
             // .tick_chars("-\\|/")
             .progress_chars("=>-"),
@@ -37,6 +39,11 @@ impl ServeProgress {
         }
     }
 
+    pub fn step_build_css(&self) {
+        self.inner.set_prefix("Building (css)      ");
+        self.inner.set_position(1);
+    }
+
     pub fn step_build_frontend(&self) {
         self.inner.set_prefix("Building (frontend) ");
         self.inner.set_position(2);
@@ -52,6 +59,16 @@ impl ServeProgress {
         self.inner.set_position(17);
     }
 
+    /// Shows the number of errors and warnings reported by the current build.
+    pub fn set_diagnostics(&self, counts: DiagnosticCounts) {
+        self.inner.set_message(counts.to_string());
+    }
+
+    /// Prints a line above the progress bar.
+    pub fn println(&self, line: &str) {
+        self.inner.suspend(|| eprintln!("{line}"));
+    }
+
     pub fn hide(self) {
         self.inner.finish_and_clear()
     }
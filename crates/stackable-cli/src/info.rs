@@ -0,0 +1,136 @@
+//! The resolved configuration of a workspace, printed by `stackctl info`.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::path::PathBuf;
+use std::process::Stdio;
+
+use serde::Serialize;
+
+/// A package in the cargo workspace.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct CrateInfo {
+    pub name: String,
+    pub version: String,
+    pub bins: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct FrontendInfo {
+    pub name: Option<String>,
+    pub index_html: PathBuf,
+    /// The binary in `data-bin` of `index.html`.
+    pub bin_name: Option<String>,
+    pub mount_path: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct Info {
+    pub stackctl_version: String,
+    pub manifest_path: PathBuf,
+    pub workspace_dir: PathBuf,
+    pub build_dir: PathBuf,
+    pub data_dir: PathBuf,
+    pub profile: String,
+    pub env_profile: String,
+    pub listen: String,
+    pub server_bin_name: String,
+    pub crates: Vec<CrateInfo>,
+    pub frontends: Vec<FrontendInfo>,
+    /// The version of each tool, `None` if it is not installed.
+    pub tools: BTreeMap<String, Option<String>>,
+    /// The features enabled for each stackable package in the dependency graph.
+    pub features: BTreeMap<String, Vec<String>>,
+    /// `stackable.toml` with default values filled in.
+    pub manifest: toml::Value,
+}
+
+/// Returns the first line that a tool prints with `--version`.
+pub(crate) async fn tool_version(program: &str) -> Option<String> {
+    use tokio::process::Command;
+
+    let output = Command::new(program)
+        .arg("--version")
+        .stdin(Stdio::null())
+        .output()
+        .await
+        .ok()
+        .filter(|m| m.status.success())?;
+
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .next()
+        .map(|m| m.trim().to_string())
+}
+
+impl Info {
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Renders the information for bug reports.
+    pub fn render(&self) -> String {
+        let mut s = String::new();
+
+        let _ = writeln!(s, "stackctl: {}", self.stackctl_version);
+        let _ = writeln!(s, "Manifest: {}", self.manifest_path.display());
+        let _ = writeln!(s, "Workspace: {}", self.workspace_dir.display());
+        let _ = writeln!(s, "Build directory: {}", self.build_dir.display());
+        let _ = writeln!(s, "Data directory: {}", self.data_dir.display());
+        let _ = writeln!(s, "Profile: {}", self.profile);
+        let _ = writeln!(s, "Env profile: {}", self.env_profile);
+        let _ = writeln!(s, "Listen: {}", self.listen);
+
+        let _ = writeln!(s, "\nCrates:");
+        for m in self.crates.iter() {
+            let _ = write!(s, "  {} {}", m.name, m.version);
+            if !m.bins.is_empty() {
+                let _ = write!(s, " (bin: {})", m.bins.join(", "));
+            }
+            if m.bins.contains(&self.server_bin_name) {
+                let _ = write!(s, " [server]");
+            }
+            let _ = writeln!(s);
+        }
+
+        let _ = writeln!(s, "\nFrontends:");
+        for m in self.frontends.iter() {
+            let _ = writeln!(
+                s,
+                "  {} at {}: {} (bin: {})",
+                m.name.as_deref().unwrap_or("default"),
+                m.mount_path,
+                m.index_html.display(),
+                m.bin_name.as_deref().unwrap_or("unknown")
+            );
+        }
+
+        let _ = writeln!(s, "\nTools:");
+        for (name, version) in self.tools.iter() {
+            let _ = writeln!(
+                s,
+                "  {}: {}",
+                name,
+                version.as_deref().unwrap_or("not found")
+            );
+        }
+
+        let _ = writeln!(s, "\nFeatures:");
+        if self.features.is_empty() {
+            let _ = writeln!(s, "  no stackable packages are found");
+        }
+        for (name, features) in self.features.iter() {
+            let _ = writeln!(s, "  {}: {}", name, features.join(", "));
+        }
+
+        let _ = writeln!(s, "\nResolved stackable.toml:");
+        for line in self.manifest.to_string().lines() {
+            let _ = writeln!(s, "  {line}");
+        }
+
+        s
+    }
+}
@@ -0,0 +1,124 @@
+//! Subresource integrity hashes of release builds.
+//!
+//! The scripts, stylesheets and wasm modules of a frontend are hashed after it is built, the
+//! hashes are added to `index.html` as `integrity` attributes and to the asset manifest, which
+//! the server uses for the elements that it adds when it renders a page.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use sha2::{Digest, Sha384};
+
+use crate::assets::is_asset;
+use crate::preload::attr;
+use crate::utils::{collect_files, relative_path};
+
+/// The extensions of files that are hashed.
+const EXTENSIONS: &[&str] = &["js", "css", "wasm"];
+
+fn sri_hash(content: &[u8]) -> String {
+    format!("sha384-{}", STANDARD.encode(Sha384::digest(content)))
+}
+
+/// Returns the hashes of the assets of a frontend build by their paths, relative to the build
+/// directory.
+fn hashes(build_dir: &Path) -> Result<BTreeMap<String, String>> {
+    let mut paths = Vec::new();
+    collect_files(build_dir, &mut paths).context("failed to list frontend assets")?;
+
+    let mut hashes = BTreeMap::new();
+    for path in paths {
+        let rel_path = relative_path(&path, build_dir)?;
+        let hashed = path
+            .extension()
+            .and_then(|m| m.to_str())
+            .map(|m| EXTENSIONS.contains(&m))
+            .unwrap_or(false);
+
+        if hashed && is_asset(&rel_path) {
+            let content =
+                fs::read(&path).with_context(|| format!("failed to read {}", path.display()))?;
+            hashes.insert(rel_path, sri_hash(&content));
+        }
+    }
+
+    Ok(hashes)
+}
+
+/// Adds `integrity` attributes to the `<script>` and `<link>` elements of assets that have a
+/// hash, elements that already have the attribute are kept.
+fn add_attributes(index_html: &str, public_url: &str, hashes: &BTreeMap<String, String>) -> String {
+    let mut output = String::with_capacity(index_html.len());
+    let mut rest = index_html;
+
+    while let Some(i) = rest.find('<') {
+        output.push_str(&rest[..i]);
+        rest = &rest[i..];
+
+        let end = match rest.find('>') {
+            Some(m) => m,
+            None => break,
+        };
+        let tag = &rest[..end];
+        rest = &rest[end..];
+
+        let lower = tag.to_ascii_lowercase();
+        let url = if lower.starts_with("<script") {
+            attr(tag, "src")
+        } else if lower.starts_with("<link") {
+            attr(tag, "href")
+        } else {
+            None
+        };
+        let hash = url
+            .filter(|_| attr(tag, "integrity").is_none())
+            .and_then(|m| m.strip_prefix(public_url))
+            .and_then(|m| hashes.get(m));
+
+        match hash {
+            Some(m) => {
+                let (tag, self_closing) = match tag.strip_suffix('/') {
+                    Some(tag) => (tag.trim_end(), true),
+                    None => (tag, false),
+                };
+
+                output.push_str(tag);
+                output.push_str(&format!(r#" integrity="{m}""#));
+                if self_closing {
+                    output.push_str(" /");
+                }
+            }
+            None => output.push_str(tag),
+        }
+    }
+
+    output.push_str(rest);
+
+    output
+}
+
+/// Hashes the assets of a frontend build and adds `integrity` attributes to its `index.html`.
+///
+/// Returns the hashes, for the asset manifest.
+pub(crate) fn write_integrity(
+    build_dir: &Path,
+    public_url: &str,
+) -> Result<BTreeMap<String, String>> {
+    let hashes = hashes(build_dir)?;
+
+    let index_html_path = build_dir.join("index.html");
+    let index_html = fs::read_to_string(&index_html_path)
+        .context("failed to read index.html of frontend build")?;
+
+    fs::write(
+        &index_html_path,
+        add_attributes(&index_html, public_url, &hashes),
+    )
+    .context("failed to write index.html of frontend build")?;
+
+    Ok(hashes)
+}
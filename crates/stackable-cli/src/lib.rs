@@ -1,34 +1,90 @@
 #![deny(clippy::all)]
 #![deny(missing_debug_implementations)]
 
+mod assets;
+mod bench;
+mod build_all;
 mod cli;
+mod compile;
+mod completions;
+mod diagnostics;
+mod diff;
+mod dry_run;
 mod env_file;
+mod exit;
+mod generate;
+mod history;
+mod images;
 mod indicators;
+mod info;
+mod integrity;
+mod lint;
+mod locales;
 mod manifest;
+mod mdns;
+mod mocks;
+mod notifications;
+mod packaging;
+mod placeholders;
+mod preload;
 mod profile;
+mod public;
+mod pwa;
+mod restart;
+mod routes;
+mod self_update;
+mod seo;
+mod server_log;
+mod signing;
+mod template;
 mod utils;
+mod versions;
+mod watch_tests;
 
+use std::collections::{BTreeMap, VecDeque};
+use std::net::IpAddr;
+use std::panic::AssertUnwindSafe;
 use std::path::{Path, PathBuf};
-use std::process::Stdio;
+use std::process::{ExitCode, Stdio};
 use std::sync::Arc;
-use std::time::{Duration, SystemTime};
+use std::time::{Duration, Instant, SystemTime};
 
 use anyhow::{bail, Context, Result};
 use cargo_metadata::Metadata;
 use clap::Parser;
-use cli::{BuildCommand, Cli, CliCommand, ServeCommand};
+use cli::{
+    BenchCommand, BuildCommand, CheckCommand, Cli, CliCommand, DiffBuildsCommand, E2eCommand,
+    FmtCommand, GenerateCommand, GenerateTemplateCommand, InfoCommand, LintCommand, MockCommand,
+    PackageCommand, RollbackCommand, SelfCommand, ServeCommand, SessionsCommand, TestCommand,
+};
 use console::{style, Term};
 use env_file::EnvFile;
 use futures::future::ready;
 use futures::stream::unfold;
 use futures::{pin_mut, FutureExt, Stream, StreamExt};
-use manifest::Manifest;
+use history::History;
+use manifest::{Manifest, NotificationEvent};
+use mdns::MdnsAnnouncement;
+use notifications::Notifier;
 use notify::{recommended_watcher, Event, RecursiveMode, Watcher};
+use packaging::ArchiveFormat;
 use profile::Profile;
-use stackable_core::dev::StackctlMetadata;
-use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
-use tokio::process::Child;
+use qrcode::render::unicode::Dense1x2;
+use qrcode::QrCode;
+use restart::RestartPolicy;
+use stackable_backend::session;
+use stackable_bridge::contract::BridgeContract;
+use stackable_core::build_info::BuildInfo;
+use stackable_core::dev::{DevStatus, LogRecord, Rebuild, ScheduledJob, StackctlMetadata};
+use stackable_core::frontend::FrontendMount;
+use stackable_core::images::ImageManifest;
+use stackable_core::observability::ObservabilityConfig;
+use stackable_core::seo::RouteList;
+use stackable_core::server::{ServerConfig, SessionStoreConfig};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWriteExt, BufReader};
+use tokio::process::{Child, ChildStderr};
 use tokio::sync::mpsc::unbounded_channel;
+use tokio::task::{self, JoinHandle};
 use tokio::time::sleep;
 use tokio::{fs, spawn};
 use tokio_stream::wrappers::UnboundedReceiverStream;
@@ -36,8 +92,73 @@ use tracing::Level;
 use tracing_subscriber::prelude::*;
 use tracing_subscriber::EnvFilter;
 
+use crate::compile::{DiagnosticCounts, DiagnosticStream};
+use crate::exit::{spawn_error, ErrorFormat, ErrorKind, Failure};
 use crate::indicators::ServeProgress;
-use crate::utils::random_str;
+use crate::utils::{
+    frontend_bin_name, frontend_cargo_features, is_unspecified_host, network_ip, random_secret,
+    random_str, relative_path, split_host_port,
+};
+
+/// Changes in the workspace, collected over a short period.
+///
+/// If neither flag is set, only translation files, files of the public directory or mocks have
+/// changed, which are copied or reloaded without a rebuild.
+#[derive(Debug, Clone)]
+struct WorkspaceChange {
+    time: SystemTime,
+    /// Sources of the frontend or the backend, which require a full rebuild.
+    sources: bool,
+    /// `Cargo.toml`, `Cargo.lock` or `stackable.toml`, which also invalidate the previous build.
+    dependencies: bool,
+    /// `stackable.toml`, which is only loaded when stackctl starts.
+    manifest: bool,
+    /// Inputs of the CSS step, which only require the frontend to be rebuilt.
+    css: bool,
+    /// `stackctl rollback` has requested to switch to a previous build.
+    rollback: bool,
+    /// The sources that have changed, whose packages are tested by `stackctl serve --test`.
+    paths: Vec<PathBuf>,
+}
+
+impl WorkspaceChange {
+    fn merge(&mut self, other: Self) {
+        self.sources |= other.sources;
+        self.dependencies |= other.dependencies;
+        self.manifest |= other.manifest;
+        self.css |= other.css;
+        self.rollback |= other.rollback;
+        self.paths.extend(other.paths);
+    }
+}
+
+/// The last lines of the stderr of a process.
+type StderrTail = Arc<std::sync::Mutex<VecDeque<String>>>;
+
+/// A development server that is ready.
+#[derive(Debug)]
+struct ServerProcess {
+    child: Child,
+    stderr_tail: StderrTail,
+    ready_at: Instant,
+    /// Periodic jobs that the server has registered.
+    jobs: Vec<ScheduledJob>,
+}
+
+/// What the development server waits for after it has started.
+#[derive(Debug)]
+enum ServeEvent {
+    Change(Option<WorkspaceChange>),
+    Exited(String),
+    Restart,
+}
+
+/// The artifacts of a development build.
+#[derive(Debug, Clone)]
+struct ServeBuild {
+    frontend_build_dir: PathBuf,
+    backend_build_path: PathBuf,
+}
 
 #[derive(Debug)]
 struct Stackctl {
@@ -52,14 +173,28 @@ impl Stackctl {
         let manifest = cli.load_manifest().await?;
 
         let profile = match cli.command {
-            CliCommand::Serve(_) => Profile::new_debug(),
-            CliCommand::Build(BuildCommand { release, .. }) => {
+            CliCommand::Build(BuildCommand {
+                profile: Some(ref m),
+                ..
+            })
+            | CliCommand::Serve(ServeCommand {
+                profile: Some(ref m),
+                ..
+            })
+            | CliCommand::E2e(E2eCommand {
+                profile: Some(ref m),
+                ..
+            }) => Profile::new(m),
+            CliCommand::Bench(_) => Profile::new_release(),
+            CliCommand::Build(BuildCommand { release, .. })
+            | CliCommand::E2e(E2eCommand { release, .. }) => {
                 if release {
                     Profile::new_release()
                 } else {
                     Profile::new_debug()
                 }
             }
+            _ => Profile::new_debug(),
         };
 
         let env_name = match cli.command {
@@ -68,6 +203,12 @@ impl Stackctl {
             })
             | CliCommand::Serve(ServeCommand {
                 env: Some(ref m), ..
+            })
+            | CliCommand::E2e(E2eCommand {
+                env: Some(ref m), ..
+            })
+            | CliCommand::Bench(BenchCommand {
+                env: Some(ref m), ..
             }) => m,
             _ => profile.name(),
         };
@@ -91,8 +232,54 @@ impl Stackctl {
             .map(|m| m.to_owned())
     }
 
-    async fn watch_changes(&self) -> Result<impl Stream<Item = SystemTime>> {
+    /// Returns the `src` directories of path dependencies outside of the workspace directory,
+    /// e.g.: `../shared-types`, which are not covered by watching the workspace.
+    async fn external_source_dirs(&self, workspace_dir: &Path) -> Vec<PathBuf> {
+        let metadata = match self.cargo_metadata().await {
+            Ok(m) => m,
+            Err(e) => {
+                tracing::warn!("failed to find path dependencies: {:#}", e);
+                return Vec::new();
+            }
+        };
+
+        let mut dirs = metadata
+            .packages
+            .iter()
+            // Packages without a source are local, e.g.: path dependencies.
+            .filter(|m| m.source.is_none())
+            .filter_map(|m| m.manifest_path.parent())
+            .map(|m| m.as_std_path().join("src"))
+            .filter(|m| !m.starts_with(workspace_dir) && m.is_dir())
+            .collect::<Vec<_>>();
+        dirs.sort();
+        dirs.dedup();
+
+        dirs
+    }
+
+    async fn watch_changes(&self) -> Result<impl Stream<Item = WorkspaceChange>> {
         let workspace_dir = self.workspace_dir().await?;
+        let locales_dir = self
+            .manifest
+            .i18n
+            .enabled
+            .then(|| workspace_dir.join(&self.manifest.i18n.dir));
+        let public_dir = workspace_dir.join(&self.manifest.build.frontend.public_dir);
+        let rollback_path = self.data_dir().await?.join(history::ROLLBACK_FILE_NAME);
+        let manifest_path = self
+            .cli
+            .manifest_path
+            .canonicalize()
+            .unwrap_or_else(|_| self.cli.manifest_path.clone());
+        let mocks_dir =
+            (self.mock() || self.replay().is_some()).then(|| workspace_dir.join(mocks::MOCKS_DIR));
+        let index_html_paths = self
+            .frontends()
+            .await?
+            .into_iter()
+            .map(|(_, m)| m)
+            .collect::<Vec<_>>();
         let (tx, rx) = unbounded_channel::<PathBuf>();
 
         let mut watcher = recommended_watcher(move |e: Result<Event, _>| {
@@ -110,28 +297,109 @@ impl Stackctl {
             .watch(&workspace_dir, RecursiveMode::Recursive)
             .context("failed to watch workspace")?;
 
-        let stream = UnboundedReceiverStream::new(rx)
-            .filter(|p| {
-                let p_str = p.as_os_str().to_string_lossy();
-                if p_str.contains("target/") {
-                    return ready(false);
-                }
-                if p_str.contains(".stackable/") {
-                    return ready(false);
-                }
-                if !p_str.contains("src/") {
-                    return ready(false);
-                }
+        for m in self.external_source_dirs(&workspace_dir).await {
+            watcher
+                .watch(&m, RecursiveMode::Recursive)
+                .with_context(|| format!("failed to watch {}", m.display()))?;
+        }
 
-                ready(true)
-            })
+        let css = self.manifest.build.frontend.css.as_ref();
+        let css_output = css.map(|m| workspace_dir.join(&m.output));
+        let css_inputs = css
+            .map(|m| m.watch_paths())
+            .unwrap_or_default()
+            .into_iter()
+            .map(|m| workspace_dir.join(m))
+            .collect::<Vec<_>>();
+
+        let is_source = move |p: &PathBuf| {
+            // Sources of all frontends are in the workspace, their index.html may not be
+            // in a src directory.
+            if index_html_paths.iter().any(|m| p == m) {
+                return true;
+            }
+
+            let p_str = p.as_os_str().to_string_lossy();
+            if p_str.contains("target/") {
+                return false;
+            }
+            if p_str.contains(".stackable/") {
+                return false;
+            }
+
+            p_str.contains("src/")
+        };
+
+        // Returns the change of a path, `None` if the path does not affect the build.
+        let classify = move |p: PathBuf| {
+            let mut change = WorkspaceChange {
+                time: SystemTime::now(),
+                sources: false,
+                dependencies: false,
+                manifest: false,
+                css: false,
+                rollback: false,
+                paths: Vec::new(),
+            };
+
+            if p == rollback_path {
+                change.rollback = true;
+                return Some(change);
+            }
+
+            // Rendered copies of `index.html` are written by stackctl.
+            if p.to_string_lossy().ends_with(placeholders::RENDERED_SUFFIX) {
+                return None;
+            }
+
+            // Manifests and lock files are not in a src directory.
+            let p_str = p.as_os_str().to_string_lossy();
+            let is_cargo_file = matches!(
+                p.file_name().and_then(|m| m.to_str()),
+                Some("Cargo.toml" | "Cargo.lock")
+            ) && !p_str.contains("target/")
+                && !p_str.contains(".stackable/");
+            if is_cargo_file || p == manifest_path {
+                change.sources = true;
+                change.dependencies = true;
+                change.manifest = p == manifest_path;
+                change.paths.push(p);
+                return Some(change);
+            }
+
+            // The CSS step writes its output, which is not a change of its inputs.
+            if css_output.as_ref() == Some(&p) {
+                return None;
+            }
+
+            let is_locale = locales_dir
+                .as_ref()
+                .map(|m| p.starts_with(m))
+                .unwrap_or(false);
+            let is_public = p.starts_with(&public_dir);
+            // Mocks are loaded by the server when it starts.
+            let is_mock = mocks_dir
+                .as_ref()
+                .map(|m| p.starts_with(m))
+                .unwrap_or(false);
+            change.sources = !is_locale && !is_public && !is_mock && is_source(&p);
+            change.css = css_inputs.iter().any(|m| p.starts_with(m));
+            if change.sources {
+                change.paths.push(p);
+            }
+
+            (change.sources || change.css || is_locale || is_public || is_mock).then_some(change)
+        };
+
+        let stream = UnboundedReceiverStream::new(rx)
+            .filter_map(move |p| ready(classify(p)))
             .boxed();
 
         Ok(unfold(
             (stream, watcher),
             |(mut stream, watcher)| async move {
                 // We wait until first item is available.
-                stream.next().await?;
+                let mut change = stream.next().await?;
 
                 let sleep_fur = sleep(Duration::from_millis(100)).fuse();
                 pin_mut!(sleep_fur);
@@ -144,11 +412,16 @@ impl Stackctl {
 
                     futures::select! {
                         _ = sleep_fur => break,
-                        _ = next_path_fur => {},
+                        m = next_path_fur => {
+                            if let Some(m) = m {
+                                change.merge(m);
+                            }
+                        },
                     }
                 }
+                change.time = SystemTime::now();
 
-                Some((SystemTime::now(), (stream, watcher)))
+                Some((change, (stream, watcher)))
             },
         ))
     }
@@ -200,15 +473,12 @@ impl Stackctl {
     }
 
     async fn frontend_build_dir(&self) -> Result<PathBuf> {
-        let frontend_build_dir = match self.cli.command {
-            CliCommand::Build { .. } => {
-                let build_dir = self.build_dir().await?;
-                build_dir.join("frontend")
-            }
-            CliCommand::Serve { .. } => {
-                let frontend_data_dir = self.frontend_data_dir().await?;
-                frontend_data_dir.join("serve-builds").join(random_str()?)
-            }
+        let frontend_build_dir = if self.is_artifact_build() {
+            let build_dir = self.build_dir().await?;
+            build_dir.join("frontend")
+        } else {
+            let frontend_data_dir = self.frontend_data_dir().await?;
+            frontend_data_dir.join("serve-builds").join(random_str()?)
         };
 
         fs::create_dir_all(&frontend_build_dir)
@@ -219,15 +489,12 @@ impl Stackctl {
     }
 
     async fn backend_build_dir(&self) -> Result<PathBuf> {
-        let frontend_build_dir = match self.cli.command {
-            CliCommand::Build { .. } => {
-                let build_dir = self.build_dir().await?;
-                build_dir.join("backend")
-            }
-            CliCommand::Serve { .. } => {
-                let frontend_data_dir = self.backend_data_dir().await?;
-                frontend_data_dir.join("serve-builds").join(random_str()?)
-            }
+        let frontend_build_dir = if self.is_artifact_build() {
+            let build_dir = self.build_dir().await?;
+            build_dir.join("backend")
+        } else {
+            let frontend_data_dir = self.backend_data_dir().await?;
+            frontend_data_dir.join("serve-builds").join(random_str()?)
         };
 
         fs::create_dir_all(&frontend_build_dir)
@@ -237,223 +504,581 @@ impl Stackctl {
         Ok(frontend_build_dir)
     }
 
-    async fn transfer_to_file<R, P>(source: R, target: P) -> Result<()>
+    /// Forwards the stderr of a process to the stderr of stackctl and returns its last lines.
+    ///
+    /// Log records of the development server are rendered, other lines are forwarded as is.
+    fn forward_stderr(source: ChildStderr) -> StderrTail {
+        const MAX_LINES: usize = 50;
+
+        let tail = StderrTail::default();
+
+        {
+            let tail = tail.clone();
+            spawn(async move {
+                let mut lines = BufReader::new(source).lines();
+
+                while let Ok(Some(mut line)) = lines.next_line().await {
+                    if let Ok(m) = LogRecord::from_json(&line) {
+                        let rendered = server_log::render(&m);
+                        line = console::strip_ansi_codes(&rendered).to_string();
+                        eprintln!("{rendered}");
+                    } else {
+                        eprintln!("{line}");
+                    }
+
+                    if let Ok(mut m) = tail.lock() {
+                        if m.len() >= MAX_LINES {
+                            m.pop_front();
+                        }
+                        m.push_back(line);
+                    }
+                }
+            });
+        }
+
+        tail
+    }
+
+    fn output_lines<R>(source: R) -> impl Stream<Item = String>
     where
-        R: 'static + AsyncRead + Send,
-        P: Into<PathBuf>,
+        R: AsyncRead + Unpin,
     {
-        let target_path = target.into();
-        let mut target = fs::File::create(&target_path)
+        unfold(BufReader::new(source).lines(), |mut lines| async move {
+            lines.next_line().await.ok().flatten().map(|m| (m, lines))
+        })
+    }
+
+    /// Prints diagnostics in the output of a build as they are reported and writes the output
+    /// into log files.
+    ///
+    /// With `json`, stdout is read as `--message-format=json-diagnostic-rendered-ansi`.
+    async fn forward_build_output(
+        child: &mut Child,
+        log_dir: &Path,
+        json: bool,
+        progress: Option<&ServeProgress>,
+    ) -> Result<DiagnosticCounts> {
+        let log_id = random_str()?;
+        let mut stdout_log = fs::File::create(log_dir.join(format!("log-stdout-{log_id}")))
             .await
-            .with_context(|| format!("failed to create {}", target_path.display()))?;
+            .context("failed to create log file")?;
+        let mut stderr_log = fs::File::create(log_dir.join(format!("log-stderr-{log_id}")))
+            .await
+            .context("failed to create log file")?;
+
+        let stdout = futures::stream::iter(child.stdout.take())
+            .flat_map(Self::output_lines)
+            .map(|m| (true, m));
+        let stderr = futures::stream::iter(child.stderr.take())
+            .flat_map(Self::output_lines)
+            .map(|m| (false, m));
+        let output = futures::stream::select(stdout, stderr);
+        pin_mut!(output);
+
+        let mut stdout_diagnostics = match json {
+            true => DiagnosticStream::cargo_json(),
+            false => DiagnosticStream::rendered(),
+        };
+        let mut stderr_diagnostics = DiagnosticStream::rendered();
 
-        let inner = async move {
-            tokio::pin!(source);
+        while let Some((is_stdout, line)) = output.next().await {
+            let (log, diagnostics) = match is_stdout {
+                true => (&mut stdout_log, &mut stdout_diagnostics),
+                false => (&mut stderr_log, &mut stderr_diagnostics),
+            };
 
-            loop {
-                let mut buf = [0_u8; 8192];
-                let buf_len = source.read(&mut buf[..]).await?;
+            log.write_all(format!("{line}\n").as_bytes()).await?;
 
-                if buf_len == 0 {
-                    break;
+            if let Some(m) = diagnostics.push_line(&line) {
+                match progress {
+                    Some(bar) => {
+                        bar.println(&m);
+                        bar.set_diagnostics(
+                            stdout_diagnostics.counts() + stderr_diagnostics.counts(),
+                        );
+                    }
+                    None => eprintln!("{m}"),
                 }
-                target.write_all(&buf[..buf_len]).await?;
             }
+        }
 
-            Ok::<(), anyhow::Error>(())
-        };
+        Ok(stdout_diagnostics.counts() + stderr_diagnostics.counts())
+    }
 
-        spawn(async move {
-            if let Err(e) = inner
-                .await
-                .with_context(|| format!("failed to transfer logs to: {}", target_path.display()))
-            {
-                tracing::error!("{:#?}", e);
-            }
-        });
+    /// Returns `true` if artifacts are built into the build directory instead of a build of the
+    /// development server.
+    fn is_artifact_build(&self) -> bool {
+        matches!(
+            self.cli.command,
+            CliCommand::Build(_) | CliCommand::E2e(_) | CliCommand::Bench(_)
+        )
+    }
 
-        Ok(())
+    /// Returns `true` if commands and file operations are printed instead of performed.
+    fn is_dry_run(&self) -> bool {
+        match self.cli.command {
+            CliCommand::Build(ref m) => m.dry_run,
+            CliCommand::Package(ref m) => m.dry_run,
+            _ => false,
+        }
     }
 
-    async fn build_frontend(&self) -> Result<PathBuf> {
+    /// Creates the information of a new build from the current commit and time.
+    async fn build_info(&self) -> Result<BuildInfo> {
         use tokio::process::Command;
 
-        let frontend_data_dir = self.frontend_data_dir().await?;
-        let frontend_build_dir = self.frontend_build_dir().await?;
         let workspace_dir = self.workspace_dir().await?;
 
-        let create_proc = || {
-            let mut proc = Command::new("trunk");
-            proc.arg("build")
-                .arg("--dist")
-                .arg(&frontend_build_dir)
-                .arg(workspace_dir.join("index.html"))
-                .current_dir(&workspace_dir)
-                .stdin(Stdio::null())
-                .stdout(Stdio::piped())
-                .stderr(Stdio::piped());
+        // The workspace may not be a git repository.
+        let git_sha = Command::new("git")
+            .arg("rev-parse")
+            .arg("HEAD")
+            .current_dir(&workspace_dir)
+            .stdin(Stdio::null())
+            .stderr(Stdio::null())
+            .output()
+            .await
+            .ok()
+            .filter(|m| m.status.success())
+            .and_then(|m| String::from_utf8(m.stdout).ok())
+            .map(|m| m.trim().to_string())
+            .filter(|m| !m.is_empty());
 
-            if let Some(m) = self.profile.to_profile_argument() {
-                proc.arg(m);
-            }
+        let timestamp = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)?
+            .as_secs();
 
-            let envs = self.env_file.load(&workspace_dir);
-            proc.envs(envs);
+        Ok(BuildInfo::new(git_sha, timestamp))
+    }
 
-            if matches!(self.cli.command, CliCommand::Build { .. }) {
-                proc.stdout(Stdio::inherit()).stderr(Stdio::inherit());
-            }
-            proc
-        };
+    /// Returns the frontends to build, their names and paths of `index.html`.
+    ///
+    /// The name is `None` for the frontend built from `index.html` in the workspace directory.
+    async fn frontends(&self) -> Result<Vec<(Option<&str>, PathBuf)>> {
+        let workspace_dir = self.workspace_dir().await?;
 
-        let mut child = create_proc().spawn()?;
+        if self.manifest.frontend.is_empty() {
+            return Ok(vec![(None, workspace_dir.join("index.html"))]);
+        }
 
-        if let Some(m) = child.stdout.take() {
-            Self::transfer_to_file(
-                m,
-                frontend_data_dir.join(format!("log-stdout-{}", random_str()?)),
-            )
-            .await?;
+        Ok(self
+            .manifest
+            .frontend
+            .iter()
+            .map(|(name, m)| (Some(name.as_str()), workspace_dir.join(&m.index_html)))
+            .collect())
+    }
+
+    /// Builds all frontends, each into a subdirectory if `[frontend.<name>]` is configured.
+    ///
+    /// The build id is only stamped into builds created by `stackctl build` as changing it
+    /// recompiles every crate that depends on it.
+    async fn build_frontend(
+        &self,
+        build_info: Option<&BuildInfo>,
+        progress: Option<&ServeProgress>,
+    ) -> Result<PathBuf> {
+        let frontend_build_dir = self.frontend_build_dir().await?;
+
+        if self.manifest.frontend.is_empty() {
+            let index_html = self.workspace_dir().await?.join("index.html");
+            self.build_frontend_once(&index_html, &frontend_build_dir, None, build_info, progress)
+                .await?;
+            self.copy_public(&frontend_build_dir).await?;
+            self.build_locales(&frontend_build_dir).await?;
+
+            return Ok(frontend_build_dir);
         }
 
-        if let Some(m) = child.stderr.take() {
-            Self::transfer_to_file(
-                m,
-                frontend_data_dir.join(format!("log-stderr-{}", random_str()?)),
+        let mut mounts = Vec::new();
+        for (name, m) in self.manifest.frontend.iter() {
+            let index_html = self.workspace_dir().await?.join(&m.index_html);
+            let public_url = format!("{}/", m.mount_path.trim_end_matches('/'));
+
+            self.build_frontend_once(
+                &index_html,
+                &frontend_build_dir.join(name),
+                Some(&public_url),
+                build_info,
+                progress,
             )
             .await?;
+
+            mounts.push(FrontendMount {
+                name: name.to_owned(),
+                mount_path: m.mount_path.clone(),
+            });
         }
 
-        let status = child.wait().await?;
+        let mounts_path = frontend_build_dir.join(FrontendMount::FILE_NAME);
+        match self.is_dry_run() {
+            true => dry_run::print_action("write", &mounts_path),
+            false => fs::write(&mounts_path, FrontendMount::list_to_json(&mounts)?)
+                .await
+                .context("failed to write frontends")?,
+        }
+        self.copy_public(&frontend_build_dir).await?;
+        self.build_locales(&frontend_build_dir).await?;
 
-        // We try again with logs printed to console.
-        if !status.success() {
-            if matches!(self.cli.command, CliCommand::Build { .. }) {
-                bail!("trunk failed with status {}", status);
+        Ok(frontend_build_dir)
+    }
+
+    /// Runs the CSS toolchain in `[build.frontend.css]`, if any.
+    async fn build_css(&self) -> Result<()> {
+        use tokio::process::Command;
+
+        let css = match self.manifest.build.frontend.css {
+            Some(ref m) => m,
+            None => return Ok(()),
+        };
+        let workspace_dir = self.workspace_dir().await?;
+
+        let mut proc = match (css.command.as_deref(), css.input.as_ref()) {
+            (Some([program, args @ ..]), _) => {
+                let mut proc = Command::new(program);
+                proc.args(args);
+                proc
+            }
+            (Some([]), _) => bail!(Failure::new(ErrorKind::Config, "css command is empty")),
+            (None, Some(input)) => {
+                let mut proc = Command::new("tailwindcss");
+                proc.arg("-i").arg(input).arg("-o").arg(&css.output);
+                if self.profile.name() != "debug" {
+                    proc.arg("--minify");
+                }
+                proc
             }
+            (None, None) => bail!(Failure::new(
+                ErrorKind::Config,
+                "either command or input is required for css"
+            )),
+        };
+        let program = proc.as_std().get_program().to_string_lossy().into_owned();
 
-            let mut proc = create_proc();
-            proc.stdout(Stdio::inherit()).stderr(Stdio::inherit());
+        proc.current_dir(&workspace_dir)
+            .envs(self.env_file.load(&workspace_dir))
+            .stdin(Stdio::null());
 
-            let mut child = proc.spawn()?;
-            let status = child.wait().await?;
+        if self.is_dry_run() {
+            dry_run::print_command(&proc);
+            return Ok(());
+        }
 
+        if self.is_artifact_build() {
+            let status = proc.status().await.map_err(|e| spawn_error(&program, e))?;
             if !status.success() {
-                bail!("trunk failed with status {}", status);
+                bail!(Failure::new(
+                    ErrorKind::Build,
+                    format!("css command failed with status {status}")
+                ));
             }
+
+            return Ok(());
         }
 
-        Ok(frontend_build_dir)
+        let output = proc.output().await.map_err(|e| spawn_error(&program, e))?;
+        if !output.status.success() {
+            eprintln!("{}", String::from_utf8_lossy(&output.stderr));
+            bail!(Failure::new(
+                ErrorKind::Build,
+                format!("css command failed with status {}", output.status)
+            ));
+        }
+
+        Ok(())
     }
 
-    async fn build_backend<P>(&self, frontend_build_dir: P) -> Result<PathBuf>
-    where
-        P: AsRef<Path>,
-    {
-        use tokio::process::Command;
+    /// Returns the public directory and the build directory of the frontend mounted at `/`, if
+    /// the public directory exists.
+    async fn public_dirs(&self, frontend_build_dir: &Path) -> Result<Option<(PathBuf, PathBuf)>> {
+        let public_dir = self
+            .workspace_dir()
+            .await?
+            .join(&self.manifest.build.frontend.public_dir);
+        if !public_dir.is_dir() {
+            return Ok(None);
+        }
 
-        let frontend_build_dir = frontend_build_dir.as_ref();
+        if self.manifest.frontend.is_empty() {
+            return Ok(Some((public_dir, frontend_build_dir.to_owned())));
+        }
 
-        let backend_data_dir = self.backend_data_dir().await?;
-        let workspace_dir = self.workspace_dir().await?;
-        let backend_build_dir = self.backend_build_dir().await?;
+        match self
+            .manifest
+            .frontend
+            .iter()
+            .find(|(_, m)| m.mount_path.trim_end_matches('/').is_empty())
+        {
+            Some((name, _)) => Ok(Some((public_dir, frontend_build_dir.join(name)))),
+            None => {
+                eprintln!(
+                    "{} {} is not used, no frontend is mounted at /",
+                    style("warning:").yellow().bold(),
+                    public_dir.display()
+                );
+                Ok(None)
+            }
+        }
+    }
 
-        let create_proc = || {
-            let mut proc = Command::new("cargo");
-            proc.arg("build")
-                .arg("--bin")
-                .arg(&self.manifest.dev_server.bin_name)
-                .current_dir(&workspace_dir)
-                .stdin(Stdio::null())
-                .stdout(Stdio::piped())
-                .stderr(Stdio::piped())
-                .kill_on_drop(true);
+    /// Copies files of the public directory into the frontend mounted at `/`, if the directory
+    /// exists.
+    async fn copy_public(&self, frontend_build_dir: &Path) -> Result<()> {
+        let (public_dir, target) = match self.public_dirs(frontend_build_dir).await? {
+            Some(m) => m,
+            None => return Ok(()),
+        };
 
-            if let Some(m) = self.profile.to_profile_argument() {
-                proc.arg(m);
+        if self.is_dry_run() {
+            dry_run::print_action("copy", &public_dir);
+            return Ok(());
+        }
+
+        public::copy_dir(&public_dir, &target)
+    }
+
+    /// Converts images of the public directory, if `[build.images]` is enabled.
+    async fn optimize_images(&self, frontend_build_dir: &Path) -> Result<()> {
+        let config = &self.manifest.build.images;
+        if !config.enabled {
+            return Ok(());
+        }
+
+        let (public_dir, target) = match self.public_dirs(frontend_build_dir).await? {
+            Some(m) => m,
+            None => return Ok(()),
+        };
+
+        if self.is_dry_run() {
+            dry_run::print_action("write", &target.join(ImageManifest::FILE_NAME));
+            return Ok(());
+        }
+
+        let manifest = images::optimize(&public_dir, &target, config)?;
+        eprintln!(
+            "Converted {} images into {} variants.",
+            manifest.images.len(),
+            manifest.images.values().map(|m| m.len()).sum::<usize>()
+        );
+
+        Ok(())
+    }
+
+    /// Validates translation files and copies them into each frontend, if `[i18n]` is enabled.
+    async fn build_locales(&self, frontend_build_dir: &Path) -> Result<()> {
+        let i18n = &self.manifest.i18n;
+        if !i18n.enabled {
+            return Ok(());
+        }
+
+        let locales_dir = self.workspace_dir().await?.join(&i18n.dir);
+        let files = locales::discover(&locales_dir)?;
+
+        let problems = locales::validate(&files)?;
+        if !problems.is_empty() {
+            for m in problems.iter() {
+                eprintln!("{} {}", style("error:").red().bold(), m);
             }
+            bail!("translation files are invalid");
+        }
 
-            let envs = self.env_file.load(&workspace_dir);
-            proc.envs(envs);
+        let targets = match self.manifest.frontend.is_empty() {
+            true => vec![(frontend_build_dir.to_owned(), "/".to_string())],
+            false => self
+                .manifest
+                .frontend
+                .iter()
+                .map(|(name, m)| (frontend_build_dir.join(name), m.mount_path.clone()))
+                .collect(),
+        };
 
-            if matches!(self.cli.command, CliCommand::Build { .. }) {
-                proc.stdout(Stdio::inherit())
-                    .stderr(Stdio::inherit())
-                    .env("RUSTFLAGS", "--cfg stackable_embedded_frontend");
+        for (dir, public_url) in targets {
+            match self.is_dry_run() {
+                true => dry_run::print_action("write", &dir.join("locales")),
+                false => {
+                    locales::write_locales(&files, &dir, &public_url, i18n)?;
+                }
             }
+        }
 
-            proc.env("STACKABLE_FRONTEND_BUILD_DIR", frontend_build_dir);
+        Ok(())
+    }
+
+    async fn build_frontend_once(
+        &self,
+        index_html: &Path,
+        frontend_build_dir: &Path,
+        public_url: Option<&str>,
+        build_info: Option<&BuildInfo>,
+        progress: Option<&ServeProgress>,
+    ) -> Result<()> {
+        use tokio::process::Command;
 
-            proc
+        let frontend_data_dir = self.frontend_data_dir().await?;
+        let workspace_dir = self.workspace_dir().await?;
+
+        let profiles = match self.profile.is_custom() {
+            true => self.cargo_profiles().await?,
+            false => None,
+        };
+        let (profile_arg, profile_envs) = self.profile.to_trunk_arguments(profiles.as_ref());
+        let envs = self
+            .env_file
+            .load_public(&workspace_dir, &self.manifest.build.frontend.public_env);
+
+        // Placeholders that are known at build time are substituted in a copy of `index.html`,
+        // which is removed after the build.
+        let rendered = match self.is_dry_run() {
+            true => None,
+            false => placeholders::render(
+                index_html,
+                public_url.unwrap_or("/"),
+                build_info.map(|m| m.build_id.as_str()),
+                &envs,
+            )?,
         };
+        let index_html = rendered.as_ref().map(|m| m.path()).unwrap_or(index_html);
 
-        let mut child = create_proc().spawn()?;
+        let mut proc = Command::new("trunk");
+        proc.arg("build")
+            .arg("--dist")
+            .arg(frontend_build_dir)
+            .arg(index_html)
+            .current_dir(&workspace_dir)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
 
-        if let Some(m) = child.stdout.take() {
-            Self::transfer_to_file(
-                m,
-                backend_data_dir.join(format!("log-stdout-{}", random_str()?)),
-            )
-            .await?;
+        if let Some(m) = profile_arg {
+            proc.arg(m);
         }
 
-        if let Some(m) = child.stderr.take() {
-            Self::transfer_to_file(
-                m,
-                backend_data_dir.join(format!("log-stderr-{}", random_str()?)),
-            )
-            .await?;
+        proc.args(self.manifest.build.frontend.cargo.to_arguments())
+            .args(self.lock_arguments());
+
+        if let Some(m) = public_url {
+            proc.arg("--public-url").arg(m);
         }
 
-        let status = child.wait().await?;
+        proc.envs(envs).envs(&profile_envs);
 
-        // We try again with logs printed to console.
-        if !status.success() {
-            if matches!(self.cli.command, CliCommand::Build { .. }) {
-                bail!("trunk failed with status {}", status);
-            }
+        if let Some(m) = build_info {
+            proc.env(BuildInfo::ENV_NAME, &m.build_id);
+        }
 
-            let mut proc = create_proc();
+        if self.is_artifact_build() {
             proc.stdout(Stdio::inherit()).stderr(Stdio::inherit());
+        } else {
+            // Diagnostics are printed by stackctl, so they are colored for the terminal.
+            proc.env("CARGO_TERM_COLOR", "always");
+        }
 
-            let mut child = proc.spawn()?;
-            let status = child.wait().await?;
+        if self.is_dry_run() {
+            dry_run::print_command(&proc);
+            return Ok(());
+        }
 
-            if !status.success() {
-                bail!("trunk failed with status {}", status);
-            }
+        let mut child = proc.spawn().map_err(|e| spawn_error("trunk", e))?;
+        let counts =
+            Self::forward_build_output(&mut child, &frontend_data_dir, false, progress).await?;
+        let status = child.wait().await?;
+
+        if !status.success() {
+            bail!(Failure::new(
+                ErrorKind::Build,
+                format!("trunk failed with status {status} ({counts})")
+            ));
         }
 
-        // Copy artifact from target directory.
-        let pkg_meta_output = Command::new("cargo")
-            .arg("metadata")
-            .arg("--format-version=1")
+        Ok(())
+    }
+
+    async fn build_backend<P>(
+        &self,
+        frontend_build_dir: P,
+        build_info: Option<&BuildInfo>,
+        progress: Option<&ServeProgress>,
+    ) -> Result<PathBuf>
+    where
+        P: AsRef<Path>,
+    {
+        use tokio::process::Command;
+
+        let frontend_build_dir = frontend_build_dir.as_ref();
+
+        let backend_data_dir = self.backend_data_dir().await?;
+        let workspace_dir = self.workspace_dir().await?;
+        let backend_build_dir = self.backend_build_dir().await?;
+
+        let mut proc = Command::new("cargo");
+        proc.arg("build")
+            .arg("--bin")
+            .arg(&self.manifest.dev_server.bin_name)
+            .current_dir(&workspace_dir)
             .stdin(Stdio::null())
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
-            .current_dir(&workspace_dir)
-            .spawn()?
-            .wait_with_output()
-            .await
-            .context("failed to read package metadata")?;
+            .kill_on_drop(true);
 
-        if !pkg_meta_output.status.success() {
-            bail!(
-                "cargo metadata failed with status {}",
-                pkg_meta_output.status
-            );
+        if let Some(m) = self.profile.to_profile_argument() {
+            proc.arg(m);
+        }
+
+        proc.args(self.manifest.build.backend.cargo.to_arguments())
+            .args(self.lock_arguments());
+
+        let envs = self.env_file.load(&workspace_dir);
+        proc.envs(envs);
+
+        // The frontend is only embedded at compile time for release builds. The development
+        // server passes its build directory at runtime with `StackctlMetadata`, as a new
+        // directory for each build would otherwise invalidate the build cache of the backend.
+        let json = if self.is_artifact_build() {
+            proc.stdout(Stdio::inherit())
+                .stderr(Stdio::inherit())
+                .env("RUSTFLAGS", "--cfg stackable_embedded_frontend")
+                .env("STACKABLE_FRONTEND_BUILD_DIR", frontend_build_dir);
+
+            false
+        } else {
+            proc.arg("--message-format=json-diagnostic-rendered-ansi")
+                .env_remove("STACKABLE_FRONTEND_BUILD_DIR");
+
+            true
+        };
+
+        if let Some(m) = build_info {
+            proc.env(BuildInfo::ENV_NAME, &m.build_id);
+        }
+
+        let backend_bin_path = backend_build_dir.join(&self.manifest.dev_server.bin_name);
+
+        if self.is_dry_run() {
+            dry_run::print_command(&proc);
+            dry_run::print_action("copy", &backend_bin_path);
+            return Ok(backend_bin_path);
+        }
+
+        let mut child = proc.spawn().map_err(|e| spawn_error("cargo", e))?;
+        let counts =
+            Self::forward_build_output(&mut child, &backend_data_dir, json, progress).await?;
+        let status = child.wait().await?;
+
+        if !status.success() {
+            bail!(Failure::new(
+                ErrorKind::Build,
+                format!("cargo failed with status {status} ({counts})")
+            ));
         }
 
-        let meta: Metadata = serde_json::from_slice(&pkg_meta_output.stdout)
-            .context("failed to parse package metadata")?;
+        // Copy artifact from target directory.
+        let meta = self.cargo_metadata().await?;
 
         let bin_path = meta
             .target_directory
-            .join_os(self.profile.name())
+            .join_os(self.profile.target_dir_name())
             .join(&self.manifest.dev_server.bin_name);
 
-        let backend_bin_path = backend_build_dir.join(&self.manifest.dev_server.bin_name);
-
         fs::copy(bin_path, &backend_bin_path)
             .await
             .context("failed to copy binary")?;
@@ -461,80 +1086,757 @@ impl Stackctl {
         Ok(backend_bin_path)
     }
 
-    async fn open_browser(&self, http_listen_addr: &str) -> Result<()> {
-        if let Err(e) = webbrowser::open(http_listen_addr) {
-            tracing::warn!("stackctl was unable to open the browser");
-            tracing::debug!("due to: {:?}", e);
+    /// Returns `--offline` and `--locked` of cargo and trunk, if they are enabled on the command
+    /// line or in `[build]`.
+    fn lock_arguments(&self) -> Vec<&'static str> {
+        let build = &self.manifest.build;
+        let mut args = Vec::new();
+
+        if self.cli.offline || build.offline {
+            args.push("--offline");
+        }
+        if self.cli.locked || build.locked {
+            args.push("--locked");
         }
 
-        Ok(())
+        args
     }
 
-    async fn serve_once(&self) -> Result<Child> {
+    async fn cargo_metadata(&self) -> Result<Metadata> {
         use tokio::process::Command;
 
-        let http_listen_addr = format!("http://{}/", self.manifest.dev_server.listen);
+        let workspace_dir = self.workspace_dir().await?;
+
+        let pkg_meta_output = Command::new("cargo")
+            .arg("metadata")
+            .arg("--format-version=1")
+            .args(self.lock_arguments())
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .current_dir(&workspace_dir)
+            .spawn()
+            .map_err(|e| spawn_error("cargo", e))?
+            .wait_with_output()
+            .await
+            .context("failed to read package metadata")?;
+
+        if !pkg_meta_output.status.success() {
+            bail!(
+                "cargo metadata failed with status {}",
+                pkg_meta_output.status
+            );
+        }
+
+        serde_json::from_slice(&pkg_meta_output.stdout).context("failed to parse package metadata")
+    }
+
+    /// Reads the `[profile]` table of the workspace manifest.
+    async fn cargo_profiles(&self) -> Result<Option<toml::Value>> {
+        let meta = self.cargo_metadata().await?;
+
+        let manifest_str = fs::read_to_string(meta.workspace_root.join("Cargo.toml"))
+            .await
+            .context("failed to read workspace manifest")?;
+        let manifest: toml::Value =
+            toml::from_str(&manifest_str).context("failed to parse workspace manifest")?;
+
+        Ok(manifest.get("profile").cloned())
+    }
+
+    async fn open_browser(&self, http_listen_addr: &str) -> Result<()> {
+        use tokio::process::Command;
+
+        let open = &self.manifest.dev_server.open;
+        let url = format!(
+            "{}/{}",
+            http_listen_addr.trim_end_matches('/'),
+            open.path.trim_start_matches('/')
+        );
+
+        let result = match open.command.as_deref().map(|m| m.split_whitespace()) {
+            Some(mut args) => match args.next() {
+                Some(program) => Command::new(program)
+                    .args(args)
+                    .arg(&url)
+                    .stdin(Stdio::null())
+                    .stdout(Stdio::null())
+                    .stderr(Stdio::null())
+                    .spawn()
+                    .map(|_| ()),
+                None => webbrowser::open(&url),
+            },
+            None => webbrowser::open(&url),
+        };
+
+        if let Err(e) = result {
+            tracing::warn!("stackctl was unable to open the browser");
+            tracing::debug!("due to: {:?}", e);
+        }
+
+        Ok(())
+    }
+
+    /// Returns the address that the development server listens on.
+    fn listen_addr(&self) -> String {
+        let listen = &self.manifest.dev_server.listen;
+
+        let (host, port) = match self.cli.command {
+            CliCommand::Serve(ServeCommand { ref host, port, .. })
+                if host.is_some() || port.is_some() =>
+            {
+                (host, port)
+            }
+            _ => return listen.to_string(),
+        };
+
+        let (listen_host, listen_port) = split_host_port(listen);
+        let host = match host {
+            Some(m) => match m.parse::<IpAddr>() {
+                Ok(IpAddr::V6(_)) => format!("[{}]", m),
+                _ => m.to_string(),
+            },
+            None => listen_host.to_string(),
+        };
+
+        match port.map(|m| m.to_string()).as_deref().or(listen_port) {
+            Some(port) => format!("{}:{}", host, port),
+            None => host,
+        }
+    }
+
+    /// Returns whether bridge requests are mocked, with `stackctl serve --mock`.
+    fn mock(&self) -> bool {
+        matches!(
+            self.cli.command,
+            CliCommand::Serve(ServeCommand { mock: true, .. })
+        )
+    }
+
+    /// Returns the name of the recording of `stackctl serve --record`.
+    fn recording(&self) -> Option<&str> {
+        match self.cli.command {
+            CliCommand::Serve(ServeCommand { ref record, .. }) => record.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Returns the name of the recording of `stackctl serve --replay`.
+    fn replay(&self) -> Option<&str> {
+        match self.cli.command {
+            CliCommand::Serve(ServeCommand { ref replay, .. }) => replay.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Returns the host aliases of `stackctl serve --host-alias`.
+    fn host_aliases(&self) -> &[(String, String)] {
+        match self.cli.command {
+            CliCommand::Serve(ServeCommand {
+                ref host_aliases, ..
+            }) => host_aliases,
+            _ => &[],
+        }
+    }
+
+    /// Returns the urls of the host aliases of `stackctl serve --host-alias`, with the hostnames
+    /// of their virtual hosts.
+    fn host_alias_urls(&self) -> Vec<(String, &str)> {
+        let listen_addr = self.listen_addr();
+        let (_, port) = split_host_port(&listen_addr);
+
+        self.host_aliases()
+            .iter()
+            .map(|(alias, host)| {
+                let url = match port {
+                    Some(port) => format!("http://{}:{}/", alias, port),
+                    None => format!("http://{}/", alias),
+                };
+
+                (url, host.as_str())
+            })
+            .collect()
+    }
+
+    /// Returns an error that describes the process holding the port, if the address of the
+    /// development server is already in use.
+    async fn port_conflict(&self) -> Option<anyhow::Error> {
+        let listen_addr = self.listen_addr();
+        let e = match std::net::TcpListener::bind(&listen_addr) {
+            Err(e) if e.kind() == std::io::ErrorKind::AddrInUse => e,
+            _ => return None,
+        };
+
+        let owner = match split_host_port(&listen_addr).1.map(|m| m.parse::<u16>()) {
+            Some(Ok(port)) => diagnostics::port_owner(port).await,
+            _ => None,
+        };
+
+        let message = match owner {
+            Some(m) => format!(
+                "{listen_addr} is already in use by {m}, \
+                 use --port to listen on another port or stop it with `{}`",
+                m.kill_command()
+            ),
+            None => format!(
+                "{listen_addr} is already in use, \
+                 use --port to listen on another port or stop the process that holds it"
+            ),
+        };
+
+        Some(anyhow::Error::new(e).context(Failure::new(ErrorKind::PortConflict, message)))
+    }
+
+    /// Returns the url to open the development server on this machine.
+    fn local_url(&self) -> String {
+        let listen_addr = self.listen_addr();
+
+        match split_host_port(&listen_addr) {
+            (host, Some(port)) if is_unspecified_host(host) => {
+                format!("http://localhost:{}/", port)
+            }
+            (host, None) if is_unspecified_host(host) => "http://localhost/".to_string(),
+            _ => format!("http://{}/", listen_addr),
+        }
+    }
+
+    /// Returns the url of the admin listener, if `[server.admin]` is configured.
+    fn admin_url(&self) -> Option<String> {
+        let listen = &self.manifest.server.admin.as_ref()?.listen;
+
+        let url = match split_host_port(listen) {
+            (host, Some(port)) if is_unspecified_host(host) => {
+                format!("http://localhost:{}/", port)
+            }
+            _ => format!("http://{}/", listen),
+        };
+
+        Some(url)
+    }
+
+    /// Returns the url to open the development server on other machines on the local network.
+    ///
+    /// This is only available if the development server listens on all interfaces.
+    fn network_url(&self) -> Option<String> {
+        let listen_addr = self.listen_addr();
+        let (host, port) = split_host_port(&listen_addr);
+
+        if !is_unspecified_host(host) {
+            return None;
+        }
+
+        let ip = match network_ip()? {
+            IpAddr::V4(m) => m.to_string(),
+            IpAddr::V6(m) => format!("[{}]", m),
+        };
+
+        Some(match port {
+            Some(port) => format!("http://{}:{}/", ip, port),
+            None => format!("http://{}/", ip),
+        })
+    }
+
+    /// Announces the development server with mDNS if it is enabled in `[dev-server]`.
+    fn announce_mdns(&self) -> Option<MdnsAnnouncement> {
+        if !self.manifest.dev_server.mdns {
+            return None;
+        }
+
+        let listen_addr = self.listen_addr();
+        let (host, port) = split_host_port(&listen_addr);
+
+        if !is_unspecified_host(host) {
+            tracing::warn!(
+                "mDNS requires the development server to listen on all interfaces, try `--host \
+                 0.0.0.0`"
+            );
+            return None;
+        }
+
+        let port = port.and_then(|m| m.parse().ok()).unwrap_or(80);
+        let ip = match network_ip() {
+            Some(m) => m,
+            None => {
+                tracing::warn!("failed to detect the address on the local network for mDNS");
+                return None;
+            }
+        };
+
+        match MdnsAnnouncement::new(&self.manifest.dev_server.bin_name, ip, port) {
+            Ok(m) => Some(m),
+            Err(e) => {
+                tracing::warn!("failed to announce the development server: {:?}", e);
+                None
+            }
+        }
+    }
+
+    /// Returns the observability configuration with defaults for the development server.
+    fn dev_observability(&self) -> ObservabilityConfig {
+        let mut config = self.manifest.observability.clone();
+
+        if config.enabled {
+            config
+                .otlp_endpoint
+                .get_or_insert_with(|| ObservabilityConfig::DEFAULT_OTLP_ENDPOINT.to_string());
+            config
+                .service_name
+                .get_or_insert_with(|| self.manifest.dev_server.bin_name.clone());
+        }
+
+        config
+    }
+
+    /// Builds and starts the development server.
+    ///
+    /// If a previous build is provided, its backend is reused. The frontend is only rebuilt if
+    /// the CSS step runs, otherwise only translation files are copied into it.
+    async fn serve_once(
+        &self,
+        previous_build: Option<&ServeBuild>,
+        build_css: bool,
+        notifier: &Notifier,
+    ) -> Result<(ServerProcess, ServeBuild)> {
+        use tokio::process::Command;
+
+        let http_listen_addr = self.local_url();
+        let start_time = Instant::now();
 
         let bar = ServeProgress::new();
 
         let workspace_dir = self.workspace_dir().await?;
+        let build_info = self.build_info().await?;
+
+        if build_css {
+            bar.step_build_css();
+            self.build_css().await?;
+        }
+
         bar.step_build_frontend();
-        let frontend_build_dir = self.build_frontend().await?;
+        let (frontend_build_dir, backend_build_path) = match previous_build {
+            Some(m) if !build_css => {
+                self.copy_public(&m.frontend_build_dir).await?;
+                self.build_locales(&m.frontend_build_dir).await?;
+
+                (m.frontend_build_dir.clone(), m.backend_build_path.clone())
+            }
+            Some(m) => {
+                let frontend_build_dir = self.build_frontend(None, Some(&bar)).await?;
+
+                (frontend_build_dir, m.backend_build_path.clone())
+            }
+            None => {
+                let frontend_build_dir = self.build_frontend(None, Some(&bar)).await?;
+
+                bar.step_build_backend();
+                let backend_build_path = self
+                    .build_backend(&frontend_build_dir, None, Some(&bar))
+                    .await?;
+
+                (frontend_build_dir, backend_build_path)
+            }
+        };
+
+        notifier.notify(
+            NotificationEvent::BuildSucceeded,
+            format!("Built in {:.2}s.", start_time.elapsed().as_secs_f64()),
+        );
+
+        // Removed first, so routes of a previous server are never checked.
+        let routes_path = self.backend_data_dir().await?.join("routes.json");
+        let _ = fs::remove_file(&routes_path).await;
+        let jobs_path = self.backend_data_dir().await?.join("jobs.json");
+        let _ = fs::remove_file(&jobs_path).await;
+
+        // Mocks and recordings are reloaded each time the server starts.
+        let mocks = match self.replay() {
+            Some(m) => {
+                let path = mocks::recording_path(&workspace_dir, m)?;
+                Some(mocks::load_recording(&path)?)
+            }
+            None if self.mock() => Some(mocks::load(&workspace_dir.join(mocks::MOCKS_DIR))?),
+            None => None,
+        };
+        let mocks_path = match mocks {
+            Some(m) => {
+                let mocks_path = self.backend_data_dir().await?.join("mocks.json");
+                fs::write(&mocks_path, m.to_json()?)
+                    .await
+                    .context("failed to write mocks")?;
+
+                Some(mocks_path)
+            }
+            None => None,
+        };
+        let record_path = match self.recording() {
+            Some(m) => {
+                fs::create_dir_all(mocks::recordings_dir(&workspace_dir))
+                    .await
+                    .context("failed to create recordings directory")?;
 
-        bar.step_build_backend();
-        let backend_build_path = self.build_backend(&frontend_build_dir).await?;
+                Some(mocks::recording_path(&workspace_dir, m)?)
+            }
+            None => None,
+        };
 
         let meta = StackctlMetadata {
-            listen_addr: self.manifest.dev_server.listen.to_string(),
+            listen_addr: self.listen_addr(),
+            admin_listen_addr: self
+                .manifest
+                .server
+                .admin
+                .as_ref()
+                .map(|m| m.listen.clone()),
             frontend_dev_build_dir: frontend_build_dir.clone(),
+            refresh_path: self.manifest.dev_server.refresh_path.clone(),
+            server_config: self.manifest.server.clone(),
+            observability: self.dev_observability(),
+            strict_mode: self.manifest.dev_server.strict_mode,
+            routes_path: Some(routes_path.clone()),
+            jobs_path: Some(jobs_path.clone()),
+            structured_logs: true,
+            log_filter: std::env::var("STACKCTL_LOG").ok(),
+            mocks_path,
+            record_path,
+            status_path: Some(self.backend_data_dir().await?.join("status.json")),
+            host_aliases: self.host_aliases().to_vec(),
         };
 
         bar.step_starting();
 
         let envs = self.env_file.load(&workspace_dir);
 
-        let server_proc = Command::new(&backend_build_path)
+        let mut server_proc = Command::new(&backend_build_path)
             .current_dir(&workspace_dir)
             .envs(envs)
             .env(StackctlMetadata::ENV_NAME, meta.to_json()?)
+            .env(BuildInfo::ENV_NAME, &build_info.build_id)
             .stdin(Stdio::null())
             .stdout(Stdio::inherit())
-            .stderr(Stdio::inherit())
+            .stderr(Stdio::piped())
             .kill_on_drop(true)
             .spawn()?;
 
-        while reqwest::ClientBuilder::default()
-            .timeout(Duration::from_secs(1))
-            .build()?
-            .get(&http_listen_addr)
-            .send()
-            .await
-            .and_then(|m| m.error_for_status())
-            .is_err()
-        {
-            sleep(Duration::from_secs(1)).await;
-        }
+        let stderr_tail = server_proc
+            .stderr
+            .take()
+            .map(Self::forward_stderr)
+            .unwrap_or_default();
+
+        // The admin listener is checked instead if it is configured.
+        let (health_url, health_setting) =
+            match (self.admin_url(), self.manifest.server.admin.as_ref()) {
+                (Some(url), Some(admin)) => (
+                    format!("{}{}", url, admin.health_path.trim_start_matches('/')),
+                    "server.admin.health-path",
+                ),
+                _ => (
+                    format!(
+                        "{}/{}",
+                        http_listen_addr.trim_end_matches('/'),
+                        self.manifest.dev_server.health_path.trim_start_matches('/')
+                    ),
+                    "dev-server.health-path",
+                ),
+            };
+        let startup_timeout = self.manifest.dev_server.startup_timeout;
+
+        let failure = {
+            let ready_fur = async {
+                while reqwest::ClientBuilder::default()
+                    .timeout(Duration::from_secs(1))
+                    .build()?
+                    .get(&health_url)
+                    .send()
+                    .await
+                    .and_then(|m| m.error_for_status())
+                    .is_err()
+                {
+                    sleep(Duration::from_secs(1)).await;
+                }
+
+                Ok::<_, anyhow::Error>(())
+            }
+            .fuse();
+            let exit_fur = server_proc.wait().fuse();
+            let timeout_fur = sleep(startup_timeout).fuse();
+            pin_mut!(ready_fur, exit_fur, timeout_fur);
+
+            // The server may exit before it is ready, e.g.: if the port is in use.
+            futures::select! {
+                m = ready_fur => {
+                    m?;
+                    None
+                },
+                m = exit_fur => Some(format!("server exited during startup with status {}", m?)),
+                _ = timeout_fur => Some(format!(
+                    "server is not ready after {:.1}s, {} did not respond successfully \
+                     (see dev-server.startup-timeout and {})",
+                    startup_timeout.as_secs_f64(),
+                    health_url,
+                    health_setting
+                )),
+            }
+        };
 
         bar.hide();
 
-        Ok(server_proc)
+        if let Some(reason) = failure {
+            // The server may have exited already.
+            let _ = server_proc.kill().await;
+
+            let lines = stderr_tail.lock().map(|m| m.clone()).unwrap_or_default();
+            if !lines.is_empty() {
+                eprintln!("{}", style("Server output:").yellow().bold());
+                for line in lines {
+                    eprintln!("{line}");
+                }
+            }
+
+            // Another process may have taken the port since stackctl has started.
+            if let Some(e) = self.port_conflict().await {
+                return Err(e);
+            }
+
+            bail!("{}", reason);
+        }
+
+        if let Err(e) = self.check_route_collisions(&routes_path).await {
+            tracing::warn!("failed to check route collisions: {:#}", e);
+        }
+
+        let build = ServeBuild {
+            frontend_build_dir,
+            backend_build_path,
+        };
+        // Servers without tasks do not write jobs.
+        let jobs = match fs::read_to_string(&jobs_path).await {
+            Ok(m) => serde_json::from_str(&m).unwrap_or_else(|e| {
+                tracing::warn!("failed to parse jobs: {}", e);
+                Vec::new()
+            }),
+            Err(_) => Vec::new(),
+        };
+
+        let server_proc = ServerProcess {
+            child: server_proc,
+            stderr_tail,
+            ready_at: Instant::now(),
+            jobs,
+        };
+
+        Ok((server_proc, build))
+    }
+
+    /// Warns about API routes of the backend that collide with page routes of frontends.
+    async fn check_route_collisions(&self, routes_path: &Path) -> Result<()> {
+        // Backends of older versions do not write their routes.
+        let api_routes: Vec<String> = match fs::read_to_string(routes_path).await {
+            Ok(m) => serde_json::from_str(&m).context("failed to parse routes")?,
+            Err(_) => return Ok(()),
+        };
+        if api_routes.is_empty() {
+            return Ok(());
+        }
+
+        let workspace_dir = self.workspace_dir().await?;
+        let metadata = self.cargo_metadata().await?;
+
+        for (name, index_html_path) in self.frontends().await? {
+            let index_html = fs::read_to_string(&index_html_path)
+                .await
+                .with_context(|| format!("failed to read {}", index_html_path.display()))?;
+            let frontend_bin_name = match frontend_bin_name(&index_html) {
+                Some(m) => m,
+                None => continue,
+            };
+
+            let src_dir = metadata
+                .packages
+                .iter()
+                .find(|m| {
+                    m.targets
+                        .iter()
+                        .any(|t| t.name == frontend_bin_name && t.kind.iter().any(|k| k == "bin"))
+                })
+                .and_then(|m| m.manifest_path.parent())
+                .map(|m| m.join("src"));
+            let src_dir = match src_dir {
+                Some(m) => m.into_std_path_buf(),
+                None => continue,
+            };
+
+            let mount_path = name
+                .and_then(|m| self.manifest.frontend.get(m))
+                .map(|m| m.mount_path.as_str())
+                .unwrap_or("/");
+            let page_routes = routes::page_routes(&src_dir, mount_path)?;
+
+            for (api_route, page_route) in routes::collisions(&api_routes, &page_routes, mount_path)
+            {
+                let file = relative_path(&page_route.file, &workspace_dir)
+                    .unwrap_or_else(|_| page_route.file.display().to_string());
+                eprintln!(
+                    "{} API route {} collides with page route {} in {}, \
+                     the backend responds instead of the page",
+                    style("warning:").yellow().bold(),
+                    api_route,
+                    page_route.path,
+                    file
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Schedules a restart of a server that has exited, or shows an error panel if it keeps
+    /// crashing.
+    fn schedule_restart(
+        &self,
+        policy: &mut RestartPolicy,
+        uptime: Duration,
+        reason: &str,
+        stderr_tail: Option<&StderrTail>,
+    ) -> Option<Duration> {
+        if let Some(delay) = policy.record_exit(uptime) {
+            eprintln!(
+                "{} {}, restarting in {:.1}s ({}/{})",
+                style("warning:").yellow().bold(),
+                reason,
+                delay.as_secs_f64(),
+                policy.crashes(),
+                self.manifest.dev_server.max_restarts
+            );
+
+            return Some(delay);
+        }
+
+        let lines = stderr_tail
+            .and_then(|m| m.lock().ok().map(|m| m.clone()))
+            .unwrap_or_default();
+
+        eprintln!();
+        eprintln!(
+            "{}",
+            style("The development server keeps crashing and is not restarted.")
+                .red()
+                .bold()
+        );
+        eprintln!();
+        eprintln!("    Last exit: {reason}");
+        eprintln!("    Crashes:   {} in a row", policy.crashes());
+        if !lines.is_empty() {
+            eprintln!();
+            eprintln!("{}", style("Last server output:").yellow().bold());
+            for line in lines {
+                eprintln!("    {line}");
+            }
+        }
+        eprintln!();
+        eprintln!("The server is started again when a file changes.");
+
+        None
     }
 
     async fn run_serve(&self, cmd_args: &ServeCommand) -> Result<()> {
+        // Fails before building, the server would otherwise only exit after a long build.
+        if let Some(e) = self.port_conflict().await {
+            return Err(e);
+        }
+        self.check_versions().await?;
+
+        for (alias, host) in cmd_args.host_aliases.iter() {
+            if !self.manifest.server.vhosts.contains_key(host) {
+                bail!(Failure::new(
+                    ErrorKind::Config,
+                    format!("host alias {alias} is not a virtual host in [server.vhosts]: {host}")
+                ));
+            }
+        }
+
         let changes = self.watch_changes().await?;
         pin_mut!(changes);
 
         let mut first_run = true;
+        let mdns = self.announce_mdns();
+        let notifier = Notifier::new(self.manifest.clone());
+        let mut last_build: Option<ServeBuild> = None;
+        let mut last_change: Option<WorkspaceChange> = None;
+        let mut restart_policy = RestartPolicy::new(self.manifest.dev_server.max_restarts);
+        // Restarts reuse the last build.
+        let mut restarting = false;
+        // The id of the build in the history that the server is switched to.
+        let mut rolled_back: Option<u64> = None;
+        // Recent builds, shown by the dashboard of the server.
+        let mut rebuilds: VecDeque<Rebuild> = VecDeque::new();
+        // Sources that have changed since the last successful build, for `--test`.
+        let mut test_paths: Vec<PathBuf> = Vec::new();
+        let mut test_run: Option<JoinHandle<()>> = None;
+        // A request of a previous session is not applied.
+        let _ = fs::remove_file(self.data_dir().await?.join(history::ROLLBACK_FILE_NAME)).await;
 
         'outer: loop {
+            // The tests of a previous build are outdated.
+            if let Some(m) = test_run.take() {
+                m.abort();
+            }
+
             let start_time = SystemTime::now();
-            let http_listen_addr = format!("http://{}/", self.manifest.dev_server.listen);
+            let http_listen_addr = self.local_url();
+            let network_url = self.network_url();
+
+            let dependencies_changed = !restarting
+                && last_change
+                    .as_ref()
+                    .map(|m| m.dependencies)
+                    .unwrap_or(false);
+            // The previous build may be built with different dependencies, so it is not reused.
+            if dependencies_changed {
+                last_build = None;
+            }
+
+            let previous_build = last_build.as_ref().filter(|_| {
+                restarting || last_change.as_ref().map(|m| !m.sources).unwrap_or(false)
+            });
+            let build_css = !restarting
+                && (last_build.is_none() || last_change.as_ref().map(|m| m.css).unwrap_or(true));
+            let mut restart_at = None;
+            let rollback = rolled_back.take();
+
+            let result = self.serve_once(previous_build, build_css, &notifier).await;
+            // Restarts and rollbacks are not builds.
+            if !restarting {
+                rebuilds.push_front(Rebuild {
+                    started_at: start_time,
+                    duration: start_time.elapsed().unwrap_or_default(),
+                    error: result.as_ref().err().map(|e| format!("{e:#}")),
+                });
+                rebuilds.truncate(10);
+
+                if let Err(e) = self.write_dev_status(&rebuilds).await {
+                    tracing::warn!("failed to write status of development server: {:#}", e);
+                }
+            }
+
+            let mut server_proc = match result {
+                Ok((server_proc, build)) => {
+                    // Restarts after crashes are not new builds.
+                    if !restarting || rollback.is_some() {
+                        if let Err(e) = self.update_history(&build, rollback).await {
+                            tracing::warn!("failed to update build history: {:#}", e);
+                        }
+                    }
+                    last_build = Some(build);
 
-            let server_proc = match self.serve_once().await {
-                Ok(server_proc) => {
                     let time_taken_in_f64 =
                         f64::try_from(i32::try_from(start_time.elapsed()?.as_millis())?)? / 1000.0;
 
+                    notifier.notify(
+                        NotificationEvent::ServerReady,
+                        format!("Listening on {http_listen_addr}."),
+                    );
+
                     Term::stderr().clear_screen()?;
 
                     eprintln!(
@@ -546,8 +1848,41 @@ impl Stackctl {
                     eprintln!("Stackable development server has started!");
                     eprintln!();
                     eprintln!();
-                    eprintln!("    Listening at: {}", http_listen_addr);
+                    eprintln!("    Local:   {}", http_listen_addr);
+                    if let Some(ref m) = network_url {
+                        eprintln!("    Network: {}", m);
+                    }
+                    if let Some(ref m) = mdns {
+                        eprintln!("    mDNS:    {}", m.url());
+                    }
+                    if let Some(m) = self.admin_url() {
+                        eprintln!("    Admin:   {}", m);
+                    }
+                    for (i, (url, host)) in self.host_alias_urls().iter().enumerate() {
+                        let label = if i == 0 { "Hosts:" } else { "" };
+                        eprintln!("    {:<9}{} ({})", label, url, host);
+                    }
+                    for (i, job) in server_proc.jobs.iter().enumerate() {
+                        let label = if i == 0 { "Jobs:" } else { "" };
+                        eprintln!("    {:<9}{} (every {:?})", label, job.name, job.interval);
+                    }
                     eprintln!();
+
+                    if let Some(m) = network_url.as_deref().filter(|_| cmd_args.qr) {
+                        match QrCode::new(m) {
+                            Ok(code) => {
+                                let code = code
+                                    .render::<Dense1x2>()
+                                    .dark_color(Dense1x2::Light)
+                                    .light_color(Dense1x2::Dark)
+                                    .build();
+
+                                eprintln!("{}", code);
+                                eprintln!();
+                            }
+                            Err(e) => tracing::warn!("failed to generate QR code: {}", e),
+                        }
+                    }
                     eprintln!();
                     eprintln!(
                         "{} This build is not optimised and should not be used in production.",
@@ -558,13 +1893,40 @@ impl Stackctl {
                         style("cargo make build").cyan().bold()
                     );
 
+                    if dependencies_changed
+                        && last_change.as_ref().map(|m| m.manifest).unwrap_or(false)
+                    {
+                        eprintln!();
+                        eprintln!(
+                            "{} stackable.toml has changed, restart `{}` to apply all settings.",
+                            style("warning:").yellow().bold(),
+                            style("stackctl serve").cyan().bold()
+                        );
+                    }
+
+                    if cmd_args.test && !restarting && !test_paths.is_empty() {
+                        let paths = std::mem::take(&mut test_paths);
+                        match self.spawn_tests(&paths, &notifier).await {
+                            Ok(m) => test_run = m,
+                            Err(e) => tracing::error!("failed to run tests: {:#}", e),
+                        }
+                    }
+
                     Some(server_proc)
                 }
+                Err(e) if restarting => {
+                    restart_at = self
+                        .schedule_restart(&mut restart_policy, Duration::ZERO, &e.to_string(), None)
+                        .map(|m| tokio::time::Instant::now() + m);
+                    None
+                }
                 Err(e) => {
                     tracing::error!("failed to build development server: {:?}", e);
+                    notifier.notify(NotificationEvent::BuildFailed, e.to_string());
                     None
                 }
             };
+            restarting = false;
 
             if cmd_args.open && first_run {
                 self.open_browser(&http_listen_addr).await?;
@@ -573,49 +1935,1701 @@ impl Stackctl {
             first_run = false;
 
             'inner: loop {
-                match changes.next().await {
-                    Some(change_time) => {
-                        if change_time > start_time {
+                let event = {
+                    let change_fur = changes.next().fuse();
+                    let exit_fur = async {
+                        match server_proc.as_mut() {
+                            Some(m) => match m.child.wait().await {
+                                Ok(status) => format!("server exited with status {status}"),
+                                Err(e) => format!("failed to wait for server: {e}"),
+                            },
+                            None => futures::future::pending().await,
+                        }
+                    }
+                    .fuse();
+                    let restart_fur = async {
+                        match restart_at {
+                            Some(m) => tokio::time::sleep_until(m).await,
+                            None => futures::future::pending().await,
+                        }
+                    }
+                    .fuse();
+                    pin_mut!(change_fur, exit_fur, restart_fur);
+
+                    futures::select! {
+                        m = change_fur => ServeEvent::Change(m),
+                        m = exit_fur => ServeEvent::Exited(m),
+                        _ = restart_fur => ServeEvent::Restart,
+                    }
+                };
+
+                match event {
+                    ServeEvent::Change(Some(change)) if change.rollback => {
+                        // Changes of sources take precedence over a rollback.
+                        if change.sources || change.css {
+                            test_paths.extend(change.paths.iter().cloned());
+                            last_change = Some(change);
+                            restart_policy.reset();
+                            break 'inner;
+                        }
+
+                        match self.read_rollback().await {
+                            Ok(Some((id, build))) => {
+                                last_build = Some(build);
+                                rolled_back = Some(id);
+                                restarting = true;
+                                restart_policy.reset();
+                                break 'inner;
+                            }
+                            Ok(None) => {}
+                            Err(e) => tracing::error!("failed to roll back: {:#}", e),
+                        }
+                    }
+                    ServeEvent::Change(Some(change)) => {
+                        if change.time > start_time {
+                            test_paths.extend(change.paths.iter().cloned());
+                            last_change = Some(change);
+                            // A rebuild starts over, even if the server has been crashing.
+                            restart_policy.reset();
                             break 'inner;
                         }
                     }
-                    None => break 'outer,
+                    ServeEvent::Change(None) => break 'outer,
+                    ServeEvent::Exited(reason) => {
+                        if let Some(m) = server_proc.take() {
+                            restart_at = self
+                                .schedule_restart(
+                                    &mut restart_policy,
+                                    m.ready_at.elapsed(),
+                                    &reason,
+                                    Some(&m.stderr_tail),
+                                )
+                                .map(|m| tokio::time::Instant::now() + m);
+                        }
+                    }
+                    ServeEvent::Restart => {
+                        restarting = true;
+                        break 'inner;
+                    }
                 }
             }
 
             if let Some(mut m) = server_proc {
-                m.kill().await.context("failed to stop server")?;
+                m.child.kill().await.context("failed to stop server")?;
             }
         }
 
         Ok(())
     }
 
-    async fn run_build(&self, _cmd_args: &BuildCommand) -> Result<()> {
-        let target_name = self.profile.name();
+    /// Writes the status of the development server for the dashboard at `/__stackable/`.
+    async fn write_dev_status(&self, rebuilds: &VecDeque<Rebuild>) -> Result<()> {
+        let workspace_dir = self.workspace_dir().await?;
+        let mut env_names = self
+            .env_file
+            .load(&workspace_dir)
+            .into_keys()
+            .collect::<Vec<_>>();
+        env_names.sort();
+
+        let status = DevStatus {
+            env_profile: self.env_file.name().to_string(),
+            env_names,
+            rebuilds: rebuilds.iter().cloned().collect(),
+        };
 
-        eprintln!(
-            "{}",
-            style(format!("Building with {target_name} profile..."))
-                .cyan()
-                .bold()
-        );
+        fs::write(
+            self.backend_data_dir().await?.join("status.json"),
+            status.to_json()?,
+        )
+        .await
+        .context("failed to write status")
+    }
 
-        let start_time = SystemTime::now();
+    /// Runs the unit tests of the packages that contain the paths in the background, the
+    /// development server is not blocked by the tests.
+    async fn spawn_tests(
+        &self,
+        paths: &[PathBuf],
+        notifier: &Notifier,
+    ) -> Result<Option<JoinHandle<()>>> {
+        use tokio::process::Command;
 
-        let build_dir = self.build_dir().await?;
-        let frontend_build_dir = self.build_frontend().await?;
-        self.build_backend(&frontend_build_dir).await?;
+        let metadata = self.cargo_metadata().await?;
+        let packages = watch_tests::affected_packages(&metadata, paths);
+        if packages.is_empty() {
+            return Ok(None);
+        }
+
+        let workspace_dir = self.workspace_dir().await?;
+        let envs = self.env_file.load(&workspace_dir);
+
+        let mut runs = Vec::new();
+        for package in packages {
+            let feature_args = self
+                .package_feature_arguments(&metadata, &package.name)
+                .await;
+
+            let mut cmd = Command::new("cargo");
+            cmd.arg("test")
+                .arg("-p")
+                .arg(&package.name)
+                .args(self.lock_arguments())
+                .args(feature_args)
+                .current_dir(&workspace_dir)
+                .envs(envs.clone())
+                .stdin(Stdio::null());
+
+            if let Some(m) = self.profile.to_profile_argument() {
+                cmd.arg(m);
+            }
+
+            if package.wasm {
+                cmd.arg("--target").arg(watch_tests::WASM_TARGET).env(
+                    "CARGO_TARGET_WASM32_UNKNOWN_UNKNOWN_RUNNER",
+                    "wasm-bindgen-test-runner",
+                );
+            }
+
+            runs.push((package, cmd));
+        }
+
+        let names = runs
+            .iter()
+            .map(|(m, _)| m.name.as_str())
+            .collect::<Vec<_>>();
+        eprintln!(
+            "    {:<9}{}",
+            "Tests:",
+            style(format!("running ({})", names.join(", "))).dim()
+        );
+
+        let notifier = notifier.clone();
+
+        Ok(Some(spawn(async move {
+            let mut outcomes = Vec::new();
+            for (package, cmd) in runs {
+                match watch_tests::run(cmd, &package).await {
+                    Ok(m) => outcomes.push(m),
+                    Err(e) => tracing::error!("{:#}", e),
+                }
+            }
+
+            if let Some(m) = watch_tests::report(&outcomes) {
+                notifier.notify(NotificationEvent::TestsFailed, m);
+            }
+        })))
+    }
+
+    /// Adds a successful build to the build history, or marks the build that the server is
+    /// switched to as current.
+    async fn update_history(&self, build: &ServeBuild, rollback: Option<u64>) -> Result<()> {
+        let size = self.manifest.dev_server.history_size;
+        if size == 0 {
+            return Ok(());
+        }
+
+        let data_dir = self.data_dir().await?;
+        let build = build.clone();
+
+        // The build is copied after the server is ready, without blocking the runtime.
+        task::spawn_blocking(move || {
+            let mut history = History::load(&data_dir)?;
+
+            match rollback {
+                Some(m) => history.current = Some(m),
+                None => history.push(
+                    &data_dir,
+                    &build.frontend_build_dir,
+                    &build.backend_build_path,
+                    size,
+                )?,
+            }
+
+            history.save(&data_dir)
+        })
+        .await
+        .context("failed to update build history")?
+    }
+
+    /// Takes the build requested by `stackctl rollback`, if any.
+    async fn read_rollback(&self) -> Result<Option<(u64, ServeBuild)>> {
+        let data_dir = self.data_dir().await?;
+        let rollback_path = data_dir.join(history::ROLLBACK_FILE_NAME);
+
+        // Removing the request is also a change of the file.
+        let id = match fs::read_to_string(&rollback_path).await {
+            Ok(m) => m,
+            Err(_) => return Ok(None),
+        };
+        let _ = fs::remove_file(&rollback_path).await;
+
+        let id = id
+            .trim()
+            .parse::<u64>()
+            .context("failed to parse the id of the build")?;
+        let history = History::load(&data_dir)?;
+        let entry = history
+            .get(id)
+            .with_context(|| format!("build {id} is not in the build history"))?;
+
+        Ok(Some((
+            id,
+            ServeBuild {
+                frontend_build_dir: entry.frontend_build_dir.clone(),
+                backend_build_path: entry.backend_build_path.clone(),
+            },
+        )))
+    }
+
+    async fn run_rollback(&self, cmd_args: &RollbackCommand) -> Result<()> {
+        let data_dir = self.data_dir().await?;
+        let history = History::load(&data_dir)?;
+
+        if cmd_args.list {
+            if history.builds.is_empty() {
+                eprintln!(
+                    "No builds in the history, builds are added by `stackctl serve` if \
+                     `dev-server.history-size` is set."
+                );
+            }
+
+            for m in history.builds.iter().rev() {
+                let current = match history.current == Some(m.id) {
+                    true => style("(current)").green().to_string(),
+                    false => String::new(),
+                };
+
+                println!(
+                    "{:>4}  {:<10} {}",
+                    m.id,
+                    history::format_age(m.built_at),
+                    current
+                );
+            }
+
+            return Ok(());
+        }
+
+        let entry = match cmd_args.id {
+            Some(m) => history.get(m).with_context(|| {
+                format!(
+                    "build {m} is not in the history, list builds with `stackctl rollback --list`"
+                )
+            })?,
+            None => history
+                .previous()
+                .context("no previous build in the history")?,
+        };
+
+        fs::write(
+            data_dir.join(history::ROLLBACK_FILE_NAME),
+            entry.id.to_string(),
+        )
+        .await
+        .context("failed to request rollback")?;
+
+        eprintln!(
+            "{}",
+            style(format!(
+                "The development server switches to build {} ({}).",
+                entry.id,
+                history::format_age(entry.built_at)
+            ))
+            .green()
+            .bold()
+        );
+
+        Ok(())
+    }
+
+    /// Writes checksums of artifacts in the build directory and signs them if a key is configured.
+    async fn sign_artifacts(&self, build_dir: &Path) -> Result<()> {
+        let checksums = signing::checksums(build_dir)?;
+
+        fs::write(build_dir.join(signing::CHECKSUMS_FILE_NAME), &checksums)
+            .await
+            .context("failed to write checksums")?;
+
+        let key_path = match self.manifest.release.signing_key {
+            Some(ref m) => self.workspace_dir().await?.join(m),
+            None => {
+                eprintln!(
+                    "{} release.signing-key is not set, checksums are not signed.",
+                    style("Note:").yellow().bold()
+                );
+                return Ok(());
+            }
+        };
+
+        let (signature, public_key) = signing::sign(&key_path, checksums.as_bytes())?;
+
+        fs::write(build_dir.join(signing::SIGNATURE_FILE_NAME), signature)
+            .await
+            .context("failed to write signature")?;
+
+        eprintln!("Checksums are signed with public key: {}", public_key);
+
+        Ok(())
+    }
+
+    /// Writes the preload manifest of each frontend, routes in `[build.preload.routes]` belong to
+    /// the frontend that serves them.
+    fn write_preload_manifests(&self, frontend_build_dir: &Path) -> Result<()> {
+        let preload = &self.manifest.build.preload;
+        if !preload.enabled {
+            return Ok(());
+        }
+
+        let mut mounts = self
+            .manifest
+            .frontend
+            .iter()
+            .map(|(name, m)| FrontendMount {
+                name: name.clone(),
+                mount_path: m.mount_path.clone(),
+            })
+            .collect::<Vec<_>>();
+        mounts.sort_by_key(|m| std::cmp::Reverse(m.mount_path.trim_end_matches('/').len()));
+
+        if mounts.is_empty() {
+            let routes = preload
+                .routes
+                .iter()
+                .map(|(route, assets)| (route.as_str(), assets.as_slice()))
+                .collect();
+
+            preload::write_manifest(frontend_build_dir, "/", &routes)?;
+            return Ok(());
+        }
+
+        for mount in mounts.iter() {
+            let routes = preload
+                .routes
+                .iter()
+                .filter(|(route, _)| {
+                    mounts.iter().find(|m| m.contains(route)).map(|m| &m.name) == Some(&mount.name)
+                })
+                .map(|(route, assets)| (route.as_str(), assets.as_slice()))
+                .collect();
+
+            preload::write_manifest(
+                &frontend_build_dir.join(&mount.name),
+                &mount.mount_path,
+                &routes,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Warns about frontends whose wasm module cannot be compiled while it is downloaded.
+    fn check_streaming_compilation(&self, frontend_build_dir: &Path) -> Result<()> {
+        let targets = match self.manifest.frontend.is_empty() {
+            true => vec![(frontend_build_dir.to_owned(), "/")],
+            false => self
+                .manifest
+                .frontend
+                .iter()
+                .map(|(name, m)| (frontend_build_dir.join(name), m.mount_path.as_str()))
+                .collect(),
+        };
+
+        for (dir, public_url) in targets {
+            for m in preload::streaming_problems(&dir, public_url)? {
+                eprintln!("{} {}", style("warning:").yellow().bold(), m);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Writes `sitemap.xml` and `robots.txt` into the frontend mounted at `/`, if `[seo]` is
+    /// enabled.
+    ///
+    /// This runs before the backend is built, so they are embedded with the frontend.
+    async fn write_seo_files(
+        &self,
+        frontend_build_dir: &Path,
+        build_info: &BuildInfo,
+    ) -> Result<()> {
+        let config = &self.manifest.seo;
+        if !config.enabled {
+            return Ok(());
+        }
+
+        let base_url = match config.base_url {
+            Some(ref m) => m,
+            None => bail!(Failure::new(
+                ErrorKind::Config,
+                "base-url is required if seo is enabled"
+            )),
+        };
+
+        let frontend_data_dir = self.frontend_data_dir().await?;
+        let mut routes = Vec::new();
+        let mut not_found = Vec::new();
+        let mut target = None;
+
+        for (name, index_html_path) in self.frontends().await? {
+            let index_html = fs::read_to_string(&index_html_path)
+                .await
+                .with_context(|| format!("failed to read {}", index_html_path.display()))?;
+            let frontend_bin_name = frontend_bin_name(&index_html).with_context(|| {
+                format!(
+                    "failed to find data-bin attribute in {}",
+                    index_html_path.display()
+                )
+            })?;
+            let mount_path = name
+                .and_then(|m| self.manifest.frontend.get(m))
+                .map(|m| m.mount_path.trim_end_matches('/'))
+                .unwrap_or_default();
+
+            if mount_path.is_empty() {
+                target = Some(frontend_build_dir.join(name.unwrap_or_default()));
+            }
+
+            let routes_path = frontend_data_dir.join(format!("routes-{}.json", random_str()?));
+            self.run_frontend_on_host(
+                &index_html,
+                frontend_bin_name,
+                RouteList::ENV_NAME,
+                &routes_path,
+            )
+            .await?;
+            let list = fs::read_to_string(&routes_path)
+                .await
+                .context("failed to read routes")
+                .and_then(|m| RouteList::from_json(&m).context("failed to parse routes"))?;
+            let _ = fs::remove_file(&routes_path).await;
+
+            if list.routes.is_empty() {
+                eprintln!(
+                    "{} {} does not register its routes with Renderer::routes",
+                    style("warning:").yellow().bold(),
+                    frontend_bin_name
+                );
+            }
+
+            let prefix = |m: &str| format!("{}/{}", mount_path, m.trim_start_matches('/'));
+            routes.extend(list.routes.iter().map(|m| prefix(m)));
+            not_found.extend(list.not_found.iter().map(|m| prefix(m)));
+        }
+
+        let target = match target {
+            Some(m) => m,
+            None => {
+                eprintln!(
+                    "{} sitemap.xml is not written, no frontend is mounted at /",
+                    style("warning:").yellow().bold()
+                );
+                return Ok(());
+            }
+        };
+
+        let paths = seo::public_paths(&routes, &not_found, config);
+        let lastmod = i64::try_from(build_info.timestamp)
+            .ok()
+            .and_then(|m| time::OffsetDateTime::from_unix_timestamp(m).ok())
+            .map(|m| m.date().to_string())
+            .unwrap_or_default();
+
+        fs::write(
+            target.join("sitemap.xml"),
+            seo::sitemap(base_url, &paths, &lastmod),
+        )
+        .await
+        .context("failed to write sitemap.xml")?;
+        fs::write(target.join("robots.txt"), seo::robots(base_url, config))
+            .await
+            .context("failed to write robots.txt")?;
+
+        eprintln!("Listed {} paths in sitemap.xml.", paths.len());
+
+        Ok(())
+    }
+
+    /// Writes the service worker and the web manifest of each frontend, if `[build.pwa]` is
+    /// enabled.
+    fn write_pwa_files(&self, frontend_build_dir: &Path, build_info: &BuildInfo) -> Result<()> {
+        let config = &self.manifest.build.pwa;
+        if !config.enabled {
+            return Ok(());
+        }
+
+        if config.name.is_none() {
+            bail!(Failure::new(
+                ErrorKind::Config,
+                "name is required if pwa is enabled"
+            ));
+        }
+
+        let targets = if self.manifest.frontend.is_empty() {
+            vec![(frontend_build_dir.to_owned(), "/")]
+        } else {
+            self.manifest
+                .frontend
+                .iter()
+                .map(|(name, m)| (frontend_build_dir.join(name), m.mount_path.as_str()))
+                .collect()
+        };
+
+        for (dir, public_url) in targets {
+            pwa::write_files(&dir, public_url, build_info, config)?;
+        }
+
+        Ok(())
+    }
+
+    /// Prints the commands and the artifacts of a build without running it.
+    async fn dry_run_build(
+        &self,
+        cmd_args: &BuildCommand,
+        build_dir: &Path,
+        build_info: &BuildInfo,
+    ) -> Result<()> {
+        eprintln!("Build ID: {}", build_info.build_id);
+        eprintln!();
+
+        // The previous frontend build is read for assets that are kept after deploy.
+        dry_run::print_action("move", &build_dir.join("frontend"));
+
+        self.build_css().await?;
+        let frontend_build_dir = self.build_frontend(Some(build_info), None).await?;
+        self.optimize_images(&frontend_build_dir).await?;
+        self.build_backend(&frontend_build_dir, Some(build_info), None)
+            .await?;
+
+        for m in [
+            BridgeContract::FILE_NAME,
+            diff::DEPENDENCIES_FILE_NAME,
+            BuildInfo::FILE_NAME,
+            ServerConfig::FILE_NAME,
+        ] {
+            dry_run::print_action("write", &build_dir.join(m));
+        }
+
+        if cmd_args.sign {
+            dry_run::print_action("write", &build_dir.join(signing::CHECKSUMS_FILE_NAME));
+            if self.manifest.release.signing_key.is_some() {
+                dry_run::print_action("write", &build_dir.join(signing::SIGNATURE_FILE_NAME));
+            }
+        }
+
+        eprintln!();
+        eprintln!(
+            "{}",
+            style("Dry run, nothing has been built.").yellow().bold()
+        );
+
+        Ok(())
+    }
+
+    async fn run_build(&self, cmd_args: &BuildCommand) -> Result<()> {
+        self.check_versions().await?;
+
+        let target_name = self.profile.name();
+
+        eprintln!(
+            "{}",
+            style(format!("Building with {target_name} profile..."))
+                .cyan()
+                .bold()
+        );
+
+        let start_time = SystemTime::now();
+
+        let build_dir = self.build_dir().await?;
+        let build_info = self.build_info().await?;
+
+        if cmd_args.dry_run {
+            return self.dry_run_build(cmd_args, &build_dir, &build_info).await;
+        }
+
+        // Trunk replaces the frontend build directory, the previous build is moved aside.
+        let frontend_dir = build_dir.join("frontend");
+        let previous_frontend_dir = self.frontend_data_dir().await?.join("previous-build");
+        if fs::metadata(&previous_frontend_dir).await.is_ok() {
+            // Left aside by an interrupted build, it is restored instead of being removed.
+            if fs::metadata(&frontend_dir).await.is_err() {
+                fs::rename(&previous_frontend_dir, &frontend_dir)
+                    .await
+                    .context("failed to restore previous frontend build")?;
+            } else {
+                let _ = fs::remove_dir_all(&previous_frontend_dir).await;
+            }
+        }
+        if fs::metadata(&frontend_dir).await.is_ok() {
+            fs::rename(&frontend_dir, &previous_frontend_dir)
+                .await
+                .context("failed to move previous frontend build")?;
+        }
+
+        let result = async {
+            self.build_css().await?;
+            let frontend_build_dir = self.build_frontend(Some(&build_info), None).await?;
+            self.optimize_images(&frontend_build_dir).await?;
+            self.write_preload_manifests(&frontend_build_dir)?;
+            self.check_streaming_compilation(&frontend_build_dir)?;
+            self.write_seo_files(&frontend_build_dir, &build_info)
+                .await?;
+            self.write_pwa_files(&frontend_build_dir, &build_info)?;
+
+            for (name, _) in self.frontends().await? {
+                let subdir = name.unwrap_or_default();
+
+                // Hashed after all files of the build are written, before previous assets are
+                // copied.
+                let integrity = match self.profile.name() {
+                    "release" => {
+                        let public_url = name
+                            .and_then(|m| self.manifest.frontend.get(m))
+                            .map(|m| format!("{}/", m.mount_path.trim_end_matches('/')))
+                            .unwrap_or_else(|| "/".to_string());
+
+                        integrity::write_integrity(&frontend_build_dir.join(subdir), &public_url)?
+                    }
+                    _ => BTreeMap::new(),
+                };
+
+                let asset_manifest = assets::retain_previous_assets(
+                    &previous_frontend_dir.join(subdir),
+                    &frontend_build_dir.join(subdir),
+                    &build_info,
+                    self.manifest.release.asset_grace_period,
+                    integrity,
+                )?;
+
+                if asset_manifest.builds.len() > 1 {
+                    eprintln!(
+                        "Assets of {} previous builds are kept for {}s after deploy.",
+                        asset_manifest.builds.len() - 1,
+                        asset_manifest.grace_period
+                    );
+                }
+            }
+
+            Ok::<_, anyhow::Error>(frontend_build_dir)
+        }
+        .await;
+        let frontend_build_dir = match result {
+            Ok(m) => m,
+            Err(e) => {
+                // Assets of the previous build are still served by clients, so it is restored.
+                if fs::metadata(&previous_frontend_dir).await.is_ok() {
+                    let _ = fs::remove_dir_all(&frontend_dir).await;
+                    let _ = fs::rename(&previous_frontend_dir, &frontend_dir).await;
+                }
+
+                return Err(e);
+            }
+        };
+        let _ = fs::remove_dir_all(&previous_frontend_dir).await;
+
+        let backend_bin_path = self
+            .build_backend(&frontend_build_dir, Some(&build_info), None)
+            .await?;
+
+        // Recorded for stackctl diff-builds.
+        self.write_backend_contract(
+            &backend_bin_path,
+            &build_dir.join(BridgeContract::FILE_NAME),
+        )
+        .await?;
+        fs::write(
+            build_dir.join(diff::DEPENDENCIES_FILE_NAME),
+            serde_json::to_string_pretty(&self.dependencies().await?)?,
+        )
+        .await
+        .context("failed to write dependencies")?;
+
+        fs::write(build_dir.join(BuildInfo::FILE_NAME), build_info.to_json()?)
+            .await
+            .context("failed to write build information")?;
+
+        fs::write(
+            build_dir.join(ServerConfig::FILE_NAME),
+            self.manifest.server.to_json()?,
+        )
+        .await
+        .context("failed to write server configuration")?;
+
+        // Checksums of a previous build are removed.
+        for m in [signing::CHECKSUMS_FILE_NAME, signing::SIGNATURE_FILE_NAME] {
+            let _ = fs::remove_file(build_dir.join(m)).await;
+        }
+
+        if cmd_args.sign {
+            self.sign_artifacts(&build_dir).await?;
+        }
+
+        let time_taken_in_f64 =
+            f64::try_from(i32::try_from(start_time.elapsed()?.as_millis())?)? / 1000.0;
+        eprintln!(
+            "{}",
+            style(format!("Built in {:.2}s!", time_taken_in_f64))
+                .green()
+                .bold()
+        );
+        eprintln!("Build ID: {}", build_info.build_id);
+        eprintln!("The artifact is available at: {}", build_dir.display());
+
+        Ok(())
+    }
+
+    /// Returns the target triple of the host, which artifacts are built for.
+    async fn host_target(&self) -> Result<String> {
+        use tokio::process::Command;
+
+        let output = Command::new("rustc")
+            .arg("-vV")
+            .stdin(Stdio::null())
+            .output()
+            .await
+            .map_err(|e| spawn_error("rustc", e))?;
+
+        String::from_utf8(output.stdout)?
+            .lines()
+            .find_map(|m| m.strip_prefix("host: "))
+            .map(|m| m.trim().to_string())
+            .context("failed to find host target")
+    }
+
+    async fn run_package(&self, cmd_args: &PackageCommand) -> Result<()> {
+        let workspace_dir = self.workspace_dir().await?;
+        let build_dir = self.build_dir().await?;
+
+        let build_info = match fs::read_to_string(build_dir.join(BuildInfo::FILE_NAME)).await {
+            Ok(m) => BuildInfo::from_json(&m)?,
+            Err(_) => bail!("no build is found, run stackctl build --release first"),
+        };
+
+        // Checksums are included even if the build is not signed.
+        if fs::metadata(build_dir.join(signing::CHECKSUMS_FILE_NAME))
+            .await
+            .is_err()
+            && !cmd_args.dry_run
+        {
+            fs::write(
+                build_dir.join(signing::CHECKSUMS_FILE_NAME),
+                signing::checksums(&build_dir)?,
+            )
+            .await
+            .context("failed to write checksums")?;
+        }
+
+        let bin_name = &self.manifest.dev_server.bin_name;
+        let meta = self.cargo_metadata().await?;
+        let version = meta
+            .packages
+            .iter()
+            .find(|p| {
+                p.targets
+                    .iter()
+                    .any(|t| &t.name == bin_name && t.kind.iter().any(|k| k == "bin"))
+            })
+            .map(|m| m.version.to_string())
+            .with_context(|| format!("failed to find package of binary {bin_name}"))?;
+
+        let mut extra_files = Vec::new();
+        let changelog_path = workspace_dir.join(packaging::CHANGELOG_FILE_NAME);
+        match fs::read_to_string(&changelog_path).await {
+            Ok(m) => {
+                if !packaging::changelog_has_version(&m, &version) {
+                    eprintln!(
+                        "{} {} has no entry for version {}.",
+                        style("Warning:").yellow().bold(),
+                        packaging::CHANGELOG_FILE_NAME,
+                        version
+                    );
+                }
+                extra_files.push(changelog_path);
+            }
+            Err(_) => {
+                eprintln!(
+                    "{} {} is not found, the archive has no changelog.",
+                    style("Note:").yellow().bold(),
+                    packaging::CHANGELOG_FILE_NAME
+                );
+            }
+        }
+
+        let target = self.host_target().await?;
+        let format = cmd_args
+            .format
+            .unwrap_or_else(|| ArchiveFormat::for_target(&target));
+        let archive_name = packaging::archive_name(bin_name, &version, &target);
+
+        let out_dir = match cmd_args.out_dir {
+            Some(ref m) => m.clone(),
+            None => workspace_dir.join("dist"),
+        };
+        let archive_path = out_dir.join(format!("{archive_name}.{}", format.extension()));
+
+        if cmd_args.dry_run {
+            eprintln!("Build ID: {}", build_info.build_id);
+            eprintln!();
+
+            let checksums_path = build_dir.join(signing::CHECKSUMS_FILE_NAME);
+            if fs::metadata(&checksums_path).await.is_err() {
+                dry_run::print_action("write", &checksums_path);
+            }
+            dry_run::print_action("archive", &archive_path);
+            for m in packaging::archive_entries(&build_dir, &extra_files, &archive_name)? {
+                eprintln!("         {m}");
+            }
+
+            eprintln!();
+            eprintln!(
+                "{}",
+                style("Dry run, nothing has been packaged.").yellow().bold()
+            );
+
+            return Ok(());
+        }
+
+        fs::create_dir_all(&out_dir)
+            .await
+            .context("failed to create output directory")?;
+
+        packaging::create_archive(
+            format,
+            &build_dir,
+            &extra_files,
+            &archive_name,
+            &archive_path,
+        )?;
+
+        eprintln!(
+            "{}",
+            style(format!("Packaged {archive_name}!")).green().bold()
+        );
+        eprintln!("Build ID: {}", build_info.build_id);
+        eprintln!("The archive is available at: {}", archive_path.display());
+
+        Ok(())
+    }
+
+    async fn run_diff_builds(&self, cmd_args: &DiffBuildsCommand) -> Result<()> {
+        let new_dir = match cmd_args.new {
+            Some(ref m) => m.clone(),
+            None => self.build_dir().await?,
+        };
+
+        let diff = diff::BuildDiff::new(&cmd_args.old, &new_dir)?;
+
+        if cmd_args.json {
+            println!("{}", diff.to_json()?);
+        } else {
+            print!("{}", diff.render());
+        }
+
+        Ok(())
+    }
+
+    async fn run_info(&self, cmd_args: &InfoCommand) -> Result<()> {
+        let workspace_dir = self.workspace_dir().await?;
+        let meta = self.cargo_metadata().await?;
+
+        let crates = meta
+            .workspace_members
+            .iter()
+            .map(|id| {
+                let pkg = &meta[id];
+                info::CrateInfo {
+                    name: pkg.name.clone(),
+                    version: pkg.version.to_string(),
+                    bins: pkg
+                        .targets
+                        .iter()
+                        .filter(|m| m.kind.iter().any(|k| k == "bin"))
+                        .map(|m| m.name.clone())
+                        .collect(),
+                }
+            })
+            .collect();
+
+        let mut frontends = Vec::new();
+        for (name, index_html) in self.frontends().await? {
+            let bin_name = fs::read_to_string(&index_html)
+                .await
+                .ok()
+                .and_then(|m| frontend_bin_name(&m).map(|m| m.to_string()));
+            let mount_path = name
+                .and_then(|m| self.manifest.frontend.get(m))
+                .map(|m| m.mount_path.clone())
+                .unwrap_or_else(|| "/".to_string());
+
+            frontends.push(info::FrontendInfo {
+                name: name.map(|m| m.to_string()),
+                index_html,
+                bin_name,
+                mount_path,
+            });
+        }
+
+        let mut tool_names = vec!["rustc", "cargo", "trunk"];
+        if let Some(ref m) = self.manifest.build.frontend.css {
+            match m.command.as_deref() {
+                Some([program, ..]) => tool_names.push(program),
+                _ => tool_names.push("tailwindcss"),
+            }
+        }
+        let mut tools = BTreeMap::new();
+        for m in tool_names {
+            tools.insert(m.to_string(), info::tool_version(m).await);
+        }
+
+        let mut features = BTreeMap::new();
+        for node in meta.resolve.iter().flat_map(|m| m.nodes.iter()) {
+            let pkg = &meta[&node.id];
+            if pkg.name.starts_with("stackable") {
+                features.insert(
+                    format!("{} {}", pkg.name, pkg.version),
+                    node.features.clone(),
+                );
+            }
+        }
+
+        let info = info::Info {
+            stackctl_version: env!("CARGO_PKG_VERSION").to_string(),
+            manifest_path: self.cli.manifest_path.canonicalize()?,
+            build_dir: workspace_dir.join("build"),
+            data_dir: workspace_dir.join(".stackable"),
+            workspace_dir,
+            profile: self.profile.name().to_string(),
+            env_profile: self.env_file.name().to_string(),
+            listen: self.listen_addr(),
+            server_bin_name: self.manifest.dev_server.bin_name.clone(),
+            crates,
+            frontends,
+            tools,
+            features,
+            manifest: toml::Value::try_from(&*self.manifest)
+                .context("failed to serialise manifest")?,
+        };
+
+        if cmd_args.json {
+            println!("{}", info.to_json()?);
+        } else {
+            print!("{}", info.render());
+        }
+
+        Ok(())
+    }
+
+    async fn version_problems(&self) -> Result<Vec<versions::VersionProblem>> {
+        let workspace_dir = self.workspace_dir().await?;
+        let metadata = self.cargo_metadata().await?;
+        let envs = self.env_file.load(&workspace_dir);
+
+        versions::check(
+            metadata.workspace_root.join("Cargo.lock").as_std_path(),
+            &workspace_dir,
+            &envs,
+        )
+        .await
+    }
+
+    /// Fails before building if the versions of wasm-bindgen or yew are inconsistent, builds
+    /// would otherwise succeed and fail at runtime.
+    async fn check_versions(&self) -> Result<()> {
+        versions::ensure_consistent(&self.version_problems().await?)
+    }
+
+    async fn run_check(&self, cmd_args: &CheckCommand) -> Result<()> {
+        let mut problems = self.version_problems().await?;
+
+        if cmd_args.fix {
+            for m in problems.iter().filter(|m| m.is_fixable()) {
+                versions::fix(m).await?;
+                eprintln!(
+                    "{} pinned wasm-bindgen in {} to the version in Cargo.lock",
+                    style("fixed:").green().bold(),
+                    versions::TRUNK_CONFIG_FILE_NAME
+                );
+            }
+            problems.retain(|m| !m.is_fixable());
+        }
+
+        versions::ensure_consistent(&problems)?;
+        eprintln!("{}", style("No problems found!").green().bold());
+
+        Ok(())
+    }
+
+    async fn run_generate(&self, cmd_args: &GenerateCommand) -> Result<()> {
+        let workspace_dir = self.workspace_dir().await?;
+
+        match cmd_args {
+            GenerateCommand::Auth(m) => generate::run_auth(&workspace_dir, m).await,
+            GenerateCommand::Component(m) => {
+                generate::run_template(&workspace_dir, "component", m).await
+            }
+            GenerateCommand::Page(m) => generate::run_template(&workspace_dir, "page", m).await,
+            GenerateCommand::Endpoint(m) => {
+                generate::run_template(&workspace_dir, "endpoint", m).await
+            }
+            GenerateCommand::List => generate::list(&workspace_dir).await,
+            GenerateCommand::Custom(args) => {
+                // The name of the generator is parsed as the binary name.
+                let m = GenerateTemplateCommand::try_parse_from(args).unwrap_or_else(|e| e.exit());
+                let name = args.first().map(|m| m.as_str()).unwrap_or_default();
+
+                generate::run_template(&workspace_dir, name, &m).await
+            }
+        }
+    }
+
+    async fn run_sessions(&self, cmd_args: &SessionsCommand) -> Result<()> {
+        let mut config = self
+            .manifest
+            .server
+            .session
+            .clone()
+            .context("no session store is configured in [server.session]")?;
+
+        match cmd_args {
+            SessionsCommand::Purge => {
+                match config.store {
+                    SessionStoreConfig::Memory => {
+                        eprintln!("Sessions in memory are removed when the server stops.");
+                        return Ok(());
+                    }
+                    SessionStoreConfig::Redis { .. } => {
+                        eprintln!("Redis removes expired sessions by itself.");
+                        return Ok(());
+                    }
+                    SessionStoreConfig::File { ref mut path } => {
+                        // The server is started in the workspace directory.
+                        *path = self.workspace_dir().await?.join(&path);
+                    }
+                }
+
+                let purged = session::create_store(&config)?.purge_expired().await?;
+
+                eprintln!(
+                    "{}",
+                    style(format!("Purged {purged} expired sessions."))
+                        .green()
+                        .bold()
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn run_mock(&self, cmd_args: &MockCommand) -> Result<()> {
+        let workspace_dir = self.workspace_dir().await?;
+
+        match cmd_args {
+            MockCommand::List => {
+                let recordings = mocks::recordings(&workspace_dir)?;
+                if recordings.is_empty() {
+                    eprintln!("No recordings, record one with `stackctl serve --record <NAME>`.");
+                }
+
+                for (name, file) in recordings {
+                    println!("{:<24} {} requests", name, file.mocks.len());
+                }
+            }
+            MockCommand::Show(m) => {
+                let path = mocks::recording_path(&workspace_dir, &m.name)?;
+
+                for mock in mocks::load_recording(&path)?.iter() {
+                    let input = mock
+                        .input
+                        .as_ref()
+                        .map(|m| m.to_string())
+                        .unwrap_or_default();
+                    let outcome = match mock.error {
+                        Some(_) => style("error").red(),
+                        None => style("ok").green(),
+                    };
+
+                    println!("{} {} {}", style(&mock.name).bold(), input, outcome);
+                }
+            }
+            MockCommand::Delete(m) => {
+                let path = mocks::recording_path(&workspace_dir, &m.name)?;
+
+                fs::remove_file(&path)
+                    .await
+                    .with_context(|| format!("failed to delete recording {}", m.name))?;
+
+                eprintln!(
+                    "{}",
+                    style(format!("Deleted recording {}.", m.name))
+                        .green()
+                        .bold()
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Reads the bridge contract written by a process to the environment variable.
+    async fn read_contract(path: &Path) -> Result<BridgeContract> {
+        let s = fs::read_to_string(path)
+            .await
+            .context("failed to read bridge contract")?;
+        let _ = fs::remove_file(path).await;
+
+        Ok(BridgeContract::from_json(&s)?)
+    }
+
+    /// Runs the backend to write the contract of its bridge.
+    async fn write_backend_contract(&self, backend_bin_path: &Path, path: &Path) -> Result<()> {
+        use tokio::process::Command;
+
+        let status = Command::new(backend_bin_path)
+            .current_dir(self.workspace_dir().await?)
+            .env(BridgeContract::ENV_NAME, path)
+            .stdin(Stdio::null())
+            .status()
+            .await?;
+        if !status.success() {
+            bail!("backend failed with status {}", status);
+        }
+
+        Ok(())
+    }
+
+    /// Returns the versions of all packages in the dependency graph of the workspace.
+    async fn dependencies(&self) -> Result<BTreeMap<String, Vec<String>>> {
+        let meta = self.cargo_metadata().await?;
+        let mut dependencies = BTreeMap::<String, Vec<String>>::new();
+
+        for m in meta.packages {
+            // Members of the workspace are not dependencies.
+            if meta.workspace_members.contains(&m.id) {
+                continue;
+            }
+
+            let versions = dependencies.entry(m.name).or_default();
+            versions.push(m.version.to_string());
+            versions.sort();
+        }
+
+        Ok(dependencies)
+    }
+
+    /// Runs a frontend built for the host, which writes its bridge contract or its routes to
+    /// `path` instead of rendering.
+    async fn run_frontend_on_host(
+        &self,
+        index_html: &str,
+        frontend_bin_name: &str,
+        env_name: &str,
+        path: &Path,
+    ) -> Result<()> {
+        use tokio::process::Command;
+
+        let workspace_dir = self.workspace_dir().await?;
+
+        let mut proc = Command::new("cargo");
+        proc.arg("run")
+            .arg("--bin")
+            .arg(frontend_bin_name)
+            .args(self.lock_arguments())
+            .current_dir(&workspace_dir)
+            .envs(
+                self.env_file
+                    .load_public(&workspace_dir, &self.manifest.build.frontend.public_env),
+            )
+            .env(env_name, path)
+            .stdin(Stdio::null());
+        if let Some(m) = self.profile.to_profile_argument() {
+            proc.arg(m);
+        }
+        proc.args(self.frontend_feature_arguments(index_html));
+        let status = proc.status().await?;
+        if !status.success() {
+            bail!("frontend failed with status {}", status);
+        }
+
+        Ok(())
+    }
+
+    /// Writes the bridge contract of the backend and each frontend, built for the host.
+    async fn bridge_contracts(&self) -> Result<(BridgeContract, Vec<(String, BridgeContract)>)> {
+        let backend_data_dir = self.backend_data_dir().await?;
+        let frontend_data_dir = self.frontend_data_dir().await?;
+
+        let frontend_build_dir = self.frontend_build_dir().await?;
+        let backend_build_path = self.build_backend(&frontend_build_dir, None, None).await?;
+
+        let backend_contract_path =
+            backend_data_dir.join(format!("bridge-contract-{}.json", random_str()?));
+        self.write_backend_contract(&backend_build_path, &backend_contract_path)
+            .await?;
+        let backend_contract = Self::read_contract(&backend_contract_path).await?;
+
+        let mut frontend_contracts = Vec::new();
+        for (name, index_html_path) in self.frontends().await? {
+            let index_html = fs::read_to_string(&index_html_path)
+                .await
+                .with_context(|| format!("failed to read {}", index_html_path.display()))?;
+            let frontend_bin_name = frontend_bin_name(&index_html).with_context(|| {
+                format!(
+                    "failed to find data-bin attribute in {}",
+                    index_html_path.display()
+                )
+            })?;
+
+            let frontend_contract_path =
+                frontend_data_dir.join(format!("bridge-contract-{}.json", random_str()?));
+            self.run_frontend_on_host(
+                &index_html,
+                frontend_bin_name,
+                BridgeContract::ENV_NAME,
+                &frontend_contract_path,
+            )
+            .await?;
+
+            frontend_contracts.push((
+                name.unwrap_or(frontend_bin_name).to_string(),
+                Self::read_contract(&frontend_contract_path).await?,
+            ));
+        }
+
+        Ok((backend_contract, frontend_contracts))
+    }
+
+    async fn run_test(&self, cmd_args: &TestCommand) -> Result<()> {
+        if !cmd_args.contracts {
+            bail!("nothing to test, try stackctl test --contracts");
+        }
+
+        eprintln!("{}", style("Checking bridge contracts...").cyan().bold());
+
+        let (backend_contract, frontend_contracts) = self.bridge_contracts().await?;
+
+        let mut has_problems = false;
+        for (name, frontend_contract) in frontend_contracts {
+            for m in frontend_contract.check(&backend_contract) {
+                eprintln!("{} {}: {}", style("error:").red().bold(), name, m);
+                has_problems = true;
+            }
+        }
+
+        if has_problems {
+            bail!("frontend and backend bridges do not match");
+        }
 
-        let time_taken_in_f64 =
-            f64::try_from(i32::try_from(start_time.elapsed()?.as_millis())?)? / 1000.0;
         eprintln!(
             "{}",
-            style(format!("Built in {:.2}s!", time_taken_in_f64))
-                .green()
+            style("Frontend and backend bridges match!").green().bold()
+        );
+
+        Ok(())
+    }
+
+    /// Starts the backend of the last build on an ephemeral port of the loopback interface and
+    /// waits until `ready_path` responds successfully, returns the server and its url.
+    ///
+    /// `section` is the section of `stackable.toml` and the command that the server is started
+    /// for, e.g.: `e2e`.
+    async fn start_built_server(
+        &self,
+        ready_path: &str,
+        startup_timeout: u64,
+        section: &str,
+        stdout: Stdio,
+    ) -> Result<(Child, String)> {
+        use tokio::process::Command;
+
+        const SECRET_KEY_ENV: &str = "STACKABLE_SECRET_KEY";
+
+        let workspace_dir = self.workspace_dir().await?;
+        let build_dir = self.build_dir().await?;
+        let backend_bin_path = build_dir
+            .join("backend")
+            .join(&self.manifest.dev_server.bin_name);
+
+        if fs::metadata(&backend_bin_path).await.is_err() {
+            bail!("no build is found, run stackctl {section} without --skip-build first");
+        }
+
+        // The port is released before the server listens to it, so it is only unlikely to be
+        // taken in between.
+        let addr = std::net::TcpListener::bind(("127.0.0.1", 0))?.local_addr()?;
+        let url = format!("http://{addr}");
+
+        let envs = self.env_file.load(&workspace_dir);
+
+        let mut server_proc = Command::new(&backend_bin_path);
+        server_proc
+            .current_dir(&workspace_dir)
+            .envs(&envs)
+            .env("STACKABLE_LISTEN_ADDR", addr.to_string())
+            .env(
+                ServerConfig::ENV_NAME,
+                build_dir.join(ServerConfig::FILE_NAME),
+            )
+            .stdin(Stdio::null())
+            .stdout(stdout)
+            .kill_on_drop(true);
+
+        // Sessions and state encryption require a secret, which does not matter for tests.
+        if !envs.contains_key(SECRET_KEY_ENV) && std::env::var_os(SECRET_KEY_ENV).is_none() {
+            server_proc.env(SECRET_KEY_ENV, random_secret());
+        }
+
+        let mut server_proc = server_proc
+            .spawn()
+            .map_err(|e| spawn_error(&self.manifest.dev_server.bin_name, e))?;
+
+        let ready_url = format!("{}/{}", url, ready_path.trim_start_matches('/'));
+        let startup_timeout = Duration::from_secs(startup_timeout);
+
+        let failure = {
+            let ready_fur = async {
+                while reqwest::ClientBuilder::default()
+                    .timeout(Duration::from_secs(1))
+                    .build()?
+                    .get(&ready_url)
+                    .send()
+                    .await
+                    .and_then(|m| m.error_for_status())
+                    .is_err()
+                {
+                    sleep(Duration::from_millis(200)).await;
+                }
+
+                Ok::<_, anyhow::Error>(())
+            }
+            .fuse();
+            let exit_fur = server_proc.wait().fuse();
+            let timeout_fur = sleep(startup_timeout).fuse();
+            pin_mut!(ready_fur, exit_fur, timeout_fur);
+
+            futures::select! {
+                m = ready_fur => {
+                    m?;
+                    None
+                },
+                m = exit_fur => Some(format!("server exited during startup with status {}", m?)),
+                _ = timeout_fur => Some(format!(
+                    "server is not ready after {}s, {} did not respond successfully \
+                     (see {section}.startup-timeout and {section}.ready-path)",
+                    startup_timeout.as_secs(),
+                    ready_url
+                )),
+            }
+        };
+
+        if let Some(reason) = failure {
+            // The server may have exited already.
+            let _ = server_proc.kill().await;
+            bail!("{}", reason);
+        }
+
+        Ok((server_proc, url))
+    }
+
+    async fn run_e2e(&self, cmd_args: &E2eCommand) -> Result<()> {
+        use tokio::process::Command;
+
+        const URL_ENV: &str = "STACKABLE_E2E_URL";
+
+        let config = &self.manifest.e2e;
+        let command = if cmd_args.command.is_empty() {
+            &config.command
+        } else {
+            &cmd_args.command
+        };
+        let (program, args) = match command.split_first() {
+            Some(m) => m,
+            None => bail!(Failure::new(
+                ErrorKind::Config,
+                "no test command is set, set e2e.command in stackable.toml or pass it after --"
+            )),
+        };
+
+        if !cmd_args.skip_build {
+            self.run_build(&BuildCommand {
+                release: cmd_args.release,
+                sign: false,
+                profile: cmd_args.profile.clone(),
+                env: cmd_args.env.clone(),
+                dry_run: false,
+            })
+            .await?;
+        }
+
+        let workspace_dir = self.workspace_dir().await?;
+        let (mut server_proc, url) = self
+            .start_built_server(
+                &config.ready_path,
+                config.startup_timeout,
+                "e2e",
+                Stdio::inherit(),
+            )
+            .await?;
+        let envs = self.env_file.load(&workspace_dir);
+
+        eprintln!(
+            "{}",
+            style(format!("Running end-to-end tests against {url}..."))
+                .cyan()
                 .bold()
         );
-        eprintln!("The artifact is available at: {}", build_dir.display());
+
+        let status = Command::new(program)
+            .args(args)
+            .current_dir(&workspace_dir)
+            .envs(&envs)
+            .env(URL_ENV, &url)
+            .status()
+            .await
+            .map_err(|e| spawn_error(program, e));
+
+        let _ = server_proc.kill().await;
+        let status = status?;
+
+        if !status.success() {
+            // The exit code of the test command is returned, so CI reports it as is.
+            let exit_code = status
+                .code()
+                .and_then(|m| u8::try_from(m).ok())
+                .filter(|m| *m != 0)
+                .unwrap_or(1);
+
+            bail!(Failure::new(
+                ErrorKind::Failure,
+                format!("end-to-end tests failed with status {status}")
+            )
+            .with_exit_code(exit_code));
+        }
+
+        eprintln!("{}", style("End-to-end tests passed!").green().bold());
+
+        Ok(())
+    }
+
+    async fn run_bench(&self, cmd_args: &BenchCommand) -> Result<()> {
+        let config = &self.manifest.bench;
+        let routes = if cmd_args.routes.is_empty() {
+            &config.routes
+        } else {
+            &cmd_args.routes
+        };
+        let duration = cmd_args.duration.unwrap_or(config.duration);
+        let concurrency = cmd_args.concurrency.unwrap_or(config.concurrency);
+
+        if routes.is_empty() || concurrency == 0 {
+            bail!(Failure::new(
+                ErrorKind::Config,
+                "at least one route and one connection are required to benchmark"
+            ));
+        }
+
+        if !cmd_args.skip_build {
+            self.run_build(&BuildCommand {
+                release: true,
+                sign: false,
+                profile: None,
+                env: cmd_args.env.clone(),
+                dry_run: false,
+            })
+            .await?;
+        }
+
+        // Logs of requests would be mixed into the JSON report.
+        let (mut server_proc, url) = self
+            .start_built_server(
+                &config.ready_path,
+                config.startup_timeout,
+                "bench",
+                Stdio::null(),
+            )
+            .await?;
+
+        eprintln!(
+            "{}",
+            style(format!("Benchmarking {url} for {duration}s..."))
+                .cyan()
+                .bold()
+        );
+
+        let report = bench::run(&url, routes, concurrency, Duration::from_secs(duration)).await;
+        let _ = server_proc.kill().await;
+
+        let mut report = report?;
+        report.build = fs::read_to_string(self.build_dir().await?.join(BuildInfo::FILE_NAME))
+            .await
+            .ok()
+            .and_then(|m| BuildInfo::from_json(&m).ok());
+
+        if cmd_args.json {
+            println!("{}", serde_json::to_string_pretty(&report)?);
+        } else {
+            bench::print_report(&report);
+        }
+
+        Ok(())
+    }
+
+    async fn run_fmt(&self, cmd_args: &FmtCommand) -> Result<()> {
+        use tokio::process::Command;
+
+        let workspace_dir = self.workspace_dir().await?;
+
+        let mut proc = Command::new("cargo");
+        proc.arg("fmt")
+            .arg("--all")
+            .current_dir(&workspace_dir)
+            .stdin(Stdio::null());
+        if cmd_args.check {
+            proc.arg("--").arg("--check");
+        }
+
+        let status = proc.status().await.map_err(|e| spawn_error("cargo", e))?;
+        if !status.success() {
+            bail!("rustfmt failed with status {}", status);
+        }
+
+        Ok(())
+    }
+
+    /// Returns the feature arguments that a package is built with, if it is the package of the
+    /// backend or of a frontend, so its tests are compiled with the same features.
+    async fn package_feature_arguments(&self, metadata: &Metadata, package: &str) -> Vec<String> {
+        let has_bin = |bin_name: &str| {
+            metadata
+                .packages
+                .iter()
+                .filter(|m| m.name == package)
+                .flat_map(|m| m.targets.iter())
+                .any(|t| t.name == bin_name && t.kind.iter().any(|k| k == "bin"))
+        };
+
+        if has_bin(&self.manifest.dev_server.bin_name) {
+            return self.manifest.build.backend.cargo.to_arguments();
+        }
+
+        for (_, index_html_path) in self.frontends().await.unwrap_or_default() {
+            let index_html = match fs::read_to_string(&index_html_path).await {
+                Ok(m) => m,
+                Err(_) => continue,
+            };
+
+            if frontend_bin_name(&index_html).map(has_bin).unwrap_or(false) {
+                return self.frontend_feature_arguments(&index_html);
+            }
+        }
+
+        Vec::new()
+    }
+
+    /// Returns the feature arguments of cargo for a frontend, `build.frontend` takes precedence
+    /// over `data-cargo-features` in its `index.html`.
+    fn frontend_feature_arguments(&self, index_html: &str) -> Vec<String> {
+        let features = &self.manifest.build.frontend.cargo;
+
+        match frontend_cargo_features(index_html) {
+            Some(m) if features.is_default() => vec!["--features".to_string(), m.to_string()],
+            _ => features.to_arguments(),
+        }
+    }
+
+    /// Runs clippy for a binary and adds its diagnostics to the report.
+    async fn clippy(
+        &self,
+        report: &mut lint::LintReport,
+        source: &str,
+        bin_name: &str,
+        target: Option<&str>,
+        feature_args: Vec<String>,
+    ) -> Result<()> {
+        use tokio::process::Command;
+
+        let workspace_dir = self.workspace_dir().await?;
+
+        let mut proc = Command::new("cargo");
+        proc.arg("clippy")
+            .arg("--bin")
+            .arg(bin_name)
+            .arg("--message-format=json")
+            .args(self.lock_arguments())
+            .current_dir(&workspace_dir)
+            .envs(self.env_file.load(&workspace_dir))
+            .stdin(Stdio::null());
+        if let Some(m) = target {
+            proc.arg("--target").arg(m);
+        }
+        proc.args(feature_args);
+
+        let output = proc.output().await.map_err(|e| spawn_error("cargo", e))?;
+        let errors = report.errors();
+        report.add_cargo_output(source, &output.stdout);
+
+        // Failures without diagnostics, e.g.: the target is not installed.
+        if !output.status.success() && report.errors() == errors {
+            eprintln!("{}", String::from_utf8_lossy(&output.stderr));
+            bail!("clippy failed for {} with status {}", source, output.status);
+        }
+
+        Ok(())
+    }
+
+    async fn run_lint(&self, cmd_args: &LintCommand) -> Result<()> {
+        eprintln!("{}", style("Checking formatting...").cyan().bold());
+        let fmt_result = self.run_fmt(&FmtCommand { check: true }).await;
+
+        eprintln!("{}", style("Running clippy...").cyan().bold());
+        let mut report = lint::LintReport::default();
+
+        let bin_name = &self.manifest.dev_server.bin_name;
+        let backend_feature_args = self.manifest.build.backend.cargo.to_arguments();
+        self.clippy(&mut report, "backend", bin_name, None, backend_feature_args)
+            .await?;
+
+        for (name, index_html_path) in self.frontends().await? {
+            let index_html = fs::read_to_string(&index_html_path)
+                .await
+                .with_context(|| format!("failed to read {}", index_html_path.display()))?;
+            let frontend_bin_name = frontend_bin_name(&index_html).with_context(|| {
+                format!(
+                    "failed to find data-bin attribute in {}",
+                    index_html_path.display()
+                )
+            })?;
+
+            let source = match name {
+                Some(m) => format!("frontend {m} ({})", lint::WASM_TARGET),
+                None => format!("frontend ({})", lint::WASM_TARGET),
+            };
+            self.clippy(
+                &mut report,
+                &source,
+                frontend_bin_name,
+                Some(lint::WASM_TARGET),
+                self.frontend_feature_arguments(&index_html),
+            )
+            .await?;
+        }
+
+        eprint!("{}", report.render());
+        eprintln!(
+            "clippy: {} error(s), {} warning(s)",
+            report.errors(),
+            report.warnings()
+        );
+
+        fmt_result.context("files are not formatted, run stackctl fmt")?;
+        if report.errors() > 0 {
+            bail!("clippy has reported errors");
+        }
+        if cmd_args.deny_warnings && report.warnings() > 0 {
+            bail!("clippy has reported warnings");
+        }
+
+        eprintln!("{}", style("No problems found!").green().bold());
 
         Ok(())
     }
@@ -628,15 +3642,64 @@ impl Stackctl {
             CliCommand::Build(ref m) => {
                 self.run_build(m).await?;
             }
+            CliCommand::Sessions(ref m) => {
+                self.run_sessions(m).await?;
+            }
+            CliCommand::Mock(ref m) => {
+                self.run_mock(m).await?;
+            }
+            CliCommand::Rollback(ref m) => {
+                self.run_rollback(m).await?;
+            }
+            CliCommand::Generate(ref m) => {
+                self.run_generate(m).await?;
+            }
+            CliCommand::Package(ref m) => {
+                self.run_package(m).await?;
+            }
+            CliCommand::DiffBuilds(ref m) => {
+                self.run_diff_builds(m).await?;
+            }
+            CliCommand::Info(ref m) => {
+                self.run_info(m).await?;
+            }
+            CliCommand::Check(ref m) => {
+                self.run_check(m).await?;
+            }
+            CliCommand::Test(ref m) => {
+                self.run_test(m).await?;
+            }
+            CliCommand::E2e(ref m) => {
+                self.run_e2e(m).await?;
+            }
+            CliCommand::Bench(ref m) => {
+                self.run_bench(m).await?;
+            }
+            CliCommand::Fmt(ref m) => {
+                self.run_fmt(m).await?;
+            }
+            CliCommand::Lint(ref m) => {
+                self.run_lint(m).await?;
+            }
+            // These commands do not require a manifest, they are run before it is loaded.
+            CliCommand::Completions(_) | CliCommand::SelfCmd(_) | CliCommand::BuildAll(_) => {}
         }
 
         Ok(())
     }
 }
 
-pub async fn main() -> Result<()> {
+pub async fn main() -> ExitCode {
+    let recent_logs = diagnostics::RecentLogs::default();
+    let log_writer = recent_logs.clone();
+
     tracing_subscriber::registry()
         .with(tracing_subscriber::fmt::layer().pretty())
+        .with(
+            tracing_subscriber::fmt::layer()
+                .with_ansi(false)
+                .with_writer(move || log_writer.clone()),
+        )
         .with(
             EnvFilter::builder()
                 .with_default_directive(Level::INFO.into())
@@ -645,8 +3708,70 @@ pub async fn main() -> Result<()> {
         )
         .init();
 
+    diagnostics::install_panic_hook();
+
     let cli = Cli::parse();
-    Stackctl::new(cli).await?.run().await?;
+    let manifest_path = cli.manifest_path.clone();
+    let error_format = cli.error_format;
+
+    if let CliCommand::Completions(ref m) = cli.command {
+        completions::print(m.shell, &manifest_path);
+        return ExitCode::SUCCESS;
+    }
+
+    if let CliCommand::SelfCmd(SelfCommand::Update(ref m)) = cli.command {
+        return match self_update::run(m).await {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => exit::report_error(error_format, &e),
+        };
+    }
+
+    // Each application is built by a separate process with its own manifest.
+    if let CliCommand::BuildAll(m) = cli.command {
+        let lock_args = [("--offline", cli.offline), ("--locked", cli.locked)]
+            .into_iter()
+            .filter_map(|(arg, enabled)| enabled.then_some(arg))
+            .collect::<Vec<_>>();
+
+        return match build_all::run(m, lock_args).await {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => exit::report_error(error_format, &e),
+        };
+    }
 
-    Ok(())
+    let result = AssertUnwindSafe(async move { Stackctl::new(cli).await?.run().await })
+        .catch_unwind()
+        .await;
+
+    // Bundles are only offered to people for internal errors and panics, scripts reading JSON
+    // errors are never prompted.
+    let offer_bundle = error_format == ErrorFormat::Human;
+
+    match result {
+        Ok(Ok(())) => ExitCode::SUCCESS,
+        Ok(Err(e)) => {
+            // Other kinds of errors are caused by the project, e.g.: a compile error.
+            if offer_bundle && ErrorKind::of(&e) == ErrorKind::Internal {
+                diagnostics::offer_bundle(&format!("{e:?}"), &manifest_path, &recent_logs).await;
+            }
+            exit::report_error(error_format, &e)
+        }
+        Err(_) => {
+            let failure = diagnostics::take_panic().unwrap_or_else(|| "unknown panic".into());
+            if !offer_bundle {
+                let message = failure.lines().next().unwrap_or_default();
+                return exit::report(
+                    error_format,
+                    ErrorKind::Internal,
+                    ErrorKind::Internal.exit_code(),
+                    message,
+                    Vec::new(),
+                );
+            }
+
+            // The panic message has been printed by the panic hook.
+            diagnostics::offer_bundle(&failure, &manifest_path, &recent_logs).await;
+            ExitCode::from(ErrorKind::Internal.exit_code())
+        }
+    }
 }
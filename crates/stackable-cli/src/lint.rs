@@ -0,0 +1,86 @@
+//! Diagnostics of clippy for the frontend and the backend, printed by `stackctl lint`.
+
+use std::fmt::Write;
+
+use cargo_metadata::diagnostic::DiagnosticLevel;
+use cargo_metadata::Message;
+
+/// The target that frontends are checked for.
+pub(crate) const WASM_TARGET: &str = "wasm32-unknown-unknown";
+
+/// A diagnostic with the builds that reported it.
+///
+/// Crates shared by the frontend and the backend are checked for both targets.
+#[derive(Debug)]
+struct LintDiagnostic {
+    level: DiagnosticLevel,
+    rendered: String,
+    sources: Vec<String>,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct LintReport {
+    diagnostics: Vec<LintDiagnostic>,
+}
+
+/// Returns `true` for summaries like `3 warnings emitted`, which are not diagnostics.
+pub(crate) fn is_summary(message: &str) -> bool {
+    message.starts_with("aborting due to")
+        || message.ends_with("warning emitted")
+        || message.ends_with("warnings emitted")
+}
+
+impl LintReport {
+    /// Adds diagnostics from the output of `cargo clippy --message-format=json`.
+    pub fn add_cargo_output(&mut self, source: &str, stdout: &[u8]) {
+        for message in Message::parse_stream(stdout).flatten() {
+            let diagnostic = match message {
+                Message::CompilerMessage(m) => m.message,
+                _ => continue,
+            };
+
+            if is_summary(&diagnostic.message) {
+                continue;
+            }
+
+            let rendered = diagnostic.rendered.unwrap_or(diagnostic.message);
+
+            match self.diagnostics.iter_mut().find(|m| m.rendered == rendered) {
+                Some(m) => {
+                    if !m.sources.iter().any(|m| m == source) {
+                        m.sources.push(source.to_string());
+                    }
+                }
+                None => self.diagnostics.push(LintDiagnostic {
+                    level: diagnostic.level,
+                    rendered,
+                    sources: vec![source.to_string()],
+                }),
+            }
+        }
+    }
+
+    fn count(&self, level: DiagnosticLevel) -> usize {
+        self.diagnostics.iter().filter(|m| m.level == level).count()
+    }
+
+    pub fn warnings(&self) -> usize {
+        self.count(DiagnosticLevel::Warning)
+    }
+
+    pub fn errors(&self) -> usize {
+        self.count(DiagnosticLevel::Error) + self.count(DiagnosticLevel::Ice)
+    }
+
+    /// Renders all diagnostics, followed by the builds that reported them.
+    pub fn render(&self) -> String {
+        let mut s = String::new();
+
+        for m in self.diagnostics.iter() {
+            let _ = writeln!(s, "{}", m.rendered.trim_end());
+            let _ = writeln!(s, "  = reported by: {}\n", m.sources.join(", "));
+        }
+
+        s
+    }
+}
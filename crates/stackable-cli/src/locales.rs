@@ -0,0 +1,279 @@
+//! Translation files under `locales/`, validated and copied into the frontend build directory.
+//!
+//! Both `locales/<locale>/<name>.ftl` and `locales/<locale>.ftl` are discovered, gettext files
+//! (`.po`) are discovered the same way.
+
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use sha2::{Digest, Sha256};
+use stackable_core::locales::{HtmlAttrs, LocaleManifest};
+
+use crate::manifest::I18n;
+
+const EXTENSIONS: [&str; 2] = ["ftl", "po"];
+
+/// A translation file of a locale.
+#[derive(Debug)]
+pub(crate) struct LocaleFile {
+    pub locale: String,
+    pub path: PathBuf,
+}
+
+fn is_locale_name(s: &str) -> bool {
+    let mut parts = s.split(['-', '_']);
+    let language = parts.next().unwrap_or_default();
+
+    (2..=3).contains(&language.len())
+        && language.chars().all(|m| m.is_ascii_alphabetic())
+        && parts.all(|m| !m.is_empty() && m.chars().all(|m| m.is_ascii_alphanumeric()))
+}
+
+fn is_translation_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|m| m.to_str())
+        .map(|m| EXTENSIONS.contains(&m))
+        .unwrap_or(false)
+}
+
+/// Discovers translation files in the locales directory, sorted by locale and path.
+pub(crate) fn discover(locales_dir: &Path) -> Result<Vec<LocaleFile>> {
+    let mut files = Vec::new();
+
+    for entry in fs::read_dir(locales_dir)
+        .with_context(|| format!("failed to read {}", locales_dir.display()))?
+    {
+        let path = entry?.path();
+
+        if path.is_dir() {
+            let locale = path.file_name().unwrap_or_default().to_string_lossy();
+            if !is_locale_name(&locale) {
+                bail!("{} is not a valid locale name", path.display());
+            }
+
+            for entry in fs::read_dir(&path)? {
+                let file_path = entry?.path();
+                if is_translation_file(&file_path) {
+                    files.push(LocaleFile {
+                        locale: locale.to_string(),
+                        path: file_path,
+                    });
+                }
+            }
+        } else if is_translation_file(&path) {
+            let locale = path.file_stem().unwrap_or_default().to_string_lossy();
+            if !is_locale_name(&locale) {
+                bail!("{} is not a valid locale name", path.display());
+            }
+
+            files.push(LocaleFile {
+                locale: locale.to_string(),
+                path,
+            });
+        }
+    }
+
+    files.sort_by(|a, b| (&a.locale, &a.path).cmp(&(&b.locale, &b.path)));
+
+    Ok(files)
+}
+
+fn is_fluent_identifier(s: &str) -> bool {
+    let s = s.strip_prefix('-').unwrap_or(s);
+    let mut chars = s.chars();
+
+    chars
+        .next()
+        .map(|m| m.is_ascii_alphabetic())
+        .unwrap_or(false)
+        && chars.all(|m| m.is_ascii_alphanumeric() || m == '-' || m == '_')
+}
+
+/// Checks that each entry of a Fluent file is a message, a term or a comment.
+fn validate_fluent(content: &str) -> Vec<(usize, String)> {
+    let mut problems = Vec::new();
+    let mut ids = HashSet::new();
+    let mut in_entry = false;
+
+    for (index, line) in content.lines().enumerate() {
+        let line_no = index + 1;
+
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        if line.starts_with('#') {
+            in_entry = false;
+            continue;
+        }
+
+        // Values, attributes and variants of the entry above.
+        if line.starts_with([' ', '\t']) {
+            if !in_entry {
+                problems.push((line_no, "indented line outside of a message".to_string()));
+            }
+            continue;
+        }
+
+        match line.split_once('=') {
+            Some((id, _)) if is_fluent_identifier(id.trim()) => {
+                if !ids.insert(id.trim().to_string()) {
+                    problems.push((line_no, format!("duplicate message {}", id.trim())));
+                }
+                in_entry = true;
+            }
+            _ => {
+                problems.push((line_no, "expected a message or a term".to_string()));
+                in_entry = false;
+            }
+        }
+    }
+
+    problems
+}
+
+/// Checks that quoted strings are closed and each `msgid` has a `msgstr`.
+fn validate_gettext(content: &str) -> Vec<(usize, String)> {
+    let mut problems = Vec::new();
+    let mut pending_msgid = None;
+
+    for (index, line) in content.lines().enumerate() {
+        let line_no = index + 1;
+        let line = line.trim();
+
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let (keyword, value) = match line.split_once(' ') {
+            Some((k, v)) if !k.starts_with('"') => (k, v.trim()),
+            _ => ("", line),
+        };
+
+        if value.len() < 2 || !value.starts_with('"') || !value.ends_with('"') {
+            problems.push((line_no, "expected a quoted string".to_string()));
+            continue;
+        }
+
+        match keyword {
+            "msgid" => {
+                if let Some(m) = pending_msgid.replace(line_no) {
+                    problems.push((m, "msgid has no msgstr".to_string()));
+                }
+            }
+            "msgctxt" | "msgid_plural" | "" => {}
+            m if m == "msgstr" || m.starts_with("msgstr[") => {
+                pending_msgid = None;
+            }
+            m => problems.push((line_no, format!("unknown keyword {m}"))),
+        }
+    }
+
+    if let Some(m) = pending_msgid {
+        problems.push((m, "msgid has no msgstr".to_string()));
+    }
+
+    problems
+}
+
+/// Validates all translation files, returns a description of each problem.
+pub(crate) fn validate(files: &[LocaleFile]) -> Result<Vec<String>> {
+    let mut problems = Vec::new();
+
+    for file in files {
+        let content = fs::read_to_string(&file.path)
+            .with_context(|| format!("failed to read {}", file.path.display()))?;
+
+        let file_problems = match file.path.extension().and_then(|m| m.to_str()) {
+            Some("po") => validate_gettext(&content),
+            _ => validate_fluent(&content),
+        };
+
+        for (line_no, m) in file_problems {
+            problems.push(format!("{}:{}: {}", file.path.display(), line_no, m));
+        }
+    }
+
+    Ok(problems)
+}
+
+/// Copies translation files into `locales/` in the frontend build directory with hashed file
+/// names and writes the locale manifest.
+///
+/// URLs in the manifest start with the public URL of the frontend.
+pub(crate) fn write_locales(
+    files: &[LocaleFile],
+    frontend_build_dir: &Path,
+    public_url: &str,
+    i18n: &I18n,
+) -> Result<LocaleManifest> {
+    let target_dir = frontend_build_dir.join("locales");
+    let _ = fs::remove_dir_all(&target_dir);
+
+    let mut locales = BTreeMap::<String, Vec<String>>::new();
+
+    for file in files {
+        let content = fs::read(&file.path)
+            .with_context(|| format!("failed to read {}", file.path.display()))?;
+
+        let digest = Sha256::digest(&content);
+        let hash = digest[..8]
+            .iter()
+            .map(|m| format!("{:02x}", m))
+            .collect::<String>();
+
+        let stem = file.path.file_stem().unwrap_or_default().to_string_lossy();
+        let ext = file.path.extension().unwrap_or_default().to_string_lossy();
+        let file_name = format!("{stem}-{hash}.{ext}");
+
+        fs::create_dir_all(target_dir.join(&file.locale))?;
+        fs::write(target_dir.join(&file.locale).join(&file_name), content)
+            .context("failed to write translation file")?;
+
+        locales
+            .entry(file.locale.clone())
+            .or_default()
+            .push(format!(
+                "{}/locales/{}/{}",
+                public_url.trim_end_matches('/'),
+                file.locale,
+                file_name
+            ));
+    }
+
+    if let Some(ref m) = i18n.default_locale {
+        if !locales.contains_key(m) {
+            bail!("default locale {} has no translation files", m);
+        }
+    }
+
+    let mut html_attrs = BTreeMap::new();
+    for name in locales.keys() {
+        let mut attrs = HtmlAttrs::for_locale(name);
+        if let Some(m) = i18n.locale.get(name) {
+            attrs.lang = m.lang.clone().unwrap_or(attrs.lang);
+            attrs.dir = m.dir.unwrap_or(attrs.dir);
+        }
+
+        html_attrs.insert(name.to_owned(), attrs);
+    }
+    if let Some(m) = i18n.locale.keys().find(|m| !locales.contains_key(*m)) {
+        bail!("locale {} in [i18n.locale] has no translation files", m);
+    }
+
+    let manifest = LocaleManifest {
+        default_locale: i18n.default_locale.clone(),
+        locales,
+        html_attrs,
+    };
+
+    fs::write(
+        frontend_build_dir.join(LocaleManifest::FILE_NAME),
+        manifest.to_json()?,
+    )
+    .context("failed to write locale manifest")?;
+
+    Ok(manifest)
+}
@@ -1,14 +1,592 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::time::Duration;
+
 use serde::{Deserialize, Serialize};
+use stackable_core::images::ImageFormat;
+use stackable_core::locales::TextDirection;
+use stackable_core::observability::ObservabilityConfig;
+use stackable_core::server::ServerConfig;
 
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub(crate) struct DevServer {
     pub listen: String,
     pub bin_name: String,
+    /// The path of the websocket that notifies the browser to refresh.
+    #[serde(default)]
+    pub refresh_path: Option<String>,
+    /// Announces the development server on the local network as `<bin-name>.local` with mDNS.
+    #[serde(default)]
+    pub mdns: bool,
+    /// The path that responds successfully once the server is ready.
+    #[serde(default = "DevServer::default_health_path")]
+    pub health_path: String,
+    /// How long to wait for the server to become ready, e.g.: `30s`.
+    #[serde(default = "DevServer::default_startup_timeout", with = "duration")]
+    pub startup_timeout: Duration,
+    /// Renders pages twice on the server and the browser and reports differences in an overlay.
+    #[serde(default)]
+    pub strict_mode: bool,
+    /// How `serve --open` opens the browser.
+    #[serde(default)]
+    pub open: OpenBrowser,
+    /// The number of consecutive crashes after which the server is not restarted until a file
+    /// changes.
+    #[serde(default = "DevServer::default_max_restarts")]
+    pub max_restarts: u32,
+    /// The number of successful builds that are kept for `stackctl rollback`.
+    ///
+    /// Each build is copied, so the history is disabled by default.
+    #[serde(default)]
+    pub history_size: usize,
+}
+
+/// The browser opened by `serve --open`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct OpenBrowser {
+    /// The command that opens the url, e.g.: `firefox` or `open -a Safari`.
+    ///
+    /// If omitted, the default browser is opened.
+    pub command: Option<String>,
+    /// The path to open, e.g.: `/dashboard`.
+    #[serde(default = "OpenBrowser::default_path")]
+    pub path: String,
+}
+
+impl OpenBrowser {
+    fn default_path() -> String {
+        "/".to_string()
+    }
+}
+
+impl DevServer {
+    fn default_health_path() -> String {
+        "/".to_string()
+    }
+
+    fn default_startup_timeout() -> Duration {
+        Duration::from_secs(60)
+    }
+
+    fn default_max_restarts() -> u32 {
+        5
+    }
+}
+
+/// Durations written as `500ms`, `30s`, `2m` or `1h`, or a number of seconds.
+mod duration {
+    use std::time::Duration;
+
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    fn parse(s: &str) -> Option<Duration> {
+        let s = s.trim();
+        let (value, unit) = s.split_at(s.find(|m: char| !m.is_ascii_digit()).unwrap_or(s.len()));
+        let value = value.parse::<u64>().ok()?;
+
+        match unit.trim() {
+            "ms" => Some(Duration::from_millis(value)),
+            "" | "s" => Some(Duration::from_secs(value)),
+            "m" => Some(Duration::from_secs(value * 60)),
+            "h" => Some(Duration::from_secs(value * 60 * 60)),
+            _ => None,
+        }
+    }
+
+    pub fn serialize<S>(value: &Duration, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match value.subsec_millis() {
+            0 => serializer.serialize_str(&format!("{}s", value.as_secs())),
+            _ => serializer.serialize_str(&format!("{}ms", value.as_millis())),
+        }
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Duration, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Value {
+            Secs(u64),
+            Text(String),
+        }
+
+        match Value::deserialize(deserializer)? {
+            Value::Secs(m) => Ok(Duration::from_secs(m)),
+            Value::Text(m) => {
+                parse(&m).ok_or_else(|| D::Error::custom(format!("invalid duration {m}")))
+            }
+        }
+    }
+}
+
+/// A frontend in `[frontend.<name>]`, built into `<name>` in the frontend build directory.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct Frontend {
+    /// The path of `index.html`, relative to the workspace directory.
+    pub index_html: PathBuf,
+    /// The path prefix that the server serves this frontend at.
+    #[serde(default = "Frontend::default_mount_path")]
+    pub mount_path: String,
+}
+
+impl Frontend {
+    fn default_mount_path() -> String {
+        "/".to_string()
+    }
+}
+
+/// A CSS toolchain that runs before trunk, read from `[build.frontend.css]`.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct Css {
+    /// The command to run in the workspace directory, e.g.: `["npx", "postcss", "-o", "out.css"]`.
+    ///
+    /// If omitted, the tailwindcss binary compiles `input` into `output`.
+    #[serde(default)]
+    pub command: Option<Vec<String>>,
+    #[serde(default)]
+    pub input: Option<PathBuf>,
+    /// The stylesheet that `index.html` links to.
+    pub output: PathBuf,
+    /// Files and directories that the CSS step reads, relative to the workspace directory.
+    ///
+    /// Defaults to `input`.
+    #[serde(default)]
+    pub watch: Vec<PathBuf>,
+}
+
+impl Css {
+    /// Returns the paths that only require the CSS step and the frontend to be rebuilt.
+    pub fn watch_paths(&self) -> Vec<PathBuf> {
+        match self.watch.is_empty() {
+            true => self.input.iter().cloned().collect(),
+            false => self.watch.clone(),
+        }
+    }
+}
+
+/// The cargo features of one side, passed to every cargo and trunk command that builds it.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", default)]
+pub(crate) struct CargoFeatures {
+    pub features: Vec<String>,
+    pub no_default_features: bool,
+}
+
+impl CargoFeatures {
+    /// Returns `true` if the default features of cargo are used.
+    pub fn is_default(&self) -> bool {
+        self.features.is_empty() && !self.no_default_features
+    }
+
+    /// Returns the arguments of cargo and trunk, e.g.: `--features hydrate`.
+    pub fn to_arguments(&self) -> Vec<String> {
+        let mut args = Vec::new();
+
+        if !self.features.is_empty() {
+            args.push("--features".to_string());
+            args.push(self.features.join(","));
+        }
+        if self.no_default_features {
+            args.push("--no-default-features".to_string());
+        }
+
+        args
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct BuildFrontend {
+    #[serde(default)]
+    pub css: Option<Css>,
+    /// The directory whose files are copied verbatim into the frontend mounted at `/`, relative
+    /// to the workspace directory.
+    #[serde(default = "BuildFrontend::default_public_dir")]
+    pub public_dir: PathBuf,
+    /// Keys of the env files that frontends are built with, e.g.: the public keys of
+    /// `stackable_core::config!`.
+    ///
+    /// Other keys are not passed to frontend builds, as their values can be compiled into the
+    /// frontend.
+    #[serde(default)]
+    pub public_env: Vec<String>,
+    /// Overrides `data-cargo-features` in `index.html` if set.
+    #[serde(flatten)]
+    pub cargo: CargoFeatures,
+}
+
+impl BuildFrontend {
+    fn default_public_dir() -> PathBuf {
+        "public".into()
+    }
+}
+
+impl Default for BuildFrontend {
+    fn default() -> Self {
+        Self {
+            css: None,
+            public_dir: Self::default_public_dir(),
+            public_env: Vec::new(),
+            cargo: CargoFeatures::default(),
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct BuildBackend {
+    #[serde(flatten)]
+    pub cargo: CargoFeatures,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct Build {
+    #[serde(default)]
+    pub frontend: BuildFrontend,
+    #[serde(default)]
+    pub backend: BuildBackend,
+    #[serde(default)]
+    pub preload: Preload,
+    #[serde(default)]
+    pub images: Images,
+    #[serde(default)]
+    pub pwa: Pwa,
+    /// The default of `--offline`.
+    #[serde(default)]
+    pub offline: bool,
+    /// The default of `--locked`.
+    #[serde(default)]
+    pub locked: bool,
+}
+
+/// Conversion of images in the public directory, read from `[build.images]`.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", default)]
+pub(crate) struct Images {
+    /// Converts images during `stackctl build`, the development server serves the originals.
+    pub enabled: bool,
+    /// The formats that images are converted to, in the order of preference.
+    pub formats: Vec<ImageFormat>,
+    /// The widths in pixels that images are resized to.
+    pub widths: Vec<u32>,
+    /// The quality of lossy encoding, from 1 to 100.
+    pub quality: u8,
+}
+
+impl Default for Images {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            formats: vec![ImageFormat::Avif, ImageFormat::Webp],
+            widths: vec![640, 1280, 1920],
+            quality: 80,
+        }
+    }
+}
+
+/// An icon of the web manifest, read from `[[build.pwa.icons]]`.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct PwaIcon {
+    /// The path relative to the frontend build directory, e.g.: `icons/192.png` of the public
+    /// directory.
+    pub src: String,
+    /// e.g.: `192x192`.
+    pub sizes: String,
+    #[serde(default)]
+    pub purpose: Option<String>,
+}
+
+/// A service worker and a web manifest for each frontend, read from `[build.pwa]`.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", default)]
+pub(crate) struct Pwa {
+    /// Generates them during `stackctl build`, the development server serves a service worker
+    /// that unregisters itself.
+    pub enabled: bool,
+    /// The name of the application, required if enabled.
+    pub name: Option<String>,
+    pub short_name: Option<String>,
+    pub description: Option<String>,
+    pub display: String,
+    pub theme_color: Option<String>,
+    pub background_color: Option<String>,
+    pub icons: Vec<PwaIcon>,
+    /// Assets that are not precached, relative to the frontend build directory and may contain
+    /// `*`.
+    pub exclude: Vec<String>,
+}
+
+impl Default for Pwa {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            name: None,
+            short_name: None,
+            description: None,
+            display: "standalone".to_string(),
+            theme_color: None,
+            background_color: None,
+            icons: Vec::new(),
+            exclude: Vec::new(),
+        }
+    }
+}
+
+/// Preloading of the assets of pages, read from `[build.preload]`.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", default)]
+pub(crate) struct Preload {
+    /// Writes the preload manifest that the server preloads assets of pages with.
+    pub enabled: bool,
+    /// Assets of routes in addition to the wasm module, its glue and stylesheets, e.g.:
+    /// `"/posts/:id" = ["fonts/*.woff2"]`.
+    ///
+    /// Paths are relative to the frontend build directory and may contain `*`.
+    pub routes: BTreeMap<String, Vec<String>>,
+}
+
+impl Default for Preload {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            routes: BTreeMap::new(),
+        }
+    }
+}
+
+/// The attributes of `<html>` for a locale, read from `[i18n.locale.<name>]`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", default)]
+pub(crate) struct Locale {
+    /// Defaults to the name of the locale.
+    pub lang: Option<String>,
+    /// Defaults to the direction of the language of the locale.
+    pub dir: Option<TextDirection>,
+}
+
+/// `sitemap.xml` and `robots.txt`, read from `[seo]`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", default)]
+pub(crate) struct Seo {
+    /// Writes them into the frontend mounted at `/` during `stackctl build`.
+    pub enabled: bool,
+    /// The origin that URLs of the sitemap start with, e.g.: `https://example.com`, required if
+    /// enabled.
+    pub base_url: Option<String>,
+    /// Routes that are not listed in the sitemap, in the syntax of yew-router, e.g.:
+    /// `/admin/*path`.
+    pub exclude: Vec<String>,
+    /// Paths of routes with parameters to list in the sitemap, e.g.: `/posts/1`.
+    pub paths: Vec<String>,
+    /// Paths that crawlers are asked not to visit in `robots.txt`.
+    pub disallow: Vec<String>,
+}
+
+/// Localisation, read from `[i18n]`.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", default)]
+pub(crate) struct I18n {
+    /// Copies translation files into the frontend build directory.
+    pub enabled: bool,
+    /// The directory of Fluent and gettext files, relative to the workspace directory.
+    pub dir: PathBuf,
+    pub default_locale: Option<String>,
+    pub locale: BTreeMap<String, Locale>,
+}
+
+impl Default for I18n {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            dir: "locales".into(),
+            default_locale: None,
+            locale: BTreeMap::new(),
+        }
+    }
+}
+
+/// An event of the development server that notifications are sent for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum NotificationEvent {
+    BuildSucceeded,
+    BuildFailed,
+    ServerReady,
+    TestsFailed,
+}
+
+/// The format of the payload of a webhook.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum WebhookFormat {
+    /// `{"text": "..."}`, also accepted by Mattermost and Google Chat.
+    #[default]
+    Slack,
+    /// `{"content": "..."}`.
+    Discord,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct Webhook {
+    pub url: String,
+    #[serde(default)]
+    pub format: WebhookFormat,
+}
+
+/// Notifications sent by `stackctl serve`, read from `[notifications]`.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", default)]
+pub(crate) struct Notifications {
+    /// Shows desktop notifications with the notifier of the operating system.
+    pub desktop: bool,
+    pub webhooks: Vec<Webhook>,
+    /// The events to notify, defaults to all events.
+    pub events: Vec<NotificationEvent>,
+}
+
+impl Default for Notifications {
+    fn default() -> Self {
+        Self {
+            desktop: false,
+            webhooks: Vec::new(),
+            events: vec![
+                NotificationEvent::BuildSucceeded,
+                NotificationEvent::BuildFailed,
+                NotificationEvent::ServerReady,
+                NotificationEvent::TestsFailed,
+            ],
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct Release {
+    /// The path to the ed25519 key that `stackctl build --sign` signs checksums with.
+    ///
+    /// Relative paths are resolved from the workspace directory.
+    #[serde(default)]
+    pub signing_key: Option<PathBuf>,
+    /// The number of seconds that frontend assets of previous builds are served after a deploy.
+    ///
+    /// Browsers with an older frontend can load its chunks during this window.
+    #[serde(default)]
+    pub asset_grace_period: u64,
+}
+
+fn default_e2e_ready_path() -> String {
+    "/".to_string()
+}
+
+fn default_e2e_startup_timeout() -> u64 {
+    60
+}
+
+/// End-to-end tests, read from `[e2e]`.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct E2e {
+    /// The test command and its arguments, e.g.: `["npx", "playwright", "test"]`.
+    #[serde(default)]
+    pub command: Vec<String>,
+    /// The path that is requested until the server responds, before tests start.
+    #[serde(default = "default_e2e_ready_path")]
+    pub ready_path: String,
+    /// The number of seconds to wait for the server to respond.
+    #[serde(default = "default_e2e_startup_timeout")]
+    pub startup_timeout: u64,
+}
+
+impl Default for E2e {
+    fn default() -> Self {
+        Self {
+            command: Vec::new(),
+            ready_path: default_e2e_ready_path(),
+            startup_timeout: default_e2e_startup_timeout(),
+        }
+    }
+}
+
+fn default_bench_routes() -> Vec<String> {
+    vec!["/".to_string()]
+}
+
+fn default_bench_duration() -> u64 {
+    10
+}
+
+fn default_bench_concurrency() -> usize {
+    32
+}
+
+/// Benchmarks of `stackctl bench`, read from `[bench]`.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct Bench {
+    /// The routes that are requested, e.g.: `["/", "/about"]`.
+    #[serde(default = "default_bench_routes")]
+    pub routes: Vec<String>,
+    /// The number of seconds that routes are requested for.
+    #[serde(default = "default_bench_duration")]
+    pub duration: u64,
+    /// The number of connections that send requests at the same time.
+    #[serde(default = "default_bench_concurrency")]
+    pub concurrency: usize,
+    /// The path that is requested until the server responds, before the benchmark starts.
+    #[serde(default = "default_e2e_ready_path")]
+    pub ready_path: String,
+    /// The number of seconds to wait for the server to respond.
+    #[serde(default = "default_e2e_startup_timeout")]
+    pub startup_timeout: u64,
+}
+
+impl Default for Bench {
+    fn default() -> Self {
+        Self {
+            routes: default_bench_routes(),
+            duration: default_bench_duration(),
+            concurrency: default_bench_concurrency(),
+            ready_path: default_e2e_ready_path(),
+            startup_timeout: default_e2e_startup_timeout(),
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub(crate) struct Manifest {
     pub dev_server: DevServer,
+    /// If omitted, a single frontend is built from `index.html` in the workspace directory.
+    #[serde(default)]
+    pub frontend: BTreeMap<String, Frontend>,
+    #[serde(default)]
+    pub build: Build,
+    #[serde(default)]
+    pub server: ServerConfig,
+    #[serde(default)]
+    pub observability: ObservabilityConfig,
+    #[serde(default)]
+    pub release: Release,
+    #[serde(default)]
+    pub i18n: I18n,
+    #[serde(default)]
+    pub notifications: Notifications,
+    #[serde(default)]
+    pub e2e: E2e,
+    #[serde(default)]
+    pub bench: Bench,
+    #[serde(default)]
+    pub seo: Seo,
 }
@@ -0,0 +1,66 @@
+use std::net::IpAddr;
+
+use anyhow::{Context, Result};
+use mdns_sd::{ServiceDaemon, ServiceInfo};
+
+const SERVICE_TYPE: &str = "_http._tcp.local.";
+
+/// Announces the development server with mDNS until it is dropped.
+pub(crate) struct MdnsAnnouncement {
+    daemon: ServiceDaemon,
+    host_name: String,
+    port: u16,
+}
+
+impl MdnsAnnouncement {
+    /// Registers a http service at `<name>.local`.
+    pub fn new(name: &str, ip: IpAddr, port: u16) -> Result<Self> {
+        // Host names can only contain letters, digits and hyphens.
+        let name = name
+            .chars()
+            .map(|m| match m {
+                'a'..='z' | '0'..='9' => m,
+                'A'..='Z' => m.to_ascii_lowercase(),
+                _ => '-',
+            })
+            .collect::<String>();
+        let host_name = format!("{}.local.", name);
+
+        let daemon = ServiceDaemon::new().context("failed to start mDNS daemon")?;
+        let service = ServiceInfo::new(
+            SERVICE_TYPE,
+            &name,
+            &host_name,
+            ip,
+            port,
+            &[("path", "/")][..],
+        )
+        .context("failed to create mDNS service")?;
+
+        daemon
+            .register(service)
+            .context("failed to register mDNS service")?;
+
+        Ok(Self {
+            daemon,
+            host_name,
+            port,
+        })
+    }
+
+    /// Returns the url of the announced service.
+    pub fn url(&self) -> String {
+        format!(
+            "http://{}:{}/",
+            self.host_name.trim_end_matches('.'),
+            self.port
+        )
+    }
+}
+
+impl Drop for MdnsAnnouncement {
+    fn drop(&mut self) {
+        // Other machines are notified that the service is gone.
+        let _ = self.daemon.shutdown();
+    }
+}
@@ -0,0 +1,120 @@
+//! Mocks of bridge queries and mutations for `stackctl serve --mock` and recordings for
+//! `stackctl serve --record` and `--replay`.
+//!
+//! Each file in the mocks directory is a list of `[[mock]]` entries in TOML or JSON, e.g.:
+//!
+//! ```toml
+//! [[mock]]
+//! name = "UserQuery"
+//! input = { id = 1 }
+//! output = { id = 1, name = "Alice" }
+//! ```
+//!
+//! Recordings are written by the server to `mocks/recordings/<name>.json` in the same format.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use stackable_core::mock::{MockFile, Mocks};
+
+/// The directory of mocks, relative to the workspace.
+pub(crate) const MOCKS_DIR: &str = "mocks";
+/// The directory of recordings, relative to the mocks directory.
+const RECORDINGS_DIR: &str = "recordings";
+
+fn load_file(path: &Path) -> Result<Option<MockFile>> {
+    let ext = match path.extension().and_then(|m| m.to_str()) {
+        Some(m @ ("toml" | "json")) => m,
+        _ => return Ok(None),
+    };
+
+    let content =
+        fs::read_to_string(path).with_context(|| format!("failed to read {}", path.display()))?;
+
+    let file = match ext {
+        "toml" => toml::from_str(&content).map_err(anyhow::Error::from),
+        _ => serde_json::from_str(&content).map_err(anyhow::Error::from),
+    }
+    .with_context(|| format!("failed to parse {}", path.display()))?;
+
+    Ok(Some(file))
+}
+
+/// Loads the mocks in a directory, in the order of their file names.
+pub(crate) fn load(dir: &Path) -> Result<Mocks> {
+    if !dir.is_dir() {
+        bail!(
+            "failed to find mocks, `--mock` reads mocks from {}",
+            dir.display()
+        );
+    }
+
+    let mut paths = fs::read_dir(dir)
+        .with_context(|| format!("failed to read {}", dir.display()))?
+        .map(|m| m.map(|m| m.path()))
+        .collect::<std::io::Result<Vec<_>>>()?;
+    paths.sort();
+
+    let mut mocks = Vec::new();
+    for path in paths {
+        if let Some(m) = load_file(&path)? {
+            mocks.extend(m.mocks);
+        }
+    }
+
+    Ok(Mocks::new(mocks))
+}
+
+/// Returns the directory of recordings in a workspace.
+pub(crate) fn recordings_dir(workspace_dir: &Path) -> PathBuf {
+    workspace_dir.join(MOCKS_DIR).join(RECORDINGS_DIR)
+}
+
+/// Returns the path of a recording by its name.
+pub(crate) fn recording_path(workspace_dir: &Path, name: &str) -> Result<PathBuf> {
+    if name.is_empty() || name.contains(['/', '\\']) || name.starts_with('.') {
+        bail!("invalid recording name: {:?}", name);
+    }
+
+    Ok(recordings_dir(workspace_dir).join(format!("{name}.json")))
+}
+
+/// Loads a recording, requests that are not in the recording fail when it is replayed.
+pub(crate) fn load_recording(path: &Path) -> Result<Mocks> {
+    if !path.exists() {
+        bail!(
+            "failed to find recording {}, record it with `stackctl serve --record`",
+            path.display()
+        );
+    }
+
+    let file = load_file(path)?.unwrap_or_default();
+
+    Ok(Mocks::new(file.mocks).with_strict())
+}
+
+/// Returns the names of recordings and their results.
+pub(crate) fn recordings(workspace_dir: &Path) -> Result<Vec<(String, MockFile)>> {
+    let dir = recordings_dir(workspace_dir);
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut recordings = Vec::new();
+    for entry in fs::read_dir(&dir).with_context(|| format!("failed to read {}", dir.display()))? {
+        let path = entry?.path();
+
+        let name = match path.file_stem().and_then(|m| m.to_str()) {
+            Some(m) if path.extension().map(|m| m == "json").unwrap_or(false) => m.to_string(),
+            _ => continue,
+        };
+
+        if let Some(m) = load_file(&path)? {
+            recordings.push((name, m));
+        }
+    }
+    recordings.sort_by(|a, b| a.0.cmp(&b.0));
+
+    Ok(recordings)
+}
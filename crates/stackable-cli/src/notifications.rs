@@ -0,0 +1,139 @@
+//! Desktop notifications and webhooks sent by the development server, so builds can run in the
+//! background.
+//!
+//! Notifications are sent in the background and failures are only logged, they never interrupt
+//! the development server.
+
+use std::process::Stdio;
+use std::sync::Arc;
+
+use tokio::process::Command;
+
+use crate::manifest::{Manifest, NotificationEvent, WebhookFormat};
+
+impl NotificationEvent {
+    fn title(&self) -> &'static str {
+        match self {
+            Self::BuildSucceeded => "Build succeeded",
+            Self::BuildFailed => "Build failed",
+            Self::ServerReady => "Server is ready",
+            Self::TestsFailed => "Tests failed",
+        }
+    }
+}
+
+/// Returns the command that shows a desktop notification on this operating system.
+fn desktop_command(title: &str, message: &str) -> Option<Command> {
+    let mut cmd;
+
+    if cfg!(target_os = "macos") {
+        // Strings are passed as arguments, so they are never parsed as AppleScript.
+        cmd = Command::new("osascript");
+        cmd.arg("-e")
+            .arg("on run argv")
+            .arg("-e")
+            .arg("display notification (item 2 of argv) with title (item 1 of argv)")
+            .arg("-e")
+            .arg("end run")
+            .arg(title)
+            .arg(message);
+    } else if cfg!(windows) {
+        cmd = Command::new("powershell");
+        cmd.arg("-NoProfile")
+            .arg("-Command")
+            .arg(
+                "[reflection.assembly]::loadwithpartialname('System.Windows.Forms') | Out-Null; \
+                 $n = New-Object System.Windows.Forms.NotifyIcon; \
+                 $n.Icon = [System.Drawing.SystemIcons]::Information; \
+                 $n.Visible = $true; \
+                 $n.ShowBalloonTip(5000, $env:STACKCTL_TITLE, $env:STACKCTL_MESSAGE, 'None'); \
+                 Start-Sleep -Seconds 5; $n.Dispose()",
+            )
+            .env("STACKCTL_TITLE", title)
+            .env("STACKCTL_MESSAGE", message);
+    } else if cfg!(unix) {
+        cmd = Command::new("notify-send");
+        cmd.arg("--app-name=stackctl").arg(title).arg(message);
+    } else {
+        return None;
+    }
+
+    cmd.stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+
+    Some(cmd)
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Notifier {
+    manifest: Arc<Manifest>,
+    client: reqwest::Client,
+}
+
+impl Notifier {
+    pub fn new(manifest: Arc<Manifest>) -> Self {
+        Self {
+            manifest,
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// Sends notifications of an event, if it is enabled in `[notifications]`.
+    pub fn notify<S>(&self, event: NotificationEvent, message: S)
+    where
+        S: Into<String>,
+    {
+        let config = &self.manifest.notifications;
+        if !config.events.contains(&event) || (!config.desktop && config.webhooks.is_empty()) {
+            return;
+        }
+
+        let notifier = self.clone();
+        let message = message.into();
+
+        tokio::spawn(async move {
+            notifier.send(event, &message).await;
+        });
+    }
+
+    async fn send(&self, event: NotificationEvent, message: &str) {
+        let config = &self.manifest.notifications;
+        let title = format!("{}: {}", self.manifest.dev_server.bin_name, event.title());
+
+        if config.desktop {
+            match desktop_command(&title, message) {
+                Some(mut m) => {
+                    if let Err(e) = m.status().await {
+                        tracing::warn!("failed to show desktop notification: {}", e);
+                    }
+                }
+                None => tracing::warn!("desktop notifications are not supported on this platform"),
+            }
+        }
+
+        for webhook in config.webhooks.iter() {
+            let text = format!("{title}\n{message}");
+            let payload = match webhook.format {
+                WebhookFormat::Slack => serde_json::json!({ "text": text }),
+                WebhookFormat::Discord => serde_json::json!({ "content": text }),
+            };
+
+            let result = self
+                .client
+                .post(&webhook.url)
+                .header("content-type", "application/json")
+                .body(payload.to_string())
+                .send()
+                .await
+                .and_then(|m| m.error_for_status());
+
+            if let Err(e) = result {
+                tracing::warn!(
+                    "failed to send notification to webhook: {}",
+                    e.without_url()
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,169 @@
+//! Versioned archives of build artifacts, created by `stackctl package`.
+//!
+//! Archives are named `{name}-v{version}-{platform}` and contain a single directory with the
+//! same name, so they can be extracted side by side.
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use zip::write::FileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+use crate::utils::{collect_files, relative_path};
+
+pub(crate) const CHANGELOG_FILE_NAME: &str = "CHANGELOG.md";
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ArchiveFormat {
+    /// A gzip compressed tarball.
+    TarGz,
+    /// A zip archive.
+    Zip,
+}
+
+impl ArchiveFormat {
+    /// Returns the conventional format of the target, zip on Windows and tar.gz elsewhere.
+    pub fn for_target(target: &str) -> Self {
+        if target.contains("windows") {
+            Self::Zip
+        } else {
+            Self::TarGz
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::TarGz => "tar.gz",
+            Self::Zip => "zip",
+        }
+    }
+}
+
+/// Returns a short name of the target triple, e.g.: `x86_64-linux` for
+/// `x86_64-unknown-linux-gnu`.
+///
+/// Targets that cannot be shortened are returned as is.
+pub(crate) fn platform_name(target: &str) -> String {
+    let arch = target.split('-').next().unwrap_or(target);
+
+    let os = if target.contains("linux") && target.ends_with("musl") {
+        "linux-musl"
+    } else if target.contains("linux") {
+        "linux"
+    } else if target.contains("darwin") {
+        "macos"
+    } else if target.contains("windows") {
+        "windows"
+    } else {
+        return target.to_string();
+    };
+
+    format!("{arch}-{os}")
+}
+
+pub(crate) fn archive_name(name: &str, version: &str, target: &str) -> String {
+    format!("{name}-v{version}-{}", platform_name(target))
+}
+
+/// Returns `true` if a heading of the changelog mentions the version.
+pub(crate) fn changelog_has_version(changelog: &str, version: &str) -> bool {
+    changelog
+        .lines()
+        .filter(|m| m.starts_with('#'))
+        .any(|m| m.contains(version))
+}
+
+/// Returns the files of an archive and their names in the archive.
+///
+/// Files are placed in a directory named `root` in the archive, extra files at the top of it.
+fn files(build_dir: &Path, extra_files: &[PathBuf], root: &str) -> Result<Vec<(PathBuf, String)>> {
+    let mut entries = Vec::new();
+    collect_files(build_dir, &mut entries).context("failed to list artifacts")?;
+    entries.sort();
+
+    let mut files = Vec::with_capacity(entries.len() + extra_files.len());
+    for path in entries {
+        let name = format!("{root}/{}", relative_path(&path, build_dir)?);
+        files.push((path, name));
+    }
+    for path in extra_files {
+        let file_name = path
+            .file_name()
+            .context("extra file has no name")?
+            .to_string_lossy();
+        files.push((path.clone(), format!("{root}/{file_name}")));
+    }
+
+    Ok(files)
+}
+
+/// Returns the names of the files that an archive would contain.
+pub(crate) fn archive_entries(
+    build_dir: &Path,
+    extra_files: &[PathBuf],
+    root: &str,
+) -> Result<Vec<String>> {
+    Ok(files(build_dir, extra_files, root)?
+        .into_iter()
+        .map(|m| m.1)
+        .collect())
+}
+
+/// Writes all files in the build directory and extra files into an archive.
+pub(crate) fn create_archive(
+    format: ArchiveFormat,
+    build_dir: &Path,
+    extra_files: &[PathBuf],
+    root: &str,
+    archive_path: &Path,
+) -> Result<()> {
+    let files = files(build_dir, extra_files, root)?;
+
+    let archive = BufWriter::new(
+        File::create(archive_path)
+            .with_context(|| format!("failed to create {}", archive_path.display()))?,
+    );
+
+    match format {
+        ArchiveFormat::TarGz => {
+            let mut builder = tar::Builder::new(GzEncoder::new(archive, Compression::default()));
+
+            for (path, name) in files {
+                builder
+                    .append_path_with_name(&path, name)
+                    .with_context(|| format!("failed to add {} to archive", path.display()))?;
+            }
+
+            builder.into_inner()?.finish()?.flush()?;
+        }
+        ArchiveFormat::Zip => {
+            let mut writer = ZipWriter::new(archive);
+
+            for (path, name) in files {
+                let options =
+                    FileOptions::default().compression_method(CompressionMethod::Deflated);
+
+                // Keeps the server binary executable.
+                #[cfg(unix)]
+                let options = {
+                    use std::os::unix::fs::PermissionsExt;
+                    options.unix_permissions(std::fs::metadata(&path)?.permissions().mode())
+                };
+
+                writer.start_file(name, options)?;
+                let mut file = File::open(&path)
+                    .with_context(|| format!("failed to read {}", path.display()))?;
+                io::copy(&mut file, &mut writer)?;
+            }
+
+            writer.finish()?.flush()?;
+        }
+    }
+
+    Ok(())
+}
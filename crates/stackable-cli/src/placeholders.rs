@@ -0,0 +1,69 @@
+//! Placeholders of `index.html` that are substituted before the frontend is built, e.g.:
+//! `{{ public_url }}`, `{{ build_id }}` and `{{ env.API_URL }}`.
+//!
+//! Trunk resolves assets relative to `index.html`, so the rendered copy is written next to it.
+//! Placeholders without a value are kept and substituted by the server when it renders a page.
+
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use stackable_core::index_html::{substitute_placeholders, ENV_PREFIX};
+
+/// The suffix of rendered copies of `index.html`, which are not changes of the workspace.
+pub(crate) const RENDERED_SUFFIX: &str = ".stackable.html";
+
+/// A rendered copy of `index.html`, it is removed when dropped.
+#[derive(Debug)]
+pub(crate) struct RenderedIndexHtml {
+    path: PathBuf,
+}
+
+impl RenderedIndexHtml {
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for RenderedIndexHtml {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// Renders the placeholders of an `index.html`, returns `None` if no placeholder has a value.
+///
+/// Environment variables are read from env files first, then from the environment of stackctl.
+pub(crate) fn render(
+    index_html: &Path,
+    public_url: &str,
+    build_id: Option<&str>,
+    envs: &HashMap<String, String>,
+) -> Result<Option<RenderedIndexHtml>> {
+    let content = fs::read_to_string(index_html)
+        .with_context(|| format!("failed to read {}", index_html.display()))?;
+
+    let rendered = substitute_placeholders(&content, |name| match name {
+        "public_url" => Some(public_url.to_string()),
+        "build_id" => build_id.map(|m| m.to_string()),
+        m => {
+            let name = m.strip_prefix(ENV_PREFIX)?;
+            envs.get(name).cloned().or_else(|| env::var(name).ok())
+        }
+    });
+    if rendered == content {
+        return Ok(None);
+    }
+
+    let stem = index_html
+        .file_stem()
+        .and_then(|m| m.to_str())
+        .unwrap_or("index");
+    let path = index_html.with_file_name(format!(".{stem}{RENDERED_SUFFIX}"));
+
+    fs::write(&path, rendered).with_context(|| format!("failed to write {}", path.display()))?;
+
+    Ok(Some(RenderedIndexHtml { path }))
+}
@@ -0,0 +1,217 @@
+//! The preload manifest of a frontend, written by `stackctl build` before assets of previous
+//! builds are copied back.
+//!
+//! Trunk builds a single wasm module, its JavaScript glue and stylesheets for each frontend,
+//! which every page requires. Other assets are assigned to routes in `[build.preload.routes]`.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use console::style;
+use stackable_core::preload::{PreloadAsset, PreloadKind, PreloadManifest, RoutePreload};
+
+use crate::utils::{collect_files, relative_path};
+
+/// Returns the value of an attribute of a html tag.
+pub(crate) fn attr<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let lower = tag.to_ascii_lowercase();
+    let mut offset = 0;
+
+    while let Some(i) = lower[offset..].find(name) {
+        let start = offset + i;
+        offset = start + name.len();
+
+        // The name must not be a part of another attribute, e.g.: `data-href`.
+        let is_start = tag[..start]
+            .chars()
+            .next_back()
+            .map(|m| m.is_whitespace())
+            .unwrap_or(false);
+        let rest = tag[offset..].trim_start();
+        if !is_start || !rest.starts_with('=') {
+            continue;
+        }
+
+        let rest = rest[1..].trim_start();
+        return match rest.chars().next()? {
+            quote @ ('"' | '\'') => rest[1..].split(quote).next(),
+            _ => rest.split(|m: char| m.is_whitespace() || m == '>').next(),
+        };
+    }
+
+    None
+}
+
+/// Returns the assets that a built `index.html` loads, which are required by every page.
+fn entry_assets(index_html: &str) -> Vec<PreloadAsset> {
+    let mut assets = Vec::new();
+
+    for (i, _) in index_html.match_indices("<link") {
+        let tag = match index_html[i..].split_once('>') {
+            Some((m, _)) => m,
+            None => continue,
+        };
+
+        let rel = attr(tag, "rel").unwrap_or_default().to_ascii_lowercase();
+        let href = match attr(tag, "href") {
+            Some(m) if ["preload", "modulepreload", "stylesheet"].contains(&rel.as_str()) => m,
+            _ => continue,
+        };
+
+        if assets.iter().any(|m: &PreloadAsset| m.href == href) {
+            continue;
+        }
+
+        let kind = match (rel.as_str(), attr(tag, "as")) {
+            ("modulepreload", _) => Some(PreloadKind::Script),
+            ("stylesheet", _) => Some(PreloadKind::Style),
+            (_, Some("style")) => Some(PreloadKind::Style),
+            (_, Some("font")) => Some(PreloadKind::Font),
+            (_, Some("image")) => Some(PreloadKind::Image),
+            _ => PreloadKind::from_path(href),
+        };
+
+        if let Some(kind) = kind {
+            assets.push(PreloadAsset {
+                href: href.to_string(),
+                kind,
+            });
+        }
+    }
+
+    assets
+}
+
+/// Returns the reasons that browsers cannot compile the wasm module of a frontend while it is
+/// downloaded, which delays the start of the frontend.
+///
+/// The JavaScript glue of wasm-bindgen only compiles with `WebAssembly.instantiateStreaming` if
+/// it is generated for the web target, and the server responds with `application/wasm`.
+pub(crate) fn streaming_problems(build_dir: &Path, public_url: &str) -> Result<Vec<String>> {
+    let index_html = fs::read_to_string(build_dir.join("index.html"))
+        .context("failed to read index.html of frontend build")?;
+
+    let mut problems = Vec::new();
+    let public_url = public_url.trim_end_matches('/');
+
+    let wasm_href = match entry_assets(&index_html)
+        .into_iter()
+        .find(|m| m.kind == PreloadKind::Wasm)
+    {
+        Some(m) => m.href,
+        None => {
+            problems.push("index.html does not load a wasm module".to_string());
+            return Ok(problems);
+        }
+    };
+
+    // The glue of `app_bg.wasm` is `app.js`.
+    let glue_path = wasm_href
+        .strip_prefix(public_url)
+        .unwrap_or(&wasm_href)
+        .trim_start_matches('/')
+        .replace("_bg.wasm", ".js");
+    match fs::read_to_string(build_dir.join(&glue_path)) {
+        Ok(m) if !m.contains("instantiateStreaming") => problems.push(format!(
+            "{glue_path} does not use WebAssembly.instantiateStreaming, wasm-bindgen may not \
+             target the web"
+        )),
+        Ok(_) => {}
+        Err(_) => problems.push(format!("the JavaScript glue {glue_path} is not found")),
+    }
+
+    Ok(problems)
+}
+
+/// Returns `true` if a path matches a glob pattern, `*` matches any characters except `/`.
+pub(crate) fn glob_matches(pattern: &str, path: &str) -> bool {
+    match pattern.split_once('*') {
+        Some((prefix, rest)) => {
+            let path = match path.strip_prefix(prefix) {
+                Some(m) => m,
+                None => return false,
+            };
+
+            path.char_indices()
+                .map(|(i, _)| i)
+                .chain([path.len()])
+                .take_while(|i| !path[..*i].contains('/'))
+                .any(|i| glob_matches(rest, &path[i..]))
+        }
+        None => pattern == path,
+    }
+}
+
+/// Writes the preload manifest of a frontend that is built into `build_dir`.
+///
+/// `routes` are the routes of `[build.preload.routes]` that belong to this frontend, with the
+/// paths of their assets relative to the frontend build directory.
+pub(crate) fn write_manifest(
+    build_dir: &Path,
+    public_url: &str,
+    routes: &BTreeMap<&str, &[String]>,
+) -> Result<PreloadManifest> {
+    let index_html = fs::read_to_string(build_dir.join("index.html"))
+        .context("failed to read index.html of frontend build")?;
+
+    let mut files = Vec::new();
+    collect_files(build_dir, &mut files).context("failed to list frontend assets")?;
+    let mut files = files
+        .iter()
+        .map(|m| relative_path(m, build_dir))
+        .collect::<Result<Vec<_>>>()?;
+    files.sort();
+
+    let mut manifest = PreloadManifest {
+        entry: entry_assets(&index_html),
+        routes: Vec::new(),
+    };
+
+    for (route, patterns) in routes.iter() {
+        let mut assets = Vec::new();
+
+        for pattern in patterns.iter() {
+            let pattern = pattern.trim_start_matches('/');
+            let matched = files
+                .iter()
+                .filter(|m| glob_matches(pattern, m))
+                .filter_map(|m| {
+                    Some(PreloadAsset {
+                        href: format!("{}/{}", public_url.trim_end_matches('/'), m),
+                        kind: PreloadKind::from_path(m)?,
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            if matched.is_empty() {
+                eprintln!(
+                    "{} no asset matches {} of preload route {}",
+                    style("warning:").yellow().bold(),
+                    pattern,
+                    route
+                );
+            }
+            assets.extend(matched);
+        }
+
+        manifest.routes.push(RoutePreload {
+            route: route.to_string(),
+            assets,
+        });
+    }
+
+    // A static route is preferred over a route with parameters that matches the same path.
+    manifest
+        .routes
+        .sort_by_key(|m| m.route.contains([':', '*']));
+
+    fs::write(
+        build_dir.join(PreloadManifest::FILE_NAME),
+        manifest.to_json()?,
+    )
+    .context("failed to write preload manifest")?;
+
+    Ok(manifest)
+}
@@ -1,3 +1,7 @@
+use std::collections::HashMap;
+
+use toml::Value;
+
 #[derive(Debug)]
 pub(crate) struct Profile {
     name: String,
@@ -22,10 +26,37 @@ impl Profile {
         }
     }
 
+    /// Creates a profile with the name of a cargo profile.
+    pub fn new<S>(name: S) -> Self
+    where
+        S: Into<String>,
+    {
+        let name = name.into();
+
+        match name.as_str() {
+            "dev" | "debug" => Self::new_debug(),
+            _ => Self { name },
+        }
+    }
+
     pub fn name(&self) -> &str {
         &self.name
     }
 
+    /// Returns `true` if this is neither the debug nor the release profile.
+    pub fn is_custom(&self) -> bool {
+        !matches!(self.name(), "debug" | "release")
+    }
+
+    /// Returns the name of the directory in the target directory that contains the artifacts.
+    pub fn target_dir_name(&self) -> &str {
+        match self.name() {
+            "test" => "debug",
+            "bench" => "release",
+            m => m,
+        }
+    }
+
     pub fn to_profile_argument(&self) -> Option<String> {
         match self.name() {
             "debug" => None,
@@ -33,4 +64,61 @@ impl Profile {
             other => Some(format!("--profile={}", other)),
         }
     }
+
+    /// Returns the argument and environment variables to build the frontend with trunk.
+    ///
+    /// Trunk can only build with the dev or release profile, so the settings of a custom profile
+    /// are applied to the profile it inherits from with `CARGO_PROFILE_*` environment variables.
+    /// `profiles` is the `[profile]` table of the workspace manifest.
+    pub fn to_trunk_arguments(
+        &self,
+        profiles: Option<&Value>,
+    ) -> (Option<&'static str>, HashMap<String, String>) {
+        let mut envs = HashMap::new();
+
+        match self.name() {
+            "debug" => return (None, envs),
+            "release" => return (Some("--release"), envs),
+            _ => {}
+        }
+
+        let profile = profiles
+            .and_then(|m| m.get(self.name()))
+            .and_then(|m| m.as_table());
+
+        let inherits = profile
+            .and_then(|m| m.get("inherits"))
+            .and_then(|m| m.as_str())
+            .unwrap_or("release");
+
+        let (arg, base_name) = match inherits {
+            "dev" | "debug" | "test" => (None, "DEV"),
+            _ => (Some("--release"), "RELEASE"),
+        };
+
+        for (key, value) in profile.into_iter().flatten() {
+            let value = match value {
+                Value::String(m) => m.to_string(),
+                Value::Integer(m) => m.to_string(),
+                Value::Boolean(m) => m.to_string(),
+                // Nested tables (package, build-override) are not supported.
+                _ => continue,
+            };
+
+            if key == "inherits" {
+                continue;
+            }
+
+            envs.insert(
+                format!(
+                    "CARGO_PROFILE_{}_{}",
+                    base_name,
+                    key.replace('-', "_").to_uppercase()
+                ),
+                value,
+            );
+        }
+
+        (arg, envs)
+    }
 }
@@ -0,0 +1,45 @@
+//! The public directory, whose files are copied verbatim into the frontend mounted at `/`, e.g.:
+//! `public/robots.txt` is served at `/robots.txt`.
+//!
+//! Trunk replaces the frontend build directory, so files are copied after every frontend build.
+//! The development server copies them again without a rebuild when only they have changed.
+
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use console::style;
+
+use crate::assets::is_asset;
+use crate::utils::{collect_files, relative_path};
+
+/// Copies files of the public directory into a frontend build directory.
+///
+/// Files of the public directory replace files of previous copies, but never `index.html` or
+/// manifests written by stackctl.
+pub(crate) fn copy_dir(public_dir: &Path, build_dir: &Path) -> Result<()> {
+    let mut files = Vec::new();
+    collect_files(public_dir, &mut files).context("failed to list public files")?;
+
+    for path in files {
+        let rel_path = relative_path(&path, public_dir)?;
+
+        if !is_asset(&rel_path) {
+            eprintln!(
+                "{} {} of the public directory is not copied, it is written by the build",
+                style("warning:").yellow().bold(),
+                rel_path
+            );
+            continue;
+        }
+
+        let target = build_dir.join(&rel_path);
+        if let Some(m) = target.parent() {
+            fs::create_dir_all(m)?;
+        }
+        fs::copy(&path, &target)
+            .with_context(|| format!("failed to copy public file {}", rel_path))?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,170 @@
+//! The service worker and the web manifest of a frontend, generated by `stackctl build` if
+//! `[build.pwa]` is enabled.
+//!
+//! The service worker precaches the assets of the current build and serves them from the cache.
+//! Pages are rendered by the server, so they are never cached.
+
+use std::fs;
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use serde_json::json;
+use stackable_core::build_info::BuildInfo;
+use stackable_core::pwa::{CACHE_PREFIX, SERVICE_WORKER_FILE_NAME, WEB_MANIFEST_FILE_NAME};
+
+use crate::assets::is_asset;
+use crate::manifest::Pwa;
+use crate::preload::glob_matches;
+use crate::utils::{collect_files, relative_path};
+
+fn service_worker(cache_name: &str, cache_prefix: &str, urls: &[String]) -> Result<String> {
+    let cache_name = serde_json::to_string(cache_name)?;
+    let cache_prefix = serde_json::to_string(cache_prefix)?;
+    let urls = serde_json::to_string_pretty(urls)?;
+
+    Ok(format!(
+        r#"const CACHE_NAME = {cache_name};
+const PRECACHE_URLS = {urls};
+
+self.addEventListener('install', (e) => {{
+    e.waitUntil((async () => {{
+        const cache = await caches.open(CACHE_NAME);
+        await cache.addAll(PRECACHE_URLS);
+        await self.skipWaiting();
+    }})());
+}});
+
+self.addEventListener('activate', (e) => {{
+    e.waitUntil((async () => {{
+        const keys = await caches.keys();
+        await Promise.all(
+            keys.filter((m) => m.startsWith({cache_prefix}) && m !== CACHE_NAME)
+                .map((m) => caches.delete(m))
+        );
+        await self.clients.claim();
+    }})());
+}});
+
+self.addEventListener('fetch', (e) => {{
+    if (e.request.method !== 'GET') {{
+        return;
+    }}
+
+    e.respondWith((async () => {{
+        const cached = await caches.match(e.request, {{ cacheName: CACHE_NAME }});
+        return cached || fetch(e.request);
+    }})());
+}});
+"#
+    ))
+}
+
+/// Returns the elements that link the web manifest and register the service worker.
+fn head_tags(public_url: &str, config: &Pwa) -> String {
+    let mut s = format!(r#"<link rel="manifest" href="{public_url}{WEB_MANIFEST_FILE_NAME}">"#);
+
+    if let Some(ref m) = config.theme_color {
+        s.push_str(&format!(r#"<meta name="theme-color" content="{m}">"#));
+    }
+
+    s.push_str(&format!(
+        r#"<script>if ('serviceWorker' in navigator) {{ navigator.serviceWorker.register('{public_url}{SERVICE_WORKER_FILE_NAME}', {{ scope: '{public_url}' }}); }}</script>"#
+    ));
+
+    s
+}
+
+/// Writes the web manifest and the service worker of a frontend that is built into `build_dir`,
+/// and registers them in its `index.html`.
+///
+/// This must run before assets of previous builds are copied back, so only assets of the current
+/// build are precached.
+pub(crate) fn write_files(
+    build_dir: &Path,
+    public_url: &str,
+    build_info: &BuildInfo,
+    config: &Pwa,
+) -> Result<()> {
+    let public_url = format!("{}/", public_url.trim_end_matches('/'));
+    let name = config.name.as_deref().unwrap_or_default();
+
+    let mut web_manifest = json!({
+        "name": name,
+        "short_name": config.short_name.as_deref().unwrap_or(name),
+        "start_url": public_url,
+        "scope": public_url,
+        "display": config.display,
+        "icons": config
+            .icons
+            .iter()
+            .map(|m| {
+                let mut icon = json!({
+                    "src": format!("{}{}", public_url, m.src.trim_start_matches('/')),
+                    "sizes": m.sizes,
+                });
+                if let Some(ref purpose) = m.purpose {
+                    icon["purpose"] = json!(purpose);
+                }
+                icon
+            })
+            .collect::<Vec<_>>(),
+    });
+    for (key, value) in [
+        ("description", config.description.as_ref()),
+        ("theme_color", config.theme_color.as_ref()),
+        ("background_color", config.background_color.as_ref()),
+    ] {
+        if let Some(m) = value {
+            web_manifest[key] = json!(m);
+        }
+    }
+    fs::write(
+        build_dir.join(WEB_MANIFEST_FILE_NAME),
+        serde_json::to_string_pretty(&web_manifest)?,
+    )
+    .context("failed to write web manifest")?;
+
+    let mut files = Vec::new();
+    collect_files(build_dir, &mut files).context("failed to list frontend assets")?;
+    let mut urls = Vec::new();
+    for path in files {
+        let rel_path = relative_path(&path, build_dir)?;
+
+        if !is_asset(&rel_path)
+            || rel_path == SERVICE_WORKER_FILE_NAME
+            || config
+                .exclude
+                .iter()
+                .any(|m| glob_matches(m.trim_start_matches('/'), &rel_path))
+        {
+            continue;
+        }
+
+        urls.push(format!("{public_url}{rel_path}"));
+    }
+    urls.sort();
+
+    // Each frontend only removes caches of its own scope.
+    let cache_prefix = format!("{CACHE_PREFIX}{public_url}-");
+    let cache_name = format!("{cache_prefix}{}", build_info.build_id);
+    fs::write(
+        build_dir.join(SERVICE_WORKER_FILE_NAME),
+        service_worker(&cache_name, &cache_prefix, &urls)?,
+    )
+    .context("failed to write service worker")?;
+
+    let index_html_path = build_dir.join("index.html");
+    let index_html = fs::read_to_string(&index_html_path).context("failed to read index.html")?;
+    let index_html = match index_html.find("</head>") {
+        Some(i) => format!(
+            "{}{}{}",
+            &index_html[..i],
+            head_tags(&public_url, config),
+            &index_html[i..]
+        ),
+        None => bail!("index.html does not have a </head>"),
+    };
+    fs::write(&index_html_path, index_html).context("failed to write index.html")?;
+
+    Ok(())
+}
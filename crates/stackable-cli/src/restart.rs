@@ -0,0 +1,56 @@
+//! Restarts of the development server after it exits.
+//!
+//! Restarts back off exponentially, so a server that exits immediately, e.g.: if its database is
+//! not running, does not keep the CPU busy. After `dev-server.max-restarts` consecutive crashes,
+//! the server is only started again when a file changes.
+
+use std::time::Duration;
+
+const BASE_DELAY: Duration = Duration::from_millis(500);
+const MAX_DELAY: Duration = Duration::from_secs(30);
+
+/// A server that has run for this long is considered stable, so its next exit is not counted
+/// as part of a crash loop.
+const STABLE_UPTIME: Duration = Duration::from_secs(30);
+
+#[derive(Debug)]
+pub(crate) struct RestartPolicy {
+    max_restarts: u32,
+    crashes: u32,
+}
+
+impl RestartPolicy {
+    pub fn new(max_restarts: u32) -> Self {
+        Self {
+            max_restarts,
+            crashes: 0,
+        }
+    }
+
+    /// The number of consecutive crashes.
+    pub fn crashes(&self) -> u32 {
+        self.crashes
+    }
+
+    /// Records that the server has exited after running for `uptime`.
+    ///
+    /// Returns the delay before the server is restarted, or `None` if it is crash-looping.
+    pub fn record_exit(&mut self, uptime: Duration) -> Option<Duration> {
+        if uptime >= STABLE_UPTIME {
+            self.crashes = 0;
+        }
+        self.crashes += 1;
+
+        if self.crashes > self.max_restarts {
+            return None;
+        }
+
+        let delay = BASE_DELAY.saturating_mul(1 << (self.crashes - 1).min(16));
+        Some(delay.min(MAX_DELAY))
+    }
+
+    /// Forgets previous crashes, e.g.: after the server is rebuilt.
+    pub fn reset(&mut self) {
+        self.crashes = 0;
+    }
+}
@@ -0,0 +1,97 @@
+//! Collisions between the API routes of the backend and the routes of frontend routers, checked
+//! by the development server.
+//!
+//! Page routes are read from `#[at("...")]` attributes in the sources of a frontend, routes that
+//! are only known at runtime are not checked.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use stackable_core::preload::route_matches;
+
+/// A route of a frontend router.
+#[derive(Debug)]
+pub(crate) struct PageRoute {
+    /// The path pattern, prefixed with the mount path of the frontend, e.g.: `/admin/users/:id`.
+    pub path: String,
+    pub file: PathBuf,
+}
+
+/// Returns the paths in `#[at("...")]` attributes of a source file.
+fn at_paths(source: &str) -> Vec<&str> {
+    source
+        .match_indices("#[at(")
+        .filter_map(|(i, m)| {
+            let rest = source[i + m.len()..].trim_start().strip_prefix('"')?;
+            rest.split_once('"').map(|(path, _)| path)
+        })
+        .collect()
+}
+
+fn collect(dir: &Path, mount_path: &str, routes: &mut Vec<PageRoute>) -> Result<()> {
+    for entry in fs::read_dir(dir).with_context(|| format!("failed to read {}", dir.display()))? {
+        let path = entry?.path();
+
+        if path.is_dir() {
+            collect(&path, mount_path, routes)?;
+        } else if path.extension().map(|m| m == "rs").unwrap_or(false) {
+            let source = fs::read_to_string(&path)
+                .with_context(|| format!("failed to read {}", path.display()))?;
+
+            for m in at_paths(&source) {
+                routes.push(PageRoute {
+                    path: format!(
+                        "{}/{}",
+                        mount_path.trim_end_matches('/'),
+                        m.trim_matches('/')
+                    ),
+                    file: path.clone(),
+                });
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the page routes in the sources of a frontend that is mounted at `mount_path`.
+pub(crate) fn page_routes(src_dir: &Path, mount_path: &str) -> Result<Vec<PageRoute>> {
+    let mut routes = Vec::new();
+    collect(src_dir, mount_path, &mut routes)?;
+
+    Ok(routes)
+}
+
+/// Returns the API routes that collide with page routes, with the page route that each of them
+/// collides with.
+///
+/// Catch-all routes at the root of a frontend, e.g.: `/*path` for a not found page, match every
+/// API route, so they are not reported.
+pub(crate) fn collisions<'a>(
+    api_routes: &'a [String],
+    page_routes: &'a [PageRoute],
+    mount_path: &str,
+) -> Vec<(&'a str, &'a PageRoute)> {
+    let mount_path = mount_path.trim_end_matches('/');
+
+    let mut found = Vec::new();
+    for page in page_routes {
+        let is_catch_all = page
+            .path
+            .strip_prefix(mount_path)
+            .map(|m| m.trim_start_matches('/').starts_with('*'))
+            .unwrap_or(false);
+        if is_catch_all {
+            continue;
+        }
+
+        for api in api_routes {
+            if route_matches(&page.path, api) {
+                found.push((api.as_str(), page));
+            }
+        }
+    }
+
+    found
+}
@@ -0,0 +1,254 @@
+//! Updates stackctl to the latest release, performed by `stackctl self update`.
+//!
+//! Binaries are downloaded from the GitHub release of the latest version, each release has an
+//! archive per platform and a `SHA256SUMS` file in the format of `sha256sum`. The latest
+//! version is looked up on crates.io if GitHub cannot be reached, which is enough for `--check`.
+
+use std::env;
+use std::fs;
+use std::io::{Cursor, Read};
+use std::path::Path;
+
+use anyhow::{anyhow, bail, Context, Result};
+use cargo_metadata::semver::Version;
+use console::style;
+use flate2::read::GzDecoder;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+use crate::cli::SelfUpdateCommand;
+use crate::signing::CHECKSUMS_FILE_NAME;
+
+const GITHUB_REPOSITORY: &str = "futursolo/stackable";
+const CRATE_NAME: &str = "stackable-cli";
+
+#[derive(Debug, Deserialize)]
+struct GithubAsset {
+    name: String,
+    browser_download_url: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GithubRelease {
+    tag_name: String,
+    assets: Vec<GithubAsset>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CratesIoCrate {
+    max_stable_version: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct CratesIoResponse {
+    #[serde(rename = "crate")]
+    krate: CratesIoCrate,
+}
+
+/// Returns the target triple that release binaries are built for on this platform.
+fn target() -> Option<&'static str> {
+    let target = match (env::consts::ARCH, env::consts::OS) {
+        ("x86_64", "linux") => "x86_64-unknown-linux-gnu",
+        ("aarch64", "linux") => "aarch64-unknown-linux-gnu",
+        ("x86_64", "macos") => "x86_64-apple-darwin",
+        ("aarch64", "macos") => "aarch64-apple-darwin",
+        ("x86_64", "windows") => "x86_64-pc-windows-msvc",
+        _ => return None,
+    };
+
+    Some(target)
+}
+
+/// Returns the name of the release archive of this platform, e.g.:
+/// `stackctl-x86_64-unknown-linux-gnu.tar.gz`.
+fn archive_name(target: &str) -> String {
+    match cfg!(windows) {
+        true => format!("stackctl-{target}.zip"),
+        false => format!("stackctl-{target}.tar.gz"),
+    }
+}
+
+fn parse_version(s: &str) -> Result<Version> {
+    let s = s.trim_start_matches('v');
+    Version::parse(s).with_context(|| format!("{s} is not a valid version"))
+}
+
+async fn get(client: &reqwest::Client, url: &str) -> Result<reqwest::Response> {
+    client
+        .get(url)
+        .send()
+        .await
+        .and_then(|m| m.error_for_status())
+        .with_context(|| format!("failed to request {url}"))
+}
+
+async fn latest_github_release(client: &reqwest::Client) -> Result<GithubRelease> {
+    let url = format!("https://api.github.com/repos/{GITHUB_REPOSITORY}/releases/latest");
+    let body = get(client, &url).await?.text().await?;
+
+    serde_json::from_str(&body).context("failed to parse the latest release")
+}
+
+async fn latest_crates_io_version(client: &reqwest::Client) -> Result<Version> {
+    let url = format!("https://crates.io/api/v1/crates/{CRATE_NAME}");
+    let body = get(client, &url).await?.text().await?;
+    let resp: CratesIoResponse =
+        serde_json::from_str(&body).context("failed to parse the crate on crates.io")?;
+
+    parse_version(&resp.krate.max_stable_version)
+}
+
+/// Returns the checksum of a file in `SHA256SUMS`.
+fn find_checksum<'a>(checksums: &'a str, file_name: &str) -> Option<&'a str> {
+    checksums.lines().find_map(|m| {
+        let (checksum, name) = m.split_once(char::is_whitespace)?;
+        // `sha256sum` marks files read in binary mode with `*`.
+        let name = name.trim_start().trim_start_matches('*');
+
+        (name == file_name).then_some(checksum)
+    })
+}
+
+/// Returns the stackctl binary in a release archive.
+fn extract_binary(archive: &[u8]) -> Result<Vec<u8>> {
+    let mut binary = Vec::new();
+
+    if cfg!(windows) {
+        let mut archive =
+            zip::ZipArchive::new(Cursor::new(archive)).context("failed to read archive")?;
+        archive
+            .by_name("stackctl.exe")
+            .context("archive does not contain stackctl.exe")?
+            .read_to_end(&mut binary)?;
+
+        return Ok(binary);
+    }
+
+    let mut archive = tar::Archive::new(GzDecoder::new(archive));
+    for entry in archive.entries().context("failed to read archive")? {
+        let mut entry = entry?;
+        if entry.path()?.file_name().and_then(|m| m.to_str()) == Some("stackctl") {
+            entry.read_to_end(&mut binary)?;
+
+            return Ok(binary);
+        }
+    }
+
+    bail!("archive does not contain stackctl")
+}
+
+/// Replaces the running binary.
+///
+/// The new binary is written next to the current one and renamed over it, Windows does not
+/// allow a running binary to be overwritten, but it can be renamed.
+fn replace_binary(exe_path: &Path, binary: &[u8]) -> Result<()> {
+    let new_path = exe_path.with_extension("new");
+    let old_path = exe_path.with_extension("old");
+
+    fs::write(&new_path, binary)
+        .with_context(|| format!("failed to write {}", new_path.display()))?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&new_path, fs::Permissions::from_mode(0o755))?;
+    }
+
+    fs::rename(exe_path, &old_path)
+        .with_context(|| format!("failed to move {}", exe_path.display()))?;
+
+    if let Err(e) = fs::rename(&new_path, exe_path) {
+        // Puts the current binary back, so stackctl is never left missing.
+        let _ = fs::rename(&old_path, exe_path);
+        return Err(e).with_context(|| format!("failed to replace {}", exe_path.display()));
+    }
+
+    // The old binary is still running on Windows and is removed by the next update.
+    let _ = fs::remove_file(&old_path);
+
+    Ok(())
+}
+
+pub(crate) async fn run(cmd: &SelfUpdateCommand) -> Result<()> {
+    let current = parse_version(env!("CARGO_PKG_VERSION"))?;
+    let client = reqwest::ClientBuilder::default()
+        .user_agent(concat!("stackctl/", env!("CARGO_PKG_VERSION")))
+        .build()?;
+
+    let release = match latest_github_release(&client).await {
+        Ok(m) => Some(m),
+        Err(e) if cmd.check => {
+            tracing::warn!("{e:#}, looking up the latest version on crates.io");
+            None
+        }
+        Err(e) => return Err(e),
+    };
+
+    let latest = match release {
+        Some(ref m) => parse_version(&m.tag_name)?,
+        None => latest_crates_io_version(&client).await?,
+    };
+
+    if latest <= current {
+        eprintln!("stackctl {current} is up to date.");
+        return Ok(());
+    }
+
+    if cmd.check {
+        bail!(
+            "stackctl {current} is outdated, the latest version is {latest}, run \
+             `stackctl self update` to update it"
+        );
+    }
+
+    let release = release.expect("release is only missing in check mode");
+    let target = target().ok_or_else(|| {
+        anyhow!(
+            "no release binary is available for {}-{}, run `cargo install {CRATE_NAME}` \
+             instead",
+            env::consts::ARCH,
+            env::consts::OS
+        )
+    })?;
+    let archive_name = archive_name(target);
+
+    let find_asset = |name: &str| {
+        release
+            .assets
+            .iter()
+            .find(|m| m.name == name)
+            .ok_or_else(|| anyhow!("release {latest} does not contain {name}"))
+    };
+    let archive_asset = find_asset(&archive_name)?;
+    let checksums_asset = find_asset(CHECKSUMS_FILE_NAME)?;
+
+    eprintln!("Downloading stackctl {latest}...");
+
+    let checksums = get(&client, &checksums_asset.browser_download_url)
+        .await?
+        .text()
+        .await?;
+    let expected = find_checksum(&checksums, &archive_name)
+        .ok_or_else(|| anyhow!("{CHECKSUMS_FILE_NAME} does not contain {archive_name}"))?;
+
+    let archive = get(&client, &archive_asset.browser_download_url)
+        .await?
+        .bytes()
+        .await?;
+
+    let actual = format!("{:x}", Sha256::digest(&archive));
+    if !actual.eq_ignore_ascii_case(expected) {
+        bail!("checksum of {archive_name} does not match, expected {expected}, got {actual}");
+    }
+
+    let binary = extract_binary(&archive)?;
+    let exe_path = env::current_exe().context("failed to find the path of stackctl")?;
+    replace_binary(&exe_path, &binary)?;
+
+    eprintln!(
+        "{} stackctl {current} -> {latest}",
+        style("Updated").green().bold()
+    );
+
+    Ok(())
+}
@@ -0,0 +1,80 @@
+//! `sitemap.xml` and `robots.txt`, written into the frontend mounted at `/` by `stackctl build`
+//! if `[seo]` is enabled.
+//!
+//! Routes are read from the routers registered with `Renderer::routes`. Routes with parameters
+//! are only listed if their paths are added to `paths`.
+
+use stackable_core::preload::route_matches;
+
+use crate::manifest::Seo;
+
+fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// Returns the paths to list in the sitemap.
+///
+/// `routes` are path patterns prefixed with the mount path of their frontend, `not_found` are the
+/// not found routes of each frontend.
+pub(crate) fn public_paths(routes: &[String], not_found: &[String], config: &Seo) -> Vec<String> {
+    let mut paths = routes
+        .iter()
+        .filter(|m| !m.contains([':', '*']))
+        .chain(config.paths.iter())
+        .filter(|m| !not_found.contains(*m))
+        .filter(|m| {
+            !config
+                .exclude
+                .iter()
+                .any(|pattern| route_matches(pattern, m))
+        })
+        .map(|m| format!("/{}", m.trim_matches('/')))
+        .collect::<Vec<_>>();
+    paths.sort();
+    paths.dedup();
+
+    paths
+}
+
+/// Returns the content of `sitemap.xml`, `lastmod` is the date of the build.
+pub(crate) fn sitemap(base_url: &str, paths: &[String], lastmod: &str) -> String {
+    let base_url = base_url.trim_end_matches('/');
+
+    let mut s = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n",
+    );
+    for path in paths {
+        s.push_str(&format!(
+            "  <url>\n    <loc>{}</loc>\n    <lastmod>{}</lastmod>\n  </url>\n",
+            escape_xml(&format!("{base_url}{path}")),
+            lastmod
+        ));
+    }
+    s.push_str("</urlset>\n");
+
+    s
+}
+
+/// Returns the content of `robots.txt`, which links the sitemap.
+pub(crate) fn robots(base_url: &str, config: &Seo) -> String {
+    let mut s = String::from("User-agent: *\n");
+
+    if config.disallow.is_empty() {
+        s.push_str("Allow: /\n");
+    }
+    for m in config.disallow.iter() {
+        s.push_str(&format!("Disallow: {m}\n"));
+    }
+
+    s.push_str(&format!(
+        "\nSitemap: {}/sitemap.xml\n",
+        base_url.trim_end_matches('/')
+    ));
+
+    s
+}
@@ -0,0 +1,37 @@
+//! Renders the structured logs of the development server.
+
+use console::style;
+use stackable_core::dev::LogRecord;
+
+/// Renders a log record of the development server in the format of stackctl's own logs.
+pub(crate) fn render(record: &LogRecord) -> String {
+    let level = style(format!("{:>5}", record.level)).for_stderr().bold();
+    let level = match record.level.as_str() {
+        "ERROR" => level.red(),
+        "WARN" => level.yellow(),
+        "INFO" => level.green(),
+        "DEBUG" => level.blue(),
+        _ => level.magenta(),
+    };
+
+    let mut line = format!("{} {}", level, style(&record.target).for_stderr().dim());
+
+    for span in record.spans.iter() {
+        line.push_str(&format!(":{}", style(span).for_stderr().bold()));
+    }
+
+    line.push_str(&format!(": {}", record.message));
+
+    for (name, value) in record.fields.iter().filter(|(name, _)| name != "backtrace") {
+        line.push_str(&format!(" {}={}", style(name).for_stderr().italic(), value));
+    }
+
+    // Backtraces of panics, e.g.: of server-side rendering, are shown below the message.
+    for (_, value) in record.fields.iter().filter(|(name, _)| name == "backtrace") {
+        for m in value.lines() {
+            line.push_str(&format!("\n    {}", style(m).for_stderr().dim()));
+        }
+    }
+
+    line
+}
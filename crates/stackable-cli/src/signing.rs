@@ -0,0 +1,74 @@
+//! Checksums and signatures of build artifacts.
+//!
+//! Checksums are written to `SHA256SUMS` in the format of `sha256sum`, so they can be verified
+//! with `sha256sum -c SHA256SUMS`. The signature in `SHA256SUMS.sig` is the base64 encoded
+//! ed25519 signature of `SHA256SUMS`.
+
+use std::fs;
+use std::path::Path;
+
+use anyhow::{anyhow, Context, Result};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use ed25519_dalek::{Keypair, PublicKey, SecretKey, Signer};
+use sha2::{Digest, Sha256};
+
+use crate::utils::{collect_files, relative_path};
+
+pub(crate) const CHECKSUMS_FILE_NAME: &str = "SHA256SUMS";
+pub(crate) const SIGNATURE_FILE_NAME: &str = "SHA256SUMS.sig";
+
+/// Returns the checksums of all files in the build directory, sorted by path.
+pub(crate) fn checksums(build_dir: &Path) -> Result<String> {
+    let mut files = Vec::new();
+    collect_files(build_dir, &mut files).context("failed to list artifacts")?;
+    files.sort();
+
+    let mut s = String::new();
+
+    for path in files {
+        let rel_path = relative_path(&path, build_dir)?;
+
+        if rel_path == CHECKSUMS_FILE_NAME || rel_path == SIGNATURE_FILE_NAME {
+            continue;
+        }
+
+        let content =
+            fs::read(&path).with_context(|| format!("failed to read {}", path.display()))?;
+        let digest = Sha256::digest(content);
+
+        for b in digest {
+            s.push_str(&format!("{:02x}", b));
+        }
+
+        s.push_str("  ");
+        s.push_str(&rel_path);
+        s.push('\n');
+    }
+
+    Ok(s)
+}
+
+/// Signs the content with the ed25519 key in `key_path`.
+///
+/// The key file contains the base64 encoded 32-byte secret key. Returns the base64 encoded
+/// signature and public key.
+pub(crate) fn sign(key_path: &Path, content: &[u8]) -> Result<(String, String)> {
+    let key = fs::read_to_string(key_path)
+        .with_context(|| format!("failed to read signing key at {}", key_path.display()))?;
+    let key = STANDARD
+        .decode(key.trim())
+        .context("signing key is not valid base64")?;
+
+    let secret = SecretKey::from_bytes(&key)
+        .map_err(|e| anyhow!("signing key is not a valid ed25519 secret key: {}", e))?;
+    let public = PublicKey::from(&secret);
+    let keypair = Keypair { secret, public };
+
+    let signature = keypair.sign(content);
+
+    Ok((
+        STANDARD.encode(signature.to_bytes()),
+        STANDARD.encode(public.to_bytes()),
+    ))
+}
@@ -0,0 +1,126 @@
+//! The template engine of generators.
+//!
+//! Expressions are written as `{{ name }}` and may be followed by filters, e.g.:
+//! `{{ name | pascal_case }}`. A string literal, e.g.: `{{ "{{" }}`, is written as is.
+
+use std::collections::BTreeMap;
+
+use anyhow::{bail, Context, Result};
+
+/// Variables that are available to a template.
+pub(crate) type Variables = BTreeMap<String, String>;
+
+/// Splits a name into lowercase words, e.g.: `["user", "profile"]` for `UserProfile`,
+/// `user-profile` and `user_profile`.
+fn words(s: &str) -> Vec<String> {
+    let chars = s.chars().collect::<Vec<_>>();
+    let mut words = Vec::new();
+    let mut current = String::new();
+
+    for (i, c) in chars.iter().copied().enumerate() {
+        if !c.is_alphanumeric() {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            continue;
+        }
+
+        if c.is_uppercase() && !current.is_empty() {
+            let prev = chars[i - 1];
+            let next_is_lower = chars.get(i + 1).map(|m| m.is_lowercase()).unwrap_or(false);
+
+            // `HTTPServer` is split into `HTTP` and `Server`.
+            if !prev.is_uppercase() || next_is_lower {
+                words.push(std::mem::take(&mut current));
+            }
+        }
+
+        current.extend(c.to_lowercase());
+    }
+
+    if !current.is_empty() {
+        words.push(current);
+    }
+
+    words
+}
+
+fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(m) => m.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+fn apply_filter(name: &str, value: &str) -> Result<String> {
+    let words = words(value);
+
+    let value = match name {
+        "snake_case" => words.join("_"),
+        "kebab_case" => words.join("-"),
+        "screaming_snake_case" => words.join("_").to_uppercase(),
+        "pascal_case" => words.iter().map(|m| capitalize(m)).collect(),
+        "camel_case" => words
+            .iter()
+            .enumerate()
+            .map(|(i, m)| if i == 0 { m.clone() } else { capitalize(m) })
+            .collect(),
+        "title_case" => words
+            .iter()
+            .map(|m| capitalize(m))
+            .collect::<Vec<_>>()
+            .join(" "),
+        "lower" => value.to_lowercase(),
+        "upper" => value.to_uppercase(),
+        _ => bail!("unknown filter `{name}`"),
+    };
+
+    Ok(value)
+}
+
+fn evaluate(expr: &str, vars: &Variables) -> Result<String> {
+    let mut parts = expr.split('|').map(|m| m.trim());
+    let head = parts.next().unwrap_or_default();
+
+    let mut value = match head.strip_prefix('"').and_then(|m| m.strip_suffix('"')) {
+        Some(m) => m.to_string(),
+        None => vars
+            .get(head)
+            .cloned()
+            .with_context(|| format!("unknown variable `{head}`"))?,
+    };
+
+    for filter in parts {
+        value = apply_filter(filter, &value)?;
+    }
+
+    Ok(value)
+}
+
+/// Renders a template with variables.
+pub(crate) fn render(template: &str, vars: &Variables) -> Result<String> {
+    let mut output = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find("{{") {
+        output.push_str(&rest[..start]);
+
+        let offset = template.len() - rest.len() + start;
+        let line = template[..offset].matches('\n').count() + 1;
+        let after_start = &rest[start + 2..];
+        let end = after_start
+            .find("}}")
+            .with_context(|| format!("unclosed expression at line {line}"))?;
+
+        let value = evaluate(&after_start[..end], vars)
+            .with_context(|| format!("failed to render expression at line {line}"))?;
+        output.push_str(&value);
+
+        rest = &after_start[end + 2..];
+    }
+
+    output.push_str(rest);
+
+    Ok(output)
+}
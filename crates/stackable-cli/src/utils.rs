@@ -1,3 +1,6 @@
+use std::fs;
+use std::net::{IpAddr, UdpSocket};
+use std::path::{Path, PathBuf};
 use std::time::SystemTime;
 
 use anyhow::Result;
@@ -19,3 +22,88 @@ pub(crate) fn random_str() -> Result<String> {
         s
     ))
 }
+
+/// Returns a random secret of 32 alphanumeric characters.
+pub(crate) fn random_secret() -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(32)
+        .map(char::from)
+        .collect()
+}
+
+/// Collects all files in the directory and its subdirectories.
+pub(crate) fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+
+        if path.is_dir() {
+            collect_files(&path, files)?;
+        } else {
+            files.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the path relative to the directory, with forward slashes on all platforms.
+pub(crate) fn relative_path(path: &Path, dir: &Path) -> Result<String> {
+    Ok(path
+        .strip_prefix(dir)?
+        .components()
+        .map(|m| m.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/"))
+}
+
+/// Returns the address of this machine on the local network.
+///
+/// Connecting a UDP socket does not send any packet, it only selects the interface to use.
+pub(crate) fn network_ip() -> Option<IpAddr> {
+    let socket = UdpSocket::bind("0.0.0.0:0").ok()?;
+    socket.connect("8.8.8.8:80").ok()?;
+
+    socket
+        .local_addr()
+        .ok()
+        .map(|m| m.ip())
+        .filter(|m| !m.is_loopback() && !m.is_unspecified())
+}
+
+/// Returns the value of the first attribute with the name in `index.html`.
+fn index_html_attr<'a>(index_html: &'a str, name: &str) -> Option<&'a str> {
+    let (_, rest) = index_html.split_once(&format!("{name}=\""))?;
+
+    rest.split_once('"').map(|(m, _)| m)
+}
+
+/// Returns the binary name of the frontend, from the `data-bin` attribute in `index.html`.
+pub(crate) fn frontend_bin_name(index_html: &str) -> Option<&str> {
+    index_html_attr(index_html, "data-bin")
+}
+
+/// Returns the cargo features that trunk builds the frontend with, from the
+/// `data-cargo-features` attribute in `index.html`.
+pub(crate) fn frontend_cargo_features(index_html: &str) -> Option<&str> {
+    index_html_attr(index_html, "data-cargo-features")
+}
+
+/// Splits a listen address into its host and port.
+pub(crate) fn split_host_port(addr: &str) -> (&str, Option<&str>) {
+    match addr.rsplit_once(':') {
+        // An IPv6 address without a port.
+        Some((host, _)) if host.contains(':') && !host.ends_with(']') => (addr, None),
+        Some((host, port)) => (host, Some(port)),
+        None => (addr, None),
+    }
+}
+
+/// Returns `true` if the host accepts connections on all interfaces.
+pub(crate) fn is_unspecified_host(host: &str) -> bool {
+    host.trim_start_matches('[')
+        .trim_end_matches(']')
+        .parse::<IpAddr>()
+        .map(|m| m.is_unspecified())
+        .unwrap_or(false)
+}
@@ -0,0 +1,243 @@
+//! Checks that the tools of a build match the dependencies of the project, performed before
+//! builds and by `stackctl check`.
+//!
+//! Trunk runs the wasm-bindgen CLI with the version in `[tools]` of `Trunk.toml`, or the version
+//! in `Cargo.lock` if it is not set. Bindings generated by a different version than the
+//! `wasm-bindgen` crate fail at runtime with errors that do not mention versions.
+
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use console::style;
+
+use crate::exit::{ErrorKind, Failure};
+
+pub(crate) const TRUNK_CONFIG_FILE_NAME: &str = "Trunk.toml";
+const TRUNK_WASM_BINDGEN_ENV: &str = "TRUNK_TOOLS_WASM_BINDGEN";
+
+/// Packages that are broken by multiple versions in the same build, e.g.: contexts of one
+/// version of yew are not visible to components of another.
+const LOCKSTEP_PACKAGES: [&str; 2] = ["wasm-bindgen", "yew"];
+
+/// Where trunk reads the version of wasm-bindgen from.
+#[derive(Debug)]
+pub(crate) enum PinSource {
+    TrunkConfig(PathBuf),
+    Env,
+}
+
+#[derive(Debug)]
+pub(crate) enum VersionProblem {
+    /// Trunk would run a different version of wasm-bindgen than the one in the lockfile.
+    WasmBindgenMismatch {
+        locked: String,
+        pinned: String,
+        source: PinSource,
+    },
+    /// A package has multiple versions in the lockfile.
+    Duplicated {
+        package: &'static str,
+        versions: Vec<String>,
+    },
+}
+
+impl VersionProblem {
+    /// Returns `true` if `stackctl check --fix` can fix the problem.
+    pub fn is_fixable(&self) -> bool {
+        matches!(
+            self,
+            Self::WasmBindgenMismatch {
+                source: PinSource::TrunkConfig(_),
+                ..
+            }
+        )
+    }
+}
+
+impl fmt::Display for VersionProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::WasmBindgenMismatch {
+                locked,
+                pinned,
+                source,
+            } => {
+                let source = match source {
+                    PinSource::TrunkConfig(m) => format!("{}", m.display()),
+                    PinSource::Env => TRUNK_WASM_BINDGEN_ENV.to_string(),
+                };
+                write!(
+                    f,
+                    "trunk will run wasm-bindgen {pinned} (set in {source}), but Cargo.lock \
+                     has wasm-bindgen {locked}, run `stackctl check --fix` to use {locked}, or \
+                     `cargo update -p wasm-bindgen --precise {pinned}`"
+                )
+            }
+            Self::Duplicated { package, versions } => write!(
+                f,
+                "Cargo.lock has multiple versions of {package} ({}), run `cargo tree -i \
+                 {package}@<version>` to find the dependencies that require them",
+                versions.join(", ")
+            ),
+        }
+    }
+}
+
+/// Returns the versions of each package in a lockfile.
+fn locked_versions(lockfile: &str) -> Result<HashMap<String, BTreeSet<String>>> {
+    let lockfile: toml::Value = toml::from_str(lockfile)?;
+
+    let mut versions: HashMap<String, BTreeSet<String>> = HashMap::new();
+    for m in lockfile
+        .get("package")
+        .and_then(|m| m.as_array())
+        .into_iter()
+        .flatten()
+    {
+        if let (Some(name), Some(version)) = (
+            m.get("name").and_then(|m| m.as_str()),
+            m.get("version").and_then(|m| m.as_str()),
+        ) {
+            versions
+                .entry(name.to_string())
+                .or_default()
+                .insert(version.to_string());
+        }
+    }
+
+    Ok(versions)
+}
+
+/// Returns the version of wasm-bindgen set in `[tools]` of a trunk configuration.
+fn trunk_pinned_version(config: &str) -> Result<Option<String>> {
+    let config: toml::Value = toml::from_str(config)?;
+
+    Ok(config
+        .get("tools")
+        .and_then(|m| m.get("wasm_bindgen"))
+        .and_then(|m| m.as_str())
+        .map(|m| m.trim_start_matches('=').to_string()))
+}
+
+/// Checks the lockfile of a cargo workspace against the trunk configuration of a stackable
+/// workspace, `envs` are the environment variables that trunk is run with.
+pub(crate) async fn check(
+    lockfile_path: &Path,
+    workspace_dir: &Path,
+    envs: &HashMap<String, String>,
+) -> Result<Vec<VersionProblem>> {
+    // The lockfile is created by the first build.
+    let lockfile = match tokio::fs::read_to_string(lockfile_path).await {
+        Ok(m) => m,
+        Err(_) => return Ok(Vec::new()),
+    };
+    let versions = locked_versions(&lockfile)
+        .with_context(|| format!("failed to parse {}", lockfile_path.display()))?;
+
+    let mut problems = Vec::new();
+
+    for package in LOCKSTEP_PACKAGES {
+        if let Some(m) = versions.get(package).filter(|m| m.len() > 1) {
+            problems.push(VersionProblem::Duplicated {
+                package,
+                versions: m.iter().cloned().collect(),
+            });
+        }
+    }
+
+    let locked = match versions
+        .get("wasm-bindgen")
+        .and_then(|m| m.iter().next_back())
+    {
+        Some(m) => m.clone(),
+        None => return Ok(problems),
+    };
+
+    let config_path = workspace_dir.join(TRUNK_CONFIG_FILE_NAME);
+    let pinned = match envs
+        .get(TRUNK_WASM_BINDGEN_ENV)
+        .cloned()
+        .or_else(|| std::env::var(TRUNK_WASM_BINDGEN_ENV).ok())
+    {
+        Some(m) => Some((m.trim_start_matches('=').to_string(), PinSource::Env)),
+        None => match tokio::fs::read_to_string(&config_path).await {
+            Ok(m) => trunk_pinned_version(&m)
+                .with_context(|| format!("failed to parse {}", config_path.display()))?
+                .map(|m| (m, PinSource::TrunkConfig(config_path.clone()))),
+            Err(_) => None,
+        },
+    };
+
+    if let Some((pinned, source)) = pinned.filter(|(m, _)| *m != locked) {
+        problems.push(VersionProblem::WasmBindgenMismatch {
+            locked,
+            pinned,
+            source,
+        });
+    }
+
+    Ok(problems)
+}
+
+/// Pins the version of wasm-bindgen in a trunk configuration to the version in the lockfile.
+pub(crate) async fn fix(problem: &VersionProblem) -> Result<()> {
+    let (locked, config_path) = match problem {
+        VersionProblem::WasmBindgenMismatch {
+            locked,
+            source: PinSource::TrunkConfig(m),
+            ..
+        } => (locked, m),
+        _ => return Ok(()),
+    };
+
+    let config = tokio::fs::read_to_string(config_path)
+        .await
+        .with_context(|| format!("failed to read {}", config_path.display()))?;
+
+    // The file is edited by lines, so comments and formatting are kept.
+    let mut section = String::new();
+    let lines = config
+        .lines()
+        .map(|line| {
+            let trimmed = line.trim();
+            if trimmed.starts_with('[') {
+                section = trimmed
+                    .trim_matches(|m| m == '[' || m == ']')
+                    .trim()
+                    .to_string();
+            } else if section == "tools"
+                && trimmed
+                    .split_once('=')
+                    .map(|(key, _)| key.trim() == "wasm_bindgen")
+                    .unwrap_or(false)
+            {
+                return format!("wasm_bindgen = \"{locked}\"");
+            }
+
+            line.to_string()
+        })
+        .collect::<Vec<_>>();
+
+    tokio::fs::write(config_path, lines.join("\n") + "\n")
+        .await
+        .with_context(|| format!("failed to write {}", config_path.display()))?;
+
+    Ok(())
+}
+
+/// Prints the problems and fails if there are any.
+pub(crate) fn ensure_consistent(problems: &[VersionProblem]) -> Result<()> {
+    if problems.is_empty() {
+        return Ok(());
+    }
+
+    for m in problems.iter() {
+        eprintln!("{} {}", style("error:").red().bold(), m);
+    }
+    bail!(Failure::new(
+        ErrorKind::Config,
+        "versions of dependencies are inconsistent"
+    ));
+}
@@ -0,0 +1,164 @@
+//! Unit tests of the packages affected by a change, run by `stackctl serve --test` after each
+//! rebuild.
+//!
+//! Packages with `wasm-bindgen-test` as a dev-dependency are tested in the browser target with
+//! `wasm-bindgen-test-runner`, other packages are tested on the host.
+
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use cargo_metadata::{DependencyKind, Metadata};
+use console::style;
+use tokio::process::Command;
+
+use crate::exit::spawn_error;
+
+/// The target of packages that are tested with `wasm-bindgen-test-runner`.
+pub(crate) const WASM_TARGET: &str = "wasm32-unknown-unknown";
+
+/// A package whose unit tests are run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct TestPackage {
+    pub name: String,
+    /// Tested with `wasm-bindgen-test-runner` instead of on the host.
+    pub wasm: bool,
+}
+
+/// Returns the local packages that contain the paths, a path belongs to the package with the
+/// innermost directory.
+pub(crate) fn affected_packages(metadata: &Metadata, paths: &[PathBuf]) -> Vec<TestPackage> {
+    let local = metadata
+        .packages
+        .iter()
+        // Packages without a source are local, e.g.: path dependencies.
+        .filter(|m| m.source.is_none())
+        .filter_map(|m| Some((m, m.manifest_path.parent()?.as_std_path())))
+        .collect::<Vec<_>>();
+
+    let mut packages: Vec<TestPackage> = Vec::new();
+    for path in paths {
+        let package = local
+            .iter()
+            .filter(|(_, dir)| path.starts_with(dir))
+            .max_by_key(|(_, dir)| dir.components().count());
+
+        if let Some((m, _)) = package {
+            if packages.iter().any(|p| p.name == m.name) {
+                continue;
+            }
+
+            packages.push(TestPackage {
+                name: m.name.clone(),
+                wasm: m.dependencies.iter().any(|d| {
+                    d.name == "wasm-bindgen-test" && d.kind == DependencyKind::Development
+                }),
+            });
+        }
+    }
+
+    packages
+}
+
+/// The outcome of the unit tests of a package.
+#[derive(Debug)]
+pub(crate) struct TestOutcome {
+    pub package: String,
+    pub passed: u32,
+    pub failed: u32,
+    /// The output of cargo, if the tests have failed or failed to build.
+    pub output: Option<String>,
+}
+
+/// Sums the counts of the `test result:` lines of cargo test.
+fn count_results(output: &str) -> (u32, u32) {
+    let mut passed = 0;
+    let mut failed = 0;
+
+    for line in output
+        .lines()
+        .filter_map(|m| m.trim().strip_prefix("test result: "))
+    {
+        for part in line.split([';', '.']).map(|m| m.trim()) {
+            if let Some(m) = part.strip_suffix(" passed") {
+                passed += m.parse().unwrap_or(0);
+            } else if let Some(m) = part.strip_suffix(" failed") {
+                failed += m.parse().unwrap_or(0);
+            }
+        }
+    }
+
+    (passed, failed)
+}
+
+/// Runs the unit tests of a package with a `cargo test` command.
+pub(crate) async fn run(mut cmd: Command, package: &TestPackage) -> Result<TestOutcome> {
+    let output = cmd
+        .kill_on_drop(true)
+        .output()
+        .await
+        .map_err(|e| spawn_error("cargo", e))
+        .with_context(|| format!("failed to test {}", package.name))?;
+
+    let mut text = String::from_utf8_lossy(&output.stdout).into_owned();
+    text.push_str(&String::from_utf8_lossy(&output.stderr));
+    let (passed, failed) = count_results(&text);
+
+    Ok(TestOutcome {
+        package: package.name.clone(),
+        passed,
+        failed,
+        output: (!output.status.success()).then_some(text),
+    })
+}
+
+/// Prints the outcomes of a test run, returns a summary if any package has failed.
+pub(crate) fn report(outcomes: &[TestOutcome]) -> Option<String> {
+    if outcomes.is_empty() {
+        return None;
+    }
+
+    let failed = outcomes
+        .iter()
+        .filter(|m| m.output.is_some())
+        .collect::<Vec<_>>();
+
+    if failed.is_empty() {
+        let passed = outcomes.iter().map(|m| m.passed).sum::<u32>();
+        let names = outcomes
+            .iter()
+            .map(|m| m.package.as_str())
+            .collect::<Vec<_>>();
+
+        eprintln!(
+            "    {:<9}{}",
+            "Tests:",
+            style(format!("{} passed ({})", passed, names.join(", "))).green()
+        );
+        return None;
+    }
+
+    for m in failed.iter() {
+        eprintln!();
+        eprintln!(
+            "{}",
+            style(format!("Tests of {} failed:", m.package))
+                .red()
+                .bold()
+        );
+        eprintln!("{}", m.output.as_deref().unwrap_or_default().trim_end());
+    }
+
+    let summary = failed
+        .iter()
+        .map(|m| match m.failed {
+            0 => format!("{} failed to build", m.package),
+            n => format!("{} failed in {}", n, m.package),
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    eprintln!();
+    eprintln!("    {:<9}{}", "Tests:", style(&summary).red().bold());
+
+    Some(summary)
+}
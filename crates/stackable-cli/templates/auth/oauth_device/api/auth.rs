@@ -0,0 +1,108 @@
+//! Authentication with the OAuth 2.0 device authorization grant (RFC 8628), generated by
+//! `stackctl generate auth`.
+//!
+//! The server requests a code from the provider, the user enters it on the website of the
+//! provider and the server polls the provider until the user has approved it.
+
+use serde::{Deserialize, Serialize};
+use stackable_bridge::types::{BridgedMutation, BridgedQuery};
+use thiserror::Error;
+
+#[cfg(feature = "resolvable")]
+mod resolvers;
+
+/// A user that is signed in.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct User {
+    pub id: String,
+    pub name: String,
+}
+
+#[derive(Debug, Error, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub enum AuthError {
+    #[error("signing in has not been started.")]
+    NotStarted,
+    #[error("signing in has been denied.")]
+    AccessDenied,
+    #[error("the code has expired, please try again.")]
+    Expired,
+    #[error("failed to communicate with the provider.")]
+    Provider,
+    #[error("sessions are not enabled on the server.")]
+    SessionUnavailable,
+    #[error("failed to communicate with server.")]
+    Network,
+}
+
+/// The user of the current session, `None` if nobody is signed in.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CurrentUserQuery {
+    pub user: Option<User>,
+}
+
+impl BridgedQuery for CurrentUserQuery {
+    type Error = AuthError;
+    type Input = ();
+
+    fn into_query_error(_e: stackable_bridge::BridgeError) -> Self::Error {
+        AuthError::Network
+    }
+}
+
+/// Starts signing in, the user enters `user_code` at `verification_uri`.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct StartDeviceLoginMutation {
+    pub user_code: String,
+    pub verification_uri: String,
+    /// The number of seconds until the code expires.
+    pub expires_in: u64,
+    /// The number of seconds to wait between polls.
+    pub interval: u64,
+}
+
+impl BridgedMutation for StartDeviceLoginMutation {
+    type Error = AuthError;
+    type Input = ();
+
+    fn into_mutation_error(_e: stackable_bridge::BridgeError) -> Self::Error {
+        AuthError::Network
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum DeviceLoginStatus {
+    /// The user has not approved the code yet.
+    Pending,
+    /// The interval between polls must be increased by 5 seconds.
+    SlowDown,
+    /// The user is signed in.
+    Complete(User),
+}
+
+/// Checks whether the user has approved the code of the current session.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PollDeviceLoginMutation {
+    pub status: DeviceLoginStatus,
+}
+
+impl BridgedMutation for PollDeviceLoginMutation {
+    type Error = AuthError;
+    type Input = ();
+
+    fn into_mutation_error(_e: stackable_bridge::BridgeError) -> Self::Error {
+        AuthError::Network
+    }
+}
+
+/// Signs the user of the current session out.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct LogoutMutation;
+
+impl BridgedMutation for LogoutMutation {
+    type Error = AuthError;
+    type Input = ();
+
+    fn into_mutation_error(_e: stackable_bridge::BridgeError) -> Self::Error {
+        AuthError::Network
+    }
+}
@@ -0,0 +1,221 @@
+//! The provider is configured with environment variables, which can be set in `.env`:
+//!
+//! - `OAUTH_CLIENT_ID`: the client id of the application registered with the provider.
+//! - `OAUTH_DEVICE_AUTHORIZATION_URL`: the device authorization endpoint of the provider.
+//! - `OAUTH_TOKEN_URL`: the token endpoint of the provider.
+//! - `OAUTH_USERINFO_URL`: the endpoint that returns the user of an access token.
+//! - `OAUTH_SCOPE`: the scopes to request, optional.
+
+use std::env;
+
+use async_trait::async_trait;
+use serde::Deserialize;
+use stackable_bridge::resolvers::{MutationResolver, QueryResolver};
+use stackable_bridge::types::{MutationResult, QueryResult};
+use stackable_bridge::BridgeMetadata;
+
+use super::{
+    AuthError, CurrentUserQuery, DeviceLoginStatus, LogoutMutation, PollDeviceLoginMutation,
+    StartDeviceLoginMutation, User,
+};
+
+/// The key of the user that is signed in, in the session.
+const USER_KEY: &str = "user";
+/// The key of the device code that is waiting for approval, in the session.
+const DEVICE_CODE_KEY: &str = "oauth-device-code";
+
+const DEVICE_CODE_GRANT_TYPE: &str = "urn:ietf:params:oauth:grant-type:device_code";
+
+fn config(name: &str) -> Result<String, AuthError> {
+    env::var(name).map_err(|_| {
+        tracing::error!("{name} is not set");
+        AuthError::Provider
+    })
+}
+
+fn provider_error(e: reqwest::Error) -> AuthError {
+    tracing::error!("failed to communicate with the provider: {e}");
+    AuthError::Provider
+}
+
+#[derive(Deserialize)]
+struct DeviceAuthorizationResponse {
+    device_code: String,
+    user_code: String,
+    verification_uri: String,
+    expires_in: u64,
+    #[serde(default = "default_interval")]
+    interval: u64,
+}
+
+fn default_interval() -> u64 {
+    5
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum TokenResponse {
+    Token { access_token: String },
+    Error { error: String },
+}
+
+/// The claims of the userinfo endpoint of OpenID Connect, adjust them for other providers.
+#[derive(Deserialize)]
+struct UserInfo {
+    sub: String,
+    name: Option<String>,
+    preferred_username: Option<String>,
+}
+
+impl From<UserInfo> for User {
+    fn from(m: UserInfo) -> Self {
+        Self {
+            name: m
+                .name
+                .or(m.preferred_username)
+                .unwrap_or_else(|| m.sub.clone()),
+            id: m.sub,
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl QueryResolver for CurrentUserQuery {
+    type Context = ();
+
+    async fn resolve(metadata: &BridgeMetadata<()>, _input: &Self::Input) -> QueryResult<Self> {
+        let user = metadata.session().and_then(|m| m.get::<User>(USER_KEY));
+
+        Ok(Self { user }.into())
+    }
+}
+
+#[async_trait(?Send)]
+impl MutationResolver for StartDeviceLoginMutation {
+    type Context = ();
+
+    async fn resolve(metadata: &BridgeMetadata<()>, _input: &Self::Input) -> MutationResult<Self> {
+        let session = metadata.session().ok_or(AuthError::SessionUnavailable)?;
+
+        let mut form = vec![("client_id", config("OAUTH_CLIENT_ID")?)];
+        if let Ok(m) = env::var("OAUTH_SCOPE") {
+            form.push(("scope", m));
+        }
+
+        let resp: DeviceAuthorizationResponse = reqwest::Client::new()
+            .post(config("OAUTH_DEVICE_AUTHORIZATION_URL")?)
+            .header("accept", "application/json")
+            .form(&form)
+            .send()
+            .await
+            .and_then(|m| m.error_for_status())
+            .map_err(provider_error)?
+            .json()
+            .await
+            .map_err(provider_error)?;
+
+        // The device code is kept on the server, only the user code is shown to the user.
+        session
+            .insert(DEVICE_CODE_KEY, &resp.device_code)
+            .map_err(|_| AuthError::SessionUnavailable)?;
+
+        Ok(Self {
+            user_code: resp.user_code,
+            verification_uri: resp.verification_uri,
+            expires_in: resp.expires_in,
+            interval: resp.interval,
+        }
+        .into())
+    }
+}
+
+#[async_trait(?Send)]
+impl MutationResolver for PollDeviceLoginMutation {
+    type Context = ();
+
+    async fn resolve(metadata: &BridgeMetadata<()>, _input: &Self::Input) -> MutationResult<Self> {
+        let session = metadata.session().ok_or(AuthError::SessionUnavailable)?;
+        let device_code = session
+            .get::<String>(DEVICE_CODE_KEY)
+            .ok_or(AuthError::NotStarted)?;
+
+        let client = reqwest::Client::new();
+        let resp: TokenResponse = client
+            .post(config("OAUTH_TOKEN_URL")?)
+            .header("accept", "application/json")
+            .form(&[
+                ("grant_type", DEVICE_CODE_GRANT_TYPE.to_string()),
+                ("device_code", device_code),
+                ("client_id", config("OAUTH_CLIENT_ID")?),
+            ])
+            .send()
+            .await
+            .map_err(provider_error)?
+            .json()
+            .await
+            .map_err(provider_error)?;
+
+        let access_token = match resp {
+            TokenResponse::Token { access_token } => access_token,
+            TokenResponse::Error { error } => {
+                let status = match error.as_str() {
+                    "authorization_pending" => DeviceLoginStatus::Pending,
+                    "slow_down" => DeviceLoginStatus::SlowDown,
+                    "access_denied" => {
+                        session.remove(DEVICE_CODE_KEY);
+                        return Err(AuthError::AccessDenied);
+                    }
+                    "expired_token" => {
+                        session.remove(DEVICE_CODE_KEY);
+                        return Err(AuthError::Expired);
+                    }
+                    _ => {
+                        tracing::error!("the provider has returned an error: {error}");
+                        return Err(AuthError::Provider);
+                    }
+                };
+
+                return Ok(Self { status }.into());
+            }
+        };
+
+        let user: User = client
+            .get(config("OAUTH_USERINFO_URL")?)
+            .bearer_auth(access_token)
+            .header("accept", "application/json")
+            .send()
+            .await
+            .and_then(|m| m.error_for_status())
+            .map_err(provider_error)?
+            .json::<UserInfo>()
+            .await
+            .map_err(provider_error)?
+            .into();
+
+        // The session id is replaced when a user signs in, so an id that is known before
+        // cannot be used to act as the user.
+        session.remove(DEVICE_CODE_KEY);
+        session.renew();
+        session
+            .insert(USER_KEY, &user)
+            .map_err(|_| AuthError::SessionUnavailable)?;
+
+        Ok(Self {
+            status: DeviceLoginStatus::Complete(user),
+        }
+        .into())
+    }
+}
+
+#[async_trait(?Send)]
+impl MutationResolver for LogoutMutation {
+    type Context = ();
+
+    async fn resolve(metadata: &BridgeMetadata<()>, _input: &Self::Input) -> MutationResult<Self> {
+        if let Some(m) = metadata.session() {
+            m.destroy();
+        }
+
+        Ok(Self.into())
+    }
+}
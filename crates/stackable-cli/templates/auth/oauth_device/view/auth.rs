@@ -0,0 +1,167 @@
+//! Authentication with the OAuth 2.0 device authorization grant, generated by
+//! `stackctl generate auth`.
+
+use std::time::Duration;
+
+use __api_crate__::{
+    CurrentUserQuery, DeviceLoginStatus, LogoutMutation, PollDeviceLoginMutation,
+    StartDeviceLoginMutation, User,
+};
+use stackable_bridge::hooks::{use_bridged_mutation, use_bridged_query};
+use yew::platform::spawn_local;
+use yew::platform::time::sleep;
+use yew::prelude::*;
+
+/// The state of authentication, provided by [`AuthProvider`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Auth {
+    /// The user that is signed in, `None` if nobody is signed in.
+    pub user: Option<User>,
+    /// Fetches the user of the session again, e.g.: after signing in or out.
+    pub refresh: Callback<()>,
+}
+
+#[derive(Properties, PartialEq)]
+pub struct AuthProviderProps {
+    #[prop_or_default]
+    pub children: Children,
+}
+
+/// Provides the user of the current session to its children.
+///
+/// The user is resolved during server-side rendering, so pages are rendered for the user that
+/// is signed in. This component suspends and must be placed under a `<Suspense />`.
+#[function_component]
+pub fn AuthProvider(props: &AuthProviderProps) -> HtmlResult {
+    let current_user = use_bridged_query::<CurrentUserQuery>(().into())?;
+
+    let refresh = {
+        let current_user = current_user.clone();
+        Callback::from(move |_| {
+            let current_user = current_user.clone();
+            spawn_local(async move {
+                let _ = current_user.refresh().await;
+            });
+        })
+    };
+
+    let auth = Auth {
+        user: current_user.as_deref().ok().and_then(|m| m.user.clone()),
+        refresh,
+    };
+
+    Ok(html! {
+        <ContextProvider<Auth> context={auth}>
+            {props.children.clone()}
+        </ContextProvider<Auth>>
+    })
+}
+
+/// Returns the state of authentication, this must be used under an [`AuthProvider`].
+#[hook]
+pub fn use_auth() -> Auth {
+    use_context::<Auth>().expect("use_auth must be used under an AuthProvider")
+}
+
+/// A button that signs a user in with a code that the user enters on the website of the provider.
+#[function_component]
+pub fn LoginButton() -> Html {
+    let auth = use_auth();
+    let start = use_bridged_mutation::<StartDeviceLoginMutation>();
+    let poll = use_bridged_mutation::<PollDeviceLoginMutation>();
+    let waiting = use_state_eq(|| false);
+
+    let onclick = {
+        let start = start.clone();
+        let poll = poll.clone();
+        let waiting = waiting.clone();
+
+        Callback::from(move |_| {
+            let start = start.clone();
+            let poll = poll.clone();
+            let waiting = waiting.clone();
+            let refresh = auth.refresh.clone();
+
+            waiting.set(true);
+            spawn_local(async move {
+                let mut interval = match start.run(()).await {
+                    Ok(m) => m.interval,
+                    Err(_) => {
+                        waiting.set(false);
+                        return;
+                    }
+                };
+
+                loop {
+                    sleep(Duration::from_secs(interval)).await;
+
+                    match poll.run(()).await.as_deref() {
+                        Ok(PollDeviceLoginMutation {
+                            status: DeviceLoginStatus::Pending,
+                        }) => {}
+                        Ok(PollDeviceLoginMutation {
+                            status: DeviceLoginStatus::SlowDown,
+                        }) => interval += 5,
+                        Ok(PollDeviceLoginMutation {
+                            status: DeviceLoginStatus::Complete(_),
+                        }) => {
+                            refresh.emit(());
+                            break;
+                        }
+                        Err(_) => break,
+                    }
+                }
+
+                waiting.set(false);
+            });
+        })
+    };
+
+    let error = match (start.result(), poll.result()) {
+        (Some(Err(e)), _) | (_, Some(Err(e))) => {
+            html! { <div class="auth-error">{e.to_string()}</div> }
+        }
+        _ => Html::default(),
+    };
+
+    let code = match start.result() {
+        Some(Ok(m)) if *waiting => html! {
+            <div class="auth-code">
+                {"Enter "}<code>{&m.user_code}</code>{" at "}
+                <a href={m.verification_uri.clone()} target="_blank" rel="noopener noreferrer">
+                    {&m.verification_uri}
+                </a>
+            </div>
+        },
+        _ => Html::default(),
+    };
+
+    html! {
+        <div class="auth-form">
+            <button disabled={*waiting} {onclick}>{"Sign In"}</button>
+            {code}
+            {error}
+        </div>
+    }
+}
+
+/// A button that signs the current user out.
+#[function_component]
+pub fn LogoutButton() -> Html {
+    let auth = use_auth();
+    let logout = use_bridged_mutation::<LogoutMutation>();
+
+    let onclick = Callback::from(move |_| {
+        let logout = logout.clone();
+        let refresh = auth.refresh.clone();
+        spawn_local(async move {
+            if logout.run(()).await.is_ok() {
+                refresh.emit(());
+            }
+        });
+    });
+
+    html! {
+        <button class="auth-logout" {onclick}>{"Sign Out"}</button>
+    }
+}
@@ -0,0 +1,85 @@
+//! Authentication with usernames and passwords, generated by `stackctl generate auth`.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use stackable_bridge::types::{BridgedMutation, BridgedQuery};
+use thiserror::Error;
+
+#[cfg(feature = "resolvable")]
+mod resolvers;
+
+/// A user that is signed in.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct User {
+    pub id: String,
+    pub name: String,
+}
+
+#[derive(Debug, Error, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub enum AuthError {
+    #[error("the username or the password is incorrect.")]
+    InvalidCredentials,
+    #[error("sessions are not enabled on the server.")]
+    SessionUnavailable,
+    #[error("failed to communicate with server.")]
+    Network,
+}
+
+/// The user of the current session, `None` if nobody is signed in.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CurrentUserQuery {
+    pub user: Option<User>,
+}
+
+impl BridgedQuery for CurrentUserQuery {
+    type Error = AuthError;
+    type Input = ();
+
+    fn into_query_error(_e: stackable_bridge::BridgeError) -> Self::Error {
+        AuthError::Network
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct LoginInput {
+    pub username: String,
+    pub password: String,
+}
+
+// Passwords must never be written to logs.
+impl fmt::Debug for LoginInput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LoginInput")
+            .field("username", &self.username)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Signs a user in and returns the user.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct LoginMutation {
+    pub user: User,
+}
+
+impl BridgedMutation for LoginMutation {
+    type Error = AuthError;
+    type Input = LoginInput;
+
+    fn into_mutation_error(_e: stackable_bridge::BridgeError) -> Self::Error {
+        AuthError::Network
+    }
+}
+
+/// Signs the user of the current session out.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct LogoutMutation;
+
+impl BridgedMutation for LogoutMutation {
+    type Error = AuthError;
+    type Input = ();
+
+    fn into_mutation_error(_e: stackable_bridge::BridgeError) -> Self::Error {
+        AuthError::Network
+    }
+}
@@ -0,0 +1,63 @@
+use async_trait::async_trait;
+use stackable_bridge::resolvers::{MutationResolver, QueryResolver};
+use stackable_bridge::types::{MutationResult, QueryResult};
+use stackable_bridge::BridgeMetadata;
+
+use super::{AuthError, CurrentUserQuery, LoginInput, LoginMutation, LogoutMutation, User};
+
+/// The key of the user that is signed in, in the session.
+const USER_KEY: &str = "user";
+
+/// Returns the user with the credentials, or `None` if they are incorrect.
+///
+/// TODO: look up the user in your user store and verify the password with a password hashing
+/// function, e.g.: argon2. Passwords must never be stored or compared in plain text.
+async fn verify_credentials(input: &LoginInput) -> Option<User> {
+    let _ = input;
+    None
+}
+
+#[async_trait(?Send)]
+impl QueryResolver for CurrentUserQuery {
+    type Context = ();
+
+    async fn resolve(metadata: &BridgeMetadata<()>, _input: &Self::Input) -> QueryResult<Self> {
+        let user = metadata.session().and_then(|m| m.get::<User>(USER_KEY));
+
+        Ok(Self { user }.into())
+    }
+}
+
+#[async_trait(?Send)]
+impl MutationResolver for LoginMutation {
+    type Context = ();
+
+    async fn resolve(metadata: &BridgeMetadata<()>, input: &Self::Input) -> MutationResult<Self> {
+        let session = metadata.session().ok_or(AuthError::SessionUnavailable)?;
+        let user = verify_credentials(input)
+            .await
+            .ok_or(AuthError::InvalidCredentials)?;
+
+        // The session id is replaced when a user signs in, so an id that is known before
+        // cannot be used to act as the user.
+        session.renew();
+        session
+            .insert(USER_KEY, &user)
+            .map_err(|_| AuthError::SessionUnavailable)?;
+
+        Ok(Self { user }.into())
+    }
+}
+
+#[async_trait(?Send)]
+impl MutationResolver for LogoutMutation {
+    type Context = ();
+
+    async fn resolve(metadata: &BridgeMetadata<()>, _input: &Self::Input) -> MutationResult<Self> {
+        if let Some(m) = metadata.session() {
+            m.destroy();
+        }
+
+        Ok(Self.into())
+    }
+}
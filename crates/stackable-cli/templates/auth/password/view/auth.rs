@@ -0,0 +1,146 @@
+//! Authentication with usernames and passwords, generated by `stackctl generate auth`.
+
+use __api_crate__::{CurrentUserQuery, LoginInput, LoginMutation, LogoutMutation, User};
+use stackable_bridge::hooks::{use_bridged_mutation, use_bridged_query};
+use web_sys::HtmlInputElement;
+use yew::platform::spawn_local;
+use yew::prelude::*;
+
+/// The state of authentication, provided by [`AuthProvider`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Auth {
+    /// The user that is signed in, `None` if nobody is signed in.
+    pub user: Option<User>,
+    /// Fetches the user of the session again, e.g.: after signing in or out.
+    pub refresh: Callback<()>,
+}
+
+#[derive(Properties, PartialEq)]
+pub struct AuthProviderProps {
+    #[prop_or_default]
+    pub children: Children,
+}
+
+/// Provides the user of the current session to its children.
+///
+/// The user is resolved during server-side rendering, so pages are rendered for the user that
+/// is signed in. This component suspends and must be placed under a `<Suspense />`.
+#[function_component]
+pub fn AuthProvider(props: &AuthProviderProps) -> HtmlResult {
+    let current_user = use_bridged_query::<CurrentUserQuery>(().into())?;
+
+    let refresh = {
+        let current_user = current_user.clone();
+        Callback::from(move |_| {
+            let current_user = current_user.clone();
+            spawn_local(async move {
+                let _ = current_user.refresh().await;
+            });
+        })
+    };
+
+    let auth = Auth {
+        user: current_user.as_deref().ok().and_then(|m| m.user.clone()),
+        refresh,
+    };
+
+    Ok(html! {
+        <ContextProvider<Auth> context={auth}>
+            {props.children.clone()}
+        </ContextProvider<Auth>>
+    })
+}
+
+/// Returns the state of authentication, this must be used under an [`AuthProvider`].
+#[hook]
+pub fn use_auth() -> Auth {
+    use_context::<Auth>().expect("use_auth must be used under an AuthProvider")
+}
+
+/// A form that signs a user in with a username and a password.
+#[function_component]
+pub fn LoginForm() -> Html {
+    let auth = use_auth();
+    let login = use_bridged_mutation::<LoginMutation>();
+
+    let username_ref = use_node_ref();
+    let password_ref = use_node_ref();
+
+    let onsubmit = {
+        let login = login.clone();
+        let username_ref = username_ref.clone();
+        let password_ref = password_ref.clone();
+
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+
+            let (username, password) = match (
+                username_ref.cast::<HtmlInputElement>(),
+                password_ref.cast::<HtmlInputElement>(),
+            ) {
+                (Some(username), Some(password)) => (username, password),
+                _ => return,
+            };
+
+            let input = LoginInput {
+                username: username.value(),
+                password: password.value(),
+            };
+
+            let login = login.clone();
+            let refresh = auth.refresh.clone();
+            spawn_local(async move {
+                if login.run(input).await.is_ok() {
+                    refresh.emit(());
+                }
+            });
+        })
+    };
+
+    let error = match login.result() {
+        Some(Err(e)) => html! { <div class="auth-error">{e.to_string()}</div> },
+        _ => Html::default(),
+    };
+
+    html! {
+        <form class="auth-form" {onsubmit}>
+            <input
+                type="text"
+                placeholder="Username"
+                autocomplete="username"
+                required={true}
+                ref={username_ref}
+            />
+            <input
+                type="password"
+                placeholder="Password"
+                autocomplete="current-password"
+                required={true}
+                ref={password_ref}
+            />
+            <button type="submit">{"Sign In"}</button>
+            {error}
+        </form>
+    }
+}
+
+/// A button that signs the current user out.
+#[function_component]
+pub fn LogoutButton() -> Html {
+    let auth = use_auth();
+    let logout = use_bridged_mutation::<LogoutMutation>();
+
+    let onclick = Callback::from(move |_| {
+        let logout = logout.clone();
+        let refresh = auth.refresh.clone();
+        spawn_local(async move {
+            if logout.run(()).await.is_ok() {
+                refresh.emit(());
+            }
+        });
+    });
+
+    html! {
+        <button class="auth-logout" {onclick}>{"Sign Out"}</button>
+    }
+}
@@ -0,0 +1,16 @@
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct {{ name | pascal_case }}Props {
+    #[prop_or_default]
+    pub children: Children,
+}
+
+#[function_component]
+pub fn {{ name | pascal_case }}(props: &{{ name | pascal_case }}Props) -> Html {
+    html! {
+        <div class="{{ name | kebab_case }}">
+            {props.children.clone()}
+        </div>
+    }
+}
@@ -0,0 +1,45 @@
+use serde::{Deserialize, Serialize};
+use stackable_bridge::types::BridgedQuery;
+use thiserror::Error;
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct {{ name | pascal_case }}Query {
+    pub value: String,
+}
+
+#[derive(Debug, Error, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub enum {{ name | pascal_case }}Error {
+    #[error("failed to communicate with server.")]
+    Network,
+}
+
+impl BridgedQuery for {{ name | pascal_case }}Query {
+    type Error = {{ name | pascal_case }}Error;
+    type Input = ();
+
+    fn into_query_error(_e: stackable_bridge::BridgeError) -> Self::Error {
+        {{ name | pascal_case }}Error::Network
+    }
+}
+
+#[cfg(feature = "resolvable")]
+mod resolver {
+    use async_trait::async_trait;
+    use stackable_bridge::resolvers::QueryResolver;
+    use stackable_bridge::types::QueryResult;
+    use stackable_bridge::BridgeMetadata;
+
+    use super::{{ name | pascal_case }}Query;
+
+    #[async_trait(?Send)]
+    impl QueryResolver for {{ name | pascal_case }}Query {
+        type Context = ();
+
+        async fn resolve(_metadata: &BridgeMetadata<()>, _input: &Self::Input) -> QueryResult<Self> {
+            Ok(Self {
+                value: String::new(),
+            }
+            .into())
+        }
+    }
+}
@@ -0,0 +1,16 @@
+use bounce::helmet::Helmet;
+use yew::prelude::*;
+
+#[function_component]
+pub fn {{ name | pascal_case }}() -> Html {
+    html! {
+        <>
+            <Helmet>
+                <title>{"{{ name | title_case }}"}</title>
+            </Helmet>
+            <div class="{{ name | kebab_case }}">
+                <h1>{"{{ name | title_case }}"}</h1>
+            </div>
+        </>
+    }
+}
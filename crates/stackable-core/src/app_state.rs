@@ -0,0 +1,64 @@
+//! Values that are shared by all requests of a server, e.g.: database pools.
+
+use std::any::{type_name, Any, TypeId};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+/// A value with the name of its type, which is shown by `Debug`.
+type NamedValue = (&'static str, Arc<dyn Any + Send + Sync>);
+
+/// A map of values by their types.
+///
+/// Pages read it with `ServerAppProps::app_state` and resolvers read it with
+/// `BridgeMetadata::app_state`. Clones share the same values.
+#[derive(Clone, Default)]
+pub struct AppState {
+    values: Arc<HashMap<TypeId, NamedValue>>,
+}
+
+impl fmt::Debug for AppState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set()
+            .entries(self.values.values().map(|(name, _)| name))
+            .finish()
+    }
+}
+
+impl AppState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Inserts a value, replacing the value of the same type.
+    pub fn insert<T>(&mut self, value: T)
+    where
+        T: 'static + Send + Sync,
+    {
+        Arc::make_mut(&mut self.values)
+            .insert(TypeId::of::<T>(), (type_name::<T>(), Arc::new(value)));
+    }
+
+    /// Returns the value of a type, if it is inserted.
+    pub fn get<T>(&self) -> Option<&T>
+    where
+        T: 'static + Send + Sync,
+    {
+        self.values
+            .get(&TypeId::of::<T>())
+            .and_then(|(_, m)| m.downcast_ref())
+    }
+
+    /// Returns the value of a type.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no value of the type is inserted.
+    pub fn expect<T>(&self) -> &T
+    where
+        T: 'static + Send + Sync,
+    {
+        self.get()
+            .unwrap_or_else(|| panic!("{} is not in the app state", type_name::<T>()))
+    }
+}
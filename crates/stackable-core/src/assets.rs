@@ -0,0 +1,91 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// The frontend assets of a build.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct AssetBuild {
+    pub build_id: String,
+    /// The paths of assets, relative to the frontend build directory.
+    pub files: Vec<String>,
+    /// Subresource integrity hashes of the scripts, stylesheets and wasm modules of release
+    /// builds by their paths, e.g.: `sha384-...`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub integrity: BTreeMap<String, String>,
+    /// SHA-256 hashes of the assets in hex by their paths, the server responds with them as
+    /// `ETag`s.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub hashes: BTreeMap<String, String>,
+    /// The time that a newer build replaced this build, in seconds since the unix epoch.
+    ///
+    /// This is `None` for the current build.
+    pub superseded_at: Option<u64>,
+}
+
+/// The history of frontend assets, written to `asset-manifest.json` by `stackctl build`.
+///
+/// Assets of previous builds are kept in the frontend build directory for the grace period, so
+/// browsers that have loaded an older frontend can still load its chunks after a deploy.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct AssetManifest {
+    /// The number of seconds that assets of previous builds are served after a deploy.
+    pub grace_period: u64,
+    /// The current build first, followed by previous builds.
+    pub builds: Vec<AssetBuild>,
+}
+
+impl AssetManifest {
+    pub const FILE_NAME: &str = "asset-manifest.json";
+
+    /// Returns the current build.
+    pub fn current(&self) -> Option<&AssetBuild> {
+        self.builds.first().filter(|m| m.superseded_at.is_none())
+    }
+
+    /// Returns `true` if the path only belongs to previous builds.
+    pub fn is_previous_asset(&self, path: &str) -> bool {
+        let in_current = self
+            .current()
+            .map(|m| m.files.iter().any(|f| f == path))
+            .unwrap_or(false);
+
+        !in_current
+            && self
+                .builds
+                .iter()
+                .any(|m| m.superseded_at.is_some() && m.files.iter().any(|f| f == path))
+    }
+
+    /// Returns the content hash of an asset, from the latest build that contains it.
+    pub fn hash(&self, path: &str) -> Option<&str> {
+        self.builds
+            .iter()
+            .find_map(|m| m.hashes.get(path))
+            .map(|m| m.as_str())
+    }
+
+    pub fn from_json(s: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(s)
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+/// Returns `true` if the file name of a path contains a content hash, e.g.: `app-1a2b3c4d.js` or
+/// `app-1a2b3c4d_bg.wasm` of trunk.
+///
+/// These files never change, so they can be cached indefinitely.
+pub fn is_hashed_file_name(path: &str) -> bool {
+    let file_name = path.rsplit('/').next().unwrap_or(path);
+    let stem = file_name.split('.').next().unwrap_or(file_name);
+    let stem = stem.strip_suffix("_bg").unwrap_or(stem);
+
+    match stem.rsplit_once('-') {
+        Some((_, hash)) => hash.len() >= 8 && hash.chars().all(|m| m.is_ascii_hexdigit()),
+        None => false,
+    }
+}
@@ -0,0 +1,60 @@
+use serde::{Deserialize, Serialize};
+
+/// The build id provided by `stackctl build` when the crate is compiled.
+///
+/// This is available on both the server and the frontend. The development server does not
+/// provide a build id at compile time.
+pub const BUILD_ID: Option<&str> = option_env!("STACKABLE_BUILD_ID");
+
+/// Information about a build, written to `build-info.json` by `stackctl build`.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct BuildInfo {
+    /// The id of the build, in the format of `{git_sha}-{timestamp}`.
+    ///
+    /// The git sha is omitted if the workspace is not a git repository.
+    pub build_id: String,
+    /// The git sha of the commit that is built.
+    pub git_sha: Option<String>,
+    /// The time of the build, in seconds since the unix epoch.
+    pub timestamp: u64,
+}
+
+impl BuildInfo {
+    pub const ENV_NAME: &str = "STACKABLE_BUILD_ID";
+    pub const FILE_NAME: &str = "build-info.json";
+
+    pub fn new(git_sha: Option<String>, timestamp: u64) -> Self {
+        let build_id = match git_sha {
+            Some(ref m) => format!("{}-{timestamp}", &m[..m.len().min(12)]),
+            None => timestamp.to_string(),
+        };
+
+        Self {
+            build_id,
+            git_sha,
+            timestamp,
+        }
+    }
+
+    /// Returns the id of the current build.
+    ///
+    /// On the server, the id provided by the `STACKABLE_BUILD_ID` environment variable at runtime
+    /// takes precedence over the id provided at compile time.
+    pub fn current_id() -> Option<String> {
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Ok(m) = std::env::var(Self::ENV_NAME) {
+            return Some(m);
+        }
+
+        BUILD_ID.map(|m| m.to_string())
+    }
+
+    pub fn from_json(s: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(s)
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+}
@@ -0,0 +1,89 @@
+//! Encryption of state payloads embedded into server-rendered pages.
+//!
+//! Each response derives its own key from the server secret and a random key id. The page
+//! carries the key id and the derived key is delivered to the browser separately in a short-lived
+//! cookie, so the payloads can neither be read nor modified from the page source alone.
+
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+/// The name of the meta tag that carries the key id.
+pub const KEY_ID_META_NAME: &str = "stackable-state-key";
+/// The attribute that marks an encrypted state payload.
+pub const ENCRYPTED_ATTR: &str = "data-stackable-encrypted";
+
+const NONCE_LEN: usize = 12;
+
+/// A key to encrypt state payloads of a single response.
+#[derive(Clone)]
+pub struct StateKey([u8; 32]);
+
+impl std::fmt::Debug for StateKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("StateKey")
+    }
+}
+
+impl StateKey {
+    /// Derives the key of a response from the server secret and the key id.
+    pub fn derive(secret: &[u8], key_id: &str) -> Self {
+        let mut mac =
+            <Hmac<Sha256> as Mac>::new_from_slice(secret).expect("hmac accepts keys of any length");
+        mac.update(key_id.as_bytes());
+
+        let mut key = [0; 32];
+        key.copy_from_slice(&mac.finalize().into_bytes());
+
+        Self(key)
+    }
+
+    /// Returns the name of the cookie that delivers the key with the key id.
+    pub fn cookie_name(key_id: &str) -> String {
+        format!("stackable-state-{key_id}")
+    }
+
+    /// Decodes a key from its base64 representation.
+    pub fn decode(s: &str) -> Option<Self> {
+        STANDARD.decode(s).ok()?.try_into().ok().map(Self)
+    }
+
+    /// Encodes the key with base64.
+    pub fn encode(&self) -> String {
+        STANDARD.encode(self.0)
+    }
+
+    /// Encrypts a payload, the nonce must not be reused with the same key.
+    ///
+    /// Returns the nonce and the ciphertext encoded with base64.
+    pub fn encrypt(&self, nonce: [u8; NONCE_LEN], plaintext: &str) -> String {
+        let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&self.0));
+        let mut s = nonce.to_vec();
+        s.extend(
+            cipher
+                .encrypt(Nonce::from_slice(&nonce), plaintext.as_bytes())
+                .expect("failed to encrypt payload"),
+        );
+
+        STANDARD.encode(s)
+    }
+
+    /// Decrypts a payload created by [`encrypt`](Self::encrypt).
+    ///
+    /// Returns `None` if the payload is malformed or has been tampered with.
+    pub fn decrypt(&self, s: &str) -> Option<String> {
+        let s = STANDARD.decode(s.trim()).ok()?;
+        if s.len() < NONCE_LEN {
+            return None;
+        }
+
+        let (nonce, ciphertext) = s.split_at(NONCE_LEN);
+        let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&self.0));
+        let plaintext = cipher.decrypt(Nonce::from_slice(nonce), ciphertext).ok()?;
+
+        String::from_utf8(plaintext).ok()
+    }
+}
@@ -0,0 +1,56 @@
+use serde::{Deserialize, Serialize};
+
+/// The attribute that marks a compressed state payload, its value is the algorithm.
+pub const COMPRESSED_ATTR: &str = "data-stackable-compressed";
+
+/// The algorithm to compress state payloads with.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum CompressionAlgorithm {
+    #[default]
+    Gzip,
+    Brotli,
+}
+
+impl CompressionAlgorithm {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Gzip => "gzip",
+            Self::Brotli => "brotli",
+        }
+    }
+
+    pub fn from_name(s: &str) -> Option<Self> {
+        match s {
+            "gzip" => Some(Self::Gzip),
+            "brotli" => Some(Self::Brotli),
+            _ => None,
+        }
+    }
+}
+
+/// State compression configuration, read from `[server.state-compression]`.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct StateCompressionConfig {
+    #[serde(default)]
+    pub algorithm: CompressionAlgorithm,
+    /// Payloads smaller than this number of bytes are not compressed.
+    #[serde(default = "StateCompressionConfig::default_threshold")]
+    pub threshold: usize,
+}
+
+impl Default for StateCompressionConfig {
+    fn default() -> Self {
+        Self {
+            algorithm: CompressionAlgorithm::default(),
+            threshold: Self::default_threshold(),
+        }
+    }
+}
+
+impl StateCompressionConfig {
+    fn default_threshold() -> usize {
+        4096
+    }
+}
@@ -0,0 +1,150 @@
+//! Typed configuration shared between the server and the frontend.
+//!
+//! Configuration keys are defined once with the [`config!`](crate::config!) macro. Public keys are
+//! available on both sides, secret keys are only available on the server.
+//!
+//! The server reads values from environment variables when the configuration is loaded. The
+//! frontend (wasm) reads public values at compile time, which stackctl provides from the env
+//! files (`.env`, `.env.{profile}`, ...) when it builds the frontend. Only keys listed in
+//! `public-env` of `[build.frontend]` in `stackable.toml` are provided, so public keys are listed
+//! there.
+//!
+//! ```
+//! stackable_core::config! {
+//!     pub struct AppConfig {
+//!         public {
+//!             api_url: String = "APP_API_URL" or "http://localhost:5000",
+//!         }
+//!         secret {
+//!             database_url: String = "APP_DATABASE_URL" or "sqlite::memory:",
+//!         }
+//!     }
+//! }
+//!
+//! let config = AppConfig::load().expect("failed to load configuration");
+//! ```
+
+use std::fmt;
+use std::str::FromStr;
+
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ConfigError {
+    #[error("configuration key {} is not set", .0)]
+    Missing(&'static str),
+    #[error("configuration key {} has an invalid value: {}", .key, .reason)]
+    Invalid { key: &'static str, reason: String },
+}
+
+pub type ConfigResult<T> = Result<T, ConfigError>;
+
+/// Reads and parses a configuration value.
+///
+/// On the server, the value is read from the environment variable at runtime.
+/// On the frontend, the value provided at compile time is used.
+#[doc(hidden)]
+pub fn read<T>(
+    key: &'static str,
+    compile_time_value: Option<&'static str>,
+    default: Option<&'static str>,
+) -> ConfigResult<T>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    #[cfg(not(target_arch = "wasm32"))]
+    let value = {
+        let _ = compile_time_value;
+        std::env::var(key).ok()
+    };
+    #[cfg(target_arch = "wasm32")]
+    let value = compile_time_value.map(String::from);
+
+    value
+        .as_deref()
+        .or(default)
+        .ok_or(ConfigError::Missing(key))?
+        .parse()
+        .map_err(|e: T::Err| ConfigError::Invalid {
+            key,
+            reason: e.to_string(),
+        })
+}
+
+/// Defines a typed configuration.
+///
+/// See the [module level documentation](crate::config) for more information.
+#[macro_export]
+macro_rules! config {
+    (
+        $(#[$meta:meta])*
+        $vis:vis struct $name:ident {
+            public {
+                $(
+                    $(#[$pub_meta:meta])*
+                    $pub_field:ident: $pub_ty:ty = $pub_key:literal $(or $pub_default:literal)?
+                ),* $(,)?
+            }
+            $(
+                secret {
+                    $(
+                        $(#[$sec_meta:meta])*
+                        $sec_field:ident: $sec_ty:ty = $sec_key:literal $(or $sec_default:literal)?
+                    ),* $(,)?
+                }
+            )?
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone)]
+        $vis struct $name {
+            $(
+                $(#[$pub_meta])*
+                pub $pub_field: $pub_ty,
+            )*
+            $($(
+                $(#[$sec_meta])*
+                #[cfg(not(target_arch = "wasm32"))]
+                pub $sec_field: $sec_ty,
+            )*)?
+        }
+
+        impl $name {
+            /// Keys that are available on both the server and the frontend.
+            pub const PUBLIC_KEYS: &'static [&'static str] = &[$($pub_key),*];
+
+            /// Loads the configuration.
+            pub fn load() -> $crate::config::ConfigResult<Self> {
+                Ok(Self {
+                    $(
+                        $pub_field: $crate::config::read(
+                            $pub_key,
+                            option_env!($pub_key),
+                            $crate::__config_default!($($pub_default)?),
+                        )?,
+                    )*
+                    $($(
+                        #[cfg(not(target_arch = "wasm32"))]
+                        $sec_field: $crate::config::read(
+                            $sec_key,
+                            None,
+                            $crate::__config_default!($($sec_default)?),
+                        )?,
+                    )*)?
+                })
+            }
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __config_default {
+    () => {
+        None
+    };
+    ($default:literal) => {
+        Some($default)
+    };
+}
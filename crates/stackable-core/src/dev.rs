@@ -1,11 +1,62 @@
 use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
 
 use serde::{Deserialize, Serialize};
 
+use crate::observability::ObservabilityConfig;
+use crate::server::ServerConfig;
+
+/// The name of the meta element that enables strict mode checks in the frontend.
+pub const STRICT_MODE_META_NAME: &str = "stackable-strict";
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct StackctlMetadata {
     pub listen_addr: String,
+    /// The address of the admin listener, if `[server.admin]` is configured.
+    #[serde(default)]
+    pub admin_listen_addr: Option<String>,
     pub frontend_dev_build_dir: PathBuf,
+    #[serde(default)]
+    pub refresh_path: Option<String>,
+    #[serde(default)]
+    pub server_config: ServerConfig,
+    #[serde(default)]
+    pub observability: ObservabilityConfig,
+    /// Renders pages twice and reports differences, configured in `[dev-server]`.
+    #[serde(default)]
+    pub strict_mode: bool,
+    /// The file that the backend writes its API routes to as a JSON array, so stackctl can
+    /// check them against the routes of frontend routers.
+    #[serde(default)]
+    pub routes_path: Option<PathBuf>,
+    /// The file that the backend writes its periodic jobs to as a JSON array, so stackctl can
+    /// list them.
+    #[serde(default)]
+    pub jobs_path: Option<PathBuf>,
+    /// Writes log events to stderr as [`LogRecord`]s, so stackctl can render them.
+    #[serde(default)]
+    pub structured_logs: bool,
+    /// The filter of log events from `STACKCTL_LOG`, in the syntax of `EnvFilter`.
+    ///
+    /// If omitted, the filter of the application is used.
+    #[serde(default)]
+    pub log_filter: Option<String>,
+    /// The file that stackctl writes the mocks of `stackctl serve --mock` and `--replay` to as
+    /// [`Mocks`](crate::mock::Mocks), bridge requests that match a mock are not resolved.
+    #[serde(default)]
+    pub mocks_path: Option<PathBuf>,
+    /// The file that the backend records the results of queries and mutations to as a
+    /// [`MockFile`](crate::mock::MockFile), for `stackctl serve --record`.
+    #[serde(default)]
+    pub record_path: Option<PathBuf>,
+    /// The file that stackctl writes the [`DevStatus`] of the development server to, shown by
+    /// the dashboard at `/__stackable/`.
+    #[serde(default)]
+    pub status_path: Option<PathBuf>,
+    /// Hostnames that are served as virtual hosts of `[server.vhosts]`, as pairs of the alias and
+    /// the hostname of the virtual host, from `stackctl serve --host-alias`.
+    #[serde(default)]
+    pub host_aliases: Vec<(String, String)>,
 }
 
 impl StackctlMetadata {
@@ -19,3 +70,101 @@ impl StackctlMetadata {
         serde_json::to_string(self)
     }
 }
+
+/// A log event of the development server, written to stderr as a JSON line.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct LogRecord {
+    pub level: String,
+    pub target: String,
+    pub message: String,
+    #[serde(default)]
+    pub fields: Vec<(String, String)>,
+    /// The names of the spans that the event is in, from the outermost.
+    #[serde(default)]
+    pub spans: Vec<String>,
+}
+
+impl LogRecord {
+    pub fn from_json(s: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(s)
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string(self)
+    }
+}
+
+/// A build of the development server.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Rebuild {
+    pub started_at: SystemTime,
+    pub duration: Duration,
+    /// The error of the build, if it has failed.
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+/// The state of the development server that is only known to stackctl.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct DevStatus {
+    /// The name of the env profile.
+    pub env_profile: String,
+    /// The names of the variables loaded from env files, values are omitted as they may be
+    /// secrets.
+    #[serde(default)]
+    pub env_names: Vec<String>,
+    /// Recent builds, the latest first.
+    #[serde(default)]
+    pub rebuilds: Vec<Rebuild>,
+}
+
+impl DevStatus {
+    pub fn from_json(s: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(s)
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string(self)
+    }
+}
+
+/// A periodic job of the backend, listed by stackctl when the development server starts.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ScheduledJob {
+    pub name: String,
+    pub interval: Duration,
+}
+
+/// Returns the surroundings of the first difference between two renders of strict mode, or
+/// `None` if the renders are identical.
+pub fn strict_mode_difference(first: &str, second: &str) -> Option<(String, String)> {
+    if first == second {
+        return None;
+    }
+
+    // Both renders share the same bytes before this index, so it is a char boundary of both.
+    let index = first
+        .char_indices()
+        .zip(second.chars())
+        .find(|((_, a), b)| a != b)
+        .map(|((i, _), _)| i)
+        .unwrap_or_else(|| first.len().min(second.len()));
+
+    let snippet = |s: &str| {
+        let start = s[..index]
+            .char_indices()
+            .rev()
+            .nth(39)
+            .map(|(i, _)| i)
+            .unwrap_or(0);
+        let end = s[index..]
+            .char_indices()
+            .nth(40)
+            .map(|(i, _)| index + i)
+            .unwrap_or(s.len());
+
+        s[start..end].to_string()
+    };
+
+    Some((snippet(first), snippet(second)))
+}
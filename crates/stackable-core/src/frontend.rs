@@ -0,0 +1,52 @@
+use serde::{Deserialize, Serialize};
+
+/// The attribute set on `<html>` once the frontend has started.
+pub const READY_ATTR_NAME: &str = "data-stackable-ready";
+
+/// The attribute set on `<html>` if the frontend failed to load.
+///
+/// Pages keep working as server-rendered pages, links and `GET` forms navigate with full page
+/// loads. Forms that post are not handled by the server unless the application serves them.
+pub const DEGRADED_ATTR_NAME: &str = "data-stackable-degraded";
+
+/// The event dispatched on `window` if the frontend failed to load.
+///
+/// The reason is in `event.detail.reason` and is also kept in `window.stackableDegraded`, for
+/// listeners that are added after the event.
+pub const DEGRADED_EVENT_NAME: &str = "stackable:degraded";
+
+/// A frontend built into a subdirectory of the frontend build directory.
+///
+/// When `[frontend.<name>]` sections are present in `stackable.toml`, stackctl writes the list of
+/// frontends to `frontends.json` in the frontend build directory and the server serves each
+/// frontend at its mount path.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct FrontendMount {
+    /// The name of the frontend, which is also the name of its subdirectory.
+    pub name: String,
+    /// The path prefix that the frontend is served at, e.g.: `/admin`.
+    pub mount_path: String,
+}
+
+impl FrontendMount {
+    pub const FILE_NAME: &str = "frontends.json";
+
+    pub fn list_from_json(s: &str) -> serde_json::Result<Vec<Self>> {
+        serde_json::from_str(s)
+    }
+
+    pub fn list_to_json(mounts: &[Self]) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(mounts)
+    }
+
+    /// Returns `true` if the request path is served by this frontend.
+    pub fn contains(&self, path: &str) -> bool {
+        let mount_path = self.mount_path.trim_end_matches('/');
+
+        match path.strip_prefix(mount_path) {
+            Some(m) => m.is_empty() || m.starts_with('/'),
+            None => false,
+        }
+    }
+}
@@ -0,0 +1,44 @@
+//! Data that components provide during server-side rendering for hydration.
+
+use serde::{Deserialize, Serialize};
+
+/// The id of the script element that carries hydration data in server-rendered pages.
+pub const HYDRATION_ELEMENT_ID: &str = "stackable-hydration";
+
+/// The encoding of hydration data.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum HydrationFormat {
+    /// Readable in the page source.
+    #[default]
+    Json,
+    /// Bincode encoded as base64, which is usually smaller for numeric data.
+    Bincode,
+}
+
+/// Hydration data configuration, read from `[server.hydration]`.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct HydrationConfig {
+    #[serde(default)]
+    pub format: HydrationFormat,
+    /// Warns if the hydration data of a page is larger than this number of bytes.
+    #[serde(default = "HydrationConfig::default_warn_size")]
+    pub warn_size: usize,
+}
+
+impl Default for HydrationConfig {
+    fn default() -> Self {
+        Self {
+            format: HydrationFormat::default(),
+            warn_size: Self::default_warn_size(),
+        }
+    }
+}
+
+impl HydrationConfig {
+    fn default_warn_size() -> usize {
+        // 32 KiB
+        32 * 1024
+    }
+}
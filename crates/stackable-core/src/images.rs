@@ -0,0 +1,89 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// The id of the script element that carries the image manifest in server-rendered pages.
+pub const IMAGES_ELEMENT_ID: &str = "stackable-images";
+
+/// A format that images of the public directory are converted to.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum ImageFormat {
+    Avif,
+    Webp,
+}
+
+impl ImageFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Avif => "avif",
+            Self::Webp => "webp",
+        }
+    }
+
+    /// Returns the `type` attribute of a `<source>` element.
+    pub fn mime(&self) -> &'static str {
+        match self {
+            Self::Avif => "image/avif",
+            Self::Webp => "image/webp",
+        }
+    }
+}
+
+/// A converted and resized copy of an image.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct ImageVariant {
+    pub href: String,
+    pub format: ImageFormat,
+    /// The width in pixels, the `w` descriptor of `srcset`.
+    pub width: u32,
+}
+
+/// The variants of each image in the public directory, written to `image-manifest.json` by
+/// `stackctl build` if `[build.images]` is enabled.
+///
+/// The manifest is embedded into server-rendered pages, so pages render the same `srcset` on the
+/// server and in the browser.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct ImageManifest {
+    /// Variants by the path of the original image, e.g.: `images/hero.png`.
+    pub images: BTreeMap<String, Vec<ImageVariant>>,
+}
+
+impl ImageManifest {
+    pub const FILE_NAME: &str = "image-manifest.json";
+
+    /// Returns the variants of an image by its URL or its path in the public directory, narrowest
+    /// first.
+    pub fn variants(&self, path: &str) -> &[ImageVariant] {
+        self.images
+            .get(path.trim_start_matches('/'))
+            .map(|m| m.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Returns the `srcset` of an image in a format, e.g.: `/hero-640w.webp 640w, ...`.
+    ///
+    /// This returns `None` if the image is not converted to the format, the original should be
+    /// used instead.
+    pub fn srcset(&self, path: &str, format: ImageFormat) -> Option<String> {
+        let srcset = self
+            .variants(path)
+            .iter()
+            .filter(|m| m.format == format)
+            .map(|m| format!("{} {}w", m.href, m.width))
+            .collect::<Vec<_>>();
+
+        (!srcset.is_empty()).then(|| srcset.join(", "))
+    }
+
+    pub fn from_json(s: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(s)
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+}
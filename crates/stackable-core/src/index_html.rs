@@ -0,0 +1,49 @@
+//! Placeholders in `index.html`, e.g.: `{{ build_id }}` or `{{ env.API_URL }}`.
+//!
+//! stackctl substitutes the placeholders that are known when the frontend is built, the server
+//! substitutes the remaining placeholders when it renders a page. Values are inserted as is, so
+//! they can be used in attributes and scripts alike.
+
+/// The prefix of placeholders of environment variables.
+pub const ENV_PREFIX: &str = "env.";
+
+fn is_placeholder_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|m| m.is_ascii_alphanumeric() || m == '_' || m == '.')
+}
+
+/// Substitutes placeholders with their values, placeholders without a value are kept.
+pub fn substitute_placeholders<F>(s: &str, mut value: F) -> String
+where
+    F: FnMut(&str) -> Option<String>,
+{
+    let mut output = String::with_capacity(s.len());
+    let mut rest = s;
+
+    while let Some(start) = rest.find("{{") {
+        let (before, after) = rest.split_at(start);
+        output.push_str(before);
+
+        let end = match after.find("}}") {
+            Some(m) => m,
+            None => {
+                rest = after;
+                break;
+            }
+        };
+
+        let name = after[2..end].trim();
+        match is_placeholder_name(name).then(|| value(name)).flatten() {
+            Some(m) => output.push_str(&m),
+            None => output.push_str(&after[..end + 2]),
+        }
+
+        rest = &after[end + 2..];
+    }
+
+    output.push_str(rest);
+
+    output
+}
@@ -1,4 +1,24 @@
 #![deny(clippy::all)]
 #![deny(missing_debug_implementations)]
 
+pub mod app_state;
+pub mod assets;
+pub mod build_info;
+#[cfg(feature = "cipher")]
+pub mod cipher;
+pub mod compression;
+pub mod config;
 pub mod dev;
+pub mod frontend;
+pub mod hydration;
+pub mod images;
+pub mod index_html;
+pub mod locales;
+pub mod mock;
+pub mod observability;
+pub mod preload;
+pub mod pwa;
+pub mod render_cache;
+pub mod seo;
+pub mod server;
+pub mod session;
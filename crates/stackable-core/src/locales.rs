@@ -0,0 +1,167 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// The id of the script element that carries the locale manifest in server-rendered pages.
+pub const LOCALES_ELEMENT_ID: &str = "stackable-locales";
+
+/// The name of the meta element that carries the locale negotiated by the server.
+pub const LOCALE_META_NAME: &str = "stackable-locale";
+
+/// The cookie that overrides the locale negotiated from `Accept-Language`.
+pub const LOCALE_COOKIE_NAME: &str = "stackable-locale";
+
+/// Languages that are written from right to left.
+const RTL_LANGUAGES: [&str; 12] = [
+    "ar", "arc", "ckb", "dv", "fa", "ha", "he", "khw", "ks", "ps", "ur", "yi",
+];
+
+/// The text direction of a locale, the `dir` attribute of `<html>`.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TextDirection {
+    #[default]
+    Ltr,
+    Rtl,
+}
+
+impl TextDirection {
+    /// Returns the text direction of the language of a locale, e.g.: `rtl` for `ar-EG`.
+    pub fn for_locale(locale: &str) -> Self {
+        let language = locale.split(['-', '_']).next().unwrap_or_default();
+
+        match RTL_LANGUAGES
+            .iter()
+            .any(|m| m.eq_ignore_ascii_case(language))
+        {
+            true => Self::Rtl,
+            false => Self::Ltr,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Ltr => "ltr",
+            Self::Rtl => "rtl",
+        }
+    }
+}
+
+/// The `lang` and `dir` attributes of `<html>` for a locale.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct HtmlAttrs {
+    pub lang: String,
+    pub dir: TextDirection,
+}
+
+impl HtmlAttrs {
+    /// Derives the attributes from the name of a locale.
+    pub fn for_locale(locale: &str) -> Self {
+        Self {
+            lang: locale.replace('_', "-"),
+            dir: TextDirection::for_locale(locale),
+        }
+    }
+}
+
+/// The available locales, written to `locales.json` in the frontend build directory by stackctl.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct LocaleManifest {
+    pub default_locale: Option<String>,
+    /// The URLs of translation files of each locale.
+    ///
+    /// File names contain a hash of their content, so they can be cached indefinitely.
+    pub locales: BTreeMap<String, Vec<String>>,
+    /// The attributes of `<html>` for each locale, configured in `[i18n.locale.<name>]`.
+    #[serde(default)]
+    pub html_attrs: BTreeMap<String, HtmlAttrs>,
+}
+
+impl LocaleManifest {
+    pub const FILE_NAME: &str = "locales.json";
+
+    /// Returns the names of available locales, e.g.: `en-US`.
+    pub fn locale_names(&self) -> impl Iterator<Item = &str> {
+        self.locales.keys().map(|m| m.as_str())
+    }
+
+    /// Returns the URLs of translation files of a locale.
+    pub fn files(&self, locale: &str) -> Option<&[String]> {
+        self.locales.get(locale).map(|m| m.as_slice())
+    }
+
+    /// Returns the attributes of `<html>` for a locale.
+    pub fn html_attrs(&self, locale: &str) -> HtmlAttrs {
+        self.html_attrs
+            .get(locale)
+            .cloned()
+            .unwrap_or_else(|| HtmlAttrs::for_locale(locale))
+    }
+
+    /// Returns the available locale that matches a language tag, e.g.: `en` matches `en-US`.
+    fn find(&self, tag: &str) -> Option<&str> {
+        let tag = tag.replace('_', "-");
+        let normalise = |m: &str| m.replace('_', "-");
+
+        self.locale_names()
+            .find(|m| normalise(m).eq_ignore_ascii_case(&tag))
+            .or_else(|| {
+                let language = tag.split('-').next().unwrap_or_default();
+                self.locale_names().find(|m| {
+                    normalise(m)
+                        .split('-')
+                        .next()
+                        .map(|m| m.eq_ignore_ascii_case(language))
+                        .unwrap_or(false)
+                })
+            })
+    }
+
+    /// Selects a locale for a request.
+    ///
+    /// The locale in the cookie is preferred, then the languages in `Accept-Language` by their
+    /// weight, then the default locale.
+    pub fn negotiate(&self, cookie: Option<&str>, accept_language: Option<&str>) -> Option<&str> {
+        if let Some(m) = cookie.and_then(|m| self.locales.get_key_value(m)) {
+            return Some(m.0.as_str());
+        }
+
+        let mut languages = accept_language
+            .unwrap_or_default()
+            .split(',')
+            .filter_map(|m| {
+                let mut parts = m.split(';');
+                let tag = parts.next()?.trim();
+                let weight = parts
+                    .find_map(|m| m.trim().strip_prefix("q="))
+                    .and_then(|m| m.parse::<f32>().ok())
+                    .unwrap_or(1.0);
+
+                (!tag.is_empty() && tag != "*" && weight > 0.0).then_some((tag, weight))
+            })
+            .collect::<Vec<_>>();
+        // The sort is stable, so languages with the same weight keep their order.
+        languages.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+        languages
+            .into_iter()
+            .find_map(|(tag, _)| self.find(tag))
+            .or_else(|| {
+                self.default_locale
+                    .as_ref()
+                    .and_then(|m| self.locales.get_key_value(m))
+                    .map(|m| m.0.as_str())
+            })
+            .or_else(|| self.locale_names().next())
+    }
+
+    pub fn from_json(s: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(s)
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string(self)
+    }
+}
@@ -0,0 +1,111 @@
+//! Canned responses of bridge queries and mutations, served by `stackctl serve --mock`.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// A canned response of a query or mutation.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Mock {
+    /// The name of the query or mutation, matched against the end of its type path, e.g.:
+    /// `UserQuery` or `queries::UserQuery`.
+    pub name: String,
+    /// A pattern of the input, if omitted, any input matches.
+    ///
+    /// Objects match inputs that have matching values for all their keys, other values match
+    /// equal inputs.
+    #[serde(default)]
+    pub input: Option<Value>,
+    /// The output that the query or mutation resolves to.
+    #[serde(default)]
+    pub output: Value,
+    /// The error that the query or mutation fails with, it takes precedence over the output.
+    #[serde(default)]
+    pub error: Option<Value>,
+}
+
+impl Mock {
+    fn matches(&self, type_name: &str, input: &Value) -> bool {
+        let name_matches = type_name == self.name
+            || type_name
+                .strip_suffix(self.name.as_str())
+                .map(|m| m.ends_with("::"))
+                .unwrap_or(false);
+
+        name_matches
+            && self
+                .input
+                .as_ref()
+                .map(|m| pattern_matches(m, input))
+                .unwrap_or(true)
+    }
+}
+
+fn pattern_matches(pattern: &Value, input: &Value) -> bool {
+    match (pattern, input) {
+        (Value::Object(pattern), Value::Object(input)) => pattern
+            .iter()
+            .all(|(k, v)| input.get(k).map(|m| pattern_matches(v, m)).unwrap_or(false)),
+        (pattern, input) => pattern == input,
+    }
+}
+
+/// A file in the mocks directory, e.g.: `mocks/users.toml`.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct MockFile {
+    #[serde(default, rename = "mock")]
+    pub mocks: Vec<Mock>,
+}
+
+/// The mocks of an application, the first mock that matches a request is used.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Mocks {
+    mocks: Vec<Mock>,
+    #[serde(default)]
+    strict: bool,
+}
+
+impl Mocks {
+    pub fn new(mocks: Vec<Mock>) -> Self {
+        Self {
+            mocks,
+            strict: false,
+        }
+    }
+
+    /// Fails requests that match no mock instead of resolving them, e.g.: when a recording is
+    /// replayed.
+    pub fn with_strict(mut self) -> Self {
+        self.strict = true;
+
+        self
+    }
+
+    pub fn is_strict(&self) -> bool {
+        self.strict
+    }
+
+    pub fn from_json(s: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(s)
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string(self)
+    }
+
+    pub fn len(&self) -> usize {
+        self.mocks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.mocks.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Mock> {
+        self.mocks.iter()
+    }
+
+    /// Returns the mock of a query or mutation by its type name and input.
+    pub fn find(&self, type_name: &str, input: &Value) -> Option<&Mock> {
+        self.mocks.iter().find(|m| m.matches(type_name, input))
+    }
+}
@@ -0,0 +1,24 @@
+use serde::{Deserialize, Serialize};
+
+/// Observability configuration, read from the `[observability]` section of `stackable.toml`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", default)]
+pub struct ObservabilityConfig {
+    /// Exports traces to an OpenTelemetry collector.
+    pub enabled: bool,
+    /// The OTLP (gRPC) endpoint of the collector.
+    ///
+    /// If omitted, `OTEL_EXPORTER_OTLP_ENDPOINT` or `http://localhost:4317` is used.
+    pub otlp_endpoint: Option<String>,
+    /// The name of the service that is reported to the collector.
+    ///
+    /// If omitted, `OTEL_SERVICE_NAME` or the name of the server binary is used.
+    pub service_name: Option<String>,
+}
+
+impl ObservabilityConfig {
+    pub const DEFAULT_OTLP_ENDPOINT: &str = "http://localhost:4317";
+}
+
+/// The W3C trace context header, which connects the spans of a request across services.
+pub const TRACEPARENT_HEADER: &str = "traceparent";
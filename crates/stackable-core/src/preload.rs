@@ -0,0 +1,159 @@
+use serde::{Deserialize, Serialize};
+
+/// The type of an asset, the `as` attribute of a preload link.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum PreloadKind {
+    /// A JavaScript module, preloaded with `rel=modulepreload`.
+    Script,
+    Style,
+    /// A WebAssembly module, fetched by the JavaScript glue of wasm-bindgen.
+    Wasm,
+    Font,
+    Image,
+}
+
+impl PreloadKind {
+    /// Guesses the type of an asset from its file extension.
+    pub fn from_path(path: &str) -> Option<Self> {
+        let (_, ext) = path.rsplit_once('.')?;
+
+        let kind = match ext.to_ascii_lowercase().as_str() {
+            "js" | "mjs" => Self::Script,
+            "css" => Self::Style,
+            "wasm" => Self::Wasm,
+            "woff" | "woff2" | "ttf" | "otf" => Self::Font,
+            "png" | "jpg" | "jpeg" | "gif" | "webp" | "avif" | "svg" => Self::Image,
+            _ => return None,
+        };
+
+        Some(kind)
+    }
+
+    /// Returns the `rel` and the attributes of a preload link.
+    fn link_attrs(&self) -> (&'static str, &'static str) {
+        match self {
+            Self::Script => ("modulepreload", ""),
+            Self::Style => ("preload", "as=\"style\""),
+            // The glue fetches the module with `fetch`, so it is preloaded as a CORS request.
+            Self::Wasm => (
+                "preload",
+                "as=\"fetch\" type=\"application/wasm\" crossorigin",
+            ),
+            Self::Font => ("preload", "as=\"font\" crossorigin"),
+            Self::Image => ("preload", "as=\"image\""),
+        }
+    }
+}
+
+/// An asset that a page requires.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct PreloadAsset {
+    /// The url of the asset, including the public url of its frontend, e.g.: `/admin/app.js`.
+    pub href: String,
+    pub kind: PreloadKind,
+}
+
+impl PreloadAsset {
+    /// Returns the value of a `Link` header that preloads the asset.
+    ///
+    /// Proxies and CDNs may send `Link` headers of a response as `103 Early Hints`.
+    pub fn to_link_header(&self) -> String {
+        let (rel, attrs) = self.kind.link_attrs();
+        let mut s = format!("<{}>; rel={}", self.href, rel);
+
+        for attr in attrs.split(' ').filter(|m| !m.is_empty()) {
+            s.push_str("; ");
+            s.push_str(attr);
+        }
+
+        s
+    }
+
+    /// Returns a `<link>` element that preloads the asset.
+    pub fn to_link_tag(&self) -> String {
+        self.to_link_tag_with_integrity(None)
+    }
+
+    /// Returns a `<link>` element that preloads the asset, with its subresource integrity hash.
+    pub fn to_link_tag_with_integrity(&self, integrity: Option<&str>) -> String {
+        let (rel, attrs) = self.kind.link_attrs();
+        let href = self.href.replace('&', "&amp;").replace('"', "&quot;");
+
+        let mut s = format!(r#"<link rel="{rel}" href="{href}""#);
+        if !attrs.is_empty() {
+            s.push(' ');
+            s.push_str(attrs);
+        }
+        if let Some(m) = integrity {
+            s.push_str(&format!(r#" integrity="{m}""#));
+        }
+        s.push('>');
+
+        s
+    }
+}
+
+/// The assets that the pages of a route require.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct RoutePreload {
+    /// The path pattern of yew-router, e.g.: `/posts/:id`.
+    pub route: String,
+    pub assets: Vec<PreloadAsset>,
+}
+
+/// The assets that each page requires, written to `preload-manifest.json` of each frontend by
+/// `stackctl build`.
+///
+/// The server preloads the assets of a page while it is rendered, with `Link` headers and
+/// `<link>` elements.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct PreloadManifest {
+    /// The assets that every page of the frontend requires, e.g.: the wasm module.
+    ///
+    /// These are already referenced by `index.html`.
+    pub entry: Vec<PreloadAsset>,
+    /// Routes with assets in addition to the entry, static routes first.
+    pub routes: Vec<RoutePreload>,
+}
+
+impl PreloadManifest {
+    pub const FILE_NAME: &str = "preload-manifest.json";
+
+    /// Returns the assets of the first route that matches the path.
+    pub fn route_assets(&self, path: &str) -> &[PreloadAsset] {
+        self.routes
+            .iter()
+            .find(|m| route_matches(&m.route, path))
+            .map(|m| m.assets.as_slice())
+            .unwrap_or_default()
+    }
+
+    pub fn from_json(s: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(s)
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+/// Returns `true` if a path matches a path pattern of yew-router.
+///
+/// `:name` matches a segment and `*name` matches the remaining segments.
+pub fn route_matches(pattern: &str, path: &str) -> bool {
+    let mut pattern_segments = pattern.split('/').filter(|m| !m.is_empty());
+    let mut path_segments = path.split('/').filter(|m| !m.is_empty());
+
+    loop {
+        match (pattern_segments.next(), path_segments.next()) {
+            (Some(m), Some(_)) if m.starts_with('*') => return true,
+            (Some(m), Some(n)) if m.starts_with(':') || m == n => {}
+            (None, None) => return true,
+            _ => return false,
+        }
+    }
+}
@@ -0,0 +1,26 @@
+//! Service workers and web manifests that `stackctl build` generates if `[build.pwa]` is
+//! enabled.
+
+/// The file name of the service worker in each frontend, which is not hashed as browsers check
+/// it for updates at the same URL.
+pub const SERVICE_WORKER_FILE_NAME: &str = "service-worker.js";
+
+/// The file name of the web manifest in each frontend.
+pub const WEB_MANIFEST_FILE_NAME: &str = "manifest.webmanifest";
+
+/// The prefix of cache names of service workers, followed by the scope and the build id.
+pub const CACHE_PREFIX: &str = "stackable-";
+
+/// A service worker that removes caches and unregisters itself, served by the development server.
+///
+/// Service workers of production builds on the same origin would otherwise keep serving assets
+/// of a previous build.
+pub const NOOP_SERVICE_WORKER: &str = r#"self.addEventListener('install', () => self.skipWaiting());
+self.addEventListener('activate', (e) => {
+    e.waitUntil((async () => {
+        const keys = await caches.keys();
+        await Promise.all(keys.filter((m) => m.startsWith('stackable-')).map((m) => caches.delete(m)));
+        await self.registration.unregister();
+    })());
+});
+"#;
@@ -0,0 +1,89 @@
+//! Caching of server-rendered pages, shared by server-side rendering and bridge resolvers.
+//!
+//! Pages of routes in `[server.render-cache]` are cached, other pages are cached if they are
+//! marked cacheable while rendering. Cached pages are invalidated by their path or by keys that
+//! they add.
+
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+#[derive(Debug, Default)]
+struct Inner {
+    /// `None` if the route of the page decides.
+    cacheable: Option<bool>,
+    ttl: Option<Duration>,
+    keys: Vec<String>,
+    invalidated: Vec<String>,
+}
+
+/// Controls the render cache for a request.
+///
+/// Clones share the same state, so pages and resolvers of the same request can invalidate pages.
+#[derive(Debug, Clone, Default)]
+pub struct RenderCacheControl {
+    inner: Arc<Mutex<Inner>>,
+}
+
+impl PartialEq for RenderCacheControl {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.inner, &other.inner)
+    }
+}
+
+impl RenderCacheControl {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn inner(&self) -> MutexGuard<'_, Inner> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Caches the page, which expires after `ttl` or the ttl of the configuration if `None`.
+    pub fn cache(&self, ttl: Option<Duration>) {
+        let mut inner = self.inner();
+        inner.cacheable = Some(true);
+        inner.ttl = ttl;
+    }
+
+    /// Does not cache the page, even if its route is cached.
+    pub fn no_cache(&self) {
+        self.inner().cacheable = Some(false);
+    }
+
+    /// Adds a key that invalidates the page, e.g.: `post:1`.
+    ///
+    /// Pages are always invalidated by their path.
+    pub fn add_key<S>(&self, key: S)
+    where
+        S: Into<String>,
+    {
+        self.inner().keys.push(key.into());
+    }
+
+    /// Invalidates cached pages of a path or a key after the response.
+    pub fn invalidate<S>(&self, key: S)
+    where
+        S: Into<String>,
+    {
+        self.inner().invalidated.push(key.into());
+    }
+
+    /// Returns whether the page is cached, or `None` if the route of the page decides.
+    pub fn is_cacheable(&self) -> Option<bool> {
+        self.inner().cacheable
+    }
+
+    pub fn ttl(&self) -> Option<Duration> {
+        self.inner().ttl
+    }
+
+    pub fn keys(&self) -> Vec<String> {
+        self.inner().keys.clone()
+    }
+
+    /// Returns the keys to invalidate, which are only returned once.
+    pub fn take_invalidated(&self) -> Vec<String> {
+        std::mem::take(&mut self.inner().invalidated)
+    }
+}
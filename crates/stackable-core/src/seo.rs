@@ -0,0 +1,25 @@
+use serde::{Deserialize, Serialize};
+
+/// The routes of a frontend router, written by a frontend built for the host for `stackctl build`
+/// if `[seo]` is enabled.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct RouteList {
+    /// The path patterns of yew-router, e.g.: `/posts/:id`.
+    pub routes: Vec<String>,
+    /// The path of the not found route, which is never listed in the sitemap.
+    pub not_found: Option<String>,
+}
+
+impl RouteList {
+    /// The path to write the routes to, set by `stackctl build`.
+    pub const ENV_NAME: &str = "STACKABLE_ROUTES_PATH";
+
+    pub fn from_json(s: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(s)
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+}
@@ -0,0 +1,479 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::compression::StateCompressionConfig;
+use crate::hydration::HydrationConfig;
+
+/// The backend to store sessions in.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case", tag = "store")]
+pub enum SessionStoreConfig {
+    /// Stores sessions in memory, sessions are lost when the server restarts.
+    #[default]
+    Memory,
+    /// Stores sessions as files in a directory.
+    File { path: PathBuf },
+    /// Stores sessions in a Redis server.
+    Redis { url: String },
+}
+
+/// Session configuration, read from `[server.session]`.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct SessionConfig {
+    #[serde(flatten)]
+    pub store: SessionStoreConfig,
+    /// The number of seconds until a session expires.
+    #[serde(default = "SessionConfig::default_max_age")]
+    pub max_age: u64,
+    /// The name of the cookie that carries the encrypted session id.
+    #[serde(default = "SessionConfig::default_cookie_name")]
+    pub cookie_name: String,
+    /// Only sends the cookie over HTTPS, the development server always disables it.
+    #[serde(default = "SessionConfig::default_secure")]
+    pub secure: bool,
+}
+
+impl Default for SessionConfig {
+    fn default() -> Self {
+        Self {
+            store: SessionStoreConfig::default(),
+            max_age: Self::default_max_age(),
+            cookie_name: Self::default_cookie_name(),
+            secure: Self::default_secure(),
+        }
+    }
+}
+
+impl SessionConfig {
+    fn default_max_age() -> u64 {
+        // 1 day
+        86400
+    }
+
+    fn default_cookie_name() -> String {
+        "stackable-session".to_string()
+    }
+
+    fn default_secure() -> bool {
+        true
+    }
+}
+
+/// The backend to cache rendered pages in.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case", tag = "store")]
+pub enum RenderCacheStoreConfig {
+    /// Caches pages in memory, the least recently used page is removed when it is full.
+    #[default]
+    Memory,
+    /// Caches pages in a Redis server, which can be shared by multiple servers.
+    Redis { url: String },
+}
+
+/// Render cache configuration, read from `[server.render-cache]`.
+///
+/// Pages are only cached for requests without a stored session, authorization or cookies other
+/// than the CSRF token and the locale, and responses that set cookies are never cached.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct RenderCacheConfig {
+    #[serde(flatten)]
+    pub store: RenderCacheStoreConfig,
+    /// Path patterns of yew-router of pages that are cached, e.g.: `/posts/:id`.
+    ///
+    /// Pages of other routes are only cached if they are marked cacheable while rendering.
+    #[serde(default)]
+    pub routes: Vec<String>,
+    /// The number of seconds until a page expires, unless the page sets its own.
+    #[serde(default = "RenderCacheConfig::default_ttl")]
+    pub ttl: u64,
+    /// The number of pages cached in memory.
+    #[serde(default = "RenderCacheConfig::default_capacity")]
+    pub capacity: usize,
+}
+
+impl Default for RenderCacheConfig {
+    fn default() -> Self {
+        Self {
+            store: RenderCacheStoreConfig::default(),
+            routes: Vec::new(),
+            ttl: Self::default_ttl(),
+            capacity: Self::default_capacity(),
+        }
+    }
+}
+
+impl RenderCacheConfig {
+    fn default_ttl() -> u64 {
+        60
+    }
+
+    fn default_capacity() -> usize {
+        1000
+    }
+}
+
+/// CSRF protection of bridge mutations and uploads, read from `[server.csrf]`.
+///
+/// Pages are served with a token in a cookie, which the frontend copies into a header of
+/// mutations and uploads. Requests without a matching header are rejected.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct CsrfConfig {
+    #[serde(default = "CsrfConfig::default_enabled")]
+    pub enabled: bool,
+}
+
+impl Default for CsrfConfig {
+    fn default() -> Self {
+        Self {
+            enabled: Self::default_enabled(),
+        }
+    }
+}
+
+impl CsrfConfig {
+    fn default_enabled() -> bool {
+        true
+    }
+}
+
+/// A listener for health checks and metrics, read from `[server.admin]`.
+///
+/// It is served over HTTP, so it should only be reachable from the internal network. Metrics are
+/// served by this listener instead of the public one.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct AdminConfig {
+    /// The address to listen to, e.g.: `127.0.0.1:9090`.
+    pub listen: String,
+    /// The path that responds successfully while the server is running.
+    #[serde(default = "AdminConfig::default_health_path")]
+    pub health_path: String,
+}
+
+impl AdminConfig {
+    fn default_health_path() -> String {
+        "/healthz".to_string()
+    }
+}
+
+/// Graceful shutdown of the production server, read from `[server.shutdown]`.
+///
+/// On `SIGTERM` or `Ctrl-C`, the server stops accepting connections and waits for requests in
+/// flight before it runs shutdown hooks and exits.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct ShutdownConfig {
+    /// The number of seconds to wait for requests in flight, `0` waits indefinitely.
+    #[serde(default = "ShutdownConfig::default_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+impl Default for ShutdownConfig {
+    fn default() -> Self {
+        Self {
+            timeout_secs: Self::default_timeout_secs(),
+        }
+    }
+}
+
+impl ShutdownConfig {
+    fn default_timeout_secs() -> u64 {
+        30
+    }
+}
+
+/// Configuration of the runtime that renders pages, read from `[server.render]`.
+///
+/// Pages are rendered on dedicated worker threads, so rendering does not delay other requests.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct RenderConfig {
+    /// The number of worker threads, defaults to the number of CPU cores.
+    pub workers: Option<usize>,
+    /// Streams pages while they are rendered, see `Endpoint::with_streaming`.
+    #[serde(default)]
+    pub streaming: bool,
+}
+
+/// The number of requests of a class that are handled at the same time.
+///
+/// Requests over the limit are queued. Requests that cannot be queued are shed with
+/// `503 Service Unavailable`.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct ConcurrencyLimit {
+    pub max_concurrent: usize,
+    /// The number of requests that can wait, requests over it are shed immediately.
+    ///
+    /// If omitted, the queue is not limited.
+    pub max_queued: Option<usize>,
+    /// The number of milliseconds that a request waits before it is shed.
+    ///
+    /// If omitted, requests wait until they can be handled.
+    pub queue_timeout_ms: Option<u64>,
+    /// The number of seconds in `Retry-After` of shed requests.
+    #[serde(default = "ConcurrencyLimit::default_retry_after")]
+    pub retry_after: u64,
+    /// Responds to shed requests with the last render of the same URL, if there is one.
+    ///
+    /// Only renders of requests without authorization or cookies other than the CSRF token and
+    /// the locale are kept and served, so pages of signed-in users are never shared. This only
+    /// applies to rendered pages that are not streamed or marked as not cacheable.
+    #[serde(default)]
+    pub serve_stale: bool,
+}
+
+impl ConcurrencyLimit {
+    fn default_retry_after() -> u64 {
+        1
+    }
+}
+
+/// Concurrency limits of each class of requests, read from `[server.limits]`.
+///
+/// Assets are never queued, so they are served while the server is busy with other requests.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct RequestLimits {
+    /// Pages rendered by the server.
+    pub render: Option<ConcurrencyLimit>,
+    /// Requests to the bridge.
+    pub bridge: Option<ConcurrencyLimit>,
+}
+
+/// A token bucket that limits the rate of requests of each client.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct RateLimit {
+    /// The number of requests that a client can send in each period.
+    pub requests: u32,
+    /// The number of seconds of a period.
+    #[serde(default = "RateLimit::default_period_secs")]
+    pub period_secs: u64,
+    /// The number of requests that a client can send at once, defaults to `requests`.
+    pub burst: Option<u32>,
+    /// Also counts requests by the value of this header, e.g.: `X-Api-Key`.
+    ///
+    /// Requests are always counted by the address of the client, as clients can send any value.
+    pub header: Option<String>,
+}
+
+impl RateLimit {
+    fn default_period_secs() -> u64 {
+        1
+    }
+}
+
+/// A rate limit of requests with a path prefix.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct RateLimitRule {
+    /// The prefix of paths that the rule applies to, e.g.: `/api`.
+    pub prefix: String,
+    #[serde(flatten)]
+    pub limit: RateLimit,
+}
+
+/// Rate limits of requests, read from `[server.rate-limit]`.
+///
+/// Requests are limited by the rule with the longest matching prefix and by the global limit.
+/// Requests over either limit are rejected with `429 Too Many Requests`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct RateLimitConfig {
+    /// The limit of all requests, including requests that match a rule and assets.
+    ///
+    /// If omitted, only requests that match a rule are limited.
+    pub global: Option<RateLimit>,
+    #[serde(default)]
+    pub rules: Vec<RateLimitRule>,
+}
+
+/// CORS of the bridge, read from `[server.cors]`.
+///
+/// This allows frontends and clients served from other origins to call the bridge.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct CorsConfig {
+    /// Origins that are allowed to call the bridge, e.g.: `https://example.com`. `"*"` allows
+    /// any origin.
+    pub origins: Vec<String>,
+    /// Methods that are allowed in requests.
+    #[serde(default = "CorsConfig::default_methods")]
+    pub methods: Vec<String>,
+    /// Headers that are allowed in requests, in addition to the headers of the bridge.
+    #[serde(default)]
+    pub headers: Vec<String>,
+    /// Allows requests with cookies, this cannot be used with `"*"`.
+    #[serde(default)]
+    pub credentials: bool,
+    /// The number of seconds that browsers cache preflight responses.
+    pub max_age: Option<u64>,
+}
+
+impl CorsConfig {
+    fn default_methods() -> Vec<String> {
+        vec!["GET".to_string(), "POST".to_string()]
+    }
+}
+
+/// TLS of the production server, read from `[server.tls]`.
+///
+/// The development server is always served over HTTP.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct TlsConfig {
+    /// The path of the certificate chain in PEM format.
+    pub cert: PathBuf,
+    /// The path of the private key in PEM format.
+    pub key: PathBuf,
+    /// Negotiates HTTP/2 with ALPN, clients without HTTP/2 support use HTTP/1.1.
+    #[serde(default = "TlsConfig::default_http2")]
+    pub http2: bool,
+    /// Serves HTTP/3 over QUIC on the UDP port of the same number, and advertises it with
+    /// `Alt-Svc`.
+    ///
+    /// This is experimental and requires the `http3` feature of `stackable-backend`.
+    #[serde(default)]
+    pub http3: bool,
+}
+
+impl TlsConfig {
+    fn default_http2() -> bool {
+        true
+    }
+}
+
+/// What the server answers for pages that are not found.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum FallbackMode {
+    /// Serves the rendered page with `404 Not Found`, e.g.: the not-found route of the router.
+    #[default]
+    Render,
+    /// Serves `index.html` with `200 OK`, so the page is rendered by the router in the browser.
+    IndexHtml,
+    /// Answers with an empty `404 Not Found`.
+    NotFound,
+}
+
+/// Paths that are not routes of the frontend, read from `[server.fallback]`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct FallbackConfig {
+    /// What pages that are answered with `404 Not Found` are replaced with.
+    #[serde(default)]
+    pub mode: FallbackMode,
+    /// Paths with these prefixes are answered with an empty `404 Not Found` instead of a page if
+    /// no route of the server matches them, e.g.: `/api/`.
+    #[serde(default)]
+    pub not_found_prefixes: Vec<String>,
+}
+
+/// A virtual host, read from `[server.vhosts."<hostname>"]`.
+///
+/// Requests to the hostname are served as if their paths had the prefix, e.g.: `/users` of
+/// `admin.example.com` is served as `/admin/users`. Paths that already have the prefix and routes
+/// of the server, such as the bridge, are served as is.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct VirtualHost {
+    /// The name of a frontend in `[frontend.<name>]`, which is served at its mount path.
+    pub frontend: Option<String>,
+    /// The path prefix, if no frontend is set, e.g.: `/admin`.
+    pub prefix: Option<String>,
+}
+
+/// Server configuration shared between stackctl and the server.
+///
+/// This is read from the `[server]` section of `stackable.toml`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", default)]
+pub struct ServerConfig {
+    /// The path that the bridge is mounted at.
+    ///
+    /// If omitted, the path of the bridge is used.
+    pub bridge_path: Option<String>,
+    /// Origins that are allowed to connect to websocket endpoints and the bridge.
+    ///
+    /// If omitted, origins are not checked. `"*"` allows any origin.
+    pub allowed_origins: Option<Vec<String>>,
+    /// CORS of the bridge.
+    pub cors: Option<CorsConfig>,
+    /// The header that a reverse proxy sets to the address of the client, e.g.:
+    /// `X-Forwarded-For`.
+    pub client_ip_header: Option<String>,
+    /// Session configuration.
+    pub session: Option<SessionConfig>,
+    /// CSRF protection of bridge mutations and uploads, enabled by default.
+    pub csrf: CsrfConfig,
+    /// Encrypts state payloads that are embedded into server-rendered pages.
+    ///
+    /// The key is derived from the secret in `STACKABLE_SECRET_KEY`.
+    pub encrypt_state: bool,
+    /// Compresses state payloads that are embedded into server-rendered pages.
+    pub state_compression: Option<StateCompressionConfig>,
+    /// The format and the size limit of hydration data.
+    pub hydration: HydrationConfig,
+    /// Serves request counts and latencies, render times and the latency and error rates of
+    /// bridge requests in the Prometheus format at `/__stackable/metrics`.
+    ///
+    /// Metrics are always collected by the development server.
+    pub metrics: bool,
+    /// Renders pages on a dedicated runtime.
+    pub render: Option<RenderConfig>,
+    /// Caches rendered pages.
+    pub render_cache: Option<RenderCacheConfig>,
+    /// Limits requests of each class, so the server degrades gracefully under load.
+    pub limits: RequestLimits,
+    /// Limits the rate of requests of each client.
+    pub rate_limit: Option<RateLimitConfig>,
+    /// Serves over HTTPS.
+    pub tls: Option<TlsConfig>,
+    /// Serves health checks and metrics on a separate listener.
+    pub admin: Option<AdminConfig>,
+    /// Graceful shutdown of the production server.
+    pub shutdown: ShutdownConfig,
+    /// What paths that are not routes of the frontend are answered with.
+    pub fallback: FallbackConfig,
+    /// Virtual hosts by their hostnames, so one server can serve hostnames from different
+    /// frontends.
+    pub vhosts: BTreeMap<String, VirtualHost>,
+}
+
+#[derive(Error, Debug)]
+pub enum ServerConfigError {
+    #[error("[server.cors] cannot allow credentials with any origin (\"*\")")]
+    CorsCredentialsWithAnyOrigin,
+}
+
+impl ServerConfig {
+    pub const ENV_NAME: &str = "STACKABLE_SERVER_CONFIG";
+    pub const FILE_NAME: &str = "server-config.json";
+
+    pub fn from_json(s: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(s)
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string(self)
+    }
+
+    /// Checks settings that can be parsed but not applied.
+    pub fn validate(&self) -> Result<(), ServerConfigError> {
+        // Browsers reject responses that allow credentials with any origin.
+        if let Some(ref m) = self.cors {
+            if m.credentials && m.origins.iter().any(|n| n == "*") {
+                return Err(ServerConfigError::CorsCredentialsWithAnyOrigin);
+            }
+        }
+
+        Ok(())
+    }
+}
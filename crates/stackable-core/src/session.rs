@@ -0,0 +1,137 @@
+//! Sessions that are shared by server-side rendering and bridge resolvers.
+//!
+//! The server loads the session of a request from its session store and saves it after the
+//! response if it has changed.
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+#[derive(Debug, Default)]
+struct Inner {
+    /// `None` if the session has not been stored yet.
+    id: Option<String>,
+    values: BTreeMap<String, serde_json::Value>,
+    changed: bool,
+    renewed: bool,
+    destroyed: bool,
+}
+
+/// The session of a request.
+///
+/// Clones share the same values, so changes made while rendering are visible to resolvers of the
+/// same request.
+#[derive(Debug, Clone, Default)]
+pub struct Session {
+    inner: Arc<Mutex<Inner>>,
+}
+
+impl PartialEq for Session {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.inner, &other.inner)
+    }
+}
+
+impl Session {
+    /// Creates an empty session, which is only stored once a value is inserted.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Restores a session from the content in a session store.
+    pub fn from_stored(id: String, data: &[u8]) -> serde_json::Result<Self> {
+        let inner = Inner {
+            id: Some(id),
+            values: serde_json::from_slice(data)?,
+            ..Default::default()
+        };
+
+        Ok(Self {
+            inner: Arc::new(Mutex::new(inner)),
+        })
+    }
+
+    fn inner(&self) -> MutexGuard<'_, Inner> {
+        // Values are only replaced, so they are consistent even if a holder has panicked.
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Returns the id of the session, or `None` if it has not been stored yet.
+    pub fn id(&self) -> Option<String> {
+        self.inner().id.clone()
+    }
+
+    /// Returns a value, or `None` if it does not exist or cannot be deserialised as `T`.
+    pub fn get<T>(&self, key: &str) -> Option<T>
+    where
+        T: DeserializeOwned,
+    {
+        let value = self.inner().values.get(key).cloned()?;
+
+        serde_json::from_value(value).ok()
+    }
+
+    pub fn insert<T>(&self, key: &str, value: T) -> serde_json::Result<()>
+    where
+        T: Serialize,
+    {
+        let value = serde_json::to_value(value)?;
+
+        let mut inner = self.inner();
+        inner.values.insert(key.to_string(), value);
+        inner.changed = true;
+        inner.destroyed = false;
+
+        Ok(())
+    }
+
+    pub fn remove(&self, key: &str) {
+        let mut inner = self.inner();
+        if inner.values.remove(key).is_some() {
+            inner.changed = true;
+        }
+    }
+
+    /// Moves the values to a new id when the session is saved.
+    ///
+    /// This should be called when a user signs in, so an id that is known before signing in
+    /// cannot be used afterwards.
+    pub fn renew(&self) {
+        let mut inner = self.inner();
+        inner.renewed = true;
+        inner.changed = true;
+    }
+
+    /// Removes all values and the session from the store, e.g.: when a user signs out.
+    pub fn destroy(&self) {
+        let mut inner = self.inner();
+        inner.values.clear();
+        inner.destroyed = true;
+        inner.changed = true;
+    }
+
+    /// Returns `true` if the session needs to be saved.
+    pub fn is_changed(&self) -> bool {
+        self.inner().changed
+    }
+
+    pub fn is_renewed(&self) -> bool {
+        self.inner().renewed
+    }
+
+    pub fn is_destroyed(&self) -> bool {
+        self.inner().destroyed
+    }
+
+    /// Returns `true` if the session has no values.
+    pub fn is_empty(&self) -> bool {
+        self.inner().values.is_empty()
+    }
+
+    /// Encodes the values for a session store.
+    pub fn to_bytes(&self) -> serde_json::Result<Vec<u8>> {
+        serde_json::to_vec(&self.inner().values)
+    }
+}
@@ -1,3 +1,5 @@
 mod client_only;
+mod reload_notice;
 
 pub use client_only::ClientOnly;
+pub(crate) use reload_notice::ReloadNotice;
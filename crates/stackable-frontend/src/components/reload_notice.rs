@@ -0,0 +1,71 @@
+use std::time::Duration;
+
+use stackable_bridge::Bridge;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use yew::platform::spawn_local;
+use yew::platform::time::sleep;
+use yew::prelude::*;
+
+/// The time the notice is shown before the page is reloaded.
+const RELOAD_DELAY: Duration = Duration::from_secs(3);
+
+/// Shows a notice and reloads the page when the bridge reports that the server has been updated.
+#[function_component]
+pub(crate) fn ReloadNotice() -> Html {
+    let reload_required = use_state_eq(|| false);
+
+    // Effects are only run on the client side.
+    {
+        use_effect_with_deps(
+            |reload_required_setter| {
+                let reload_required_setter = reload_required_setter.clone();
+                let listener = Closure::<dyn Fn()>::new(move || reload_required_setter.set(true));
+                let window = web_sys::window().expect("failed to get window");
+
+                let _ = window.add_event_listener_with_callback(
+                    Bridge::RELOAD_REQUIRED_EVENT,
+                    listener.as_ref().unchecked_ref(),
+                );
+
+                move || {
+                    let _ = window.remove_event_listener_with_callback(
+                        Bridge::RELOAD_REQUIRED_EVENT,
+                        listener.as_ref().unchecked_ref(),
+                    );
+                }
+            },
+            reload_required.setter(),
+        );
+    }
+
+    {
+        use_effect_with_deps(
+            |reload_required| {
+                if *reload_required {
+                    spawn_local(async move {
+                        sleep(RELOAD_DELAY).await;
+
+                        if let Some(m) = web_sys::window() {
+                            let _ = m.location().reload();
+                        }
+                    });
+                }
+            },
+            *reload_required,
+        );
+    }
+
+    if !*reload_required {
+        return Html::default();
+    }
+
+    html! {
+        <div
+            role="status"
+            style="position:fixed;bottom:16px;left:50%;transform:translateX(-50%);z-index:2147483647;padding:12px 20px;border-radius:6px;background:#1f2937;color:#fff;font:14px sans-serif;box-shadow:0 4px 12px rgba(0,0,0,.3);"
+        >
+            {"A new version is available. Reloading..."}
+        </div>
+    }
+}
@@ -0,0 +1,24 @@
+use stackable_bridge::hydration::HydrationData;
+use stackable_core::hydration::HYDRATION_ELEMENT_ID;
+
+/// Reads the hydration data that the server has written into the page.
+///
+/// Pages rendered only in the browser have no hydration data.
+pub(crate) fn restore_hydration_data() -> HydrationData {
+    let content = match web_sys::window()
+        .and_then(|m| m.document())
+        .and_then(|m| m.get_element_by_id(HYDRATION_ELEMENT_ID))
+        .and_then(|m| m.text_content())
+    {
+        Some(m) => m,
+        None => return HydrationData::default(),
+    };
+
+    match HydrationData::from_json(&content) {
+        Ok(m) => m,
+        Err(e) => {
+            tracing::error!("failed to read hydration data: {e}");
+            HydrationData::default()
+        }
+    }
+}
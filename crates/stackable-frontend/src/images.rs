@@ -0,0 +1,24 @@
+//! Variants of images in the public directory, if `[build.images]` is enabled in
+//! `stackable.toml`.
+
+use stackable_core::images::IMAGES_ELEMENT_ID;
+pub use stackable_core::images::{ImageFormat, ImageManifest, ImageVariant};
+
+/// Returns the variants of images, which `srcset` of `<source>` elements can be created with.
+///
+/// The image manifest is embedded into pages rendered by the server, this returns `None` for
+/// pages rendered only in the browser and in the development server.
+pub fn image_manifest() -> Option<ImageManifest> {
+    let content = web_sys::window()?
+        .document()?
+        .get_element_by_id(IMAGES_ELEMENT_ID)?
+        .text_content()?;
+
+    match ImageManifest::from_json(&content) {
+        Ok(m) => Some(m),
+        Err(e) => {
+            tracing::error!("failed to read images: {e}");
+            None
+        }
+    }
+}
@@ -3,14 +3,49 @@
 
 use std::marker::PhantomData;
 
+#[cfg(not(target_arch = "wasm32"))]
+use stackable_bridge::contract::BridgeContract;
+use stackable_bridge::request::RequestContext;
 use stackable_bridge::Bridge;
+pub use stackable_core::build_info::BUILD_ID;
+use stackable_core::frontend::READY_ATTR_NAME;
+use stackable_core::seo::RouteList;
+use web_sys::Document;
 use yew::prelude::*;
+use yew_router::Routable;
 
+use crate::hydration::restore_hydration_data;
+use crate::prepared_state::restore_prepared_states;
 use crate::root::{StackableRoot, StackableRootProps};
 pub mod components;
+mod hydration;
+pub mod images;
+pub mod locales;
+mod prepared_state;
 mod root;
+mod strict;
 pub mod trace;
 
+/// Hydrates the application if the page is rendered by the server, otherwise renders it.
+fn mount<COMP>(document: Option<Document>, props: StackableRootProps)
+where
+    COMP: BaseComponent,
+{
+    let renderer = yew::Renderer::<StackableRoot<COMP>>::with_props(props);
+
+    if let Some(document) = document.filter(|m| {
+        m.query_selector(r#"meta[name="stackable-mode"][content="hydrate"]"#)
+            .ok()
+            .flatten()
+            .is_some()
+    }) {
+        restore_prepared_states(&document);
+        renderer.hydrate();
+    } else {
+        renderer.render();
+    }
+}
+
 #[derive(Debug)]
 pub struct Renderer<COMP>
 where
@@ -18,6 +53,8 @@ where
 {
     props: COMP::Properties,
     bridge: Option<Bridge>,
+    routes: Option<RouteList>,
+    request_context: RequestContext,
     _marker: PhantomData<COMP>,
 }
 
@@ -46,6 +83,8 @@ where
         Renderer {
             props,
             bridge: None,
+            routes: None,
+            request_context: RequestContext::default(),
             _marker: PhantomData,
         }
     }
@@ -56,34 +95,103 @@ where
         self
     }
 
-    fn into_yew_renderer(self) -> yew::Renderer<StackableRoot<COMP>> {
-        let Self { props, bridge, .. } = self;
+    /// Registers the routes of the router, which `stackctl build` lists in `sitemap.xml` if
+    /// `[seo]` is enabled.
+    pub fn routes<R>(mut self) -> Self
+    where
+        R: Routable,
+    {
+        self.routes = Some(RouteList {
+            routes: R::routes().into_iter().map(|m| m.to_string()).collect(),
+            not_found: R::not_found_route().map(|m| m.to_path()),
+        });
+
+        self
+    }
+
+    /// Sets the request that components see in the browser, which has no access to the request.
+    ///
+    /// The locale defaults to the locale that the server has negotiated.
+    pub fn request_context(mut self, request_context: RequestContext) -> Self {
+        self.request_context = request_context;
+
+        self
+    }
+
+    fn into_root_props(self) -> StackableRootProps {
+        let Self {
+            props,
+            bridge,
+            request_context,
+            ..
+        } = self;
         let bridge = bridge.unwrap_or_default();
+        let request_context = match (request_context.locale(), locales::current_locale()) {
+            (None, Some(m)) => request_context.with_locale(m),
+            _ => request_context,
+        };
 
         let children = html! {
             <COMP ..props />
         };
 
-        let props = StackableRootProps { bridge, children };
-
-        yew::Renderer::with_props(props)
+        StackableRootProps {
+            bridge,
+            children,
+            request_context: request_context.into(),
+            hydration_data: restore_hydration_data(),
+        }
     }
 
     pub fn render(self) {
-        let renderer = self.into_yew_renderer();
-
-        if web_sys::window()
-            .and_then(|m| m.document())
-            .and_then(|m| {
-                m.query_selector(r#"meta[name="stackable-mode"][content="hydrate"]"#)
-                    .ok()
-                    .flatten()
-            })
-            .is_some()
-        {
-            renderer.hydrate();
-        } else {
-            renderer.render();
+        // A frontend built for the host writes the contract of its bridge for
+        // `stackctl test --contracts` instead of rendering.
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Ok(path) = std::env::var(BridgeContract::ENV_NAME) {
+            let contract = self
+                .bridge
+                .as_ref()
+                .map(|m| m.contract().clone())
+                .unwrap_or_default();
+            let contract = contract.to_json().expect("failed to serialise contract");
+            std::fs::write(path, contract).expect("failed to write contract");
+
+            return;
+        }
+
+        // The routes are written for the sitemap of `stackctl build`.
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Ok(path) = std::env::var(RouteList::ENV_NAME) {
+            let routes = self.routes.clone().unwrap_or_default();
+            let routes = routes.to_json().expect("failed to serialise routes");
+            std::fs::write(path, routes).expect("failed to write routes");
+
+            return;
         }
+
+        // Panics are written to the browser console with a backtrace, which the development
+        // server forwards to stackctl.
+        console_error_panic_hook::set_once();
+
+        let props = self.into_root_props();
+
+        let document = web_sys::window().and_then(|m| m.document());
+
+        // The page is no longer marked as degraded by errors after the frontend has started.
+        if let Some(m) = document.as_ref().and_then(|m| m.document_element()) {
+            let _ = m.set_attribute(READY_ATTR_NAME, "");
+        }
+
+        // Strict mode mounts the application twice before it is hydrated.
+        if let Some(document) = document.clone().filter(strict::is_enabled) {
+            yew::platform::spawn_local(async move {
+                strict::check::<COMP>(&document, props.clone()).await;
+                mount::<COMP>(Some(document), props);
+            });
+
+            return;
+        }
+
+        mount::<COMP>(document, props);
     }
 }
@@ -0,0 +1,58 @@
+//! The locales that stackctl copied into the frontend, if `[i18n]` is enabled in `stackable.toml`.
+
+pub use stackable_core::locales::{HtmlAttrs, LocaleManifest, TextDirection};
+use stackable_core::locales::{LOCALES_ELEMENT_ID, LOCALE_COOKIE_NAME, LOCALE_META_NAME};
+use wasm_bindgen::JsCast;
+use web_sys::HtmlDocument;
+
+/// Returns the available locales and the URLs of their translation files.
+///
+/// The locale manifest is embedded into pages rendered by the server, this returns `None` for
+/// pages rendered only in the browser.
+pub fn available_locales() -> Option<LocaleManifest> {
+    let content = web_sys::window()?
+        .document()?
+        .get_element_by_id(LOCALES_ELEMENT_ID)?
+        .text_content()?;
+
+    match LocaleManifest::from_json(&content) {
+        Ok(m) => Some(m),
+        Err(e) => {
+            tracing::error!("failed to read locales: {e}");
+            None
+        }
+    }
+}
+
+/// Returns the locale that the server has negotiated for this page.
+pub fn current_locale() -> Option<String> {
+    web_sys::window()?
+        .document()?
+        .query_selector(&format!(r#"meta[name="{LOCALE_META_NAME}"]"#))
+        .ok()
+        .flatten()?
+        .get_attribute("content")
+}
+
+/// Switches to a locale.
+///
+/// This updates `lang` and `dir` of `<html>` and remembers the locale in a cookie, so the
+/// server renders later pages with it.
+pub fn set_locale(locales: &LocaleManifest, locale: &str) {
+    let document = match web_sys::window().and_then(|m| m.document()) {
+        Some(m) => m,
+        None => return,
+    };
+
+    if let Some(element) = document.document_element() {
+        let attrs = locales.html_attrs(locale);
+        let _ = element.set_attribute("lang", &attrs.lang);
+        let _ = element.set_attribute("dir", attrs.dir.as_str());
+    }
+
+    if let Some(m) = document.dyn_ref::<HtmlDocument>() {
+        let _ = m.set_cookie(&format!(
+            "{LOCALE_COOKIE_NAME}={locale}; Path=/; Max-Age=31536000; SameSite=Lax"
+        ));
+    }
+}
@@ -0,0 +1,123 @@
+//! Restores the state that components prepared during server-side rendering before hydration.
+
+use std::io::{self, Read};
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use flate2::read::GzDecoder;
+use stackable_core::cipher::{StateKey, ENCRYPTED_ATTR, KEY_ID_META_NAME};
+use stackable_core::compression::{CompressionAlgorithm, COMPRESSED_ATTR};
+use wasm_bindgen::JsCast;
+use web_sys::{Document, Element, HtmlDocument};
+
+fn read_cookie(document: &HtmlDocument, name: &str) -> Option<String> {
+    let cookies = document.cookie().ok()?;
+
+    cookies
+        .split(';')
+        .filter_map(|m| m.trim().split_once('='))
+        .find(|(k, _)| *k == name)
+        .map(|(_, v)| v.to_string())
+}
+
+/// Reads the key of encrypted states.
+///
+/// The key is only valid for this page, so the cookie is removed as soon as it is read.
+fn read_key(document: &Document) -> Option<StateKey> {
+    let key_id = document
+        .query_selector(&format!(r#"meta[name="{KEY_ID_META_NAME}"]"#))
+        .ok()
+        .flatten()
+        .and_then(|m| m.get_attribute("content"))?;
+
+    let html_document = document.dyn_ref::<HtmlDocument>()?;
+
+    let cookie_name = StateKey::cookie_name(&key_id);
+    let key = read_cookie(html_document, &cookie_name).and_then(|m| StateKey::decode(&m));
+    let _ = html_document.set_cookie(&format!("{cookie_name}=; Path=/; Max-Age=0"));
+
+    if key.is_none() {
+        tracing::error!("failed to read the key of encrypted state, hydration will fail");
+    }
+
+    key
+}
+
+fn decompress(algorithm: CompressionAlgorithm, s: &str) -> io::Result<String> {
+    let data = STANDARD
+        .decode(s.trim())
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+    let mut decompressed = Vec::new();
+    match algorithm {
+        CompressionAlgorithm::Gzip => {
+            GzDecoder::new(data.as_slice()).read_to_end(&mut decompressed)?;
+        }
+        CompressionAlgorithm::Brotli => {
+            brotli_decompressor::Decompressor::new(data.as_slice(), 4096)
+                .read_to_end(&mut decompressed)?;
+        }
+    }
+
+    Ok(STANDARD.encode(decompressed))
+}
+
+/// Decrypts and decompresses prepared states that have been processed by the server.
+pub(crate) fn restore_prepared_states(document: &Document) {
+    let states = match document.query_selector_all(&format!(
+        r#"script[type="application/x-yew-comp-state"][{ENCRYPTED_ATTR}],
+        script[type="application/x-yew-comp-state"][{COMPRESSED_ATTR}]"#
+    )) {
+        Ok(m) if m.length() > 0 => m,
+        _ => return,
+    };
+
+    let mut key = None;
+    if document
+        .query_selector(&format!("[{ENCRYPTED_ATTR}]"))
+        .ok()
+        .flatten()
+        .is_some()
+    {
+        key = read_key(document);
+    }
+
+    for i in 0..states.length() {
+        let state = match states.item(i).and_then(|m| m.dyn_into::<Element>().ok()) {
+            Some(m) => m,
+            None => continue,
+        };
+
+        let mut content = match state.text_content() {
+            Some(m) => m,
+            None => continue,
+        };
+
+        if state.has_attribute(ENCRYPTED_ATTR) {
+            match key.as_ref().and_then(|m| m.decrypt(&content)) {
+                Some(m) => content = m,
+                None => {
+                    tracing::error!("failed to decrypt state, it may have been modified");
+                    continue;
+                }
+            }
+        }
+
+        if let Some(algorithm) = state
+            .get_attribute(COMPRESSED_ATTR)
+            .and_then(|m| CompressionAlgorithm::from_name(&m))
+        {
+            match decompress(algorithm, &content) {
+                Ok(m) => content = m,
+                Err(e) => {
+                    tracing::error!("failed to decompress state: {}", e);
+                    continue;
+                }
+            }
+        }
+
+        state.set_text_content(Some(&content));
+        let _ = state.remove_attribute(ENCRYPTED_ATTR);
+        let _ = state.remove_attribute(COMPRESSED_ATTR);
+    }
+}
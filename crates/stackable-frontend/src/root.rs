@@ -1,25 +1,46 @@
+use std::rc::Rc;
+
 use bounce::helmet::HelmetBridge;
 use bounce::{use_atom_setter, BounceRoot};
+use stackable_bridge::hydration::{HydrationData, HydrationDataState};
+use stackable_bridge::request::{RequestContext, RequestContextState};
 use stackable_bridge::state::BridgeState;
 use stackable_bridge::Bridge;
 use yew::prelude::*;
 use yew_router::BrowserRouter;
 
+use crate::components::ReloadNotice;
+
 #[derive(Properties, PartialEq, Clone)]
 pub struct StackableRootProps {
     #[prop_or_default]
     pub children: Html,
     pub bridge: Bridge,
+    pub request_context: Rc<RequestContext>,
+    pub hydration_data: HydrationData,
 }
 
 #[function_component]
 pub fn Inner(props: &StackableRootProps) -> Html {
-    let StackableRootProps { children, bridge } = props.clone();
+    let StackableRootProps {
+        children,
+        bridge,
+        request_context,
+        hydration_data,
+    } = props.clone();
     let set_bridge = use_atom_setter::<BridgeState>();
+    let set_request_context = use_atom_setter::<RequestContextState>();
+    let set_hydration_data = use_atom_setter::<HydrationDataState>();
 
     use_memo(
         move |_| {
             set_bridge(BridgeState { inner: bridge });
+            set_request_context(RequestContextState {
+                inner: request_context,
+            });
+            set_hydration_data(HydrationDataState {
+                inner: hydration_data,
+            });
         },
         (),
     );
@@ -28,6 +49,7 @@ pub fn Inner(props: &StackableRootProps) -> Html {
         <BrowserRouter>
             <HelmetBridge />
             {children}
+            <ReloadNotice />
         </BrowserRouter>
     }
 }
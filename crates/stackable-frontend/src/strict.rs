@@ -0,0 +1,88 @@
+//! Strict mode of the development server.
+//!
+//! Before hydration, the application is mounted twice into detached elements. Each mount runs
+//! its effects and is then destroyed, which runs their cleanups. If the second mount renders
+//! differently, rendering or effects depend on state that is not restored by their cleanups.
+
+use std::time::Duration;
+
+use stackable_core::dev::{strict_mode_difference, STRICT_MODE_META_NAME};
+use web_sys::Document;
+use yew::platform::time::sleep;
+use yew::BaseComponent;
+
+use crate::root::{StackableRoot, StackableRootProps};
+
+/// How long effects of each mount can run before its content is recorded.
+const SETTLE_DURATION: Duration = Duration::from_millis(100);
+
+/// Returns `true` if the server has enabled strict mode for this page.
+pub(crate) fn is_enabled(document: &Document) -> bool {
+    document
+        .query_selector(&format!(
+            r#"meta[name="{STRICT_MODE_META_NAME}"][content="true"]"#
+        ))
+        .ok()
+        .flatten()
+        .is_some()
+}
+
+/// Mounts the application into a detached element and returns its content before destroying it.
+async fn mount_once<COMP>(document: &Document, props: StackableRootProps) -> Option<String>
+where
+    COMP: BaseComponent,
+{
+    let root = document.create_element("div").ok()?;
+    let handle =
+        yew::Renderer::<StackableRoot<COMP>>::with_root_and_props(root.clone(), props).render();
+
+    sleep(SETTLE_DURATION).await;
+    let content = root.inner_html();
+    handle.destroy();
+
+    Some(content)
+}
+
+fn show_overlay(document: &Document, message: &str) {
+    let overlay = match document.create_element("div") {
+        Ok(m) => m,
+        Err(_) => return,
+    };
+
+    overlay.set_text_content(Some(message));
+    let _ = overlay.set_attribute(
+        "style",
+        "position: fixed; top: 8px; left: 8px; z-index: 2147483647; padding: 4px 8px; \
+         background: rgba(160, 0, 0, 0.85); color: #fff; font: 12px monospace; \
+         border-radius: 4px; white-space: pre-wrap; max-width: 640px; pointer-events: none;",
+    );
+
+    if let Some(m) = document.document_element() {
+        let _ = m.append_child(&overlay);
+    }
+}
+
+/// Mounts the application twice and reports if the mounts render differently.
+pub(crate) async fn check<COMP>(document: &Document, props: StackableRootProps)
+where
+    COMP: BaseComponent,
+{
+    let first = match mount_once::<COMP>(document, props.clone()).await {
+        Some(m) => m,
+        None => return,
+    };
+    let second = match mount_once::<COMP>(document, props).await {
+        Some(m) => m,
+        None => return,
+    };
+
+    if let Some((first, second)) = strict_mode_difference(&first, &second) {
+        let message = format!(
+            "Strict mode: the application rendered differently when mounted again.\n\
+             First:  {first}\nSecond: {second}"
+        );
+
+        tracing::error!("{message}");
+        show_overlay(document, &message);
+    }
+}
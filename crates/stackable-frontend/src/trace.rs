@@ -6,6 +6,11 @@ use tracing_subscriber::util::SubscriberInitExt;
 use tracing_web::{performance_layer, MakeConsoleWriter};
 
 pub fn init_default(min_level: LevelFilter) {
+    // The browser console is not available when the frontend is built for the host.
+    if cfg!(not(target_arch = "wasm32")) {
+        return;
+    }
+
     let fmt_layer = tracing_subscriber::fmt::layer()
         .with_ansi(false)
         .with_timer(UtcTime::rfc_3339())
@@ -1,7 +1,7 @@
 use async_trait::async_trait;
 use stackable_bridge::resolvers::{MutationResolver, QueryResolver};
 use stackable_bridge::types::{MutationResult, QueryResult};
-use stackable_bridge::BridgeMetadata;
+use stackable_bridge::{BridgeMetadata, ServerError};
 use time::OffsetDateTime;
 
 use crate::types::*;
@@ -23,6 +23,10 @@ impl MutationResolver for GreetingMutation {
     type Context = ();
 
     async fn resolve(_metadata: &BridgeMetadata<()>, name: &Self::Input) -> MutationResult<Self> {
+        if name.trim().is_empty() {
+            return Err(ServerError::bad_request("name cannot be empty"));
+        }
+
         Ok(Self {
             message: format!("Hello, {name}!"),
         }
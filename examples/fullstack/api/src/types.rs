@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 use stackable_bridge::types::{BridgedMutation, BridgedQuery};
-use thiserror::Error;
+use stackable_bridge::{BridgeError, ServerError};
 use time::OffsetDateTime;
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
@@ -8,18 +8,12 @@ pub struct ServerTimeQuery {
     pub value: OffsetDateTime,
 }
 
-#[derive(Debug, Error, PartialEq, Eq, Clone, Serialize, Deserialize)]
-pub enum Error {
-    #[error("failed to communicate with server.")]
-    Network,
-}
-
 impl BridgedQuery for ServerTimeQuery {
-    type Error = Error;
+    type Error = ServerError;
     type Input = ();
 
-    fn into_query_error(_e: stackable_bridge::BridgeError) -> Self::Error {
-        Error::Network
+    fn into_query_error(e: BridgeError) -> Self::Error {
+        e.into()
     }
 }
 
@@ -29,10 +23,10 @@ pub struct GreetingMutation {
 }
 
 impl BridgedMutation for GreetingMutation {
-    type Error = Error;
+    type Error = ServerError;
     type Input = String;
 
-    fn into_mutation_error(_e: stackable_bridge::BridgeError) -> Self::Error {
-        Error::Network
+    fn into_mutation_error(e: BridgeError) -> Self::Error {
+        e.into()
     }
 }
@@ -1,7 +1,8 @@
 #![deny(clippy::all)]
 #![deny(missing_debug_implementations)]
 
-use bounce::helmet::Helmet;
+use stackable_bridge::head::PageMeta;
+use stackable_bridge::profiler::Profiled;
 use yew::prelude::*;
 
 mod pages;
@@ -13,15 +14,18 @@ pub fn Main() -> Html {
 
     html! {
         <>
-            <Helmet>
-                <title>{"Welcome to Stackable!"}</title>
-            </Helmet>
+            <PageMeta
+                title="Welcome to Stackable!"
+                description="A fullstack example of Stackable."
+            />
             <div class="container">
                 <div class="title">{"Welcome to Stackable!"}</div>
                 <Suspense {fallback}>
                     <ServerTime />
                 </Suspense>
-                <Greeting />
+                <Profiled name="Greeting">
+                    <Greeting />
+                </Profiled>
             </div>
         </>
     }
@@ -1,5 +1,6 @@
 use example_fullstack_api::GreetingMutation;
 use stackable_bridge::hooks::use_bridged_mutation;
+use stackable_bridge::ServerErrorKind;
 use web_sys::HtmlInputElement;
 use yew::platform::spawn_local;
 use yew::prelude::*;
@@ -11,7 +12,10 @@ pub fn Greeting() -> Html {
     let message = match handle.result() {
         None => "".to_string(),
         Some(Ok(m)) => m.message.to_string(),
-        Some(Err(_)) => "failed to communicate with server...".into(),
+        Some(Err(e)) => match e.kind() {
+            ServerErrorKind::Invalid => e.message().to_string(),
+            _ => "failed to communicate with server...".into(),
+        },
     };
 
     let input_ref = use_node_ref();